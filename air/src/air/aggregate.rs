@@ -0,0 +1,376 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    Air, AirContext, Assertion, AuxTraceRandElements, EvaluationFrame, EvaluationFrameBatch,
+    TraceInfo,
+};
+use crate::ProofOptions;
+use core::{ops::Range, slice};
+use math::{ExtensionOf, FieldElement, StarkField, ToElements};
+use utils::collections::Vec;
+
+// AGGREGATE PUBLIC INPUTS
+// ================================================================================================
+
+/// Public inputs for all instances of a computation aggregated into a single proof via
+/// [AggregateAir].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateInputs<P>(Vec<P>);
+
+impl<P> AggregateInputs<P> {
+    /// Returns public inputs for a set of instances of a computation instantiated from the
+    /// provided list; the i-th element of the list contains public inputs for the i-th instance.
+    ///
+    /// # Panics
+    /// Panics if `inputs` is empty.
+    pub fn new(inputs: Vec<P>) -> Self {
+        assert!(
+            !inputs.is_empty(),
+            "at least one instance must be specified"
+        );
+        Self(inputs)
+    }
+
+    /// Returns the number of aggregated instances.
+    pub fn num_instances(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns public inputs for all aggregated instances.
+    pub fn inputs(&self) -> &[P] {
+        &self.0
+    }
+}
+
+impl<B: StarkField, P: ToElements<B>> ToElements<B> for AggregateInputs<P> {
+    /// Returns the number of instances followed by public inputs of all instances, where the
+    /// inputs of each instance are prefixed with their length.
+    fn to_elements(&self) -> Vec<B> {
+        let mut result = vec![B::from(self.0.len() as u32)];
+        for inputs in self.0.iter() {
+            let elements = inputs.to_elements();
+            result.push(B::from(elements.len() as u32));
+            result.extend_from_slice(&elements);
+        }
+        result
+    }
+}
+
+// AGGREGATE AIR
+// ================================================================================================
+
+/// AIR for a set of instances of the computation described by `A` which are proven together in a
+/// single STARK proof.
+///
+/// Execution traces of all instances must have the same length and metadata. The trace of the
+/// aggregated computation is built by placing the traces of individual instances side by side:
+/// each segment of the aggregated trace consists of the corresponding segments of all instances,
+/// such that the i-th instance occupies the i-th group of columns in every segment. Thus, rows of
+/// all instances are committed to in shared Merkle trees, and a single DEEP composition
+/// polynomial and low-degree proof are built for all instances.
+///
+/// Constraints of each instance are evaluated against the columns of this instance only, and are
+/// then combined with constraints of all other instances using random coefficients drawn from
+/// the public coin. Random elements used to build auxiliary trace segments are shared by all
+/// instances.
+///
+/// The total width of the aggregated trace cannot exceed [TraceInfo::MAX_TRACE_WIDTH], and thus,
+/// the number of instances which can be aggregated into a single proof is limited by the width
+/// of the trace of a single instance. All instances must have the same number of transition
+/// exemptions.
+pub struct AggregateAir<A: Air> {
+    context: AirContext<A::BaseField>,
+    instances: Vec<A>,
+    layouts: Vec<InstanceLayout>,
+    main_width: usize,
+    aux_width: usize,
+}
+
+impl<A: Air> AggregateAir<A> {
+    /// Returns AIRs of all aggregated instances.
+    pub fn instances(&self) -> &[A] {
+        &self.instances
+    }
+}
+
+impl<A: Air> Air for AggregateAir<A> {
+    type BaseField = A::BaseField;
+    type PublicInputs = AggregateInputs<A::PublicInputs>;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an AIR for the aggregated computation.
+    ///
+    /// # Panics
+    /// Panics if the width of some segment of the trace described by `trace_info` is not
+    /// divisible by the number of instances, or if the instances have different numbers of
+    /// transition exemptions.
+    fn new(trace_info: TraceInfo, pub_inputs: Self::PublicInputs, options: ProofOptions) -> Self {
+        let num_instances = pub_inputs.num_instances();
+        let instance_info = TraceInfo::new_multi_segment(
+            trace_info.layout().split(num_instances),
+            trace_info.length(),
+            trace_info.meta().to_vec(),
+        );
+
+        let instances = pub_inputs
+            .0
+            .into_iter()
+            .map(|inputs| A::new(instance_info.clone(), inputs, options.clone()))
+            .collect::<Vec<_>>();
+
+        // determine which columns, periodic values, and constraints belong to each instance
+        let layout = instance_info.layout();
+        let mut layouts = Vec::with_capacity(num_instances);
+        let (mut num_periodic_columns, mut num_main_constraints, mut num_aux_constraints) =
+            (0, 0, 0);
+        for (i, instance) in instances.iter().enumerate() {
+            let context = instance.context();
+            let mut aux_columns = Vec::with_capacity(layout.num_aux_segments());
+            let mut segment_offset = 0;
+            for segment_idx in 0..layout.num_aux_segments() {
+                let width = layout.get_aux_segment_width(segment_idx);
+                let start = segment_offset + i * width;
+                aux_columns.push(start..start + width);
+                segment_offset += width * num_instances;
+            }
+
+            let num_periodic = instance.get_periodic_column_values().len();
+            layouts.push(InstanceLayout {
+                main_columns: i * layout.main_trace_width()..(i + 1) * layout.main_trace_width(),
+                aux_columns,
+                periodic_values: num_periodic_columns..num_periodic_columns + num_periodic,
+                main_constraints: num_main_constraints
+                    ..num_main_constraints + context.num_main_transition_constraints(),
+                aux_constraints: num_aux_constraints
+                    ..num_aux_constraints + context.num_aux_transition_constraints(),
+            });
+            num_periodic_columns += num_periodic;
+            num_main_constraints += context.num_main_transition_constraints();
+            num_aux_constraints += context.num_aux_transition_constraints();
+        }
+
+        // build the context of the aggregated computation from the contexts of all instances
+        let num_exemptions = instances[0].context().num_transition_exemptions();
+        let mut main_degrees = Vec::with_capacity(num_main_constraints);
+        let mut aux_degrees = Vec::with_capacity(num_aux_constraints);
+        let (mut num_main_assertions, mut num_aux_assertions) = (0, 0);
+        for instance in instances.iter() {
+            let context = instance.context();
+            assert_eq!(
+                num_exemptions,
+                context.num_transition_exemptions(),
+                "all instances must have the same number of transition exemptions"
+            );
+            main_degrees.extend_from_slice(&context.main_transition_constraint_degrees);
+            aux_degrees.extend_from_slice(&context.aux_transition_constraint_degrees);
+            num_main_assertions += context.num_main_assertions;
+            num_aux_assertions += context.num_aux_assertions;
+        }
+
+        let mut context = AirContext::new_multi_segment(
+            trace_info,
+            main_degrees,
+            aux_degrees,
+            num_main_assertions,
+            num_aux_assertions,
+            options,
+        );
+        if num_exemptions > 1 {
+            context = context.set_num_transition_exemptions(num_exemptions);
+        }
+
+        Self {
+            context,
+            instances,
+            layouts,
+            main_width: layout.main_trace_width(),
+            aux_width: layout.aux_trace_width(),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn domain_separator(&self) -> &[u8] {
+        self.instances[0].domain_separator()
+    }
+
+    // MAIN TRACE CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    fn evaluate_transition<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        // columns of each instance are copied into the same frame to avoid allocating a new
+        // frame for every instance
+        let mut instance_frame = EvaluationFrame::new(self.main_width);
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            frame.read_columns_into(slice::from_ref(&layout.main_columns), &mut instance_frame);
+            instance.evaluate_transition(
+                &instance_frame,
+                &periodic_values[layout.periodic_values.clone()],
+                &mut result[layout.main_constraints.clone()],
+            );
+        }
+    }
+
+    fn evaluate_transition_batch<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let n = frames.num_rows();
+        let mut instance_frames = EvaluationFrameBatch::new(self.main_width, n);
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            frames.read_columns_into(slice::from_ref(&layout.main_columns), &mut instance_frames);
+            instance.evaluate_transition_batch(
+                &instance_frames,
+                &periodic_values[scale(&layout.periodic_values, n)],
+                &mut result[scale(&layout.main_constraints, n)],
+            );
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        let mut result = Vec::new();
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            result.extend(instance.get_assertions().into_iter().map(|mut assertion| {
+                assertion.column += layout.main_columns.start;
+                assertion
+            }));
+        }
+        result
+    }
+
+    // AUXILIARY TRACE CONSTRAINTS
+    // --------------------------------------------------------------------------------------------
+
+    fn evaluate_aux_transition<F, E>(
+        &self,
+        main_frame: &EvaluationFrame<F>,
+        aux_frame: &EvaluationFrame<E>,
+        periodic_values: &[F],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let mut instance_main_frame = EvaluationFrame::new(self.main_width);
+        let mut instance_aux_frame = EvaluationFrame::new(self.aux_width);
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            main_frame.read_columns_into(
+                slice::from_ref(&layout.main_columns),
+                &mut instance_main_frame,
+            );
+            aux_frame.read_columns_into(&layout.aux_columns, &mut instance_aux_frame);
+            instance.evaluate_aux_transition(
+                &instance_main_frame,
+                &instance_aux_frame,
+                &periodic_values[layout.periodic_values.clone()],
+                aux_rand_elements,
+                &mut result[layout.aux_constraints.clone()],
+            );
+        }
+    }
+
+    fn evaluate_aux_transition_batch<F, E>(
+        &self,
+        main_frames: &EvaluationFrameBatch<F>,
+        aux_frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[F],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let n = main_frames.num_rows();
+        let mut instance_main_frames = EvaluationFrameBatch::new(self.main_width, n);
+        let mut instance_aux_frames = EvaluationFrameBatch::new(self.aux_width, n);
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            main_frames.read_columns_into(
+                slice::from_ref(&layout.main_columns),
+                &mut instance_main_frames,
+            );
+            aux_frames.read_columns_into(&layout.aux_columns, &mut instance_aux_frames);
+            instance.evaluate_aux_transition_batch(
+                &instance_main_frames,
+                &instance_aux_frames,
+                &periodic_values[scale(&layout.periodic_values, n)],
+                aux_rand_elements,
+                &mut result[scale(&layout.aux_constraints, n)],
+            );
+        }
+    }
+
+    fn get_aux_assertions<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        aux_rand_elements: &AuxTraceRandElements<E>,
+    ) -> Vec<Assertion<E>> {
+        let mut result = Vec::new();
+        for (instance, layout) in self.instances.iter().zip(self.layouts.iter()) {
+            for mut assertion in instance.get_aux_assertions(aux_rand_elements) {
+                // map the column index across auxiliary segments of the instance to the index of
+                // the corresponding column across auxiliary segments of the aggregated trace
+                let mut column = assertion.column;
+                for columns in layout.aux_columns.iter() {
+                    if column < columns.len() {
+                        assertion.column = columns.start + column;
+                        break;
+                    }
+                    column -= columns.len();
+                }
+                result.push(assertion);
+            }
+        }
+        result
+    }
+
+    // PERIODIC COLUMNS
+    // --------------------------------------------------------------------------------------------
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.instances
+            .iter()
+            .flat_map(|instance| instance.get_periodic_column_values())
+            .collect()
+    }
+}
+
+// INSTANCE LAYOUT
+// ================================================================================================
+
+/// Positions of values related to a single instance in the aggregated computation.
+struct InstanceLayout {
+    /// Columns of the main trace segment occupied by the instance.
+    main_columns: Range<usize>,
+    /// Columns occupied by the instance in each auxiliary trace segment; indexes are relative to
+    /// the start of all auxiliary trace segments.
+    aux_columns: Vec<Range<usize>>,
+    /// Periodic columns of the instance.
+    periodic_values: Range<usize>,
+    /// Transition constraints of the instance against the main trace segment.
+    main_constraints: Range<usize>,
+    /// Transition constraints of the instance against auxiliary trace segments.
+    aux_constraints: Range<usize>,
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the range of column-major batch values occupied by the specified range of columns in
+/// a batch of `num_rows` rows.
+fn scale(columns: &Range<usize>, num_rows: usize) -> Range<usize> {
+    columns.start * num_rows..columns.end * num_rows
+}
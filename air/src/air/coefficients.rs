@@ -4,7 +4,9 @@
 // LICENSE file in the root directory of this source tree.
 
 use math::FieldElement;
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 // AUXILIARY TRACE SEGMENT RANDOMNESS
 // ================================================================================================
@@ -42,6 +44,34 @@ impl<E: FieldElement> Default for AuxTraceRandElements<E> {
     }
 }
 
+impl<E: FieldElement> Serializable for AuxTraceRandElements<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u16(self.0.len() as u16);
+        for rand_elements in self.0.iter() {
+            target.write_u16(rand_elements.len() as u16);
+            rand_elements.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement> Deserializable for AuxTraceRandElements<E> {
+    /// Reads random elements for auxiliary trace segments from the specified `source` and
+    /// returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if the random elements could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_segments = source.read_u16()? as usize;
+        let mut result = Vec::with_capacity(num_segments);
+        for _ in 0..num_segments {
+            let num_elements = source.read_u16()? as usize;
+            result.push(E::read_batch_from(source, num_elements)?);
+        }
+        Ok(Self(result))
+    }
+}
+
 // CONSTRAINT COMPOSITION COEFFICIENTS
 // ================================================================================================
 /// Coefficients used in construction of constraint composition polynomial.
@@ -22,7 +22,8 @@ pub use boundary::{BoundaryConstraint, BoundaryConstraintGroup, BoundaryConstrai
 
 mod transition;
 pub use transition::{
-    EvaluationFrame, TransitionConstraintDegree, TransitionConstraintGroup, TransitionConstraints,
+    EvaluationFrame, EvaluationFrameBatch, TransitionConstraintDegree, TransitionConstraintGroup,
+    TransitionConstraints,
 };
 
 mod coefficients;
@@ -33,6 +34,9 @@ pub use coefficients::{
 mod divisor;
 pub use divisor::ConstraintDivisor;
 
+mod aggregate;
+pub use aggregate::{AggregateAir, AggregateInputs};
+
 #[cfg(test)]
 mod tests;
 
@@ -284,6 +288,87 @@ pub trait Air: Send + Sync {
         Vec::new()
     }
 
+    // BATCH CONSTRAINT EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates transition constraints over a batch of evaluation frames for consecutive steps
+    /// of the constraint evaluation domain.
+    ///
+    /// All parameters are in column-major form, where `n` is the number of frames in the batch
+    /// (see [EvaluationFrameBatch::num_rows()]):
+    /// * `periodic_values` contains `n` values for each periodic column; values of the i-th
+    ///   periodic column are located at `periodic_values[i * n..(i + 1) * n]`.
+    /// * `result` has room for `n` evaluations of each transition constraint; evaluations of the
+    ///   i-th constraint should be written into `result[i * n..(i + 1) * n]`, in the same order
+    ///   of constraints as expected by [evaluate_transition()](Air::evaluate_transition).
+    ///
+    /// The prover uses this method to evaluate constraints over the constraint evaluation domain.
+    /// Overriding it allows amortizing per-row overhead across the batch and evaluating
+    /// constraints over contiguous slices of column values (e.g., using SIMD instructions).
+    ///
+    /// The default implementation evaluates constraints for each frame of the batch separately
+    /// via [evaluate_transition()](Air::evaluate_transition).
+    fn evaluate_transition_batch<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let num_rows = frames.num_rows();
+        let mut frame = EvaluationFrame::new(frames.num_columns());
+        let mut row_periodic_values = E::zeroed_vector(periodic_values.len() / num_rows);
+        let mut row_result = E::zeroed_vector(result.len() / num_rows);
+
+        for row in 0..num_rows {
+            frames.read_frame_into(row, &mut frame);
+            read_batch_row(periodic_values, row, num_rows, &mut row_periodic_values);
+            row_result.fill(E::ZERO);
+            self.evaluate_transition(&frame, &row_periodic_values, &mut row_result);
+            write_batch_row(&row_result, row, num_rows, result);
+        }
+    }
+
+    /// Evaluates auxiliary transition constraints over batches of evaluation frames for the main
+    /// and auxiliary trace segments.
+    ///
+    /// This method is to [evaluate_aux_transition()](Air::evaluate_aux_transition) what
+    /// [evaluate_transition_batch()](Air::evaluate_transition_batch) is to
+    /// [evaluate_transition()](Air::evaluate_transition): `periodic_values` and `result` are in
+    /// column-major form, and the default implementation evaluates constraints for each pair of
+    /// frames in the batches separately.
+    fn evaluate_aux_transition_batch<F, E>(
+        &self,
+        main_frames: &EvaluationFrameBatch<F>,
+        aux_frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[F],
+        aux_rand_elements: &AuxTraceRandElements<E>,
+        result: &mut [E],
+    ) where
+        F: FieldElement<BaseField = Self::BaseField>,
+        E: FieldElement<BaseField = Self::BaseField> + ExtensionOf<F>,
+    {
+        let num_rows = main_frames.num_rows();
+        let mut main_frame = EvaluationFrame::new(main_frames.num_columns());
+        let mut aux_frame = EvaluationFrame::new(aux_frames.num_columns());
+        let mut row_periodic_values = F::zeroed_vector(periodic_values.len() / num_rows);
+        let mut row_result = E::zeroed_vector(result.len() / num_rows);
+
+        for row in 0..num_rows {
+            main_frames.read_frame_into(row, &mut main_frame);
+            aux_frames.read_frame_into(row, &mut aux_frame);
+            read_batch_row(periodic_values, row, num_rows, &mut row_periodic_values);
+            row_result.fill(E::ZERO);
+            self.evaluate_aux_transition(
+                &main_frame,
+                &aux_frame,
+                &row_periodic_values,
+                aux_rand_elements,
+                &mut row_result,
+            );
+            write_batch_row(&row_result, row, num_rows, result);
+        }
+    }
+
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -300,6 +385,20 @@ pub trait Air: Send + Sync {
         Vec::new()
     }
 
+    /// Returns a tag which identifies the computation described by this AIR.
+    ///
+    /// The tag is included into the proof context, and is absorbed into the seed of the public
+    /// coin by both the prover and the verifier. Thus, a proof generated for one AIR will not be
+    /// accepted by the verifier for another AIR with a different tag, even if the two AIRs have
+    /// the same trace shape and public inputs.
+    ///
+    /// The default implementation of this method returns an empty tag. Computations which may
+    /// share trace shape and public inputs with other computations should override this method.
+    /// The tag must not be longer than 255 bytes.
+    fn domain_separator(&self) -> &[u8] {
+        &[]
+    }
+
     /// Returns polynomial for all periodic columns.
     ///
     /// These polynomials are interpolated from the values returned from the
@@ -554,3 +653,21 @@ pub trait Air: Send + Sync {
         })
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Copies values of the specified row from column-major `batch` with `num_rows` rows into
+/// `result`.
+fn read_batch_row<E: Copy>(batch: &[E], row: usize, num_rows: usize, result: &mut [E]) {
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = batch[i * num_rows + row];
+    }
+}
+
+/// Copies `values` into the specified row of column-major `batch` with `num_rows` rows.
+fn write_batch_row<E: Copy>(values: &[E], row: usize, num_rows: usize, batch: &mut [E]) {
+    for (i, &value) in values.iter().enumerate() {
+        batch[i * num_rows + row] = value;
+    }
+}
@@ -4,12 +4,13 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    Air, AirContext, Assertion, EvaluationFrame, ProofOptions, TraceInfo,
-    TransitionConstraintDegree,
+    AggregateAir, AggregateInputs, Air, AirContext, Assertion, EvaluationFrame,
+    EvaluationFrameBatch, ProofOptions, TraceInfo, TraceLayout, TransitionConstraintDegree,
 };
 use crate::{AuxTraceRandElements, FieldExtension};
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
 use math::{fields::f128::BaseElement, get_power_series, polynom, FieldElement, StarkField};
+use rand_utils::rand_vector;
 use utils::collections::{BTreeMap, Vec};
 
 // PERIODIC COLUMNS
@@ -217,6 +218,100 @@ fn get_boundary_constraints() {
     assert_eq!(expected_cc[&7], constraint.cc().clone());
 }
 
+// BATCH CONSTRAINT EVALUATION
+// ================================================================================================
+
+#[test]
+fn evaluate_transition_batch() {
+    let air = BatchTestAir::with_periodic_columns(vec![vec![BaseElement::ONE; 2]; 2], 16);
+    let num_rows = 8;
+
+    // build a batch of random frames together with random periodic values
+    let rows: Vec<(Vec<BaseElement>, Vec<BaseElement>)> = (0..num_rows)
+        .map(|_| (rand_vector(4), rand_vector(4)))
+        .collect();
+    let mut frames = EvaluationFrameBatch::new(4, num_rows);
+    for (i, (current, next)) in rows.iter().enumerate() {
+        frames.set_frame(i, current, next);
+    }
+    let periodic_values: Vec<BaseElement> = rand_vector(2 * num_rows);
+
+    // the default batch evaluation must be the same as evaluating each frame separately
+    let mut result = vec![BaseElement::ZERO; 2 * num_rows];
+    air.evaluate_transition_batch(&frames, &periodic_values, &mut result);
+
+    for (i, (current, next)) in rows.into_iter().enumerate() {
+        let frame = EvaluationFrame::from_rows(current, next);
+        let row_periodic_values = [periodic_values[i], periodic_values[num_rows + i]];
+        let mut expected = [BaseElement::ZERO; 2];
+        air.evaluate_transition(&frame, &row_periodic_values, &mut expected);
+        assert_eq!(expected[0], result[i]);
+        assert_eq!(expected[1], result[num_rows + i]);
+    }
+}
+
+// AGGREGATION
+// ================================================================================================
+
+#[test]
+fn aggregate_trace_layout() {
+    let layout = TraceLayout::new(4, [3], [2]);
+    let aggregated = layout.aggregate(5);
+    assert_eq!(20, aggregated.main_trace_width());
+    assert_eq!(15, aggregated.get_aux_segment_width(0));
+    assert_eq!(2, aggregated.get_aux_segment_rand_elements(0));
+    assert_eq!(layout, aggregated.split(5));
+}
+
+#[test]
+fn aggregate_air() {
+    let num_instances = 3;
+    let trace_info = TraceInfo::new(4 * num_instances, 16);
+    let options = ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31);
+    let pub_inputs = AggregateInputs::new(vec![(); num_instances]);
+    let air = AggregateAir::<BatchTestAir>::new(trace_info, pub_inputs, options);
+    assert_eq!(
+        2 * num_instances,
+        air.context().num_transition_constraints()
+    );
+    assert_eq!(num_instances, air.context().num_assertions());
+
+    // constraints of each instance must be evaluated against the columns of this instance only
+    let num_rows = 4;
+    let rows: Vec<(Vec<BaseElement>, Vec<BaseElement>)> = (0..num_rows)
+        .map(|_| {
+            (
+                rand_vector(4 * num_instances),
+                rand_vector(4 * num_instances),
+            )
+        })
+        .collect();
+    let mut frames = EvaluationFrameBatch::new(4 * num_instances, num_rows);
+    for (i, (current, next)) in rows.iter().enumerate() {
+        frames.set_frame(i, current, next);
+    }
+    let mut result = vec![BaseElement::ZERO; 2 * num_instances * num_rows];
+    air.evaluate_transition_batch(&frames, &[], &mut result);
+
+    for (i, (current, next)) in rows.into_iter().enumerate() {
+        let mut expected = vec![BaseElement::ZERO; 2 * num_instances];
+        for (j, instance) in air.instances().iter().enumerate() {
+            let frame = EvaluationFrame::from_rows(
+                current[j * 4..(j + 1) * 4].to_vec(),
+                next[j * 4..(j + 1) * 4].to_vec(),
+            );
+            instance.evaluate_transition(&frame, &[], &mut expected[j * 2..(j + 1) * 2]);
+        }
+
+        let mut actual = vec![BaseElement::ZERO; 2 * num_instances];
+        air.evaluate_transition(&EvaluationFrame::from_rows(current, next), &[], &mut actual);
+        assert_eq!(expected, actual);
+        for (j, &value) in expected.iter().enumerate() {
+            assert_eq!(value, result[j * num_rows + i]);
+        }
+    }
+}
+
 // MOCK AIR
 // ================================================================================================
 
@@ -286,6 +381,71 @@ impl Air for MockAir {
     }
 }
 
+// BATCH TEST AIR
+// ================================================================================================
+
+/// AIR with two transition constraints which depend on all columns of the trace and on the
+/// values of all periodic columns; used to test batch evaluation of transition constraints.
+struct BatchTestAir {
+    context: AirContext<BaseElement>,
+    periodic_columns: Vec<Vec<BaseElement>>,
+}
+
+impl BatchTestAir {
+    pub fn with_periodic_columns(
+        column_values: Vec<Vec<BaseElement>>,
+        trace_length: usize,
+    ) -> Self {
+        let mut result = Self::new(
+            TraceInfo::new(4, trace_length),
+            (),
+            ProofOptions::new(32, 8, 0, FieldExtension::None, 4, 31),
+        );
+        result.periodic_columns = column_values;
+        result
+    }
+}
+
+impl Air for BatchTestAir {
+    type BaseField = BaseElement;
+    type PublicInputs = ();
+
+    fn new(trace_info: TraceInfo, _pub_inputs: (), options: ProofOptions) -> Self {
+        let degrees = vec![
+            TransitionConstraintDegree::new(2),
+            TransitionConstraintDegree::new(1),
+        ];
+        BatchTestAir {
+            context: AirContext::new(trace_info, degrees, 1, options),
+            periodic_columns: Vec::new(),
+        }
+    }
+
+    fn context(&self) -> &AirContext<Self::BaseField> {
+        &self.context
+    }
+
+    fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
+        self.periodic_columns.clone()
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
+        vec![Assertion::single(0, 0, BaseElement::ZERO)]
+    }
+
+    fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        let (current, next) = (frame.current(), frame.next());
+        let periodic_sum = periodic_values.iter().fold(E::ZERO, |sum, &v| sum + v);
+        result[0] = next[0] - current[0] * current[1] + periodic_sum;
+        result[1] = next[1] - current[2] - current[3];
+    }
+}
+
 // UTILITY FUNCTIONS
 // ================================================================================================
 
@@ -267,6 +267,72 @@ impl TraceLayout {
         // TODO: panic if segment_idx is not within num_aux_segments
         self.aux_segment_rands[segment_idx]
     }
+
+    // AGGREGATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a layout of a trace in which the corresponding segments of `num_instances` traces
+    /// with this layout are placed side by side.
+    ///
+    /// The number of random elements required by each auxiliary segment is not changed, since the
+    /// random elements are shared by all instances.
+    ///
+    /// # Panics
+    /// Panics if `num_instances` is zero or greater than
+    /// [TraceLayout::max_aggregate_instances()].
+    pub fn aggregate(&self, num_instances: usize) -> Self {
+        assert!(
+            num_instances > 0,
+            "number of instances must be greater than zero"
+        );
+        let mut aux_widths = self.aux_segment_widths;
+        aux_widths
+            .iter_mut()
+            .for_each(|width| *width *= num_instances);
+        Self::new(
+            self.main_segment_width * num_instances,
+            aux_widths,
+            self.aux_segment_rands,
+        )
+    }
+
+    /// Returns the maximum number of instances of a trace with this layout which can be placed
+    /// side by side via [TraceLayout::aggregate()] without exceeding
+    /// [TraceInfo::MAX_TRACE_WIDTH] columns in total.
+    pub fn max_aggregate_instances(&self) -> usize {
+        TraceInfo::MAX_TRACE_WIDTH / (self.main_trace_width() + self.aux_trace_width())
+    }
+
+    /// Returns a layout of a single instance of a trace with this layout in which the segments of
+    /// `num_instances` instances are placed side by side.
+    ///
+    /// This is the inverse of [TraceLayout::aggregate()].
+    ///
+    /// # Panics
+    /// Panics if `num_instances` is zero or the width of some segment is not divisible by
+    /// `num_instances`.
+    pub fn split(&self, num_instances: usize) -> Self {
+        assert!(
+            num_instances > 0,
+            "number of instances must be greater than zero"
+        );
+        let split_width = |width: usize| {
+            assert!(
+                width % num_instances == 0,
+                "trace segment width {width} is not divisible by the number of instances {num_instances}"
+            );
+            width / num_instances
+        };
+        let mut aux_widths = self.aux_segment_widths;
+        aux_widths
+            .iter_mut()
+            .for_each(|width| *width = split_width(*width));
+        Self::new(
+            split_width(self.main_segment_width),
+            aux_widths,
+            self.aux_segment_rands,
+        )
+    }
 }
 
 impl<E: StarkField> ToElements<E> for TraceLayout {
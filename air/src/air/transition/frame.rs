@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{FieldElement, Vec};
+use core::ops::Range;
 
 // EVALUATION FRAME
 // ================================================================================================
@@ -82,4 +83,136 @@ impl<E: FieldElement> EvaluationFrame<E> {
     pub fn next_mut(&mut self) -> &mut [E] {
         &mut self.next
     }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies values of the specified ranges of columns of this frame into the `target` frame.
+    ///
+    /// The `target` frame must have as many columns as the specified ranges contain in total.
+    pub(crate) fn read_columns_into(&self, columns: &[Range<usize>], target: &mut Self) {
+        let mut offset = 0;
+        for range in columns.iter() {
+            let end = offset + range.len();
+            target.current[offset..end].copy_from_slice(&self.current[range.clone()]);
+            target.next[offset..end].copy_from_slice(&self.next[range.clone()]);
+            offset = end;
+        }
+        debug_assert_eq!(offset, target.current.len(), "invalid target frame width");
+    }
+}
+
+// EVALUATION FRAME BATCH
+// ================================================================================================
+/// A batch of evaluation frames for consecutive steps of the constraint evaluation domain.
+///
+/// Values are stored in column-major form: for each column of the execution trace, the batch
+/// contains a contiguous slice of values of this column in the current rows of all frames, and
+/// a contiguous slice of values of this column in the next rows of all frames. A batch is passed
+/// in as one of the parameters into
+/// [Air::evaluate_transition_batch()](crate::Air::evaluate_transition_batch) function.
+#[derive(Debug, Clone)]
+pub struct EvaluationFrameBatch<E: FieldElement> {
+    current: Vec<E>,
+    next: Vec<E>,
+    num_rows: usize,
+}
+
+impl<E: FieldElement> EvaluationFrameBatch<E> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new batch of `num_rows` evaluation frames instantiated with the specified number
+    /// of columns.
+    ///
+    /// # Panics
+    /// Panics if `num_columns` or `num_rows` is zero.
+    pub fn new(num_columns: usize, num_rows: usize) -> Self {
+        assert!(
+            num_columns > 0,
+            "number of columns must be greater than zero"
+        );
+        assert!(num_rows > 0, "number of rows must be greater than zero");
+        EvaluationFrameBatch {
+            current: E::zeroed_vector(num_columns * num_rows),
+            next: E::zeroed_vector(num_columns * num_rows),
+            num_rows,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of columns in each frame of this batch.
+    pub fn num_columns(&self) -> usize {
+        self.current.len() / self.num_rows
+    }
+
+    /// Returns the number of frames in this batch.
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Returns values of the specified column in the current rows of all frames.
+    #[inline(always)]
+    pub fn current_column(&self, column: usize) -> &[E] {
+        let start = column * self.num_rows;
+        &self.current[start..start + self.num_rows]
+    }
+
+    /// Returns values of the specified column in the next rows of all frames.
+    #[inline(always)]
+    pub fn next_column(&self, column: usize) -> &[E] {
+        let start = column * self.num_rows;
+        &self.next[start..start + self.num_rows]
+    }
+
+    /// Copies the current and next rows of the frame at the specified index into `frame`.
+    pub fn read_frame_into(&self, row: usize, frame: &mut EvaluationFrame<E>) {
+        let num_rows = self.num_rows;
+        for (i, value) in frame.current_mut().iter_mut().enumerate() {
+            *value = self.current[i * num_rows + row];
+        }
+        for (i, value) in frame.next_mut().iter_mut().enumerate() {
+            *value = self.next[i * num_rows + row];
+        }
+    }
+
+    // DATA MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Sets the current and next rows of the frame at the specified index to the provided values.
+    ///
+    /// # Panics
+    /// Panics if the length of `current` or `next` is not equal to the number of columns.
+    pub fn set_frame(&mut self, row: usize, current: &[E], next: &[E]) {
+        let num_columns = self.num_columns();
+        assert_eq!(num_columns, current.len(), "invalid current row length");
+        assert_eq!(num_columns, next.len(), "invalid next row length");
+        for (i, (&current, &next)) in current.iter().zip(next).enumerate() {
+            self.current[i * self.num_rows + row] = current;
+            self.next[i * self.num_rows + row] = next;
+        }
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Copies values of the specified ranges of columns of this batch into the `target` batch.
+    ///
+    /// The `target` batch must have the same number of frames as this batch, and as many columns
+    /// as the specified ranges contain in total.
+    pub(crate) fn read_columns_into(&self, columns: &[Range<usize>], target: &mut Self) {
+        let num_rows = self.num_rows;
+        debug_assert_eq!(num_rows, target.num_rows, "invalid target batch size");
+        let mut offset = 0;
+        for range in columns.iter() {
+            let source = range.start * num_rows..range.end * num_rows;
+            let end = offset + source.len();
+            target.current[offset..end].copy_from_slice(&self.current[source.clone()]);
+            target.next[offset..end].copy_from_slice(&self.next[source]);
+            offset = end;
+        }
+        debug_assert_eq!(offset, target.current.len(), "invalid target batch width");
+    }
 }
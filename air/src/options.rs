@@ -5,6 +5,7 @@
 
 use fri::FriOptions;
 use math::{StarkField, ToElements};
+use stir::StirOptions;
 use utils::{
     collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
 };
@@ -24,6 +25,12 @@ const MAX_GRINDING_FACTOR: u32 = 32;
 const FRI_MIN_FOLDING_FACTOR: usize = 2;
 const FRI_MAX_FOLDING_FACTOR: usize = 16;
 const FRI_MAX_REMAINDER_DEGREE: usize = 255;
+const FRI_MAX_FOLDING_SCHEDULE_LENGTH: usize = 32;
+
+const MAX_MERKLE_CAP_HEIGHT: usize = 4;
+
+const MIN_MERKLE_ARITY: usize = 2;
+const MAX_MERKLE_ARITY: usize = 16;
 
 // TYPES AND INTERFACES
 // ================================================================================================
@@ -52,6 +59,19 @@ pub enum FieldExtension {
     Cubic = 3,
 }
 
+/// Defines the protocol used to prove that the DEEP composition polynomial is of low degree.
+///
+/// STIR achieves the same soundness as FRI with significantly fewer queries, and thus, results in
+/// smaller proofs; however, STIR proofs are more expensive to generate and verify.
+#[repr(u8)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LowDegreeTest {
+    /// Low-degree test is performed using the FRI protocol.
+    Fri = 0,
+    /// Low-degree test is performed using the STIR protocol.
+    Stir = 1,
+}
+
 /// STARK protocol parameters.
 ///
 /// These parameters have a direct impact on proof soundness, proof generation time, and proof
@@ -69,6 +89,32 @@ pub enum FieldExtension {
 /// 4. Grinding factor - higher values increase proof soundness, but also may increase proof
 ///    generation time. More precisely, conjectured proof soundness is bounded by
 ///    `num_queries * log2(blowup_factor) + grinding_factor`.
+/// 5. Merkle cap height - instead of tree roots, the prover commits to all 2^h nodes at depth `h`
+///    of each Merkle tree. This increases the size of commitments but shortens each Merkle
+///    authentication path by `h` nodes, and thus, may reduce proof size when the number of queries
+///    is large. Merkle cap height does not affect proof soundness and defaults to 0 (i.e., trees
+///    are committed to via their roots).
+/// 6. FRI folding schedule - the factors by which individual FRI layers are folded. Layers not
+///    covered by the schedule are folded by the FRI folding factor. A schedule such as
+///    `[16, 16, 8, 4]` can be used to reduce the number of FRI layers while still reaching the
+///    remainder exactly. The schedule is empty by default.
+/// 7. FRI grinding factor - proof-of-work performed before drawing the folding challenge for each
+///    FRI layer. This makes it more expensive for a dishonest prover to resample folding
+///    challenges, and thus, increases the field-size-related part of conjectured proof soundness
+///    (but not the query-related part). Defaults to 0 (no grinding).
+/// 8. Low-degree test - the protocol used to prove that the DEEP composition polynomial is of low
+///    degree. Defaults to FRI. When STIR is used, the number of queries specifies the number of
+///    queries in the first STIR round, the FRI folding factor and remainder degree are used as
+///    the STIR folding factor and final polynomial degree, and the grinding factor specifies the
+///    proof-of-work performed before query positions are drawn in every STIR round. The FRI
+///    folding schedule and FRI grinding factor are not used by STIR.
+/// 9. Merkle tree arity - the number of children of every internal node in the Merkle trees used
+///    to commit to the execution trace, constraint evaluations, and FRI layers. For algebraic
+///    hash functions, trees of higher arity reduce the number of hashes needed to verify a Merkle
+///    path at the cost of longer paths. The LDE domain size and the domain size of every FRI
+///    layer divided by its folding factor must be powers of the arity; this is checked when a
+///    proof is generated. Merkle arity does not affect proof soundness and defaults to 2. Oracles
+///    of the STIR protocol are always committed to using binary trees.
 ///
 /// Another important parameter in defining STARK security level, which is not a part of [ProofOptions]
 /// is the hash function used in the protocol. The soundness of a STARK proof is limited by the
@@ -82,6 +128,11 @@ pub struct ProofOptions {
     field_extension: FieldExtension,
     fri_folding_factor: u8,
     fri_remainder_max_degree: u8,
+    merkle_cap_height: u8,
+    fri_folding_schedule: Vec<u8>,
+    fri_grinding_factor: u8,
+    low_degree_test: LowDegreeTest,
+    merkle_arity: u8,
 }
 
 // PROOF OPTIONS IMPLEMENTATION
@@ -147,9 +198,90 @@ impl ProofOptions {
             field_extension,
             fri_folding_factor: fri_folding_factor as u8,
             fri_remainder_max_degree: fri_remainder_max_degree as u8,
+            merkle_cap_height: 0,
+            fri_folding_schedule: Vec::new(),
+            fri_grinding_factor: 0,
+            low_degree_test: LowDegreeTest::Fri,
+            merkle_arity: 2,
         }
     }
 
+    /// Updates the height of Merkle tree caps used to commit to the execution trace, constraint
+    /// evaluations, and FRI layers.
+    ///
+    /// # Panics
+    /// Panics if `merkle_cap_height` is greater than 4.
+    pub fn with_merkle_cap_height(mut self, merkle_cap_height: usize) -> ProofOptions {
+        assert!(
+            merkle_cap_height <= MAX_MERKLE_CAP_HEIGHT,
+            "Merkle cap height cannot be greater than {MAX_MERKLE_CAP_HEIGHT}"
+        );
+        self.merkle_cap_height = merkle_cap_height as u8;
+        self
+    }
+
+    /// Updates the arity of Merkle trees used to commit to the execution trace, constraint
+    /// evaluations, and FRI layers.
+    ///
+    /// # Panics
+    /// Panics if `merkle_arity` is not 2, 4, 8, or 16.
+    #[rustfmt::skip]
+    pub fn with_merkle_arity(mut self, merkle_arity: usize) -> ProofOptions {
+        assert!(merkle_arity.is_power_of_two(), "Merkle tree arity must be a power of 2");
+        assert!(merkle_arity >= MIN_MERKLE_ARITY, "Merkle tree arity cannot be smaller than {MIN_MERKLE_ARITY}");
+        assert!(merkle_arity <= MAX_MERKLE_ARITY, "Merkle tree arity cannot be greater than {MAX_MERKLE_ARITY}");
+        self.merkle_arity = merkle_arity as u8;
+        self
+    }
+
+    /// Updates the number of bits of proof-of-work grinding performed before drawing the folding
+    /// challenge for each FRI layer.
+    ///
+    /// # Panics
+    /// Panics if `fri_grinding_factor` is greater than 32.
+    pub fn with_fri_grinding_factor(mut self, fri_grinding_factor: u32) -> ProofOptions {
+        assert!(
+            fri_grinding_factor <= MAX_GRINDING_FACTOR,
+            "FRI grinding factor cannot be greater than {MAX_GRINDING_FACTOR}"
+        );
+        self.fri_grinding_factor = fri_grinding_factor as u8;
+        self
+    }
+
+    /// Updates the protocol used to prove that the DEEP composition polynomial is of low degree.
+    pub fn with_low_degree_test(mut self, low_degree_test: LowDegreeTest) -> ProofOptions {
+        self.low_degree_test = low_degree_test;
+        self
+    }
+
+    /// Updates the factors by which individual FRI layers are folded.
+    ///
+    /// The i-th FRI layer is folded by the i-th factor of the `fri_folding_schedule`; layers past
+    /// the end of the schedule are folded by the FRI folding factor.
+    ///
+    /// The schedule must not fold the LDE domain of a computation into a remainder domain which
+    /// is smaller than the blowup factor; since the LDE domain size depends on the trace length,
+    /// this is checked when a proof is generated.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `fri_folding_schedule` contains more than 32 factors.
+    /// - Any of the factors in `fri_folding_schedule` is not 2, 4, 8, or 16.
+    #[rustfmt::skip]
+    pub fn with_fri_folding_schedule(mut self, fri_folding_schedule: &[usize]) -> ProofOptions {
+        assert!(
+            fri_folding_schedule.len() <= FRI_MAX_FOLDING_SCHEDULE_LENGTH,
+            "FRI folding schedule cannot contain more than {FRI_MAX_FOLDING_SCHEDULE_LENGTH} factors"
+        );
+        for &folding_factor in fri_folding_schedule {
+            assert!(folding_factor.is_power_of_two(), "FRI folding factor must be a power of 2");
+            assert!(folding_factor >= FRI_MIN_FOLDING_FACTOR, "FRI folding factor cannot be smaller than {FRI_MIN_FOLDING_FACTOR}");
+            assert!(folding_factor <= FRI_MAX_FOLDING_FACTOR, "FRI folding factor cannot be greater than {FRI_MAX_FOLDING_FACTOR}");
+        }
+        self.fri_folding_schedule = fri_folding_schedule.iter().map(|&f| f as u8).collect();
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -183,6 +315,21 @@ impl ProofOptions {
         self.grinding_factor as u32
     }
 
+    /// Returns the number of bits of proof-of-work grinding performed before drawing the folding
+    /// challenge for each FRI layer.
+    ///
+    /// Unlike query seed grinding, this makes it more difficult for a dishonest prover to find
+    /// folding challenges which are favorable to them. Setting this factor too high will
+    /// adversely affect prover time as grinding is performed once per FRI layer.
+    pub fn fri_grinding_factor(&self) -> u32 {
+        self.fri_grinding_factor as u32
+    }
+
+    /// Returns the protocol used to prove that the DEEP composition polynomial is of low degree.
+    pub fn low_degree_test(&self) -> LowDegreeTest {
+        self.low_degree_test
+    }
+
     /// Specifies whether composition polynomial should be constructed in an extension field
     /// of STARK protocol.
     ///
@@ -200,27 +347,90 @@ impl ProofOptions {
         B::GENERATOR
     }
 
+    /// Returns the arity of Merkle trees used for commitments in a STARK proof.
+    ///
+    /// Every internal node of a tree is computed by hashing this number of its children; for
+    /// binary trees, the nodes are merged via `Hasher::merge()`, and for trees of higher arity via
+    /// `Hasher::merge_many()`.
+    pub fn merkle_arity(&self) -> usize {
+        self.merkle_arity as usize
+    }
+
+    /// Returns the height of Merkle tree caps used for commitments in a STARK proof.
+    ///
+    /// A cap of height `h` consists of arity^h nodes; thus, the cap of height 0 is the tree root.
+    /// Higher caps make commitments larger but shorten Merkle authentication paths for queries.
+    /// For trees of depth `h` or smaller, the cap consists of the parents of the leaves.
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height as usize
+    }
+
+    /// Returns the factors by which individual FRI layers are folded.
+    ///
+    /// FRI layers which are not covered by the schedule are folded by the FRI folding factor.
+    pub fn fri_folding_schedule(&self) -> Vec<usize> {
+        self.fri_folding_schedule
+            .iter()
+            .map(|&f| f as usize)
+            .collect()
+    }
+
     /// Returns options for FRI protocol instantiated with parameters from this proof options.
     pub fn to_fri_options(&self) -> FriOptions {
         let folding_factor = self.fri_folding_factor as usize;
         let remainder_max_degree = self.fri_remainder_max_degree as usize;
         FriOptions::new(self.blowup_factor(), folding_factor, remainder_max_degree)
+            .with_merkle_cap_height(self.merkle_cap_height())
+            .with_merkle_arity(self.merkle_arity())
+            .with_folding_schedule(&self.fri_folding_schedule())
+            .with_folding_grinding_factor(self.fri_grinding_factor())
+    }
+
+    /// Returns options for STIR protocol instantiated with parameters from this proof options.
+    pub fn to_stir_options(&self) -> StirOptions {
+        let folding_factor = self.fri_folding_factor as usize;
+        let remainder_max_degree = self.fri_remainder_max_degree as usize;
+        StirOptions::new(
+            self.blowup_factor(),
+            folding_factor,
+            remainder_max_degree,
+            self.num_queries(),
+        )
+        .with_merkle_cap_height(self.merkle_cap_height())
+        .with_grinding_factor(self.grinding_factor())
     }
 }
 
 impl<E: StarkField> ToElements<E> for ProofOptions {
     fn to_elements(&self) -> Vec<E> {
-        // encode field extension and FRI parameters into a single field element
-        let mut buf = self.field_extension as u32;
+        // encode Merkle cap height, field extension and FRI parameters into a single field element
+        let mut buf = self.merkle_cap_height as u32;
+        buf = (buf << 8) | self.field_extension as u32;
         buf = (buf << 8) | self.fri_folding_factor as u32;
         buf = (buf << 8) | self.fri_remainder_max_degree as u32;
 
-        vec![
+        // encode Merkle arity, low-degree test, FRI grinding factor and query seed grinding factor
+        // into a single field element
+        let mut grinding = self.merkle_arity as u32;
+        grinding = (grinding << 8) | self.low_degree_test as u32;
+        grinding = (grinding << 8) | self.fri_grinding_factor as u32;
+        grinding = (grinding << 8) | self.grinding_factor as u32;
+
+        let mut result = vec![
             E::from(buf),
-            E::from(self.grinding_factor),
+            E::from(grinding),
             E::from(self.blowup_factor),
             E::from(self.num_queries),
-        ]
+        ];
+
+        // encode FRI folding schedule as its length followed by the factors packed 4 per element
+        result.push(E::from(self.fri_folding_schedule.len() as u8));
+        for chunk in self.fri_folding_schedule.chunks(4) {
+            let buf = chunk.iter().fold(0u32, |acc, &f| (acc << 8) | f as u32);
+            result.push(E::from(buf));
+        }
+
+        result
     }
 }
 
@@ -233,6 +443,12 @@ impl Serializable for ProofOptions {
         target.write(self.field_extension);
         target.write_u8(self.fri_folding_factor);
         target.write_u8(self.fri_remainder_max_degree);
+        target.write_u8(self.merkle_cap_height);
+        target.write_u8(self.fri_folding_schedule.len() as u8);
+        target.write_bytes(&self.fri_folding_schedule);
+        target.write_u8(self.fri_grinding_factor);
+        target.write(self.low_degree_test);
+        target.write_u8(self.merkle_arity);
     }
 }
 
@@ -242,14 +458,65 @@ impl Deserializable for ProofOptions {
     /// # Errors
     /// Returns an error of a valid proof options could not be read from the specified `source`.
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        Ok(ProofOptions::new(
+        let options = ProofOptions::new(
             source.read_u8()? as usize,
             source.read_u8()? as usize,
             source.read_u8()? as u32,
             FieldExtension::read_from(source)?,
             source.read_u8()? as usize,
             source.read_u8()? as usize,
-        ))
+        );
+
+        let merkle_cap_height = source.read_u8()? as usize;
+        if merkle_cap_height > MAX_MERKLE_CAP_HEIGHT {
+            return Err(DeserializationError::InvalidValue(format!(
+                "Merkle cap height cannot be greater than {MAX_MERKLE_CAP_HEIGHT}, but was {merkle_cap_height}"
+            )));
+        }
+
+        let schedule_length = source.read_u8()? as usize;
+        if schedule_length > FRI_MAX_FOLDING_SCHEDULE_LENGTH {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI folding schedule cannot contain more than {FRI_MAX_FOLDING_SCHEDULE_LENGTH} factors, but was {schedule_length}"
+            )));
+        }
+        let mut fri_folding_schedule = Vec::with_capacity(schedule_length);
+        for _ in 0..schedule_length {
+            let folding_factor = source.read_u8()? as usize;
+            if !folding_factor.is_power_of_two()
+                || !(FRI_MIN_FOLDING_FACTOR..=FRI_MAX_FOLDING_FACTOR).contains(&folding_factor)
+            {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "FRI folding factor {folding_factor} is not supported"
+                )));
+            }
+            fri_folding_schedule.push(folding_factor);
+        }
+
+        let fri_grinding_factor = source.read_u8()? as u32;
+        if fri_grinding_factor > MAX_GRINDING_FACTOR {
+            return Err(DeserializationError::InvalidValue(format!(
+                "FRI grinding factor cannot be greater than {MAX_GRINDING_FACTOR}, but was {fri_grinding_factor}"
+            )));
+        }
+
+        let low_degree_test = LowDegreeTest::read_from(source)?;
+
+        let merkle_arity = source.read_u8()? as usize;
+        if !merkle_arity.is_power_of_two()
+            || !(MIN_MERKLE_ARITY..=MAX_MERKLE_ARITY).contains(&merkle_arity)
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "Merkle tree arity {merkle_arity} is not supported"
+            )));
+        }
+
+        Ok(options
+            .with_merkle_cap_height(merkle_cap_height)
+            .with_merkle_arity(merkle_arity)
+            .with_fri_folding_schedule(&fri_folding_schedule)
+            .with_fri_grinding_factor(fri_grinding_factor)
+            .with_low_degree_test(low_degree_test))
     }
 }
 
@@ -293,13 +560,37 @@ impl Deserializable for FieldExtension {
     }
 }
 
+// LOW-DEGREE TEST IMPLEMENTATION
+// ================================================================================================
+
+impl Serializable for LowDegreeTest {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for LowDegreeTest {
+    /// Reads a low-degree test enum from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(LowDegreeTest::Fri),
+            1 => Ok(LowDegreeTest::Stir),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as LowDegreeTest enum"
+            ))),
+        }
+    }
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
-    use super::{FieldExtension, ProofOptions, ToElements};
+    use super::{FieldExtension, LowDegreeTest, ProofOptions, ToElements};
     use math::fields::f64::BaseElement;
+    use utils::{Deserializable, Serializable, SliceReader};
 
     #[test]
     fn proof_options_to_elements() {
@@ -318,9 +609,10 @@ mod tests {
         ]);
         let expected = vec![
             BaseElement::from(ext_fri),
-            BaseElement::from(grinding_factor as u32),
+            BaseElement::from((2_u32 << 24) | grinding_factor), // Merkle arity and grinding factor
             BaseElement::from(blowup_factor as u32),
             BaseElement::from(num_queries as u32),
+            BaseElement::from(0_u8), // length of the FRI folding schedule
         ];
 
         let options = ProofOptions::new(
@@ -333,4 +625,133 @@ mod tests {
         );
         assert_eq!(expected, options.to_elements());
     }
+
+    #[test]
+    fn proof_options_with_merkle_cap() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert_eq!(0, options.merkle_cap_height());
+        assert_eq!(0, options.to_fri_options().merkle_cap_height());
+
+        let options = options.with_merkle_cap_height(4);
+        assert_eq!(4, options.merkle_cap_height());
+        assert_eq!(4, options.to_fri_options().merkle_cap_height());
+
+        // Merkle cap height is encoded in the most significant byte of the first element
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from(0x0402087f_u32), elements[0]);
+
+        let bytes = options.to_bytes();
+        let result = ProofOptions::read_from(&mut SliceReader::new(&bytes)).unwrap();
+        assert_eq!(options, result);
+    }
+
+    #[test]
+    fn proof_options_with_fri_folding_schedule() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 2, 127);
+        assert!(options.fri_folding_schedule().is_empty());
+
+        let options = options.with_fri_folding_schedule(&[16, 16, 8, 4, 2]);
+        assert_eq!(vec![16, 16, 8, 4, 2], options.fri_folding_schedule());
+
+        let fri_options = options.to_fri_options();
+        assert_eq!(16, fri_options.layer_folding_factor(0));
+        assert_eq!(4, fri_options.layer_folding_factor(3));
+        assert_eq!(2, fri_options.layer_folding_factor(5));
+
+        // the schedule is encoded as its length followed by the factors packed 4 per element
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(7, elements.len());
+        assert_eq!(BaseElement::from(5_u8), elements[4]);
+        assert_eq!(BaseElement::from(0x10100804_u32), elements[5]);
+        assert_eq!(BaseElement::from(2_u32), elements[6]);
+
+        let bytes = options.to_bytes();
+        let result = ProofOptions::read_from(&mut SliceReader::new(&bytes)).unwrap();
+        assert_eq!(options, result);
+
+        // unsupported folding factors are rejected during deserialization
+        let mut bytes = options.to_bytes();
+        let last_factor = bytes.len() - 4;
+        bytes[last_factor] = 32;
+        assert!(ProofOptions::read_from(&mut SliceReader::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn proof_options_with_fri_grinding_factor() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert_eq!(0, options.fri_grinding_factor());
+        assert_eq!(0, options.to_fri_options().folding_grinding_factor());
+
+        let options = options.with_fri_grinding_factor(12);
+        assert_eq!(12, options.fri_grinding_factor());
+        assert_eq!(12, options.to_fri_options().folding_grinding_factor());
+
+        // FRI grinding factor is encoded together with the query seed grinding factor
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(
+            BaseElement::from((2_u32 << 24) | (12 << 8) | 20),
+            elements[1]
+        );
+
+        let bytes = options.to_bytes();
+        let result = ProofOptions::read_from(&mut SliceReader::new(&bytes)).unwrap();
+        assert_eq!(options, result);
+    }
+
+    #[test]
+    fn proof_options_with_low_degree_test() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert_eq!(LowDegreeTest::Fri, options.low_degree_test());
+
+        let options = options.with_low_degree_test(LowDegreeTest::Stir);
+        assert_eq!(LowDegreeTest::Stir, options.low_degree_test());
+
+        let stir_options = options.to_stir_options();
+        assert_eq!(30, stir_options.num_queries());
+        assert_eq!(8, stir_options.folding_factor());
+        assert_eq!(127, stir_options.remainder_max_degree());
+        assert_eq!(20, stir_options.grinding_factor());
+
+        // low-degree test is encoded together with the grinding factors
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(
+            BaseElement::from((2_u32 << 24) | (1 << 16) | 20),
+            elements[1]
+        );
+
+        let bytes = options.to_bytes();
+        let result = ProofOptions::read_from(&mut SliceReader::new(&bytes)).unwrap();
+        assert_eq!(options, result);
+
+        // unknown low-degree tests are rejected during deserialization
+        let mut bytes = options.to_bytes();
+        let ldt_byte = bytes.len() - 2;
+        bytes[ldt_byte] = 2;
+        assert!(ProofOptions::read_from(&mut SliceReader::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn proof_options_with_merkle_arity() {
+        let options = ProofOptions::new(30, 8, 20, FieldExtension::Quadratic, 8, 127);
+        assert_eq!(2, options.merkle_arity());
+        assert_eq!(2, options.to_fri_options().merkle_arity());
+
+        let options = options.with_merkle_arity(4);
+        assert_eq!(4, options.merkle_arity());
+        assert_eq!(4, options.to_fri_options().merkle_arity());
+
+        // Merkle arity is encoded in the most significant byte of the second element
+        let elements: Vec<BaseElement> = options.to_elements();
+        assert_eq!(BaseElement::from((4_u32 << 24) | 20), elements[1]);
+
+        let bytes = options.to_bytes();
+        let result = ProofOptions::read_from(&mut SliceReader::new(&bytes)).unwrap();
+        assert_eq!(options, result);
+
+        // unsupported arities are rejected during deserialization
+        let mut bytes = options.to_bytes();
+        let last_byte = bytes.len() - 1;
+        bytes[last_byte] = 3;
+        assert!(ProofOptions::read_from(&mut SliceReader::new(&bytes)).is_err());
+    }
 }
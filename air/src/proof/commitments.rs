@@ -3,12 +3,19 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crypto::Hasher;
+use crypto::{CommitmentHashers, Hasher};
 use utils::{
     collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
     SliceReader,
 };
 
+// TYPE ALIASES
+// ================================================================================================
+
+type TraceDigest<H> = <<H as CommitmentHashers>::TraceHasher as Hasher>::Digest;
+type ConstraintDigest<H> = <<H as CommitmentHashers>::ConstraintHasher as Hasher>::Digest;
+type FriDigest<H> = <<H as CommitmentHashers>::FriHasher as Hasher>::Digest;
+
 // COMMITMENTS
 // ================================================================================================
 /// Commitments made by the prover during commit phase of the protocol.
@@ -19,6 +26,10 @@ use utils::{
 /// * Commitment to the evaluations of constraint composition polynomial over LDE domain.
 /// * Commitments to the evaluations of polynomials at all FRI layers.
 ///
+/// Each commitment is a Merkle tree cap (i.e., all 2^h nodes at depth `h` of the tree); a cap of
+/// height 0 consists of the tree root only. Since a FRI remainder is committed to directly, its
+/// commitment is always a single digest.
+///
 /// Internally, the commitments are stored as a sequence of bytes. Thus, to retrieve the
 /// commitments, [parse()](Commitments::parse) function should be used.
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
@@ -28,24 +39,43 @@ impl Commitments {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Returns a new Commitments struct initialized with the provided commitments.
-    pub fn new<H: Hasher>(
-        trace_roots: Vec<H::Digest>,
-        constraint_root: H::Digest,
-        fri_roots: Vec<H::Digest>,
+    ///
+    /// Trace, constraint, and FRI layer commitments are expected to be built using the respective
+    /// hash functions specified by `H`.
+    ///
+    /// # Panics
+    /// Panics if the number of nodes in any of the caps is not a power of two.
+    pub fn new<H: CommitmentHashers>(
+        trace_caps: Vec<Vec<TraceDigest<H>>>,
+        constraint_cap: Vec<ConstraintDigest<H>>,
+        fri_caps: Vec<Vec<FriDigest<H>>>,
     ) -> Self {
-        let mut bytes = Vec::new();
-        bytes.write(trace_roots);
-        bytes.write(constraint_root);
-        bytes.write(fri_roots);
-        Commitments(bytes)
+        let mut commitments = Commitments::default();
+        for trace_cap in trace_caps.iter() {
+            commitments.add::<H::TraceHasher>(trace_cap);
+        }
+        commitments.add::<H::ConstraintHasher>(&constraint_cap);
+        for fri_cap in fri_caps.iter() {
+            commitments.add::<H::FriHasher>(fri_cap);
+        }
+        commitments
     }
 
     // PUBLIC METHODS
     // --------------------------------------------------------------------------------------------
 
-    /// Adds the specified commitment to the list of commitments.
-    pub fn add<H: Hasher>(&mut self, commitment: &H::Digest) {
-        commitment.write_into(&mut self.0);
+    /// Adds the specified commitment (a Merkle tree cap) to the list of commitments.
+    ///
+    /// # Panics
+    /// Panics if the number of nodes in the `commitment` is not a power of two.
+    pub fn add<H: Hasher>(&mut self, commitment: &[H::Digest]) {
+        assert!(
+            commitment.len().is_power_of_two(),
+            "number of nodes in a Merkle tree cap must be a power of two, but was {}",
+            commitment.len()
+        );
+        self.0.write_u8(commitment.len().ilog2() as u8);
+        self.0.write(commitment);
     }
 
     // PARSING
@@ -58,25 +88,41 @@ impl Commitments {
     /// 2. Constraint composition polynomial evaluation commitment.
     /// 3. FRI layer commitments.
     ///
+    /// When STIR is used as the low-degree test, the last part contains STIR oracle commitments
+    /// instead, and `num_fri_layers` should be set to the number of STIR rounds.
+    ///
+    /// Each part is parsed into digests of the respective hash function specified by `H`.
+    ///
     /// # Errors
     /// Returns an error if the bytes stored in self could not be parsed into the requested number
     /// of commitments, or if there are any unconsumed bytes remaining after the parsing completes.
     #[allow(clippy::type_complexity)]
-    pub fn parse<H: Hasher>(
+    pub fn parse<H: CommitmentHashers>(
         self,
         num_trace_segments: usize,
         num_fri_layers: usize,
-    ) -> Result<(Vec<H::Digest>, H::Digest, Vec<H::Digest>), DeserializationError> {
+    ) -> Result<
+        (
+            Vec<Vec<TraceDigest<H>>>,
+            Vec<ConstraintDigest<H>>,
+            Vec<Vec<FriDigest<H>>>,
+        ),
+        DeserializationError,
+    > {
         let mut reader = SliceReader::new(&self.0);
 
         // parse trace commitments
-        let trace_commitments = H::Digest::read_batch_from(&mut reader, num_trace_segments)?;
+        let trace_commitments = (0..num_trace_segments)
+            .map(|_| read_cap::<H::TraceHasher>(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // parse constraint evaluation commitment:
-        let constraint_commitment = H::Digest::read_from(&mut reader)?;
+        let constraint_commitment = read_cap::<H::ConstraintHasher>(&mut reader)?;
 
         // read FRI commitments (+ 1 for remainder polynomial commitment)
-        let fri_commitments = H::Digest::read_batch_from(&mut reader, num_fri_layers + 1)?;
+        let fri_commitments = (0..num_fri_layers + 1)
+            .map(|_| read_cap::<H::FriHasher>(&mut reader))
+            .collect::<Result<Vec<_>, _>>()?;
 
         // make sure we consumed all available commitment bytes
         if reader.has_more_bytes() {
@@ -107,3 +153,20 @@ impl Deserializable for Commitments {
         Ok(Commitments(result))
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads a single Merkle tree cap from the specified `source`.
+///
+/// The cap is expected to be encoded as its height (a single byte) followed by 2^height digests.
+fn read_cap<H: Hasher>(source: &mut SliceReader) -> Result<Vec<H::Digest>, DeserializationError> {
+    let cap_height = source.read_u8()? as u32;
+    if cap_height >= u16::BITS {
+        return Err(DeserializationError::InvalidValue(format!(
+            "Merkle cap height cannot be greater than {}, but was {cap_height}",
+            u16::BITS - 1
+        )));
+    }
+    H::Digest::read_batch_from(source, 1 << cap_height)
+}
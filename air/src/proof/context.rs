@@ -15,6 +15,7 @@ use utils::{
 /// Basic metadata about a specific execution of a computation.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Context {
+    domain_separator: Vec<u8>,
     trace_layout: TraceLayout,
     trace_length: usize,
     trace_meta: Vec<u8>,
@@ -27,8 +28,12 @@ impl Context {
     // --------------------------------------------------------------------------------------------
     /// Creates a new context for a computation described by the specified field, trace info, and
     /// proof options.
+    ///
+    /// The domain separator of the returned context is empty; it can be set via
+    /// [Context::with_domain_separator()].
     pub fn new<B: StarkField>(trace_info: &TraceInfo, options: ProofOptions) -> Self {
         Context {
+            domain_separator: Vec::new(),
             trace_layout: trace_info.layout().clone(),
             trace_length: trace_info.length(),
             trace_meta: trace_info.meta().to_vec(),
@@ -37,9 +42,32 @@ impl Context {
         }
     }
 
+    /// Sets the domain separator of this context to the specified tag.
+    ///
+    /// The tag identifies the computation described by this context (see
+    /// [Air::domain_separator()](crate::Air::domain_separator)).
+    ///
+    /// # Panics
+    /// Panics if the tag is longer than 255 bytes.
+    pub fn with_domain_separator(mut self, tag: &[u8]) -> Self {
+        assert!(
+            tag.len() <= u8::MAX as usize,
+            "domain separator cannot be longer than {} bytes, but was {} bytes",
+            u8::MAX,
+            tag.len()
+        );
+        self.domain_separator = tag.to_vec();
+        self
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns a tag which identifies the computation described by this context.
+    pub fn domain_separator(&self) -> &[u8] {
+        &self.domain_separator
+    }
+
     /// Returns a layout describing how columns of the execution trace described by this context
     /// are arranged into segments.
     pub fn trace_layout(&self) -> &TraceLayout {
@@ -97,17 +125,28 @@ impl<E: StarkField> ToElements<E> for Context {
     /// Converts this [Context] into a vector of field elements.
     ///
     /// The elements are layed out as follows:
+    /// - domain separator length [1 element].
+    /// - domain separator [0 or more elements].
     /// - trace layout info [1 or more elements].
     /// - field modulus bytes [2 field elements].
     /// - field extension and FRI parameters [1 element].
     /// - grinding factor [1 element].
     /// - blowup factor [1 element].
     /// - number of queries [1 element].
+    /// - FRI folding schedule length [1 element].
+    /// - FRI folding schedule [0 or more elements].
     /// - trace length [1 element].
     /// - trace metadata [0 or more elements].
     fn to_elements(&self) -> Vec<E> {
+        // convert domain separator; the length of the separator is included so that the
+        // separator cannot be confused with the elements which follow it
+        let mut result = vec![E::from(self.domain_separator.len() as u8)];
+        for chunk in self.domain_separator.chunks(E::ELEMENT_BYTES - 1) {
+            result.push(bytes_to_element(chunk));
+        }
+
         // convert trace layout
-        let mut result = self.trace_layout.to_elements();
+        result.append(&mut self.trace_layout.to_elements());
 
         // convert field modulus bytes into 2 elements
         let num_modulus_bytes = self.field_modulus_bytes.len();
@@ -135,6 +174,8 @@ impl<E: StarkField> ToElements<E> for Context {
 impl Serializable for Context {
     /// Serializes `self` and writes the resulting bytes into the `target`.
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.domain_separator.len() as u8);
+        target.write_bytes(&self.domain_separator);
         self.trace_layout.write_into(target);
         target.write_u8(self.trace_length.ilog2() as u8); // store as power of two
         target.write_u16(self.trace_meta.len() as u16);
@@ -152,6 +193,10 @@ impl Deserializable for Context {
     /// # Errors
     /// Returns an error of a valid Context struct could not be read from the specified `source`.
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read domain separator
+        let num_separator_bytes = source.read_u8()? as usize;
+        let domain_separator = source.read_vec(num_separator_bytes)?;
+
         // read and validate trace layout info
         let trace_layout = TraceLayout::read_from(source)?;
 
@@ -187,6 +232,7 @@ impl Deserializable for Context {
         let options = ProofOptions::read_from(source)?;
 
         Ok(Context {
+            domain_separator,
             trace_layout,
             trace_length,
             trace_meta,
@@ -224,6 +270,7 @@ mod tests {
     use super::{Context, ProofOptions, ToElements, TraceInfo};
     use crate::{FieldExtension, TraceLayout};
     use math::fields::f64::BaseElement;
+    use utils::{Deserializable, Serializable, SliceReader};
 
     #[test]
     fn context_to_elements() {
@@ -250,13 +297,15 @@ mod tests {
         let layout_info = u32::from_le_bytes([aux_rands, aux_width, num_aux_segments, main_width]);
 
         let expected = vec![
+            BaseElement::from(0_u8), // length of the domain separator
             BaseElement::from(layout_info),
             BaseElement::from(1_u32),    // lower bits of field modulus
             BaseElement::from(u32::MAX), // upper bits of field modulus
             BaseElement::from(ext_fri),
-            BaseElement::from(grinding_factor as u32),
+            BaseElement::from((2_u32 << 24) | grinding_factor), // Merkle arity and grinding factor
             BaseElement::from(blowup_factor as u32),
             BaseElement::from(num_queries as u32),
+            BaseElement::from(0_u8), // length of the FRI folding schedule
             BaseElement::from(trace_length as u32),
         ];
 
@@ -277,4 +326,26 @@ mod tests {
         let context = Context::new::<BaseElement>(&trace_info, options);
         assert_eq!(expected, context.to_elements());
     }
+
+    #[test]
+    fn context_domain_separator() {
+        let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 31);
+        let trace_info = TraceInfo::new(4, 1024);
+        let context = Context::new::<BaseElement>(&trace_info, options.clone());
+        let tag = b"winterfell/test/domain-separator";
+        let tagged = Context::new::<BaseElement>(&trace_info, options).with_domain_separator(tag);
+        assert_eq!(tag.as_slice(), tagged.domain_separator());
+
+        // the tag is encoded as its length followed by 7-byte chunks of the tag
+        let elements: Vec<BaseElement> = context.to_elements();
+        let tagged_elements: Vec<BaseElement> = tagged.to_elements();
+        assert_eq!(BaseElement::from(tag.len() as u8), tagged_elements[0]);
+        assert_eq!(elements.len() + 5, tagged_elements.len());
+        assert_eq!(elements[1..], tagged_elements[6..]);
+
+        // the tag is preserved during serialization
+        let bytes = tagged.to_bytes();
+        let mut reader = SliceReader::new(&bytes);
+        assert_eq!(tagged, Context::read_from(&mut reader).unwrap());
+    }
 }
@@ -0,0 +1,75 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::LowDegreeTest;
+use fri::FriProof;
+use stir::StirProof;
+use utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// LOW-DEGREE PROOF
+// ================================================================================================
+/// Low-degree proof for a DEEP composition polynomial.
+///
+/// The variant of the proof is determined by the [LowDegreeTest] specified in the proof options.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum LowDegreeProof {
+    /// Proof generated by the FRI protocol.
+    Fri(FriProof),
+    /// Proof generated by the STIR protocol.
+    Stir(StirProof),
+}
+
+impl LowDegreeProof {
+    /// Returns the low-degree test which was used to generate this proof.
+    pub fn low_degree_test(&self) -> LowDegreeTest {
+        match self {
+            Self::Fri(_) => LowDegreeTest::Fri,
+            Self::Stir(_) => LowDegreeTest::Stir,
+        }
+    }
+
+    /// Returns the underlying FRI proof, or None if this proof was not generated by FRI.
+    pub fn as_fri(&self) -> Option<&FriProof> {
+        match self {
+            Self::Fri(proof) => Some(proof),
+            Self::Stir(_) => None,
+        }
+    }
+
+    /// Returns the underlying STIR proof, or None if this proof was not generated by STIR.
+    pub fn as_stir(&self) -> Option<&StirProof> {
+        match self {
+            Self::Fri(_) => None,
+            Self::Stir(proof) => Some(proof),
+        }
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for LowDegreeProof {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.low_degree_test().write_into(target);
+        match self {
+            Self::Fri(proof) => proof.write_into(target),
+            Self::Stir(proof) => proof.write_into(target),
+        }
+    }
+}
+
+impl Deserializable for LowDegreeProof {
+    /// Reads a low-degree proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error of a valid low-degree proof could not be read from the specified `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match LowDegreeTest::read_from(source)? {
+            LowDegreeTest::Fri => Ok(Self::Fri(FriProof::read_from(source)?)),
+            LowDegreeTest::Stir => Ok(Self::Stir(StirProof::read_from(source)?)),
+        }
+    }
+}
@@ -5,10 +5,9 @@
 
 //! Contains STARK proof struct and associated components.
 
-use crate::{ProofOptions, TraceInfo, TraceLayout};
+use crate::{LowDegreeTest, ProofOptions, TraceInfo, TraceLayout};
 use core::cmp;
-use crypto::Hasher;
-use fri::FriProof;
+use crypto::{CommitmentHashers, RandomCoin};
 use utils::{
     collections::Vec, ByteReader, Deserializable, DeserializationError, Serializable, SliceReader,
 };
@@ -28,11 +27,18 @@ pub use ood_frame::OodFrame;
 mod table;
 pub use table::Table;
 
+mod low_degree;
+pub use low_degree::LowDegreeProof;
+
 // CONSTANTS
 // ================================================================================================
 
 const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 
+/// Version of the proof format; this is incremented whenever the proof format, or the way in
+/// which the proof is generated from the transcript, changes in an incompatible way.
+pub const PROOF_VERSION: u8 = 1;
+
 // STARK PROOF
 // ================================================================================================
 /// A proof generated by Winterfell prover.
@@ -43,7 +49,8 @@ const GRINDING_CONTRIBUTION_FLOOR: u32 = 80;
 ///
 /// A proof can be serialized into a sequence of bytes using [to_bytes()](StarkProof::to_bytes)
 /// function, and deserialized from a sequence of bytes using [from_bytes()](StarkProof::from_bytes)
-/// function.
+/// function. Serialized proofs start with the version of the proof format ([PROOF_VERSION]), and
+/// proofs of other versions cannot be deserialized.
 ///
 /// To estimate soundness of a proof (in bits), [security_level()](StarkProof::security_level)
 /// function can be used.
@@ -62,7 +69,7 @@ pub struct StarkProof {
     /// Trace and constraint polynomial evaluations at an out-of-domain point.
     pub ood_frame: OodFrame,
     /// Low-degree proof for a DEEP composition polynomial.
-    pub fri_proof: FriProof,
+    pub low_degree_proof: LowDegreeProof,
     /// Proof-of-work nonce for query seed grinding.
     pub pow_nonce: u64,
 }
@@ -102,13 +109,18 @@ impl StarkProof {
     /// security level is returned. Usually, the number of queries needed for provable security is
     /// 2x - 3x higher than the number of queries needed for conjectured security at the same
     /// security level.
-    pub fn security_level<H: Hasher>(&self, conjectured: bool) -> u32 {
+    ///
+    /// The hash functions used to build the proof are specified via the `H` type parameter, and the
+    /// random coin used to draw challenges is specified via the `R` type parameter; the security
+    /// level is limited by the weakest of the hash functions used for trace, constraint, and FRI
+    /// commitments and by the random coin.
+    pub fn security_level<H: CommitmentHashers, R: RandomCoin>(&self, conjectured: bool) -> u32 {
         if conjectured {
             get_conjectured_security(
                 self.context.options(),
                 self.context.num_modulus_bits(),
                 self.trace_length() as u64,
-                H::COLLISION_RESISTANCE,
+                H::collision_resistance::<R>(),
             )
         } else {
             #[cfg(not(feature = "std"))]
@@ -119,7 +131,7 @@ impl StarkProof {
                 self.context.options(),
                 self.context.num_modulus_bits(),
                 self.lde_domain_size() as u64,
-                H::COLLISION_RESISTANCE,
+                H::collision_resistance::<R>(),
             )
         }
     }
@@ -129,13 +141,13 @@ impl StarkProof {
 
     /// Serializes this proof into a vector of bytes.
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut result = Vec::new();
+        let mut result = vec![PROOF_VERSION];
         self.context.write_into(&mut result);
         self.commitments.write_into(&mut result);
         self.trace_queries.write_into(&mut result);
         self.constraint_queries.write_into(&mut result);
         self.ood_frame.write_into(&mut result);
-        self.fri_proof.write_into(&mut result);
+        self.low_degree_proof.write_into(&mut result);
         result.extend_from_slice(&self.pow_nonce.to_le_bytes());
         result
     }
@@ -147,6 +159,14 @@ impl StarkProof {
     pub fn from_bytes(source: &[u8]) -> Result<Self, DeserializationError> {
         let mut source = SliceReader::new(source);
 
+        // make sure the proof was serialized using the current version of the proof format
+        let version = source.read_u8()?;
+        if version != PROOF_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "proof version {PROOF_VERSION} is supported, but the proof has version {version}"
+            )));
+        }
+
         // parse the context
         let context = Context::read_from(&mut source)?;
 
@@ -167,9 +187,18 @@ impl StarkProof {
             trace_queries,
             constraint_queries: Queries::read_from(&mut source)?,
             ood_frame: OodFrame::read_from(&mut source)?,
-            fri_proof: FriProof::read_from(&mut source)?,
+            low_degree_proof: LowDegreeProof::read_from(&mut source)?,
             pow_nonce: source.read_u64()?,
         };
+
+        // make sure the low-degree proof was generated by the protocol specified in the options
+        let low_degree_test = proof.options().low_degree_test();
+        if proof.low_degree_proof.low_degree_test() != low_degree_test {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected low-degree proof generated by {low_degree_test:?}, but the proof was generated by {:?}",
+                proof.low_degree_proof.low_degree_test()
+            )));
+        }
         if source.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
@@ -187,18 +216,44 @@ fn get_conjectured_security(
     trace_domain_size: u64,
     collision_resistance: u32,
 ) -> u32 {
-    // compute max security we can get for a given field size
+    // compute max security we can get for a given field size; soundness of the ALI and DEEP
+    // composition steps is limited by the size of the trace domain
     let field_size = base_field_bits * options.field_extension().degree();
-    let field_security = field_size - trace_domain_size.trailing_zeros();
-
-    // compute security we get by executing multiple query rounds
-    let security_per_query = options.blowup_factor().ilog2();
-    let mut query_security = security_per_query * options.num_queries() as u32;
-
-    // include grinding factor contributions only for proofs adequate security
-    if query_security >= GRINDING_CONTRIBUTION_FLOOR {
-        query_security += options.grinding_factor();
-    }
+    let ali_deep_security = field_size - trace_domain_size.trailing_zeros();
+
+    // soundness of the commit phase of the low-degree test is limited by the size of the LDE
+    // domain; grinding before each folding challenge makes resampling of the challenges more
+    // expensive, and thus, is credited only to this term
+    let lde_domain_size = trace_domain_size * options.blowup_factor() as u64;
+    let folding_security =
+        field_size - lde_domain_size.trailing_zeros() + options.fri_grinding_factor();
+    let field_security = cmp::min(ali_deep_security, folding_security);
+
+    let query_security = match options.low_degree_test() {
+        LowDegreeTest::Fri => {
+            // compute security we get by executing multiple query rounds
+            let security_per_query = options.blowup_factor().ilog2();
+            let query_security = security_per_query * options.num_queries() as u32;
+            add_grinding_contribution(query_security, options.grinding_factor())
+        }
+        LowDegreeTest::Stir => {
+            // in STIR, queries are made in every round, and the rate of the queried oracle
+            // improves from round to round; query security is limited by the weakest round
+            let stir_options = options.to_stir_options();
+            let lde_domain_size = trace_domain_size as usize * options.blowup_factor();
+            (0..=stir_options.num_rounds(lde_domain_size))
+                .map(|round| {
+                    let security_per_query = stir_options.round_rate_bits(round);
+                    let num_queries = stir_options.num_round_queries(round) as u32;
+                    add_grinding_contribution(
+                        security_per_query * num_queries,
+                        options.grinding_factor(),
+                    )
+                })
+                .min()
+                .expect("at least one STIR query round")
+        }
+    };
 
     cmp::min(
         cmp::min(field_security, query_security) - 1,
@@ -214,6 +269,15 @@ fn get_proven_security(
     lde_domain_size: u64,
     collision_resistance: u32,
 ) -> u32 {
+    if options.low_degree_test() == LowDegreeTest::Stir {
+        return get_proven_stir_security(
+            options,
+            base_field_bits,
+            lde_domain_size,
+            collision_resistance,
+        );
+    }
+
     let extension_field_bits = (base_field_bits * options.field_extension().degree()) as f64;
     let blowup_bits = options.blowup_factor().ilog2() as f64;
     let num_fri_queries = options.num_queries() as f64;
@@ -254,3 +318,82 @@ fn get_proven_security(
         collision_resistance,
     )
 }
+
+#[cfg(feature = "std")]
+/// Estimates proven security level for the specified proof parameters when STIR is used as the
+/// low-degree test.
+///
+/// This is a conservative estimate based on the unique decoding regime: a query to an oracle of
+/// rate ρ which is δ-far from the code with δ = (1 - ρ) / 2 is rejected with probability at least
+/// δ, and thus, each query contributes -log2((1 + ρ) / 2) bits of security. Errors introduced by
+/// folding, combination, and out-of-domain challenges are bounded by the size of the evaluation
+/// domain relative to the size of the field, summed over all rounds.
+fn get_proven_stir_security(
+    options: &ProofOptions,
+    base_field_bits: u32,
+    lde_domain_size: u64,
+    collision_resistance: u32,
+) -> u32 {
+    let extension_field_bits = (base_field_bits * options.field_extension().degree()) as f64;
+    let lde_size_bits = lde_domain_size.trailing_zeros() as f64;
+    let stir_options = options.to_stir_options();
+    let num_rounds = stir_options.num_rounds(lde_domain_size as usize);
+
+    // compute pre-query security; this accounts for the degree of out-of-domain and combination
+    // polynomials in every round
+    let pre_query_security =
+        (extension_field_bits - 2.0 * lde_size_bits - ((num_rounds + 1) as f64).log2()) as u32;
+
+    // compute security we get by executing queries in every round; this is limited by the
+    // weakest round
+    let query_security = (0..=num_rounds)
+        .map(|round| {
+            let rate = 2.0_f64.powi(-(stir_options.round_rate_bits(round) as i32));
+            let security_per_query = -((1.0 + rate) / 2.0).log2();
+            let num_queries = stir_options.num_round_queries(round) as f64;
+            (security_per_query * num_queries) as u32 + options.grinding_factor()
+        })
+        .min()
+        .expect("at least one STIR query round");
+
+    cmp::min(
+        cmp::min(pre_query_security, query_security) - 1,
+        collision_resistance,
+    )
+}
+
+/// Adds query seed grinding contribution to the specified query security; grinding is taken into
+/// account only for proofs which already have adequate query security.
+fn add_grinding_contribution(query_security: u32, grinding_factor: u32) -> u32 {
+    if query_security >= GRINDING_CONTRIBUTION_FLOOR {
+        query_security + grinding_factor
+    } else {
+        query_security
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::get_conjectured_security;
+    use crate::{FieldExtension, ProofOptions};
+
+    #[test]
+    fn conjectured_security_with_fri_grinding() {
+        // with 64-bit base field, quadratic extension, trace length 2^20 and blowup factor 8,
+        // ALI/DEEP soundness is limited to 108 bits, and FRI commit phase soundness to 105 bits
+        let options = ProofOptions::new(64, 8, 0, FieldExtension::Quadratic, 4, 31);
+        let security = |options: &ProofOptions| get_conjectured_security(options, 64, 1 << 20, 128);
+        assert_eq!(104, security(&options));
+
+        // grinding raises security while the FRI commit phase is the binding term
+        assert_eq!(106, security(&options.clone().with_fri_grinding_factor(2)));
+        assert_eq!(107, security(&options.clone().with_fri_grinding_factor(3)));
+
+        // once ALI/DEEP is the binding term, more grinding does not raise security
+        assert_eq!(107, security(&options.clone().with_fri_grinding_factor(4)));
+        assert_eq!(107, security(&options.with_fri_grinding_factor(16)));
+    }
+}
@@ -84,6 +84,13 @@ impl Queries {
     /// Convert internally stored bytes into a set of query values and the corresponding Merkle
     /// authentication paths.
     ///
+    /// The authentication paths are expected to be for a Merkle tree with the specified arity
+    /// and one leaf per element of the domain.
+    ///
+    /// # Errors
+    /// Returns an error if `domain_size` is not a power of `merkle_arity`, or if the stored bytes
+    /// could not be parsed into the expected number of queries and a valid batch Merkle proof.
+    ///
     /// # Panics
     /// Panics if:
     /// * `domain_size` is not a power of two.
@@ -94,6 +101,7 @@ impl Queries {
         domain_size: usize,
         num_queries: usize,
         values_per_query: usize,
+        merkle_arity: usize,
     ) -> Result<(BatchMerkleProof<H>, Table<E>), DeserializationError>
     where
         E: FieldElement,
@@ -129,9 +137,17 @@ impl Queries {
             .collect();
 
         // build batch Merkle proof
+        if !matches!(merkle_arity, 2 | 4 | 8 | 16)
+            || domain_size.ilog2() % merkle_arity.ilog2() != 0
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "domain of size {domain_size} cannot be committed to by a Merkle tree of arity {merkle_arity}"
+            )));
+        }
         let mut reader = SliceReader::new(&self.paths);
-        let tree_depth = domain_size.ilog2() as u8;
-        let merkle_proof = BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth)?;
+        let tree_depth = (domain_size.ilog2() / merkle_arity.ilog2()) as u8;
+        let merkle_proof =
+            BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth, merkle_arity)?;
         if reader.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
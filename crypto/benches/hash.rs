@@ -7,7 +7,10 @@ use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion
 use math::fields::f128;
 use rand_utils::rand_value;
 use winter_crypto::{
-    hashers::{Blake3_256, GriffinJive64_256, Rp62_248, Rp64_256, RpJive64_256, Sha3_256},
+    hashers::{
+        Blake3_256, GriffinJive64_256, Poseidon2Jive64_256, Poseidon2_64_256, Rp62_248, Rp64_256,
+        RpJive64_256, Sha3_256,
+    },
     Hasher,
 };
 
@@ -21,6 +24,8 @@ type Rp62_248Digest = <Rp62_248 as Hasher>::Digest;
 type Rp64_256Digest = <Rp64_256 as Hasher>::Digest;
 type RpJive64_256Digest = <RpJive64_256 as Hasher>::Digest;
 type GriffinJive64_256Digest = <GriffinJive64_256 as Hasher>::Digest;
+type Poseidon2_64_256Digest = <Poseidon2_64_256 as Hasher>::Digest;
+type Poseidon2Jive64_256Digest = <Poseidon2Jive64_256 as Hasher>::Digest;
 
 fn blake3(c: &mut Criterion) {
     let v: [Blake3Digest; 2] = [Blake3::hash(&[1u8]), Blake3::hash(&[2u8])];
@@ -145,6 +150,52 @@ fn griffin_jive256(c: &mut Criterion) {
     });
 }
 
+fn poseidon2_256(c: &mut Criterion) {
+    let v: [Poseidon2_64_256Digest; 2] = [
+        Poseidon2_64_256::hash(&[1u8]),
+        Poseidon2_64_256::hash(&[2u8]),
+    ];
+    c.bench_function("hash_poseidon2_64_256 (cached)", |bench| {
+        bench.iter(|| Poseidon2_64_256::merge(black_box(&v)))
+    });
+
+    c.bench_function("hash_poseidon2_64_256 (random)", |b| {
+        b.iter_batched(
+            || {
+                [
+                    Poseidon2_64_256::hash(&rand_value::<u64>().to_le_bytes()),
+                    Poseidon2_64_256::hash(&rand_value::<u64>().to_le_bytes()),
+                ]
+            },
+            |state| Poseidon2_64_256::merge(&state),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn poseidon2_jive256(c: &mut Criterion) {
+    let v: [Poseidon2Jive64_256Digest; 2] = [
+        Poseidon2Jive64_256::hash(&[1u8]),
+        Poseidon2Jive64_256::hash(&[2u8]),
+    ];
+    c.bench_function("hash_poseidon2_jive64_256 (cached)", |bench| {
+        bench.iter(|| Poseidon2Jive64_256::merge(black_box(&v)))
+    });
+
+    c.bench_function("hash_poseidon2_jive64_256 (random)", |b| {
+        b.iter_batched(
+            || {
+                [
+                    Poseidon2Jive64_256::hash(&rand_value::<u64>().to_le_bytes()),
+                    Poseidon2Jive64_256::hash(&rand_value::<u64>().to_le_bytes()),
+                ]
+            },
+            |state| Poseidon2Jive64_256::merge(&state),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
 criterion_group!(
     hash_group,
     blake3,
@@ -153,5 +204,7 @@ criterion_group!(
     rescue256,
     rescue_jive256,
     griffin_jive256,
+    poseidon2_256,
+    poseidon2_jive256,
 );
 criterion_main!(hash_group);
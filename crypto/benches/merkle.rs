@@ -7,7 +7,9 @@ use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use math::fields::f128::BaseElement;
 use rand_utils::rand_value;
 use utils::uninit_vector;
-use winter_crypto::{build_merkle_nodes, concurrent, hashers::Blake3_256, Hasher};
+use winter_crypto::{
+    build_merkle_nodes, build_nary_merkle_nodes, concurrent, hashers::Blake3_256, Hasher,
+};
 
 type Blake3 = Blake3_256<BaseElement>;
 type Blake3Digest = <Blake3 as Hasher>::Digest;
@@ -16,6 +18,7 @@ pub fn merkle_tree_construction(c: &mut Criterion) {
     let mut merkle_group = c.benchmark_group("merkle tree construction");
 
     static BATCH_SIZES: [usize; 3] = [65536, 131072, 262144];
+    static BATCH_SIZES_4: [usize; 2] = [65536, 262144];
 
     for size in &BATCH_SIZES {
         let data: Vec<Blake3Digest> = {
@@ -32,6 +35,18 @@ pub fn merkle_tree_construction(c: &mut Criterion) {
             b.iter(|| concurrent::build_merkle_nodes::<Blake3>(&i))
         });
     }
+
+    for size in &BATCH_SIZES_4 {
+        let data: Vec<Blake3Digest> = (0..*size)
+            .map(|_| Blake3::hash(&rand_value::<u128>().to_le_bytes()))
+            .collect();
+        merkle_group.bench_with_input(BenchmarkId::new("sequential 4-ary", size), &data, |b, i| {
+            b.iter(|| build_nary_merkle_nodes::<Blake3>(&i, 4))
+        });
+        merkle_group.bench_with_input(BenchmarkId::new("concurrent 4-ary", size), &data, |b, i| {
+            b.iter(|| concurrent::build_nary_merkle_nodes::<Blake3>(&i, 4))
+        });
+    }
 }
 
 criterion_group!(merkle_group, merkle_tree_construction,);
@@ -11,10 +11,12 @@ use core::fmt;
 /// Defines errors which can occur when using Merkle trees.
 #[derive(Debug, PartialEq, Eq)]
 pub enum MerkleTreeError {
-    /// Fewer than two leaves were used to construct a Merkle tree.
+    /// Fewer leaves than the tree arity were used to construct a Merkle tree.
     TooFewLeaves(usize, usize),
     /// Number of leaves for a Merkle tree was not a power of two.
     NumberOfLeavesNotPowerOfTwo(usize),
+    /// Number of leaves for a Merkle tree was not a power of the tree arity.
+    NumberOfLeavesNotPowerOfArity(usize, usize),
     /// A leaf index was greater than or equal to the number of leaves in the tree.
     LeafIndexOutOfBounds(usize, usize),
     /// A leaf index was included more than once in the list of indexes for a batch proof.
@@ -25,6 +27,12 @@ pub enum MerkleTreeError {
     TooManyLeafIndexes(usize, usize),
     /// Merkle proof is not valid for the specified position(s).
     InvalidProof,
+    /// Height of a Merkle tree cap was greater than the max height supported by the tree.
+    InvalidCapHeight(usize, usize),
+    /// Number of nodes in a Merkle tree cap was not a power of the tree arity.
+    InvalidNumberOfCapNodes(usize, usize),
+    /// Arity of a Merkle tree was not one of the supported values.
+    UnsupportedArity(usize),
 }
 
 impl fmt::Display for MerkleTreeError {
@@ -42,6 +50,12 @@ impl fmt::Display for MerkleTreeError {
                     "number of leaves must be a power of two, but {num_leaves} were provided"
                 )
             }
+            Self::NumberOfLeavesNotPowerOfArity(arity, num_leaves) => {
+                write!(
+                    f,
+                    "number of leaves must be a power of {arity}, but {num_leaves} were provided"
+                )
+            }
             Self::LeafIndexOutOfBounds(expected, actual) => {
                 write!(f, "a leaf index cannot exceed {expected}, but was {actual}")
             }
@@ -60,6 +74,24 @@ impl fmt::Display for MerkleTreeError {
             Self::InvalidProof => {
                 write!(f, "Merkle proof is invalid")
             }
+            Self::InvalidCapHeight(max_height, height) => {
+                write!(
+                    f,
+                    "Merkle tree cap height cannot exceed {max_height}, but was {height}"
+                )
+            }
+            Self::InvalidNumberOfCapNodes(arity, num_nodes) => {
+                write!(
+                    f,
+                    "number of nodes in a Merkle tree cap must be a power of {arity}, but was {num_nodes}"
+                )
+            }
+            Self::UnsupportedArity(arity) => {
+                write!(
+                    f,
+                    "Merkle tree arity must be 2, 4, 8, or 16, but was {arity}"
+                )
+            }
         }
     }
 }
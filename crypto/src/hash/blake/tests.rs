@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{Blake3_256, ElementHasher, Hasher};
+use super::{Blake3_192, Blake3_256, ElementHasher, Hasher};
 use math::{fields::f62::BaseElement, FieldElement};
 use rand_utils::rand_array;
 
@@ -28,3 +28,26 @@ fn hash_elements_padding() {
     let r2 = Blake3_256::hash_elements(&e2);
     assert_ne!(r1, r2);
 }
+
+#[test]
+fn merge_with_int_batch() {
+    let seed = Blake3_256::<BaseElement>::hash(&[1_u8, 2, 3]);
+    let mut result = [Default::default(); 5];
+    Blake3_256::<BaseElement>::merge_with_int_batch(seed, 42, &mut result);
+    for (i, digest) in result.iter().enumerate() {
+        assert_eq!(
+            Blake3_256::<BaseElement>::merge_with_int(seed, 42 + i as u64),
+            *digest
+        );
+    }
+
+    let seed = Blake3_192::<BaseElement>::hash(&[1_u8, 2, 3]);
+    let mut result = [Default::default(); 5];
+    Blake3_192::<BaseElement>::merge_with_int_batch(seed, 42, &mut result);
+    for (i, digest) in result.iter().enumerate() {
+        assert_eq!(
+            Blake3_192::<BaseElement>::merge_with_int(seed, 42 + i as u64),
+            *digest
+        );
+    }
+}
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::super::mds::mds_f64_8x8::mds_multiply;
-use super::{Digest, ElementHasher, Hasher};
+use super::{AlgebraicSponge, Digest, ElementHasher, Hasher};
 use core::convert::TryInto;
 use core::ops::Range;
 use math::{fields::f64::BaseElement, FieldElement, StarkField};
@@ -53,7 +53,7 @@ const NUM_ROUNDS: usize = 7;
 ///   margin used in the specifications (a 20% margin rounds up to 8 rounds). The primary
 ///   motivation for this is that having the number of rounds be one less than a power of two
 ///   simplifies AIR design for computations involving the hash function.
-/// *  When hashing a sequence of elements, implement the Hirose padding rule. However, it also
+/// * When hashing a sequence of elements, implement the Hirose padding rule. However, it also
 ///   means that our instantiation of Griffin cannot be used in a stream mode as the number
 ///   of elements to be hashed must be known upfront.
 /// * Instead of using the suggested matrix as described in Griffin paper, we use a methodology
@@ -178,8 +178,14 @@ impl Hasher for GriffinJive64_256 {
         Self::apply_jive_summation(&initial_state, &state)
     }
 
-    // We do not rely on the sponge construction to build our compression function. Instead, we use
-    // the Jive compression mode designed in https://eprint.iacr.org/2022/840.pdf.
+    // The Jive compression mode compresses exactly two digests which fill the entire Griffin
+    // state, and thus, it cannot be used for nodes of Merkle trees of higher arity. Instead, the
+    // elements of all digests are hashed using the sponge construction; for two digests, the
+    // result differs from the result of merge().
+    fn merge_many(values: &[Self::Digest]) -> Self::Digest {
+        Self::hash_elements(ElementDigest::digests_as_elements(values))
+    }
+
     fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
         // initialize the state as follows:
         // - seed is copied into the first 4 elements of the state.
@@ -252,6 +258,21 @@ impl ElementHasher for GriffinJive64_256 {
     }
 }
 
+impl AlgebraicSponge for GriffinJive64_256 {
+    const STATE_WIDTH: usize = STATE_WIDTH;
+    const RATE_RANGE: Range<usize> = RATE_RANGE;
+    const CAPACITY_RANGE: Range<usize> = CAPACITY_RANGE;
+
+    fn apply_permutation(state: &mut [BaseElement]) {
+        let state: &mut [BaseElement; STATE_WIDTH] = state.try_into().expect("invalid state width");
+        Self::apply_permutation(state);
+    }
+
+    fn digest_as_elements(digest: &Self::Digest) -> &[BaseElement] {
+        digest.as_elements()
+    }
+}
+
 // HASH FUNCTION IMPLEMENTATION
 // ================================================================================================
 
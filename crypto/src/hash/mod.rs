@@ -3,15 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use core::{fmt::Debug, slice};
+use crate::RandomCoin;
+use core::{fmt::Debug, marker::PhantomData, ops::Range, slice};
 use math::{FieldElement, StarkField};
-use utils::{ByteReader, Deserializable, DeserializationError, Serializable};
+use utils::{
+    collections::Vec, ByteReader, Deserializable, DeserializationError, Randomizable, Serializable,
+};
 
 mod blake;
 pub use blake::{Blake3_192, Blake3_256};
 
 mod sha;
-pub use sha::Sha3_256;
+pub use sha::{Keccak256, Sha3_256};
 
 mod mds;
 
@@ -21,6 +24,9 @@ pub use rescue::{Rp62_248, Rp64_256, RpJive64_256};
 mod griffin;
 pub use griffin::GriffinJive64_256;
 
+mod poseidon2;
+pub use poseidon2::{Poseidon2Jive64_256, Poseidon2_64_256};
+
 // HASHER TRAITS
 // ================================================================================================
 
@@ -29,6 +35,8 @@ pub use griffin::GriffinJive64_256;
 /// This trait defines hash procedures for the following inputs:
 /// * A sequence of bytes.
 /// * Two digests - this is intended for use in Merkle tree constructions.
+/// * Many digests - this is intended for use in construction of Merkle trees with arity greater
+///   than two.
 /// * A digests and a u64 value - this intended for use in PRNG or PoW contexts.
 pub trait Hasher {
     /// Specifies a digest type returned by this hasher.
@@ -44,8 +52,36 @@ pub trait Hasher {
     /// Merkle trees.
     fn merge(values: &[Self::Digest; 2]) -> Self::Digest;
 
+    /// Returns a hash of the provided digests. This method is intended for use in construction of
+    /// Merkle trees with arity greater than two.
+    ///
+    /// For two digests, the result is not required to be the same as the result of
+    /// [merge()](Hasher::merge). The default implementation hashes a concatenation of byte
+    /// representations of all digests.
+    fn merge_many(values: &[Self::Digest]) -> Self::Digest {
+        let bytes = values
+            .iter()
+            .flat_map(|v| v.as_bytes())
+            .collect::<Vec<u8>>();
+        Self::hash(&bytes)
+    }
+
     /// Returns hash(`seed` || `value`). This method is intended for use in PRNG and PoW contexts.
     fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest;
+
+    /// Computes hash(`seed` || `value`) for `result.len()` consecutive values starting with
+    /// `first_value`, and writes the digests into `result`. This method is intended for checking
+    /// batches of nonces in PoW contexts.
+    ///
+    /// The digests must be the same as the ones returned by
+    /// [merge_with_int()](Hasher::merge_with_int). The default implementation computes the digests
+    /// one by one; implementations which can hash several values at once (e.g., using SIMD
+    /// instructions) may override this method.
+    fn merge_with_int_batch(seed: Self::Digest, first_value: u64, result: &mut [Self::Digest]) {
+        for (i, digest) in result.iter_mut().enumerate() {
+            *digest = Self::merge_with_int(seed, first_value + i as u64);
+        }
+    }
 }
 
 /// Defines a cryptographic hash function for hashing field elements.
@@ -62,12 +98,129 @@ pub trait ElementHasher: Hasher {
         E: FieldElement<BaseField = Self::BaseField>;
 }
 
+/// Defines a cryptographic permutation over field elements which can be used in a sponge
+/// construction.
+///
+/// This trait exposes the permutation underlying an algebraic hash function, and is intended for
+/// constructions which need to absorb and squeeze field elements directly (e.g., a duplex sponge).
+pub trait AlgebraicSponge: ElementHasher {
+    /// Number of field elements in the permutation state.
+    const STATE_WIDTH: usize;
+
+    /// Range of the state elements which form the rate portion of the sponge.
+    const RATE_RANGE: Range<usize>;
+
+    /// Range of the state elements which form the capacity portion of the sponge; the first
+    /// element of this range is used for domain separation (e.g., to encode the input length).
+    const CAPACITY_RANGE: Range<usize>;
+
+    /// Applies the permutation to the provided state.
+    ///
+    /// # Panics
+    /// Panics if the length of the `state` is not equal to `STATE_WIDTH`.
+    fn apply_permutation(state: &mut [Self::BaseField]);
+
+    /// Returns the provided digest as a slice of field elements.
+    fn digest_as_elements(digest: &Self::Digest) -> &[Self::BaseField];
+}
+
+// COMMITMENT HASHERS
+// ================================================================================================
+
+/// Defines hash functions used to commit to different parts of a STARK proof.
+///
+/// A proof contains commitments to the extended execution trace, to the evaluations of the
+/// constraint composition polynomial, and to the layers of the FRI protocol. Each of these
+/// commitments may be built using a different hash function. For example, a fast byte-oriented
+/// hash function could be used for large trace commitments, while an algebraic hash function could
+/// be used for FRI layer commitments to keep verification in a recursive context cheap.
+///
+/// This trait is implemented for every [ElementHasher] (in which case the same hash function is
+/// used for all commitments), and for [DistinctHashers] which can be used to specify a different
+/// hash function for each commitment.
+pub trait CommitmentHashers {
+    /// Base field for elements which can be hashed with the hash functions.
+    type BaseField: StarkField;
+
+    /// Hash function used to commit to the extended execution trace.
+    type TraceHasher: ElementHasher<BaseField = Self::BaseField>;
+
+    /// Hash function used to commit to the evaluations of the constraint composition polynomial.
+    type ConstraintHasher: ElementHasher<BaseField = Self::BaseField>;
+
+    /// Hash function used to commit to the FRI layers.
+    type FriHasher: ElementHasher<BaseField = Self::BaseField>;
+
+    /// Returns the collision resistance (in bits) of the weakest of the hash functions, including
+    /// the hash function used by the random coin `R`.
+    ///
+    /// The hash function of the random coin is taken into account because all commitments are
+    /// absorbed into the coin, and thus, collisions in this function could be used to obtain the
+    /// same challenges for different commitments.
+    fn collision_resistance<R: RandomCoin>() -> u32 {
+        Self::TraceHasher::COLLISION_RESISTANCE
+            .min(Self::ConstraintHasher::COLLISION_RESISTANCE)
+            .min(Self::FriHasher::COLLISION_RESISTANCE)
+            .min(R::Hasher::COLLISION_RESISTANCE)
+    }
+}
+
+impl<H: ElementHasher> CommitmentHashers for H {
+    type BaseField = H::BaseField;
+    type TraceHasher = H;
+    type ConstraintHasher = H;
+    type FriHasher = H;
+}
+
+/// A set of hash functions where trace, constraint, and FRI layer commitments are built using the
+/// hash functions specified by `T`, `C`, and `F` type parameters respectively.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{
+/// #     hashers::{Blake3_192, Blake3_256, Rp64_256},
+/// #     CommitmentHashers, DefaultRandomCoin, DistinctHashers,
+/// # };
+/// # use math::fields::f64::BaseElement;
+/// // use BLAKE3 for trace and constraint commitments, and Rescue Prime for FRI commitments
+/// type Hashers = DistinctHashers<Blake3_256<BaseElement>, Blake3_256<BaseElement>, Rp64_256>;
+/// assert_eq!(128, Hashers::collision_resistance::<DefaultRandomCoin<Rp64_256>>());
+///
+/// // a random coin with a weaker hash function limits the collision resistance of all hashers
+/// type WeakCoin = DefaultRandomCoin<Blake3_192<BaseElement>>;
+/// assert_eq!(96, Hashers::collision_resistance::<WeakCoin>());
+/// ```
+pub struct DistinctHashers<T, C, F>(PhantomData<(T, C, F)>);
+
+impl<B, T, C, F> CommitmentHashers for DistinctHashers<T, C, F>
+where
+    B: StarkField,
+    T: ElementHasher<BaseField = B>,
+    C: ElementHasher<BaseField = B>,
+    F: ElementHasher<BaseField = B>,
+{
+    type BaseField = B;
+    type TraceHasher = T;
+    type ConstraintHasher = C;
+    type FriHasher = F;
+}
+
 // DIGEST TRAIT
 // ================================================================================================
 
 /// Defines output type for a cryptographic hash function.
 pub trait Digest:
-    Debug + Default + Copy + Clone + Eq + PartialEq + Send + Sync + Serializable + Deserializable
+    Debug
+    + Default
+    + Copy
+    + Clone
+    + Eq
+    + PartialEq
+    + Send
+    + Sync
+    + Serializable
+    + Deserializable
+    + 'static
 {
     /// Returns this digest serialized into an array of bytes.
     ///
@@ -78,6 +231,52 @@ pub trait Digest:
     fn as_bytes(&self) -> [u8; 32];
 }
 
+// HASH TO FIELD
+// ================================================================================================
+
+/// Maps an arbitrary sequence of bytes to an element in the field `E` using hash function `H`.
+///
+/// The mapping works as follows:
+/// - First, `seed` is computed as hash(`data`).
+/// - Then, for each base field element of the result, we compute hash(`seed` || `counter`) for
+///   an incrementing `counter`, clear all bits of the resulting value above
+///   `E::BaseField::MODULUS_BITS`, and try to interpret the result as a field element using
+///   [Randomizable::from_random_bytes()]. Values which are not valid field elements are rejected.
+///
+/// Since rejected values are discarded rather than reduced modulo the field modulus, the output
+/// is not biased towards small field elements. Each attempt succeeds with probability at least
+/// 1/2, and thus the expected number of hash invocations per base element is at most 2.
+///
+/// For the output to be uniformly distributed, digests of `H` must be uniformly distributed
+/// sequences of bytes (as is the case for [Blake3_256] or [Sha3_256], but not necessarily for
+/// algebraic hash functions).
+pub fn hash_to_field<H: Hasher, E: FieldElement>(data: &[u8]) -> E {
+    let element_bytes = E::BaseField::ELEMENT_BYTES;
+    let excess_bits = (element_bytes * 8) as u32 - E::BaseField::MODULUS_BITS;
+    debug_assert!(
+        excess_bits < 8,
+        "field elements must be tightly packed into bytes"
+    );
+
+    let seed = H::hash(data);
+    let mut counter = 0;
+    let mut result = Vec::with_capacity(E::EXTENSION_DEGREE);
+    while result.len() < E::EXTENSION_DEGREE {
+        counter += 1;
+        let mut bytes = H::merge_with_int(seed, counter).as_bytes();
+
+        // clear the bits which cannot be set in a valid field element; this does not introduce
+        // any bias since the remaining bits are still uniformly distributed
+        bytes[element_bytes - 1] &= 0xFF >> excess_bits;
+
+        if let Some(element) = E::BaseField::from_random_bytes(&bytes[..element_bytes]) {
+            result.push(element);
+        }
+    }
+
+    E::slice_from_base_elements(&result)[0]
+}
+
 // BYTE DIGEST
 // ================================================================================================
 
@@ -132,7 +331,15 @@ impl<const N: usize> Deserializable for ByteDigest<N> {
 
 #[cfg(test)]
 mod tests {
-    use super::{ByteDigest, Digest};
+    use super::{
+        hash_to_field, Blake3_192, Blake3_256, ByteDigest, CommitmentHashers, Digest,
+        DistinctHashers, Rp64_256, Sha3_256,
+    };
+    use crate::{DefaultRandomCoin, DuplexRandomCoin};
+    use math::{
+        fields::{f62, f64, CubeExtension, QuadExtension},
+        FieldElement,
+    };
 
     #[test]
     fn byte_digest_as_bytes() {
@@ -144,4 +351,66 @@ mod tests {
         expected[31] = 0;
         assert_eq!(expected, d.as_bytes());
     }
+
+    #[test]
+    fn commitment_hashers_collision_resistance() {
+        type Blake3 = Blake3_256<f64::BaseElement>;
+        type WeakBlake3 = Blake3_192<f64::BaseElement>;
+        type Hashers = DistinctHashers<Blake3, Blake3, Rp64_256>;
+
+        assert_eq!(
+            128,
+            Blake3::collision_resistance::<DefaultRandomCoin<Blake3>>()
+        );
+        assert_eq!(
+            128,
+            Hashers::collision_resistance::<DuplexRandomCoin<Rp64_256>>()
+        );
+
+        // a random coin with a weak hash function limits the collision resistance even if all
+        // commitments are built using strong hash functions
+        assert_eq!(
+            96,
+            Blake3::collision_resistance::<DefaultRandomCoin<WeakBlake3>>()
+        );
+        assert_eq!(
+            96,
+            Hashers::collision_resistance::<DefaultRandomCoin<WeakBlake3>>()
+        );
+
+        // a weak commitment hash function limits the collision resistance as before
+        type WeakHashers = DistinctHashers<Blake3, WeakBlake3, Rp64_256>;
+        assert_eq!(
+            96,
+            WeakHashers::collision_resistance::<DefaultRandomCoin<Blake3>>()
+        );
+    }
+
+    #[test]
+    fn hash_to_field_deterministic() {
+        type E = QuadExtension<f64::BaseElement>;
+
+        let a: E = hash_to_field::<Blake3_256<f64::BaseElement>, _>(b"foo");
+        let b: E = hash_to_field::<Blake3_256<f64::BaseElement>, _>(b"foo");
+        assert_eq!(a, b);
+
+        let c: E = hash_to_field::<Blake3_256<f64::BaseElement>, _>(b"bar");
+        assert_ne!(a, c);
+
+        // the two coordinates are drawn independently
+        assert_ne!(a.base_element(0), a.base_element(1));
+    }
+
+    #[test]
+    fn hash_to_field_extension() {
+        // the f62 modulus is about 2^62, so the top two bits of every candidate are cleared
+        for i in 0u32..100 {
+            let a: CubeExtension<f62::BaseElement> =
+                hash_to_field::<Sha3_256<f62::BaseElement>, _>(&i.to_le_bytes());
+            let b: CubeExtension<f62::BaseElement> =
+                hash_to_field::<Sha3_256<f62::BaseElement>, _>(&i.to_le_bytes());
+            assert_eq!(a, b);
+            assert_ne!(CubeExtension::ZERO, a);
+        }
+    }
 }
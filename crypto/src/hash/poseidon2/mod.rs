@@ -0,0 +1,142 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{AlgebraicSponge, Digest, ElementHasher, Hasher};
+use math::{fields::f64::BaseElement, FieldElement};
+
+mod poseidon2_64_256;
+pub use poseidon2_64_256::Poseidon2_64_256;
+
+mod poseidon2_64_256_jive;
+pub use poseidon2_64_256_jive::Poseidon2Jive64_256;
+
+#[cfg(test)]
+mod tests;
+
+// CONSTANTS
+// ================================================================================================
+
+/// The number of full (external) rounds; half of them are applied at the beginning of the
+/// permutation and the other half at the end.
+const NUM_FULL_ROUNDS: usize = 8;
+
+/// The number of partial (internal) rounds.
+const NUM_PARTIAL_ROUNDS: usize = 22;
+
+/// S-Box power; this is the smallest integer d such that gcd(d, p - 1) = 1 for p = 2^64 - 2^32 + 1.
+#[cfg(test)]
+const ALPHA: u64 = 7;
+
+// POSEIDON2 PERMUTATION
+// ================================================================================================
+
+/// Applies Poseidon2 permutation to the provided state using the specified round constants and
+/// internal matrix diagonal.
+///
+/// Implementation follows algorithm 1 from <https://eprint.iacr.org/2023/323.pdf>: the external
+/// linear layer is applied to the input, followed by NUM_FULL_ROUNDS / 2 full rounds,
+/// NUM_PARTIAL_ROUNDS partial rounds, and then another NUM_FULL_ROUNDS / 2 full rounds.
+#[inline(always)]
+fn apply_permutation<const N: usize>(
+    state: &mut [BaseElement; N],
+    ark_external: &[[BaseElement; N]; NUM_FULL_ROUNDS],
+    ark_internal: &[BaseElement; NUM_PARTIAL_ROUNDS],
+    internal_diag: &[BaseElement; N],
+) {
+    apply_external_linear_layer(state);
+
+    for ark in ark_external[..NUM_FULL_ROUNDS / 2].iter() {
+        apply_full_round(state, ark);
+    }
+
+    for &ark in ark_internal.iter() {
+        state[0] += ark;
+        state[0] = state[0].exp7();
+        apply_internal_linear_layer(state, internal_diag);
+    }
+
+    for ark in ark_external[NUM_FULL_ROUNDS / 2..].iter() {
+        apply_full_round(state, ark);
+    }
+}
+
+/// Poseidon2 full round function: adds round constants to all state elements, applies the S-Box
+/// to all state elements, and then applies the external linear layer.
+#[inline(always)]
+fn apply_full_round<const N: usize>(state: &mut [BaseElement; N], ark: &[BaseElement; N]) {
+    state
+        .iter_mut()
+        .zip(ark)
+        .for_each(|(s, &k)| *s = (*s + k).exp7());
+    apply_external_linear_layer(state);
+}
+
+// LINEAR LAYERS
+// ================================================================================================
+
+/// Multiplies the state by the external matrix M_E = circ(2 * M4, M4, ..., M4).
+///
+/// This is done by first multiplying each 4-element chunk of the state by M4, and then adding
+/// to each element the sum of elements at the same position across all chunks.
+///
+/// The MDS matrices in [hash::mds](super::mds) are not used here because the Poseidon2
+/// specifications fix the structure of the external matrix, and using a different matrix would
+/// make the outputs incompatible with other Poseidon2 implementations.
+#[inline(always)]
+fn apply_external_linear_layer<const N: usize>(state: &mut [BaseElement; N]) {
+    debug_assert_eq!(N % 4, 0, "state width must be divisible by 4");
+
+    state.chunks_exact_mut(4).for_each(apply_m4);
+
+    let mut sums = [BaseElement::ZERO; 4];
+    for chunk in state.chunks_exact(4) {
+        sums.iter_mut().zip(chunk).for_each(|(s, &x)| *s += x);
+    }
+
+    for chunk in state.chunks_exact_mut(4) {
+        chunk.iter_mut().zip(sums).for_each(|(x, s)| *x += s);
+    }
+}
+
+/// Multiplies a 4-element vector by the matrix
+///
+/// ```text
+/// [5 7 1 3]
+/// [4 6 1 1]
+/// [1 3 5 7]
+/// [1 1 4 6]
+/// ```
+///
+/// using 8 additions and 4 doublings, as described in appendix B of the Poseidon2 paper.
+#[inline(always)]
+fn apply_m4(x: &mut [BaseElement]) {
+    let t0 = x[0] + x[1];
+    let t1 = x[2] + x[3];
+    let t2 = x[1].double() + t1;
+    let t3 = x[3].double() + t0;
+    let t4 = t1.double().double() + t3;
+    let t5 = t0.double().double() + t2;
+    let t6 = t3 + t5;
+    let t7 = t2 + t4;
+
+    x[0] = t6;
+    x[1] = t5;
+    x[2] = t7;
+    x[3] = t4;
+}
+
+/// Multiplies the state by the internal matrix M_I = 1 + diag(`internal_diag`), where 1 is the
+/// matrix with all entries set to one.
+#[inline(always)]
+fn apply_internal_linear_layer<const N: usize>(
+    state: &mut [BaseElement; N],
+    internal_diag: &[BaseElement; N],
+) {
+    let sum = state.iter().fold(BaseElement::ZERO, |acc, &s| acc + s);
+    state
+        .iter_mut()
+        .zip(internal_diag)
+        .for_each(|(s, &d)| *s = *s * d + sum);
+}
@@ -0,0 +1,123 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    BaseElement, ElementDigest, ElementHasher, FieldElement, Hasher, Poseidon2Jive64_256,
+    StarkField, STATE_WIDTH,
+};
+use core::convert::TryInto;
+
+use rand_utils::{rand_array, rand_value};
+
+#[test]
+fn apply_permutation() {
+    let mut state: [BaseElement; STATE_WIDTH] = [
+        BaseElement::new(0),
+        BaseElement::new(1),
+        BaseElement::new(2),
+        BaseElement::new(3),
+        BaseElement::new(4),
+        BaseElement::new(5),
+        BaseElement::new(6),
+        BaseElement::new(7),
+    ];
+
+    Poseidon2Jive64_256::apply_permutation(&mut state);
+
+    // expected values are the test vectors of the reference implementation of Poseidon2
+    // (https://github.com/HorizenLabs/poseidon2) for the Goldilocks field with state width 8
+    let expected = vec![
+        BaseElement::new(14266028122062624699),
+        BaseElement::new(5353147180106052723),
+        BaseElement::new(15203350112844181434),
+        BaseElement::new(17630919042639565165),
+        BaseElement::new(16601551015858213987),
+        BaseElement::new(10184091939013874068),
+        BaseElement::new(16774100645754596496),
+        BaseElement::new(12047415603622314780),
+    ];
+
+    assert_eq!(expected, state);
+}
+
+#[test]
+fn hash_elements_vs_merge() {
+    let elements: [BaseElement; 8] = rand_array();
+
+    let digests: [ElementDigest; 2] = [
+        ElementDigest::new(elements[..4].try_into().unwrap()),
+        ElementDigest::new(elements[4..].try_into().unwrap()),
+    ];
+
+    let m_result = Poseidon2Jive64_256::merge(&digests);
+    let h_result = Poseidon2Jive64_256::hash_elements(&elements);
+
+    // Because we use the Jive compression mode, `merge` and
+    // `hash_elements` methods are incompatible.
+    assert_ne!(m_result, h_result);
+}
+
+#[test]
+fn hash_elements_vs_merge_with_int() {
+    let seed = ElementDigest::new(rand_array());
+
+    // ----- value fits into a field element ------------------------------------------------------
+    let val: BaseElement = rand_value();
+    let m_result = Poseidon2Jive64_256::merge_with_int(seed, val.as_int());
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(val);
+    let h_result = Poseidon2Jive64_256::hash_elements(&elements);
+
+    // Because we use the Jive compression mode, `merge` and
+    // `hash_elements` methods are incompatible.
+    assert_ne!(m_result, h_result);
+
+    // ----- value does not fit into a field element ----------------------------------------------
+    let val = BaseElement::MODULUS + 2;
+    let m_result = Poseidon2Jive64_256::merge_with_int(seed, val);
+
+    let mut elements = seed.as_elements().to_vec();
+    elements.push(BaseElement::new(val));
+    elements.push(BaseElement::new(1));
+    let h_result = Poseidon2Jive64_256::hash_elements(&elements);
+
+    // Because we use the Jive compression mode, `merge` and
+    // `hash_elements` methods are incompatible.
+    assert_ne!(m_result, h_result);
+}
+
+#[test]
+fn hash_padding() {
+    // adding a zero bytes at the end of a byte string should result in a different hash
+    let r1 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3]);
+    let r2 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 0]);
+    assert_ne!(r1, r2);
+
+    // same as above but with bigger inputs
+    let r1 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6]);
+    let r2 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 0]);
+    assert_ne!(r1, r2);
+
+    // same as above but with input splitting over two elements
+    let r1 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7]);
+    let r2 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0]);
+    assert_ne!(r1, r2);
+
+    // same as above but with multiple zeros
+    let r1 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0]);
+    let r2 = Poseidon2Jive64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0]);
+    assert_ne!(r1, r2);
+}
+
+#[test]
+fn hash_elements_padding() {
+    let e1: [BaseElement; 2] = rand_array();
+    let e2 = [e1[0], e1[1], BaseElement::ZERO];
+
+    let r1 = Poseidon2Jive64_256::hash_elements(&e1);
+    let r2 = Poseidon2Jive64_256::hash_elements(&e2);
+    assert_ne!(r1, r2);
+}
@@ -0,0 +1,91 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    apply_external_linear_layer, apply_internal_linear_layer, BaseElement, FieldElement, ALPHA,
+};
+use proptest::prelude::*;
+
+const M4: [[u64; 4]; 4] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+
+#[test]
+fn test_sbox() {
+    let e: BaseElement = rand_utils::rand_value();
+    assert_eq!(e.exp(ALPHA), e.exp7());
+}
+
+/// Multiplies the state by M_E = circ(2 * M4, M4, ..., M4) using the explicit matrix.
+fn apply_external_linear_layer_naive<const N: usize>(state: &mut [BaseElement; N]) {
+    let mut result = [BaseElement::ZERO; N];
+    for (i, r) in result.iter_mut().enumerate() {
+        for (j, &s) in state.iter().enumerate() {
+            let mut m = BaseElement::new(M4[i % 4][j % 4]);
+            if i / 4 == j / 4 {
+                m = m.double();
+            }
+            *r += m * s;
+        }
+    }
+    *state = result;
+}
+
+/// Multiplies the state by M_I = 1 + diag(`diag`) using the explicit matrix.
+fn apply_internal_linear_layer_naive<const N: usize>(
+    state: &mut [BaseElement; N],
+    diag: &[BaseElement; N],
+) {
+    let mut result = [BaseElement::ZERO; N];
+    for (i, r) in result.iter_mut().enumerate() {
+        for (j, &s) in state.iter().enumerate() {
+            let m = if i == j {
+                diag[i] + BaseElement::ONE
+            } else {
+                BaseElement::ONE
+            };
+            *r += m * s;
+        }
+    }
+    *state = result;
+}
+
+fn to_elements<const N: usize>(values: [u64; N]) -> [BaseElement; N] {
+    values.map(BaseElement::new)
+}
+
+proptest! {
+    #[test]
+    fn external_linear_layer_8_proptest(a in any::<[u64; 8]>()) {
+        let mut v1 = to_elements(a);
+        let mut v2 = v1;
+
+        apply_external_linear_layer_naive(&mut v1);
+        apply_external_linear_layer(&mut v2);
+
+        prop_assert_eq!(v1, v2);
+    }
+
+    #[test]
+    fn external_linear_layer_12_proptest(a in any::<[u64; 12]>()) {
+        let mut v1 = to_elements(a);
+        let mut v2 = v1;
+
+        apply_external_linear_layer_naive(&mut v1);
+        apply_external_linear_layer(&mut v2);
+
+        prop_assert_eq!(v1, v2);
+    }
+
+    #[test]
+    fn internal_linear_layer_proptest(a in any::<[u64; 12]>(), d in any::<[u64; 12]>()) {
+        let mut v1 = to_elements(a);
+        let mut v2 = v1;
+        let diag = to_elements(d);
+
+        apply_internal_linear_layer_naive(&mut v1, &diag);
+        apply_internal_linear_layer(&mut v2, &diag);
+
+        prop_assert_eq!(v1, v2);
+    }
+}
@@ -132,6 +132,20 @@ fn hash_padding() {
     assert_ne!(r1, r2);
 }
 
+#[test]
+fn hash_long_input() {
+    // inputs which span more than one permutation and end with a partial chunk must be hashed
+    // without panicking, and must still be padded
+    let r1 = Rp62_248::hash(&[1_u8; 100]);
+    let r2 = Rp62_248::hash(&[1_u8; 101]);
+    assert_ne!(r1, r2);
+
+    let mut bytes = [1_u8; 101];
+    bytes[100] = 0;
+    let r2 = Rp62_248::hash(&bytes);
+    assert_ne!(r1, r2);
+}
+
 #[test]
 fn hash_elements_padding() {
     let e1: [BaseElement; 2] = rand_array();
@@ -166,6 +166,19 @@ fn hash_padding() {
     let r1 = Rp64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0]);
     let r2 = Rp64_256::hash(&[1_u8, 2, 3, 4, 5, 6, 7, 0, 0, 0, 0]);
     assert_ne!(r1, r2);
+
+    // same as above but with inputs which span more than one permutation
+    let r1 = Rp64_256::hash(&[1_u8; 64]);
+    let mut bytes = [1_u8; 70];
+    bytes[64..].copy_from_slice(&[0; 6]);
+    let r2 = Rp64_256::hash(&bytes);
+    assert_ne!(r1, r2);
+
+    let r1 = Rp64_256::hash(&[1_u8; 70]);
+    let mut bytes = [1_u8; 71];
+    bytes[70] = 0;
+    let r2 = Rp64_256::hash(&bytes);
+    assert_ne!(r1, r2);
 }
 
 #[test]
@@ -199,7 +212,7 @@ proptest! {
         for i in 0..STATE_WIDTH {
             v1[i] = BaseElement::new(a[i]);
         }
-        v2 = v1.clone();
+        v2 = v1;
 
         apply_mds_naive(&mut v1);
         Rp64_256::apply_mds(&mut v2);
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::super::mds::mds_f64_8x8::mds_multiply;
-use super::{exp_acc, Digest, ElementHasher, Hasher};
+use super::{exp_acc, AlgebraicSponge, Digest, ElementHasher, Hasher};
 use core::convert::TryInto;
 use core::ops::Range;
 use math::{fields::f64::BaseElement, FieldElement, StarkField};
@@ -196,8 +196,14 @@ impl Hasher for RpJive64_256 {
         Self::apply_jive_summation(&initial_state, &state)
     }
 
-    // We do not rely on the sponge construction to build our compression function. Instead, we use
-    // the Jive compression mode designed in https://eprint.iacr.org/2022/840.pdf.
+    // The Jive compression mode compresses exactly two digests which fill the entire Rescue
+    // state, and thus, it cannot be used for nodes of Merkle trees of higher arity. Instead, the
+    // elements of all digests are hashed using the sponge construction; for two digests, the
+    // result differs from the result of merge().
+    fn merge_many(values: &[Self::Digest]) -> Self::Digest {
+        Self::hash_elements(ElementDigest::digests_as_elements(values))
+    }
+
     fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
         // initialize the state as follows:
         // - seed is copied into the first 4 elements of the state.
@@ -270,6 +276,21 @@ impl ElementHasher for RpJive64_256 {
     }
 }
 
+impl AlgebraicSponge for RpJive64_256 {
+    const STATE_WIDTH: usize = STATE_WIDTH;
+    const RATE_RANGE: Range<usize> = RATE_RANGE;
+    const CAPACITY_RANGE: Range<usize> = CAPACITY_RANGE;
+
+    fn apply_permutation(state: &mut [BaseElement]) {
+        let state: &mut [BaseElement; STATE_WIDTH] = state.try_into().expect("invalid state width");
+        Self::apply_permutation(state);
+    }
+
+    fn digest_as_elements(digest: &Self::Digest) -> &[BaseElement] {
+        digest.as_elements()
+    }
+}
+
 // HASH FUNCTION IMPLEMENTATION
 // ================================================================================================
 
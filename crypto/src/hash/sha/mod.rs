@@ -7,7 +7,10 @@ use super::{ByteDigest, ElementHasher, Hasher};
 use core::marker::PhantomData;
 use math::{FieldElement, StarkField};
 use sha3::Digest;
-use utils::ByteWriter;
+use utils::{collections::Vec, ByteWriter};
+
+#[cfg(test)]
+mod tests;
 
 // SHA3 WITH 256-BIT OUTPUT
 // ================================================================================================
@@ -29,6 +32,10 @@ impl<B: StarkField> Hasher for Sha3_256<B> {
         ByteDigest(sha3::Sha3_256::digest(ByteDigest::digests_as_bytes(values)).into())
     }
 
+    fn merge_many(values: &[Self::Digest]) -> Self::Digest {
+        ByteDigest(sha3::Sha3_256::digest(ByteDigest::digests_as_bytes(values)).into())
+    }
+
     fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
         let mut data = [0; 40];
         data[..32].copy_from_slice(&seed.0);
@@ -49,7 +56,88 @@ impl<B: StarkField> ElementHasher for Sha3_256<B> {
         } else {
             // when elements' internal and canonical representations differ, we need to serialize
             // them before hashing
-            let mut hasher = ShaHasher::new();
+            let mut hasher = ShaHasher::<sha3::Sha3_256>::new();
+            hasher.write(elements);
+            ByteDigest(hasher.finalize())
+        }
+    }
+}
+
+// KECCAK WITH 256-BIT OUTPUT
+// ================================================================================================
+
+/// Implementation of the [Hasher](super::Hasher) trait for Keccak hash function with 256-bit
+/// output.
+///
+/// Unlike [Sha3_256], this hash function uses the original Keccak padding rule rather than the
+/// padding rule standardized in FIPS 202. Thus, its output is the same as the output of the
+/// `keccak256` function available in the Ethereum Virtual Machine, which makes it possible to
+/// recompute Merkle roots and random coin draws in EVM-based verifiers.
+///
+/// Field elements and integers are encoded into bytes before hashing as follows:
+/// * When `BIG_ENDIAN` is false (the default), each base field element is encoded using its
+///   canonical little-endian representation of `ELEMENT_BYTES` bytes, and integers in
+///   [merge_with_int()](Keccak256::merge_with_int) are encoded as 8 little-endian bytes. This
+///   encoding is the same as the one used by [Sha3_256].
+/// * When `BIG_ENDIAN` is true, the byte order of each encoded base field element and integer is
+///   reversed. This matches the default encoding of unsigned integers in Solidity (e.g., as
+///   produced by `abi.encodePacked` for `uint64` values).
+pub struct Keccak256<B: StarkField, const BIG_ENDIAN: bool = false>(PhantomData<B>);
+
+impl<B: StarkField, const BIG_ENDIAN: bool> Hasher for Keccak256<B, BIG_ENDIAN> {
+    type Digest = ByteDigest<32>;
+
+    const COLLISION_RESISTANCE: u32 = 128;
+
+    fn hash(bytes: &[u8]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(bytes).into())
+    }
+
+    fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(ByteDigest::digests_as_bytes(values)).into())
+    }
+
+    fn merge_many(values: &[Self::Digest]) -> Self::Digest {
+        ByteDigest(sha3::Keccak256::digest(ByteDigest::digests_as_bytes(values)).into())
+    }
+
+    fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+        let mut data = [0; 40];
+        data[..32].copy_from_slice(&seed.0);
+        if BIG_ENDIAN {
+            data[32..].copy_from_slice(&value.to_be_bytes());
+        } else {
+            data[32..].copy_from_slice(&value.to_le_bytes());
+        }
+        ByteDigest(sha3::Keccak256::digest(data).into())
+    }
+}
+
+impl<B: StarkField, const BIG_ENDIAN: bool> ElementHasher for Keccak256<B, BIG_ENDIAN> {
+    type BaseField = B;
+
+    fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
+        if BIG_ENDIAN {
+            // serialize each base element into its canonical little-endian representation, and
+            // then reverse the order of its bytes
+            let mut hasher = ShaHasher::<sha3::Keccak256>::new();
+            let mut buf = Vec::with_capacity(B::ELEMENT_BYTES);
+            for element in E::slice_as_base_elements(elements) {
+                buf.clear();
+                element.write_into(&mut buf);
+                buf.reverse();
+                hasher.write_bytes(&buf);
+            }
+            ByteDigest(hasher.finalize())
+        } else if B::IS_CANONICAL {
+            // when element's internal and canonical representations are the same, we can hash
+            // element bytes directly
+            let bytes = E::elements_as_bytes(elements);
+            ByteDigest(sha3::Keccak256::digest(bytes).into())
+        } else {
+            // when elements' internal and canonical representations differ, we need to serialize
+            // them before hashing
+            let mut hasher = ShaHasher::<sha3::Keccak256>::new();
             hasher.write(elements);
             ByteDigest(hasher.finalize())
         }
@@ -59,20 +147,22 @@ impl<B: StarkField> ElementHasher for Sha3_256<B> {
 // SHA HASHER
 // ================================================================================================
 
-/// Wrapper around SHA3 hasher to implement [ByteWriter] trait for it.
-struct ShaHasher(sha3::Sha3_256);
+/// Wrapper around SHA3 and Keccak hashers to implement [ByteWriter] trait for them.
+struct ShaHasher<D: Digest>(D);
 
-impl ShaHasher {
+impl<D: Digest> ShaHasher<D> {
     pub fn new() -> Self {
-        Self(sha3::Sha3_256::new())
+        Self(D::new())
     }
 
     pub fn finalize(self) -> [u8; 32] {
-        self.0.finalize().into()
+        let mut result = [0; 32];
+        result.copy_from_slice(&self.0.finalize());
+        result
     }
 }
 
-impl ByteWriter for ShaHasher {
+impl<D: Digest> ByteWriter for ShaHasher<D> {
     fn write_u8(&mut self, value: u8) {
         self.0.update([value]);
     }
@@ -0,0 +1,134 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Keccak256, Sha3_256};
+use crate::{Digest, ElementHasher, Hasher};
+use math::{
+    fields::{f64::BaseElement, QuadExtension},
+    FieldElement, StarkField,
+};
+use rand_utils::{rand_array, rand_value};
+
+type KeccakLe = Keccak256<BaseElement>;
+type KeccakBe = Keccak256<BaseElement, true>;
+
+#[test]
+fn keccak_test_vectors() {
+    // expected values are the outputs of the keccak256 function in the EVM
+    let r = KeccakLe::hash(b"");
+    assert_eq!(
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+        to_hex(&r.as_bytes())
+    );
+
+    let r = KeccakLe::hash(b"abc");
+    assert_eq!(
+        "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+        to_hex(&r.as_bytes())
+    );
+
+    // Keccak and SHA3 use different padding rules
+    assert_ne!(
+        KeccakLe::hash(b"abc"),
+        Sha3_256::<BaseElement>::hash(b"abc")
+    );
+}
+
+#[test]
+fn keccak_hash_elements() {
+    let elements: [BaseElement; 3] = rand_array();
+
+    // ----- little-endian encoding ---------------------------------------------------------------
+    let bytes = elements
+        .iter()
+        .flat_map(|e| e.as_int().to_le_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(KeccakLe::hash(&bytes), KeccakLe::hash_elements(&elements));
+
+    // ----- big-endian encoding ------------------------------------------------------------------
+    let bytes = elements
+        .iter()
+        .flat_map(|e| e.as_int().to_be_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(KeccakBe::hash(&bytes), KeccakBe::hash_elements(&elements));
+    assert_ne!(
+        KeccakLe::hash_elements(&elements),
+        KeccakBe::hash_elements(&elements)
+    );
+}
+
+#[test]
+fn keccak_hash_ext_elements() {
+    let elements: [QuadExtension<BaseElement>; 2] = rand_array();
+
+    // each base element of the extension field element is encoded separately
+    let bytes = QuadExtension::slice_as_base_elements(&elements)
+        .iter()
+        .flat_map(|e| e.as_int().to_be_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(KeccakBe::hash(&bytes), KeccakBe::hash_elements(&elements));
+}
+
+#[test]
+fn keccak_merge_with_int() {
+    let seed = KeccakLe::hash(&[1_u8, 2, 3]);
+    let value: u64 = rand_value();
+
+    let mut bytes = seed.as_bytes().to_vec();
+    bytes.extend_from_slice(&value.to_le_bytes());
+    assert_eq!(
+        KeccakLe::hash(&bytes),
+        KeccakLe::merge_with_int(seed, value)
+    );
+
+    let mut bytes = seed.as_bytes().to_vec();
+    bytes.extend_from_slice(&value.to_be_bytes());
+    assert_eq!(
+        KeccakBe::hash(&bytes),
+        KeccakBe::merge_with_int(seed, value)
+    );
+}
+
+#[test]
+fn merge_with_int_batch() {
+    let seed = Sha3_256::<BaseElement>::hash(&[1_u8, 2, 3]);
+    let mut result = [Default::default(); 5];
+    Sha3_256::<BaseElement>::merge_with_int_batch(seed, 42, &mut result);
+    for (i, digest) in result.iter().enumerate() {
+        assert_eq!(
+            Sha3_256::<BaseElement>::merge_with_int(seed, 42 + i as u64),
+            *digest
+        );
+    }
+
+    let mut result = [Default::default(); 5];
+    KeccakLe::merge_with_int_batch(seed, 42, &mut result);
+    for (i, digest) in result.iter().enumerate() {
+        assert_eq!(KeccakLe::merge_with_int(seed, 42 + i as u64), *digest);
+    }
+
+    let mut result = [Default::default(); 5];
+    KeccakBe::merge_with_int_batch(seed, 42, &mut result);
+    for (i, digest) in result.iter().enumerate() {
+        assert_eq!(KeccakBe::merge_with_int(seed, 42 + i as u64), *digest);
+    }
+}
+
+#[test]
+fn keccak_hash_elements_padding() {
+    let e1: [BaseElement; 2] = rand_array();
+    let e2 = [e1[0], e1[1], BaseElement::ZERO];
+
+    // adding a zero element at the end of a list of elements should result in a different hash
+    assert_ne!(KeccakLe::hash_elements(&e1), KeccakLe::hash_elements(&e2));
+    assert_ne!(KeccakBe::hash_elements(&e1), KeccakBe::hash_elements(&e2));
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
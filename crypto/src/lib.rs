@@ -14,7 +14,8 @@
 //!   [Octopus](https://eprint.iacr.org/2017/933) algorithm.
 //! * **PRNG** - which is used to generate pseudo-random elements in a finite field. The
 //!   [RandomCoin] implementation uses a cryptographic hash function to generate pseudo-random
-//!   elements form a seed.
+//!   elements form a seed. The [DuplexRandomCoin] implementation uses the permutation of an
+//!   algebraic hash function directly to absorb and squeeze field elements.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -23,13 +24,19 @@
 extern crate alloc;
 
 mod hash;
-pub use hash::{Digest, ElementHasher, Hasher};
+pub use hash::{
+    hash_to_field, AlgebraicSponge, CommitmentHashers, Digest, DistinctHashers, ElementHasher,
+    Hasher,
+};
 pub mod hashers {
     //! Contains implementations of currently supported hash functions.
 
     pub use super::hash::Blake3_192;
     pub use super::hash::Blake3_256;
     pub use super::hash::GriffinJive64_256;
+    pub use super::hash::Keccak256;
+    pub use super::hash::Poseidon2Jive64_256;
+    pub use super::hash::Poseidon2_64_256;
     pub use super::hash::Rp62_248;
     pub use super::hash::Rp64_256;
     pub use super::hash::RpJive64_256;
@@ -37,13 +44,13 @@ pub mod hashers {
 }
 
 mod merkle;
-pub use merkle::{build_merkle_nodes, BatchMerkleProof, MerkleTree};
+pub use merkle::{build_merkle_nodes, build_nary_merkle_nodes, BatchMerkleProof, MerkleTree};
 
 #[cfg(feature = "concurrent")]
 pub use merkle::concurrent;
 
 mod random;
-pub use random::{DefaultRandomCoin, RandomCoin};
+pub use random::{DefaultRandomCoin, DuplexRandomCoin, RandomCoin};
 
 mod errors;
 pub use errors::{MerkleTreeError, RandomCoinError};
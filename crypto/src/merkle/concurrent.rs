@@ -69,6 +69,44 @@ pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
     nodes
 }
 
+/// Builds all internal nodes of a Merkle tree of the specified `arity` using all available
+/// threads and stores the results in a single vector such that root of the tree is at position 1,
+/// and children of the node at position `i` are at positions `arity * i`, ...,
+/// `arity * i + arity - 1`.
+///
+/// For binary trees, this is equivalent to [build_merkle_nodes()].
+pub fn build_nary_merkle_nodes<H: Hasher>(leaves: &[H::Digest], arity: usize) -> Vec<H::Digest> {
+    if arity == 2 {
+        return build_merkle_nodes::<H>(leaves);
+    }
+
+    let n = leaves.len() / arity;
+
+    // create un-initialized array to hold all intermediate nodes; the positions which are not
+    // used (including position 0) are set to the default value
+    let mut nodes = unsafe { utils::uninit_vector::<H::Digest>(2 * n) };
+    nodes[..n].fill(H::Digest::default());
+
+    // build first row of internal nodes (parents of leaves)
+    nodes[n..]
+        .par_iter_mut()
+        .zip(leaves.par_chunks(arity))
+        .for_each(|(target, source)| *target = H::merge_many(source));
+
+    // calculate all other tree nodes level by level; each level is processed in parallel
+    let mut level_start = n / arity;
+    while level_start > 0 {
+        let (parents, children) = nodes.split_at_mut(arity * level_start);
+        parents[level_start..]
+            .par_iter_mut()
+            .zip(children[..arity * level_start].par_chunks(arity))
+            .for_each(|(target, source)| *target = H::merge_many(source));
+        level_start /= arity;
+    }
+
+    nodes
+}
+
 // TESTS
 // ================================================================================================
 
@@ -87,5 +125,13 @@ mod tests {
             let concurrent = super::build_merkle_nodes::<Sha3_256<BaseElement>>(&leaves);
             assert_eq!(concurrent, sequential);
         }
+
+        #[test]
+        fn build_nary_merkle_nodes_concurrent(ref data in vec(any::<[u8; 32]>(), 256..257).no_shrink()) {
+            let leaves = ByteDigest::bytes_as_digests(&data).to_vec();
+            let sequential = super::super::build_nary_merkle_nodes::<Sha3_256<BaseElement>>(&leaves, 4);
+            let concurrent = super::build_nary_merkle_nodes::<Sha3_256<BaseElement>>(&leaves, 4);
+            assert_eq!(concurrent, sequential);
+        }
     }
 }
@@ -4,8 +4,11 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{errors::MerkleTreeError, hash::Hasher};
-use core::slice;
-use utils::collections::{BTreeMap, BTreeSet, Vec};
+use utils::{
+    collections::{BTreeMap, BTreeSet, Vec},
+    string::ToString,
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 mod proofs;
 pub use proofs::BatchMerkleProof;
@@ -44,6 +47,12 @@ mod tests;
 /// The depth of a tree is zero-based. Thus, a tree with two leaves has depth 1, a tree with four
 /// leaves has depth 2 etc.
 ///
+/// By default, a Merkle tree is binary. Trees of arity 4, 8, or 16 can be built using
+/// [MerkleTree::with_arity()] function; in such trees, each internal node is computed by hashing
+/// all of its children via [Hasher::merge_many()], and the number of leaves must be a power of
+/// the arity. For algebraic hash functions, this reduces tree depth (and thus, the number of hashes
+/// which need to be computed to verify a Merkle path) at the cost of slightly longer Merkle paths.
+///
 /// When the crate is compiled with `concurrent` feature enabled, tree construction will be
 /// performed in multiple threads (usually, as many threads as there are logical cores on the
 /// machine). The number of threads can be configured via `RAYON_NUM_THREADS` environment variable.
@@ -57,6 +66,11 @@ mod tests;
 /// To verify proofs, [MerkleTree::verify()] and [MerkleTree::verify_batch()] functions can be
 /// used respectively.
 ///
+/// Instead of committing to the tree root, it is also possible to commit to a tree cap - i.e.,
+/// to all nodes at a given depth of the tree (see [MerkleTree::cap()]). Batch proofs against a cap
+/// can be generated and verified via [MerkleTree::prove_batch_with_cap()] and
+/// [MerkleTree::verify_batch_with_cap()] methods respectively.
+///
 /// # Examples
 /// ```
 /// # use winter_crypto::{MerkleTree, Hasher, hashers::Blake3_256};
@@ -87,6 +101,7 @@ mod tests;
 pub struct MerkleTree<H: Hasher> {
     nodes: Vec<H::Digest>,
     leaves: Vec<H::Digest>,
+    arity: usize,
 }
 
 // MERKLE TREE IMPLEMENTATION
@@ -96,8 +111,8 @@ impl<H: Hasher> MerkleTree<H> {
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns new Merkle tree built from the provide leaves using hash function specified by the
-    /// `H` generic parameter.
+    /// Returns new binary Merkle tree built from the provide leaves using hash function specified
+    /// by the `H` generic parameter.
     ///
     /// When `concurrent` feature is enabled, the tree is built using multiple threads.
     ///
@@ -106,49 +121,65 @@ impl<H: Hasher> MerkleTree<H> {
     /// * Fewer than two leaves were provided.
     /// * Number of leaves is not a power of two.
     pub fn new(leaves: Vec<H::Digest>) -> Result<Self, MerkleTreeError> {
-        if leaves.len() < 2 {
-            return Err(MerkleTreeError::TooFewLeaves(2, leaves.len()));
-        }
-        if !leaves.len().is_power_of_two() {
-            return Err(MerkleTreeError::NumberOfLeavesNotPowerOfTwo(leaves.len()));
-        }
+        Self::with_arity(leaves, 2)
+    }
+
+    /// Returns new Merkle tree of the specified `arity` built from the provide leaves using hash
+    /// function specified by the `H` generic parameter.
+    ///
+    /// When `concurrent` feature is enabled, the tree is built using multiple threads.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `arity` is not 2, 4, 8, or 16.
+    /// * Fewer than `arity` leaves were provided.
+    /// * Number of leaves is not a power of `arity`.
+    pub fn with_arity(leaves: Vec<H::Digest>, arity: usize) -> Result<Self, MerkleTreeError> {
+        validate_leaves(leaves.len(), arity)?;
 
         #[cfg(not(feature = "concurrent"))]
-        let nodes = build_merkle_nodes::<H>(&leaves);
+        let nodes = build_nary_merkle_nodes::<H>(&leaves, arity);
 
         #[cfg(feature = "concurrent")]
         let nodes = if leaves.len() <= concurrent::MIN_CONCURRENT_LEAVES {
-            build_merkle_nodes::<H>(&leaves)
+            build_nary_merkle_nodes::<H>(&leaves, arity)
         } else {
-            concurrent::build_merkle_nodes::<H>(&leaves)
+            concurrent::build_nary_merkle_nodes::<H>(&leaves, arity)
         };
 
-        Ok(MerkleTree { nodes, leaves })
+        Ok(MerkleTree {
+            nodes,
+            leaves,
+            arity,
+        })
     }
 
-    /// Forms a MerkleTree from a list of nodes and leaves.
+    /// Forms a MerkleTree of the specified `arity` from a list of nodes and leaves.
     ///
-    /// Nodes are supplied as a vector where the root is stored at position 1.
+    /// Nodes are supplied as a vector where the root is stored at position 1, and children of
+    /// the node at position `i` are stored at positions `arity * i`, ..., `arity * i + arity - 1`.
     ///
     /// # Errors
     /// Returns an error if:
-    /// * Fewer than two leaves were provided.
-    /// * Number of leaves is not a power of two.
+    /// * `arity` is not 2, 4, 8, or 16.
+    /// * Fewer than `arity` leaves were provided.
+    /// * Number of leaves is not a power of `arity`.
     ///
     /// # Panics
-    /// Panics if nodes doesn't have the same length as leaves.
+    /// Panics if the number of nodes is not equal to 2 * `leaves.len()` / `arity` (for binary
+    /// trees, this means that the number of nodes must be the same as the number of leaves).
     pub fn from_raw_parts(
         nodes: Vec<H::Digest>,
         leaves: Vec<H::Digest>,
+        arity: usize,
     ) -> Result<Self, MerkleTreeError> {
-        if leaves.len() < 2 {
-            return Err(MerkleTreeError::TooFewLeaves(2, leaves.len()));
-        }
-        if !leaves.len().is_power_of_two() {
-            return Err(MerkleTreeError::NumberOfLeavesNotPowerOfTwo(leaves.len()));
-        }
-        assert_eq!(nodes.len(), leaves.len());
-        Ok(MerkleTree { nodes, leaves })
+        validate_leaves(leaves.len(), arity)?;
+        assert_eq!(nodes.len(), 2 * leaves.len() / arity);
+        Ok(MerkleTree {
+            nodes,
+            leaves,
+            arity,
+        })
     }
 
     // PUBLIC ACCESSORS
@@ -159,12 +190,17 @@ impl<H: Hasher> MerkleTree<H> {
         &self.nodes[1]
     }
 
+    /// Returns arity of the tree (i.e., the number of children of every internal node).
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
     /// Returns depth of the tree.
     ///
-    /// The depth of a tree is zero-based. Thus, a tree with two leaves has depth 1, a tree with
-    /// four leaves has depth 2 etc.
+    /// The depth of a tree is zero-based. Thus, a binary tree with two leaves has depth 1, a
+    /// binary tree with four leaves has depth 2 etc.
     pub fn depth(&self) -> usize {
-        self.leaves.len().ilog2() as usize
+        (self.leaves.len().ilog2() / self.arity.ilog2()) as usize
     }
 
     /// Returns leaf nodes of the tree.
@@ -172,12 +208,28 @@ impl<H: Hasher> MerkleTree<H> {
         &self.leaves
     }
 
+    /// Returns a cap of the tree at the specified height.
+    ///
+    /// A cap of height `h` consists of all arity^h nodes located at depth `h` of the tree (in order
+    /// from left to right). Thus, a cap of height 0 consists of the tree root only. Committing to
+    /// a cap instead of the root shortens Merkle paths by `h` levels.
+    ///
+    /// Cap height is capped at `depth - 1` (i.e., the cap never consists of the leaves
+    /// themselves). Thus, if `cap_height` is greater than or equal to the depth of the tree, the
+    /// cap will consist of the parents of the leaves.
+    pub fn cap(&self, cap_height: usize) -> &[H::Digest] {
+        let cap_offset = self.arity.pow(self.clamp_cap_height(cap_height) as u32);
+        &self.nodes[cap_offset..2 * cap_offset]
+    }
+
     // PROVING METHODS
     // --------------------------------------------------------------------------------------------
 
     /// Returns a Merkle path to a leaf at the specified `index`.
     ///
-    /// The leaf itself will be the first element in the path.
+    /// The leaf itself will be the first element in the path. It is followed by the `arity - 1`
+    /// siblings of the leaf, and then by the `arity - 1` siblings of each of the leaf's ancestors
+    /// (except for the root). Siblings at each level are listed from left to right.
     ///
     /// # Errors
     /// Returns an error if the specified index is greater than or equal to the number of leaves
@@ -190,12 +242,24 @@ impl<H: Hasher> MerkleTree<H> {
             ));
         }
 
-        let mut proof = vec![self.leaves[index], self.leaves[index ^ 1]];
+        let arity = self.arity;
+        let mut proof = vec![self.leaves[index]];
+        let first_sibling = index - index % arity;
+        proof.extend(
+            (first_sibling..first_sibling + arity)
+                .filter(|&i| i != index)
+                .map(|i| self.leaves[i]),
+        );
 
-        let mut index = (index + self.nodes.len()) >> 1;
+        let mut index = (index + self.leaves.len()) / arity;
         while index > 1 {
-            proof.push(self.nodes[index ^ 1]);
-            index >>= 1;
+            let first_sibling = index - index % arity;
+            proof.extend(
+                (first_sibling..first_sibling + arity)
+                    .filter(|&i| i != index)
+                    .map(|i| self.nodes[i]),
+            );
+            index /= arity;
         }
 
         Ok(proof)
@@ -211,73 +275,48 @@ impl<H: Hasher> MerkleTree<H> {
     ///   tree.
     /// * List of indexes contains duplicates.
     pub fn prove_batch(&self, indexes: &[usize]) -> Result<BatchMerkleProof<H>, MerkleTreeError> {
-        if indexes.is_empty() {
-            return Err(MerkleTreeError::TooFewLeafIndexes);
-        }
-        if indexes.len() > proofs::MAX_PATHS {
-            return Err(MerkleTreeError::TooManyLeafIndexes(
-                proofs::MAX_PATHS,
-                indexes.len(),
-            ));
-        }
-
-        let index_map = map_indexes(indexes, self.depth())?;
-        let indexes = normalize_indexes(indexes);
-        let mut leaves = vec![H::Digest::default(); index_map.len()];
-        let mut nodes: Vec<Vec<H::Digest>> = Vec::with_capacity(indexes.len());
-
-        // populate the proof with leaf node values
-        let n = self.leaves.len();
-        let mut next_indexes: Vec<usize> = Vec::new();
-        for index in indexes {
-            let missing: Vec<H::Digest> = (index..index + 2)
-                .flat_map(|i| {
-                    let v = self.leaves[i];
-                    if let Some(idx) = index_map.get(&i) {
-                        leaves[*idx] = v;
-                        None
-                    } else {
-                        Some(v)
-                    }
-                })
-                .collect();
-            nodes.push(missing);
-
-            next_indexes.push((index + n) >> 1);
-        }
-
-        // add required internal nodes to the proof, skipping redundancies
-        for _ in 1..self.depth() {
-            let indexes = next_indexes.clone();
-            next_indexes.truncate(0);
+        self.prove_batch_with_cap(indexes, 0)
+    }
 
-            let mut i = 0;
-            while i < indexes.len() {
-                let sibling_index = indexes[i] ^ 1;
-                if i + 1 < indexes.len() && indexes[i + 1] == sibling_index {
-                    i += 1;
+    /// Computes Merkle paths for the provided indexes up to the tree cap of the specified height,
+    /// and compresses the paths into a single proof.
+    ///
+    /// The resulting proof does not include any nodes located above the cap, and thus, must be
+    /// verified against the cap returned by [MerkleTree::cap()] for the same `cap_height`. As for
+    /// [MerkleTree::cap()], cap height is capped at `depth - 1`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * No indexes were provided (i.e., `indexes` is an empty slice).
+    /// * Number of provided indexes is greater than 255.
+    /// * Any of the provided indexes are greater than or equal to the number of leaves in the
+    ///   tree.
+    /// * List of indexes contains duplicates.
+    pub fn prove_batch_with_cap(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+    ) -> Result<BatchMerkleProof<H>, MerkleTreeError> {
+        let num_leaves = self.leaves.len();
+        BatchMerkleProof::build(
+            indexes,
+            self.depth(),
+            self.clamp_cap_height(cap_height),
+            self.arity,
+            |i| {
+                if i >= num_leaves {
+                    self.leaves[i - num_leaves]
                 } else {
-                    nodes[i].push(self.nodes[sibling_index]);
+                    self.nodes[i]
                 }
-
-                // add parent index to the set of next indexes
-                next_indexes.push(sibling_index >> 1);
-
-                i += 1;
-            }
-        }
-
-        Ok(BatchMerkleProof {
-            leaves,
-            nodes,
-            depth: self.depth() as u8,
-        })
+            },
+        )
     }
 
     // VERIFICATION METHODS
     // --------------------------------------------------------------------------------------------
 
-    /// Checks whether the `proof` for the specified `index` is valid.
+    /// Checks whether the `proof` for the specified `index` is valid for a binary tree.
     ///
     /// # Errors
     /// Returns an error if the specified `proof` (which is a Merkle path) does not resolve to the
@@ -287,17 +326,44 @@ impl<H: Hasher> MerkleTree<H> {
         index: usize,
         proof: &[H::Digest],
     ) -> Result<(), MerkleTreeError> {
-        let r = index & 1;
-        let mut v = H::merge(&[proof[r], proof[1 - r]]);
-
-        let mut index = (index + 2usize.pow((proof.len() - 1) as u32)) >> 1;
-        for &p in proof.iter().skip(2) {
-            v = if index & 1 == 0 {
-                H::merge(&[v, p])
-            } else {
-                H::merge(&[p, v])
-            };
-            index >>= 1;
+        Self::verify_with_arity(root, index, proof, 2)
+    }
+
+    /// Checks whether the `proof` for the specified `index` is valid for a tree of the specified
+    /// `arity`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `arity` is not 2, 4, 8, or 16.
+    /// * The specified `proof` (which is a Merkle path) does not resolve to the specified `root`.
+    pub fn verify_with_arity(
+        root: H::Digest,
+        index: usize,
+        proof: &[H::Digest],
+        arity: usize,
+    ) -> Result<(), MerkleTreeError> {
+        validate_arity(arity)?;
+        if proof.is_empty() || (proof.len() - 1) % (arity - 1) != 0 {
+            return Err(MerkleTreeError::InvalidProof);
+        }
+
+        let mut v = proof[0];
+        let mut index = index;
+        let mut children = vec![H::Digest::default(); arity];
+        for siblings in proof[1..].chunks(arity - 1) {
+            // insert the current node into the list of its siblings at its position
+            let position = index % arity;
+            let mut siblings = siblings.iter();
+            for (i, child) in children.iter_mut().enumerate() {
+                *child = if i == position {
+                    v
+                } else {
+                    *siblings.next().unwrap()
+                };
+            }
+
+            v = merge_nodes::<H>(&children);
+            index /= arity;
         }
 
         if v != root {
@@ -308,8 +374,11 @@ impl<H: Hasher> MerkleTree<H> {
 
     /// Checks whether the batch proof contains Merkle paths for the of the specified `indexes`.
     ///
+    /// The arity of the tree is defined by the arity of the batch proof.
+    ///
     /// # Errors
     /// Returns an error if:
+    /// * Arity of the batch proof is not 2, 4, 8, or 16.
     /// * No indexes were provided (i.e., `indexes` is an empty slice).
     /// * Number of provided indexes is greater than 255.
     /// * Any of the specified `indexes` is greater than or equal to the number of leaves in the
@@ -326,49 +395,204 @@ impl<H: Hasher> MerkleTree<H> {
         }
         Ok(())
     }
+
+    /// Checks whether the batch proof contains Merkle paths from the specified `indexes` to the
+    /// specified tree `cap`.
+    ///
+    /// Cap height is inferred from the number of nodes in the cap, and thus, a cap consisting of
+    /// a single node is equivalent to the tree root. The arity of the tree is defined by the arity
+    /// of the batch proof.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Arity of the batch proof is not 2, 4, 8, or 16.
+    /// * The number of nodes in the `cap` is not a power of the arity.
+    /// * Cap height is greater than or equal to the depth of the tree from which the batch proof
+    ///   was generated.
+    /// * No indexes were provided (i.e., `indexes` is an empty slice).
+    /// * Number of provided indexes is greater than 255.
+    /// * Any of the specified `indexes` is greater than or equal to the number of leaves in the
+    ///   tree from which the batch proof was generated.
+    /// * List of indexes contains duplicates.
+    /// * Any of the paths in the batch proof does not resolve to the corresponding node of the
+    ///   specified `cap`.
+    pub fn verify_batch_with_cap(
+        cap: &[H::Digest],
+        indexes: &[usize],
+        proof: &BatchMerkleProof<H>,
+    ) -> Result<(), MerkleTreeError> {
+        let cap_height = get_cap_height(cap.len(), proof.arity)?;
+        let cap_offset = cap.len();
+
+        let nodes = proof.resolve_nodes(indexes, cap_height)?;
+        for (&index, node) in nodes.range(cap_offset..2 * cap_offset) {
+            if *node != cap[index - cap_offset] {
+                return Err(MerkleTreeError::InvalidProof);
+            }
+        }
+        Ok(())
+    }
+
+    /// Computes the root of a tree of the specified `arity` from the specified tree `cap`.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `arity` is not 2, 4, 8, or 16.
+    /// * The number of nodes in the `cap` is not a power of `arity`.
+    pub fn get_cap_root(cap: &[H::Digest], arity: usize) -> Result<H::Digest, MerkleTreeError> {
+        get_cap_height(cap.len(), arity)?;
+
+        let mut nodes = cap.to_vec();
+        while nodes.len() > 1 {
+            nodes = nodes.chunks(arity).map(merge_nodes::<H>).collect();
+        }
+        Ok(nodes[0])
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the specified cap height reduced to at most `depth - 1`.
+    fn clamp_cap_height(&self, cap_height: usize) -> usize {
+        cap_height.min(self.depth() - 1)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<H: Hasher> Serializable for MerkleTree<H> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    ///
+    /// Both the leaves and the internal nodes of the tree are written, and thus, reading the tree
+    /// back does not require re-hashing.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(self.arity as u8);
+        target.write_u64(self.leaves.len() as u64);
+        self.leaves.write_into(target);
+        self.nodes.write_into(target);
+    }
+}
+
+impl<H: Hasher> Deserializable for MerkleTree<H> {
+    /// Reads a Merkle tree from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid Merkle tree could not be read from the `source`. Internal nodes
+    /// of the tree are not checked for consistency with the leaves.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let arity = source.read_u8()? as usize;
+        let num_leaves = source.read_u64()? as usize;
+        validate_leaves(num_leaves, arity)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+
+        let leaves = H::Digest::read_batch_from(source, num_leaves)?;
+        let nodes = H::Digest::read_batch_from(source, 2 * num_leaves / arity)?;
+        Ok(MerkleTree {
+            nodes,
+            leaves,
+            arity,
+        })
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Returns the internal nodes of a Merkle tree defined by the specified leaves.
-///
-/// The internal nodes are turned as a vector where the root is stored at position 1, its children
-/// are stored at positions 2, 3, their children are stored at positions 4, 5, 6, 7 etc.
-///
-/// This function is exposed primarily for benchmarking purposes. It is not intended to be used
-/// directly by the end users of the crate.
+/// Builds all internal nodes of a binary Merkle tree and stores the results in a single vector
+/// such that root of the tree is at position 1, nodes immediately under the root is at positions
+/// 2 and 3 etc.
 pub fn build_merkle_nodes<H: Hasher>(leaves: &[H::Digest]) -> Vec<H::Digest> {
-    let n = leaves.len() / 2;
+    build_nary_merkle_nodes::<H>(leaves, 2)
+}
 
-    // create un-initialized array to hold all intermediate nodes
+/// Builds all internal nodes of a Merkle tree of the specified `arity` and stores the results in
+/// a single vector such that root of the tree is at position 1, and children of the node at
+/// position `i` are at positions `arity * i`, ..., `arity * i + arity - 1`.
+///
+/// Thus, nodes at depth `d` of the tree occupy positions arity^d, ..., 2 * arity^d - 1. For arity
+/// greater than 2, the positions between levels are not used and are set to the default digest
+/// value.
+pub fn build_nary_merkle_nodes<H: Hasher>(leaves: &[H::Digest], arity: usize) -> Vec<H::Digest> {
+    let n = leaves.len() / arity;
+
+    // create un-initialized array to hold all intermediate nodes; the positions which are not
+    // used (including position 0) are set to the default value
     let mut nodes = unsafe { utils::uninit_vector::<H::Digest>(2 * n) };
-    nodes[0] = H::Digest::default();
-
-    // re-interpret leaves as an array of two leaves fused together
-    let two_leaves = unsafe { slice::from_raw_parts(leaves.as_ptr() as *const [H::Digest; 2], n) };
+    nodes[..n].fill(H::Digest::default());
 
     // build first row of internal nodes (parents of leaves)
-    for (i, j) in (0..n).zip(n..nodes.len()) {
-        nodes[j] = H::merge(&two_leaves[i]);
+    for (node, children) in nodes[n..].iter_mut().zip(leaves.chunks(arity)) {
+        *node = merge_nodes::<H>(children);
     }
 
-    // re-interpret nodes as an array of two nodes fused together
-    let two_nodes = unsafe { slice::from_raw_parts(nodes.as_ptr() as *const [H::Digest; 2], n) };
-
-    // calculate all other tree nodes
-    for i in (1..n).rev() {
-        nodes[i] = H::merge(&two_nodes[i]);
+    // calculate all other tree nodes level by level
+    let mut level_start = n / arity;
+    while level_start > 0 {
+        let (parents, children) = nodes.split_at_mut(arity * level_start);
+        let grouped_children = children[..arity * level_start].chunks(arity);
+        for (node, children) in parents[level_start..].iter_mut().zip(grouped_children) {
+            *node = merge_nodes::<H>(children);
+        }
+        level_start /= arity;
     }
 
     nodes
 }
 
+/// Hashes sibling nodes into their parent node.
+///
+/// Nodes of binary trees are merged via [Hasher::merge()], while nodes of trees of higher arity
+/// are merged via [Hasher::merge_many()].
+#[inline(always)]
+fn merge_nodes<H: Hasher>(children: &[H::Digest]) -> H::Digest {
+    if children.len() == 2 {
+        H::merge(&[children[0], children[1]])
+    } else {
+        H::merge_many(children)
+    }
+}
+
+/// Returns an error if the tree `arity` is not supported.
+pub(crate) fn validate_arity(arity: usize) -> Result<(), MerkleTreeError> {
+    if !matches!(arity, 2 | 4 | 8 | 16) {
+        return Err(MerkleTreeError::UnsupportedArity(arity));
+    }
+    Ok(())
+}
+
+/// Returns an error if a tree of the specified `arity` cannot be built from the specified number
+/// of leaves.
+fn validate_leaves(num_leaves: usize, arity: usize) -> Result<(), MerkleTreeError> {
+    validate_arity(arity)?;
+    if num_leaves < arity {
+        return Err(MerkleTreeError::TooFewLeaves(arity, num_leaves));
+    }
+    if !num_leaves.is_power_of_two() {
+        return Err(MerkleTreeError::NumberOfLeavesNotPowerOfTwo(num_leaves));
+    }
+    if num_leaves.ilog2() % arity.ilog2() != 0 {
+        return Err(MerkleTreeError::NumberOfLeavesNotPowerOfArity(
+            arity, num_leaves,
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the height of a cap of a tree of the specified `arity` consisting of the specified
+/// number of nodes.
+fn get_cap_height(num_nodes: usize, arity: usize) -> Result<usize, MerkleTreeError> {
+    validate_arity(arity)?;
+    if !num_nodes.is_power_of_two() || num_nodes.ilog2() % arity.ilog2() != 0 {
+        return Err(MerkleTreeError::InvalidNumberOfCapNodes(arity, num_nodes));
+    }
+    Ok((num_nodes.ilog2() / arity.ilog2()) as usize)
+}
+
 fn map_indexes(
     indexes: &[usize],
-    tree_depth: usize,
+    num_leaves: usize,
 ) -> Result<BTreeMap<usize, usize>, MerkleTreeError> {
-    let num_leaves = 2usize.pow(tree_depth as u32);
     let mut map = BTreeMap::new();
     for (i, index) in indexes.iter().cloned().enumerate() {
         map.insert(index, i);
@@ -384,10 +608,12 @@ fn map_indexes(
     Ok(map)
 }
 
-fn normalize_indexes(indexes: &[usize]) -> Vec<usize> {
+/// Replaces each index with the index of the first leaf in its group of `arity` siblings, and
+/// returns the resulting set of indexes sorted in ascending order.
+fn normalize_indexes(indexes: &[usize], arity: usize) -> Vec<usize> {
     let mut set = BTreeSet::new();
     for &index in indexes {
-        set.insert(index - (index & 1));
+        set.insert(index - index % arity);
     }
     set.into_iter().collect()
 }
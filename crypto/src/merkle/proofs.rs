@@ -27,6 +27,15 @@ pub(super) const MAX_PATHS: usize = 255;
 ///
 /// Currently, at most 255 paths can be aggregated into a single proof. This limitation is
 /// imposed primarily for serialization purposes.
+///
+/// A proof generated via [MerkleTree::prove_batch_with_cap()](super::MerkleTree::prove_batch_with_cap)
+/// stops at the level of the tree cap rather than at the root, and thus, can be verified only
+/// against the cap (via [MerkleTree::verify_batch_with_cap()](super::MerkleTree::verify_batch_with_cap)).
+///
+/// Proofs for trees of arity greater than two are structured in the same way as proofs for
+/// binary trees, except that for every node on a path, the proof may include up to `arity - 1`
+/// of its siblings (listed from left to right). The arity is not serialized with the proof, and
+/// thus, must be known to the party deserializing the proof.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct BatchMerkleProof<H: Hasher> {
     /// The leaves being proven
@@ -35,19 +44,33 @@ pub struct BatchMerkleProof<H: Hasher> {
     pub nodes: Vec<Vec<H::Digest>>,
     /// Depth of the leaves
     pub depth: u8,
+    /// Arity of the tree
+    pub arity: usize,
 }
 
 impl<H: Hasher> BatchMerkleProof<H> {
-    /// Constructs a batch Merkle proof from individual Merkle authentication paths.
+    /// Constructs a batch Merkle proof from individual Merkle authentication paths in a tree of
+    /// the specified `arity`.
     ///
     /// # Panics
     /// Panics if:
+    /// * `arity` is not 2, 4, 8, or 16.
     /// * No paths have been provided (i.e., `paths` is an empty slice).
     /// * More than 255 paths have been provided.
     /// * Number of paths is not equal to the number of indexes.
-    /// * Not all paths have the same length.
-    pub fn from_paths(paths: &[Vec<H::Digest>], indexes: &[usize]) -> BatchMerkleProof<H> {
-        // TODO: optimize this to reduce amount of vector cloning.
+    /// * Not all paths have the same length, or the length is not valid for a tree of the
+    ///   specified `arity`.
+    /// * Any of the indexes is out of bounds for the tree, or the list of indexes contains
+    ///   duplicates.
+    pub fn from_paths(
+        paths: &[Vec<H::Digest>],
+        indexes: &[usize],
+        arity: usize,
+    ) -> BatchMerkleProof<H> {
+        assert!(
+            super::validate_arity(arity).is_ok(),
+            "tree arity {arity} is not supported"
+        );
         assert!(!paths.is_empty(), "at least one path must be provided");
         assert!(
             paths.len() <= MAX_PATHS,
@@ -59,68 +82,128 @@ impl<H: Hasher> BatchMerkleProof<H> {
             "number of paths must equal number of indexes"
         );
 
-        let depth = paths[0].len();
+        let path_len = paths[0].len();
+        assert!(
+            path_len > 1 && (path_len - 1) % (arity - 1) == 0,
+            "invalid path length"
+        );
+        let depth = (path_len - 1) / (arity - 1);
+        let num_leaves = arity.checked_pow(depth as u32).expect("paths are too long");
+
+        // put all nodes from the paths into a partial tree
+        let mut partial_tree = BTreeMap::new();
+        for (&index, path) in indexes.iter().zip(paths.iter()) {
+            assert_eq!(path_len, path.len(), "not all paths have the same length");
+            assert!(index < num_leaves, "leaf index {index} is out of bounds");
+
+            let mut index = index + num_leaves;
+            partial_tree.insert(index, path[0]);
+            for siblings in path[1..].chunks(arity - 1) {
+                let first_sibling = index - index % arity;
+                let sibling_indexes =
+                    (first_sibling..first_sibling + arity).filter(|&i| i != index);
+                for (i, &sibling) in sibling_indexes.zip(siblings) {
+                    partial_tree.insert(i, sibling);
+                }
+                index /= arity;
+            }
+        }
+
+        // all nodes needed to build the proof are siblings of the nodes on the paths, and thus,
+        // are guaranteed to be present in the partial tree
+        Self::build(indexes, depth, 0, arity, |i| partial_tree[&i])
+            .expect("failed to build a batch proof from paths")
+    }
 
-        // sort indexes in ascending order, and also re-arrange paths accordingly
-        let mut path_map = BTreeMap::new();
-        for (&index, path) in indexes.iter().zip(paths.iter().cloned()) {
-            assert_eq!(depth, path.len(), "not all paths have the same length");
-            path_map.insert(index, path);
+    /// Builds a batch Merkle proof for the specified `indexes` in a tree of the specified
+    /// `depth` and `arity`. The proof stops at the tree cap located at `cap_height`.
+    ///
+    /// Nodes of the tree are retrieved via `get_node` closure. The closure takes a node index,
+    /// where the root has index 1 and children of node `i` have indexes `arity * i`, ...,
+    /// `arity * i + arity - 1`; thus, the leaves have indexes in the range
+    /// [arity^depth, 2 * arity^depth).
+    pub(super) fn build<F>(
+        indexes: &[usize],
+        depth: usize,
+        cap_height: usize,
+        arity: usize,
+        get_node: F,
+    ) -> Result<Self, MerkleTreeError>
+    where
+        F: Fn(usize) -> H::Digest,
+    {
+        if indexes.is_empty() {
+            return Err(MerkleTreeError::TooFewLeafIndexes);
+        }
+        if indexes.len() > MAX_PATHS {
+            return Err(MerkleTreeError::TooManyLeafIndexes(
+                MAX_PATHS,
+                indexes.len(),
+            ));
         }
-        let indexes = path_map.keys().cloned().collect::<Vec<_>>();
-        let paths = path_map.values().cloned().collect::<Vec<_>>();
-        path_map.clear();
 
-        let mut leaves = vec![H::Digest::default(); indexes.len()];
+        let num_leaves = arity.pow(depth as u32);
+        let index_map = super::map_indexes(indexes, num_leaves)?;
+        let indexes = super::normalize_indexes(indexes, arity);
+        let mut leaves = vec![H::Digest::default(); index_map.len()];
         let mut nodes: Vec<Vec<H::Digest>> = Vec::with_capacity(indexes.len());
 
-        // populate values and the first layer of proof nodes
-        let mut i = 0;
-        while i < indexes.len() {
-            leaves[i] = paths[i][0];
-            if indexes.len() > i + 1 && are_siblings(indexes[i], indexes[i + 1]) {
-                leaves[i + 1] = paths[i][1];
-                nodes.push(vec![]);
-                i += 1;
-            } else {
-                nodes.push(vec![paths[i][1]]);
-            }
-            path_map.insert(indexes[i] >> 1, paths[i].clone());
-            i += 1;
+        // populate the proof with leaf node values
+        let mut next_indexes: Vec<usize> = Vec::new();
+        for index in indexes {
+            let missing: Vec<H::Digest> = (index..index + arity)
+                .flat_map(|i| {
+                    let v = get_node(i + num_leaves);
+                    if let Some(idx) = index_map.get(&i) {
+                        leaves[*idx] = v;
+                        None
+                    } else {
+                        Some(v)
+                    }
+                })
+                .collect();
+            nodes.push(missing);
+
+            next_indexes.push((index + num_leaves) / arity);
         }
 
-        // populate all remaining layers of proof nodes
-        for d in 2..depth {
-            let indexes = path_map.keys().cloned().collect::<Vec<_>>();
-            let mut next_path_map = BTreeMap::new();
+        // add required internal nodes to the proof, skipping redundancies; nodes at the cap level
+        // and above are not included in the proof
+        for _ in 1..(depth - cap_height) {
+            let indexes = core::mem::take(&mut next_indexes);
 
             let mut i = 0;
             while i < indexes.len() {
-                let index = indexes[i];
-                let path = path_map.get(&index).unwrap();
-                if indexes.len() > i + 1 && are_siblings(index, indexes[i + 1]) {
-                    i += 1;
-                } else {
-                    nodes[i].push(path[d]);
+                // nodes with the same parent are located next to each other in the list; the
+                // siblings which are not in the list are added to the proof
+                let run_end = get_siblings_end(&indexes, i, arity);
+                let parent_index = indexes[i] / arity;
+                for sibling_index in arity * parent_index..arity * parent_index + arity {
+                    if !indexes[i..run_end].contains(&sibling_index) {
+                        nodes[i].push(get_node(sibling_index));
+                    }
                 }
-                next_path_map.insert(index >> 1, path.clone());
-                i += 1;
-            }
 
-            core::mem::swap(&mut path_map, &mut next_path_map);
+                // add parent index to the set of next indexes
+                next_indexes.push(parent_index);
+
+                i = run_end;
+            }
         }
 
-        BatchMerkleProof {
+        Ok(BatchMerkleProof {
             leaves,
             nodes,
-            depth: (depth - 1) as u8,
-        }
+            depth: depth as u8,
+            arity,
+        })
     }
 
     /// Computes a node to which all Merkle paths aggregated in this proof resolve.
     ///
     /// # Errors
     /// Returns an error if:
+    /// * Arity of this proof is not 2, 4, 8, or 16.
     /// * No indexes were provided (i.e., `indexes` is an empty slice).
     /// * Number of provided indexes is greater than 255.
     /// * Any of the specified `indexes` is greater than or equal to the number of leaves in the
@@ -128,6 +211,31 @@ impl<H: Hasher> BatchMerkleProof<H> {
     /// * List of indexes contains duplicates.
     /// * The proof does not resolve to a single root.
     pub fn get_root(&self, indexes: &[usize]) -> Result<H::Digest, MerkleTreeError> {
+        self.resolve_nodes(indexes, 0)?
+            .remove(&1)
+            .ok_or(MerkleTreeError::InvalidProof)
+    }
+
+    /// Computes all nodes which can be derived from this proof, stopping at the level of the tree
+    /// located at `cap_height`.
+    ///
+    /// The returned map is keyed by node index, where the root has index 1 and children of node
+    /// `i` have indexes `arity * i`, ..., `arity * i + arity - 1`. Thus, nodes at the level
+    /// `cap_height` have indexes in the range [arity^cap_height, 2 * arity^cap_height). Besides the computed nodes, the map
+    /// also contains all leaves and all sibling nodes included in the proof.
+    pub(super) fn resolve_nodes(
+        &self,
+        indexes: &[usize],
+        cap_height: usize,
+    ) -> Result<BTreeMap<usize, H::Digest>, MerkleTreeError> {
+        let arity = self.arity;
+        super::validate_arity(arity)?;
+        if cap_height >= self.depth as usize {
+            return Err(MerkleTreeError::InvalidCapHeight(
+                (self.depth as usize).saturating_sub(1),
+                cap_height,
+            ));
+        }
         if indexes.is_empty() {
             return Err(MerkleTreeError::TooFewLeafIndexes);
         }
@@ -138,129 +246,88 @@ impl<H: Hasher> BatchMerkleProof<H> {
             ));
         }
 
-        let mut buf = [H::Digest::default(); 2];
+        let num_leaves = arity
+            .checked_pow(self.depth as u32)
+            .ok_or(MerkleTreeError::InvalidProof)?;
+
+        let mut buf = vec![H::Digest::default(); arity];
         let mut v = BTreeMap::new();
 
-        // replace odd indexes, offset, and sort in ascending order
-        let index_map = super::map_indexes(indexes, self.depth as usize)?;
-        let indexes = super::normalize_indexes(indexes);
+        // replace indexes with indexes of their first siblings, and sort in ascending order
+        let index_map = super::map_indexes(indexes, num_leaves)?;
+        let indexes = super::normalize_indexes(indexes, arity);
         if indexes.len() != self.nodes.len() {
             return Err(MerkleTreeError::InvalidProof);
         }
 
         // for each index use values to compute parent nodes
-        let offset = 2usize.pow(self.depth as u32);
         let mut next_indexes: Vec<usize> = Vec::new();
         let mut proof_pointers: Vec<usize> = Vec::with_capacity(indexes.len());
         for (i, index) in indexes.into_iter().enumerate() {
-            // copy values of leaf sibling leaf nodes into the buffer
-            match index_map.get(&index) {
-                Some(&index1) => {
-                    if self.leaves.len() <= index1 {
-                        return Err(MerkleTreeError::InvalidProof);
-                    }
-                    buf[0] = self.leaves[index1];
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.leaves.len() <= index2 {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.leaves[index2];
-                            proof_pointers.push(0);
-                        }
-                        None => {
-                            if self.nodes[i].is_empty() {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.nodes[i][0];
-                            proof_pointers.push(1);
-                        }
-                    }
-                }
-                None => {
-                    if self.nodes[i].is_empty() {
-                        return Err(MerkleTreeError::InvalidProof);
-                    }
-                    buf[0] = self.nodes[i][0];
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.leaves.len() <= index2 {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.leaves[index2];
-                        }
-                        None => return Err(MerkleTreeError::InvalidProof),
+            // copy values of sibling leaf nodes into the buffer; the leaves which are not being
+            // proven are taken from the proof
+            let mut pointer = 0;
+            for (j, value) in buf.iter_mut().enumerate() {
+                *value = match index_map.get(&(index + j)) {
+                    Some(&leaf_index) => self.get_leaf(leaf_index)?,
+                    None => {
+                        pointer += 1;
+                        self.get_node(i, pointer - 1)?
                     }
-                    proof_pointers.push(1);
-                }
+                };
+                v.insert(num_leaves + index + j, *value);
             }
+            proof_pointers.push(pointer);
 
             // hash sibling nodes into their parent
-            let parent = H::merge(&buf);
+            let parent = super::merge_nodes::<H>(&buf);
 
-            let parent_index = (offset + index) >> 1;
+            let parent_index = (num_leaves + index) / arity;
             v.insert(parent_index, parent);
             next_indexes.push(parent_index);
         }
 
-        // iteratively move up, until we get to the root
-        for _ in 1..self.depth {
-            let indexes = next_indexes.clone();
-            next_indexes.truncate(0);
+        // iteratively move up, until we get to the root (or to the cap)
+        for _ in 1..(self.depth as usize - cap_height) {
+            let indexes = core::mem::take(&mut next_indexes);
 
             let mut i = 0;
             while i < indexes.len() {
-                let node_index = indexes[i];
-                let sibling_index = node_index ^ 1;
-
-                // determine the sibling
-                let sibling: H::Digest;
-                if i + 1 < indexes.len() && indexes[i + 1] == sibling_index {
-                    sibling = match v.get(&sibling_index) {
-                        Some(sibling) => *sibling,
-                        None => return Err(MerkleTreeError::InvalidProof),
-                    };
-                    i += 1;
-                } else {
-                    let pointer = proof_pointers[i];
-                    if self.nodes[i].len() <= pointer {
-                        return Err(MerkleTreeError::InvalidProof);
+                let run_end = get_siblings_end(&indexes, i, arity);
+                let parent_index = indexes[i] / arity;
+
+                // determine the siblings; the ones which have already been computed are taken from
+                // the map of hashed nodes, and the rest are taken from the proof
+                for (j, value) in buf.iter_mut().enumerate() {
+                    let node_index = arity * parent_index + j;
+                    if indexes[i..run_end].contains(&node_index) {
+                        *value = match v.get(&node_index) {
+                            Some(node) => *node,
+                            None => return Err(MerkleTreeError::InvalidProof),
+                        };
+                    } else {
+                        *value = self.get_node(i, proof_pointers[i])?;
+                        proof_pointers[i] += 1;
+                        v.insert(node_index, *value);
                     }
-                    sibling = self.nodes[i][pointer];
-                    proof_pointers[i] += 1;
-                }
-
-                // get the node from the map of hashed nodes
-                let node = match v.get(&node_index) {
-                    Some(node) => node,
-                    None => return Err(MerkleTreeError::InvalidProof),
-                };
-
-                // compute parent node from node and sibling
-                if node_index & 1 != 0 {
-                    buf[0] = sibling;
-                    buf[1] = *node;
-                } else {
-                    buf[0] = *node;
-                    buf[1] = sibling;
                 }
-                let parent = H::merge(&buf);
 
-                // add the parent node to the next set of nodes
-                let parent_index = node_index >> 1;
+                // compute parent node from the siblings and add it to the next set of nodes
+                let parent = super::merge_nodes::<H>(&buf);
                 v.insert(parent_index, parent);
                 next_indexes.push(parent_index);
 
-                i += 1;
+                i = run_end;
             }
         }
-        v.remove(&1).ok_or(MerkleTreeError::InvalidProof)
+        Ok(v)
     }
 
     /// Computes the uncompressed Merkle paths which aggregate to this proof.
     ///
     /// # Errors
     /// Returns an error if:
+    /// * Arity of this proof is not 2, 4, 8, or 16.
     /// * No indexes were provided (i.e., `indexes` is an empty slice).
     /// * Number of provided indexes is greater than 255.
     /// * Number of provided indexes does not match the number of leaf nodes in the proof.
@@ -278,134 +345,32 @@ impl<H: Hasher> BatchMerkleProof<H> {
             return Err(MerkleTreeError::InvalidProof);
         }
 
-        let mut partial_tree_map = BTreeMap::new();
+        // the map of resolved nodes contains all leaves and all their siblings along the paths
+        let partial_tree_map = self.resolve_nodes(indexes, 0)?;
 
-        for (&i, leaf) in indexes.iter().zip(self.leaves.iter()) {
-            partial_tree_map.insert(i + (1 << (self.depth)), *leaf);
-        }
-
-        let mut buf = [H::Digest::default(); 2];
-        let mut v = BTreeMap::new();
-
-        // replace odd indexes, offset, and sort in ascending order
-        let original_indexes = indexes;
-        let index_map = super::map_indexes(indexes, self.depth as usize)?;
-        let indexes = super::normalize_indexes(indexes);
-        if indexes.len() != self.nodes.len() {
-            return Err(MerkleTreeError::InvalidProof);
-        }
-
-        // for each index use values to compute parent nodes
-        let offset = 2usize.pow(self.depth as u32);
-        let mut next_indexes: Vec<usize> = Vec::new();
-        let mut proof_pointers: Vec<usize> = Vec::with_capacity(indexes.len());
-        for (i, index) in indexes.into_iter().enumerate() {
-            // copy values of leaf sibling leaf nodes into the buffer
-            match index_map.get(&index) {
-                Some(&index1) => {
-                    if self.leaves.len() <= index1 {
-                        return Err(MerkleTreeError::InvalidProof);
-                    }
-                    buf[0] = self.leaves[index1];
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.leaves.len() <= index2 {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.leaves[index2];
-                            proof_pointers.push(0);
-                        }
-                        None => {
-                            if self.nodes[i].is_empty() {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.nodes[i][0];
-                            proof_pointers.push(1);
-                        }
-                    }
-                }
-                None => {
-                    if self.nodes[i].is_empty() {
-                        return Err(MerkleTreeError::InvalidProof);
-                    }
-                    buf[0] = self.nodes[i][0];
-                    match index_map.get(&(index + 1)) {
-                        Some(&index2) => {
-                            if self.leaves.len() <= index2 {
-                                return Err(MerkleTreeError::InvalidProof);
-                            }
-                            buf[1] = self.leaves[index2];
-                        }
-                        None => return Err(MerkleTreeError::InvalidProof),
-                    }
-                    proof_pointers.push(1);
-                }
-            }
-
-            // hash sibling nodes into their parent and add it to partial_tree
-            let parent = H::merge(&buf);
-            partial_tree_map.insert(offset + index, buf[0]);
-            partial_tree_map.insert((offset + index) ^ 1, buf[1]);
-            let parent_index = (offset + index) >> 1;
-            v.insert(parent_index, parent);
-            next_indexes.push(parent_index);
-            partial_tree_map.insert(parent_index, parent);
-        }
-
-        // iteratively move up, until we get to the root
-        for _ in 1..self.depth {
-            let indexes = next_indexes.clone();
-            next_indexes.clear();
-
-            let mut i = 0;
-            while i < indexes.len() {
-                let node_index = indexes[i];
-                let sibling_index = node_index ^ 1;
-
-                // determine the sibling
-                let sibling = if i + 1 < indexes.len() && indexes[i + 1] == sibling_index {
-                    i += 1;
-                    match v.get(&sibling_index) {
-                        Some(sibling) => *sibling,
-                        None => return Err(MerkleTreeError::InvalidProof),
-                    }
-                } else {
-                    let pointer = proof_pointers[i];
-                    if self.nodes[i].len() <= pointer {
-                        return Err(MerkleTreeError::InvalidProof);
-                    }
-                    proof_pointers[i] += 1;
-                    self.nodes[i][pointer]
-                };
-
-                // get the node from the map of hashed nodes
-                let node = match v.get(&node_index) {
-                    Some(node) => node,
-                    None => return Err(MerkleTreeError::InvalidProof),
-                };
-
-                // compute parent node from node and sibling
-                partial_tree_map.insert(node_index ^ 1, sibling);
-                let parent = if node_index & 1 != 0 {
-                    H::merge(&[sibling, *node])
-                } else {
-                    H::merge(&[*node, sibling])
-                };
+        indexes
+            .iter()
+            .map(|&i| get_path::<H>(i, &partial_tree_map, self.depth as usize, self.arity))
+            .collect()
+    }
 
-                // add the parent node to the next set of nodes and partial_tree
-                let parent_index = node_index >> 1;
-                v.insert(parent_index, parent);
-                next_indexes.push(parent_index);
-                partial_tree_map.insert(parent_index, parent);
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
 
-                i += 1;
-            }
-        }
+    /// Returns the leaf at the specified position in the list of proven leaves.
+    fn get_leaf(&self, index: usize) -> Result<H::Digest, MerkleTreeError> {
+        self.leaves
+            .get(index)
+            .copied()
+            .ok_or(MerkleTreeError::InvalidProof)
+    }
 
-        original_indexes
-            .iter()
-            .map(|&i| get_path::<H>(i, &partial_tree_map, self.depth as usize))
-            .collect()
+    /// Returns the node at the specified `pointer` in the `i`-th vector of proof nodes.
+    fn get_node(&self, i: usize, pointer: usize) -> Result<H::Digest, MerkleTreeError> {
+        self.nodes[i]
+            .get(pointer)
+            .copied()
+            .ok_or(MerkleTreeError::InvalidProof)
     }
 
     // SERIALIZATION / DESERIALIZATION
@@ -438,19 +403,23 @@ impl<H: Hasher> BatchMerkleProof<H> {
     }
 
     /// Parses internal nodes from the provided `node_bytes`, and constructs a batch Merkle proof
-    /// from these nodes, provided `leaves`, and provided tree `depth`.
+    /// from these nodes, provided `leaves`, and provided tree `depth` and `arity`.
     ///
     /// # Errors
     /// Returns an error if:
     /// * No leaves were provided (i.e., `leaves` is an empty slice).
     /// * Number of provided leaves is greater than 255.
     /// * Tree `depth` was set to zero.
+    /// * Tree `arity` is not 2, 4, 8, or 16.
     /// * `node_bytes` could not be deserialized into a valid set of internal nodes.
     pub fn deserialize<R: ByteReader>(
         node_bytes: &mut R,
         leaves: Vec<H::Digest>,
         depth: u8,
+        arity: usize,
     ) -> Result<Self, DeserializationError> {
+        super::validate_arity(arity)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
         if depth == 0 {
             return Err(DeserializationError::InvalidValue(
                 "tree depth must be greater than zero".to_string(),
@@ -484,6 +453,7 @@ impl<H: Hasher> BatchMerkleProof<H> {
             leaves,
             nodes,
             depth,
+            arity,
         })
     }
 }
@@ -491,10 +461,15 @@ impl<H: Hasher> BatchMerkleProof<H> {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Two nodes are siblings if index of the left node is even and right node
-/// immediately follows the left node.
-fn are_siblings(left: usize, right: usize) -> bool {
-    left & 1 == 0 && right - 1 == left
+/// Returns the end of the run of nodes which start at position `start` in the sorted list of
+/// node `indexes` and share the same parent in a tree of the specified `arity`.
+fn get_siblings_end(indexes: &[usize], start: usize, arity: usize) -> usize {
+    let parent_index = indexes[start] / arity;
+    let mut end = start + 1;
+    while end < indexes.len() && indexes[end] / arity == parent_index {
+        end += 1;
+    }
+    end
 }
 
 /// Computes the Merkle path from the computed (partial) tree.
@@ -502,8 +477,9 @@ pub fn get_path<H: Hasher>(
     index: usize,
     tree: &BTreeMap<usize, <H as Hasher>::Digest>,
     depth: usize,
+    arity: usize,
 ) -> Result<Vec<H::Digest>, MerkleTreeError> {
-    let mut index = index + (1 << depth);
+    let mut index = index + arity.pow(depth as u32);
     let leaf = if let Some(leaf) = tree.get(&index) {
         *leaf
     } else {
@@ -512,14 +488,16 @@ pub fn get_path<H: Hasher>(
 
     let mut proof = vec![leaf];
     while index > 1 {
-        let leaf = if let Some(leaf) = tree.get(&(index ^ 1)) {
-            *leaf
-        } else {
-            return Err(MerkleTreeError::InvalidProof);
-        };
-
-        proof.push(leaf);
-        index >>= 1;
+        let first_sibling = index - index % arity;
+        for sibling_index in (first_sibling..first_sibling + arity).filter(|&i| i != index) {
+            let sibling = if let Some(sibling) = tree.get(&sibling_index) {
+                *sibling
+            } else {
+                return Err(MerkleTreeError::InvalidProof);
+            };
+            proof.push(sibling);
+        }
+        index /= arity;
     }
 
     Ok(proof)
@@ -232,6 +232,88 @@ fn verify_batch() {
     assert!(MerkleTree::verify_batch(tree.root(), &[0, 1, 2, 3, 4, 5, 6, 7], &proof).is_ok());
 }
 
+#[test]
+fn tree_cap() {
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone()).unwrap();
+
+    let node2 = hash_2x1(
+        hash_2x1(leaves[0], leaves[1]),
+        hash_2x1(leaves[2], leaves[3]),
+    );
+    let node3 = hash_2x1(
+        hash_2x1(leaves[4], leaves[5]),
+        hash_2x1(leaves[6], leaves[7]),
+    );
+
+    assert_eq!(&[*tree.root()], tree.cap(0));
+    assert_eq!(&[node2, node3], tree.cap(1));
+    assert_eq!(4, tree.cap(2).len());
+
+    // cap height is capped at depth - 1
+    assert_eq!(tree.cap(2), tree.cap(3));
+    assert_eq!(tree.cap(2), tree.cap(10));
+
+    for cap_height in 0..3 {
+        let root = MerkleTree::<Blake3_256>::get_cap_root(tree.cap(cap_height), 2).unwrap();
+        assert_eq!(*tree.root(), root);
+    }
+    assert_eq!(
+        Err(MerkleTreeError::InvalidNumberOfCapNodes(2, 3)),
+        MerkleTree::<Blake3_256>::get_cap_root(&tree.cap(2)[..3], 2)
+    );
+}
+
+#[test]
+fn verify_batch_with_cap() {
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let tree = MerkleTree::<Blake3_256>::new(leaves).unwrap();
+
+    // a cap of height 0 is the same as the root
+    let proof = tree.prove_batch_with_cap(&[1, 6], 0).unwrap();
+    assert_eq!(tree.prove_batch(&[1, 6]).unwrap(), proof);
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(0), &[1, 6], &proof).is_ok());
+
+    // paths to a cap of height 1 contain the leaf sibling and one internal node
+    let proof = tree.prove_batch_with_cap(&[1], 1).unwrap();
+    assert_eq!(2, proof.nodes[0].len());
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(1), &[1], &proof).is_ok());
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(1), &[2], &proof).is_err());
+    assert!(MerkleTree::verify_batch(tree.root(), &[1], &proof).is_err());
+
+    let proof = tree.prove_batch_with_cap(&[1, 3, 6], 1).unwrap();
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(1), &[1, 3, 6], &proof).is_ok());
+
+    // a cap of height 2 consists of parents of leaves; only leaf siblings are in the proof
+    let proof = tree.prove_batch_with_cap(&[0, 5], 2).unwrap();
+    assert_eq!(
+        vec![vec![leaves_at(&tree, 1)], vec![leaves_at(&tree, 4)]],
+        proof.nodes
+    );
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(2), &[0, 5], &proof).is_ok());
+
+    // a proof does not verify against a cap of a different height
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(1), &[0, 5], &proof).is_err());
+
+    // a proof does not verify against a modified cap
+    let mut cap = tree.cap(2).to_vec();
+    cap[2] = cap[3];
+    assert_eq!(
+        Err(MerkleTreeError::InvalidProof),
+        MerkleTree::verify_batch_with_cap(&cap, &[0, 5], &proof)
+    );
+
+    // cap height must be smaller than tree depth and cap size must be a power of two
+    assert_eq!(
+        Err(MerkleTreeError::InvalidCapHeight(2, 3)),
+        MerkleTree::verify_batch_with_cap(tree.leaves(), &[0, 5], &proof)
+    );
+    assert_eq!(
+        Err(MerkleTreeError::InvalidNumberOfCapNodes(2, 3)),
+        MerkleTree::verify_batch_with_cap(&cap[..3], &[0, 5], &proof)
+    );
+}
+
 #[test]
 fn verify_into_paths() {
     let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
@@ -256,9 +338,124 @@ fn verify_into_paths() {
     assert_eq!(proof6, result[2]);
 }
 
+#[test]
+fn new_nary_tree() {
+    let leaves = hashed_leaves(16);
+    let tree = MerkleTree::<Blake3_256>::with_arity(leaves.clone(), 4).unwrap();
+    assert_eq!(2, tree.depth());
+    let parents = leaves
+        .chunks(4)
+        .map(Blake3_256::merge_many)
+        .collect::<Vec<_>>();
+    assert_eq!(&Blake3_256::merge_many(&parents), tree.root());
+    assert_eq!(&parents, tree.cap(1));
+
+    let leaves = Digest256::bytes_as_digests(&LEAVES8).to_vec();
+    let tree = MerkleTree::<Blake3_256>::with_arity(leaves.clone(), 8).unwrap();
+    assert_eq!(1, tree.depth());
+    assert_eq!(&Blake3_256::merge_many(&leaves), tree.root());
+
+    // number of leaves must be a power of the tree arity
+    assert_eq!(
+        Err(MerkleTreeError::NumberOfLeavesNotPowerOfArity(4, 8)),
+        MerkleTree::<Blake3_256>::with_arity(leaves.clone(), 4).map(|_| ())
+    );
+    assert_eq!(
+        Err(MerkleTreeError::TooFewLeaves(8, 4)),
+        MerkleTree::<Blake3_256>::with_arity(leaves[..4].to_vec(), 8).map(|_| ())
+    );
+    assert_eq!(
+        Err(MerkleTreeError::UnsupportedArity(3)),
+        MerkleTree::<Blake3_256>::with_arity(hashed_leaves(9), 3).map(|_| ())
+    );
+}
+
+#[test]
+fn prove_nary() {
+    let leaves = hashed_leaves(16);
+    let tree = MerkleTree::<Blake3_256>::with_arity(leaves.clone(), 4).unwrap();
+    let parents = leaves
+        .chunks(4)
+        .map(Blake3_256::merge_many)
+        .collect::<Vec<_>>();
+
+    let proof = vec![
+        leaves[6], leaves[4], leaves[5], leaves[7], parents[0], parents[2], parents[3],
+    ];
+    assert_eq!(proof, tree.prove(6).unwrap());
+    assert!(MerkleTree::<Blake3_256>::verify_with_arity(*tree.root(), 6, &proof, 4).is_ok());
+    assert_eq!(
+        Err(MerkleTreeError::InvalidProof),
+        MerkleTree::<Blake3_256>::verify_with_arity(*tree.root(), 5, &proof, 4)
+    );
+    assert_eq!(
+        Err(MerkleTreeError::InvalidProof),
+        MerkleTree::<Blake3_256>::verify_with_arity(*tree.root(), 6, &proof[..6], 4)
+    );
+
+    // leaves 4 and 6 share all nodes on their paths, and thus the proof for these leaves contains
+    // only the remaining siblings
+    let proof = tree.prove_batch(&[6, 4]).unwrap();
+    assert_eq!(vec![leaves[6], leaves[4]], proof.leaves);
+    assert_eq!(
+        vec![vec![
+            leaves[5], leaves[7], parents[0], parents[2], parents[3]
+        ]],
+        proof.nodes
+    );
+    assert_eq!(2, proof.depth);
+    assert!(MerkleTree::verify_batch(tree.root(), &[6, 4], &proof).is_ok());
+
+    // against a cap of height 1, only the siblings of the leaves are included in the proof
+    let proof = tree.prove_batch_with_cap(&[6, 4, 13], 1).unwrap();
+    assert_eq!(
+        vec![
+            vec![leaves[5], leaves[7]],
+            vec![leaves[12], leaves[14], leaves[15]]
+        ],
+        proof.nodes
+    );
+    assert!(MerkleTree::verify_batch_with_cap(tree.cap(1), &[6, 4, 13], &proof).is_ok());
+    assert_eq!(
+        Err(MerkleTreeError::InvalidNumberOfCapNodes(4, 2)),
+        MerkleTree::verify_batch_with_cap(&tree.cap(1)[..2], &[6, 4, 13], &proof)
+    );
+    assert_eq!(
+        Ok(*tree.root()),
+        MerkleTree::<Blake3_256>::get_cap_root(tree.cap(1), 4)
+    );
+}
+
+#[test]
+fn tree_serialization() {
+    let leaves = hashed_leaves(16);
+    let tree = MerkleTree::<Blake3_256>::new(leaves.clone()).unwrap();
+    let bytes = tree.to_bytes();
+    let tree2 = MerkleTree::<Blake3_256>::read_from_bytes(&bytes).unwrap();
+    assert_eq!(tree.root(), tree2.root());
+    assert_eq!(tree.leaves(), tree2.leaves());
+    assert_eq!(tree.prove(5).unwrap(), tree2.prove(5).unwrap());
+
+    let tree = MerkleTree::<Blake3_256>::with_arity(leaves, 4).unwrap();
+    let tree2 = MerkleTree::<Blake3_256>::read_from_bytes(&tree.to_bytes()).unwrap();
+    assert_eq!(4, tree2.arity());
+    assert_eq!(tree.root(), tree2.root());
+    assert_eq!(tree.cap(1), tree2.cap(1));
+
+    // a tree with a number of leaves which is not a power of the arity cannot be read
+    let mut bytes8 = MerkleTree::<Blake3_256>::new(hashed_leaves(8))
+        .unwrap()
+        .to_bytes();
+    bytes8[0] = 4;
+    assert!(MerkleTree::<Blake3_256>::read_from_bytes(&bytes8).is_err());
+
+    // truncated bytes cannot be read
+    assert!(MerkleTree::<Blake3_256>::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
+
 proptest! {
     #[test]
-    fn prove_n_verify(tree in random_blake3_merkle_tree(128),
+    fn prove_n_verify(tree in random_blake3_merkle_tree(128, 2),
                       proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
     )  {
         for proof_index in proof_indices{
@@ -268,7 +465,7 @@ proptest! {
     }
 
     #[test]
-    fn prove_batch_n_verify(tree in random_blake3_merkle_tree(128),
+    fn prove_batch_n_verify(tree in random_blake3_merkle_tree(128, 2),
                       proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
     )  {
         let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
@@ -278,7 +475,18 @@ proptest! {
     }
 
     #[test]
-    fn batch_proof_from_paths(tree in random_blake3_merkle_tree(128),
+    fn prove_batch_with_cap_n_verify(tree in random_blake3_merkle_tree(128, 2),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20),
+                      cap_height in 0..7_usize
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let proof = tree.prove_batch_with_cap(&indices[..], cap_height).unwrap();
+        prop_assert!(MerkleTree::verify_batch_with_cap(tree.cap(cap_height), &indices[..], &proof).is_ok());
+    }
+
+    #[test]
+    fn batch_proof_from_paths(tree in random_blake3_merkle_tree(128, 2),
                       proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
     )  {
         let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(128)).collect();
@@ -289,13 +497,13 @@ proptest! {
         for &idx in indices.iter() {
             paths.push(tree.prove(idx).unwrap());
         }
-        let proof2 = BatchMerkleProof::from_paths(&paths, &indices);
+        let proof2 = BatchMerkleProof::from_paths(&paths, &indices, 2);
 
         prop_assert!(proof1 == proof2);
     }
 
     #[test]
-    fn into_paths(tree in random_blake3_merkle_tree(32),
+    fn into_paths(tree in random_blake3_merkle_tree(32, 2),
                       proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 1..30)
     )  {
         let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(32)).collect();
@@ -313,17 +521,84 @@ proptest! {
     }
 }
 
+proptest! {
+    #[test]
+    fn prove_nary_n_verify(tree4 in random_blake3_merkle_tree(256, 4),
+                      tree8 in random_blake3_merkle_tree(512, 8),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20)
+    )  {
+        for proof_index in proof_indices {
+            let proof = tree4.prove(proof_index.index(256)).unwrap();
+            prop_assert!(MerkleTree::<Blake3_256>::verify_with_arity(*tree4.root(), proof_index.index(256), &proof, 4).is_ok());
+
+            let proof = tree8.prove(proof_index.index(512)).unwrap();
+            prop_assert!(MerkleTree::<Blake3_256>::verify_with_arity(*tree8.root(), proof_index.index(512), &proof, 8).is_ok());
+        }
+    }
+
+    #[test]
+    fn prove_nary_batch_n_verify(tree4 in random_blake3_merkle_tree(256, 4),
+                      tree8 in random_blake3_merkle_tree(512, 8),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 10..20),
+                      cap_height in 0..4_usize
+    )  {
+        let indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(256)).collect();
+        let mut indices4 = indices.clone();
+        indices4.sort_unstable(); indices4.dedup();
+        let proof = tree4.prove_batch_with_cap(&indices4, cap_height).unwrap();
+        prop_assert!(MerkleTree::verify_batch_with_cap(tree4.cap(cap_height), &indices4, &proof).is_ok());
+        let proof = tree4.prove_batch(&indices4).unwrap();
+        prop_assert!(MerkleTree::verify_batch(tree4.root(), &indices4, &proof).is_ok());
+
+        let mut indices8: Vec<usize> = proof_indices.iter().map(|idx| idx.index(512)).collect();
+        indices8.sort_unstable(); indices8.dedup();
+        let proof = tree8.prove_batch_with_cap(&indices8, cap_height).unwrap();
+        prop_assert!(MerkleTree::verify_batch_with_cap(tree8.cap(cap_height), &indices8, &proof).is_ok());
+        let proof = tree8.prove_batch(&indices8).unwrap();
+        prop_assert!(MerkleTree::verify_batch(tree8.root(), &indices8, &proof).is_ok());
+    }
+
+    #[test]
+    fn nary_batch_proof_paths(tree in random_blake3_merkle_tree(256, 4),
+                      proof_indices in prop::collection::vec(any::<prop::sample::Index>(), 1..30)
+    )  {
+        let mut indices: Vec<usize> = proof_indices.iter().map(|idx| idx.index(256)).collect();
+        indices.sort_unstable(); indices.dedup();
+        let proof1 = tree.prove_batch(&indices[..]).unwrap();
+
+        let mut paths = Vec::new();
+        for &idx in indices.iter() {
+            paths.push(tree.prove(idx).unwrap());
+        }
+        let proof2 = BatchMerkleProof::<Blake3_256>::from_paths(&paths, &indices, 4);
+        prop_assert!(proof1 == proof2);
+
+        prop_assert!(paths == proof1.into_paths(&indices).unwrap());
+    }
+}
+
 // HELPER FUNCTIONS
 // --------------------------------------------------------------------------------------------
 fn hash_2x1(v1: Digest256, v2: Digest256) -> Digest256 {
     Blake3_256::merge(&[v1, v2])
 }
 
+fn leaves_at(tree: &MerkleTree<Blake3_256>, index: usize) -> Digest256 {
+    tree.leaves()[index]
+}
+
+fn hashed_leaves(num_leaves: usize) -> Vec<Digest256> {
+    (0..num_leaves as u64)
+        .map(|i| Blake3_256::hash(&i.to_le_bytes()))
+        .collect()
+}
+
 pub fn random_blake3_merkle_tree(
     leave_count: usize,
+    arity: usize,
 ) -> impl Strategy<Value = MerkleTree<Blake3_256>> {
-    prop::collection::vec(any::<[u8; 32]>(), leave_count).prop_map(|leaves| {
+    prop::collection::vec(any::<[u8; 32]>(), leave_count).prop_map(move |leaves| {
         let leaves = Digest256::bytes_as_digests(&leaves).to_vec();
-        MerkleTree::<Blake3_256>::new(leaves).unwrap()
+        MerkleTree::<Blake3_256>::with_arity(leaves, arity).unwrap()
     })
 }
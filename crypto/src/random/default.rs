@@ -3,10 +3,20 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use super::as_native_digest;
 use crate::{errors::RandomCoinError, Digest, ElementHasher, RandomCoin};
-use core::convert::TryInto;
+use core::{convert::TryInto, ops::Range};
 use math::{FieldElement, StarkField};
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of values checked per batch when searching for a nonce with a given number of leading
+/// zeros.
+const NONCE_BATCH_SIZE: usize = 64;
 
 // DEFAULT RANDOM COIN IMPLEMENTATION
 // ================================================================================================
@@ -146,6 +156,22 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
         self.counter = 0;
     }
 
+    /// Reseeds the coin with the specified digest.
+    ///
+    /// If the digest is of the same type as digests of the coin's hash function, this is the same
+    /// as [reseed()](RandomCoin::reseed). Otherwise, the new seed is set to hash(`seed` ||
+    /// `digest`), where both the seed and the digest are serialized into bytes.
+    fn reseed_with_digest<D: Digest>(&mut self, digest: &D) {
+        if let Some(&digest) = as_native_digest::<H, D>(digest) {
+            self.reseed(digest);
+            return;
+        }
+        let mut bytes = self.seed.to_bytes();
+        digest.write_into(&mut bytes);
+        self.seed = H::hash(&bytes);
+        self.counter = 0;
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -184,6 +210,31 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
         seed_head.trailing_zeros()
     }
 
+    /// Returns the smallest value in the specified range for which
+    /// [check_leading_zeros()](RandomCoin::check_leading_zeros) returns at least
+    /// `min_leading_zeros`, or `None` if there is no such value in the range.
+    ///
+    /// The values are checked in batches of 64 values using
+    /// [merge_with_int_batch()](crate::Hasher::merge_with_int_batch).
+    fn find_nonce(&self, values: Range<u64>, min_leading_zeros: u32) -> Option<u64> {
+        let mut digests = [H::Digest::default(); NONCE_BATCH_SIZE];
+        let mut batch_start = values.start;
+        while batch_start < values.end {
+            let batch_size = (values.end - batch_start).min(NONCE_BATCH_SIZE as u64) as usize;
+            let digests = &mut digests[..batch_size];
+            H::merge_with_int_batch(self.seed, batch_start, digests);
+            for (i, digest) in digests.iter().enumerate() {
+                let bytes = digest.as_bytes();
+                let head = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+                if head.trailing_zeros() >= min_leading_zeros {
+                    return Some(batch_start + i as u64);
+                }
+            }
+            batch_start += batch_size as u64;
+        }
+        None
+    }
+
     // DRAW METHODS
     // --------------------------------------------------------------------------------------------
 
@@ -288,3 +339,26 @@ impl<B: StarkField, H: ElementHasher<BaseField = B>> RandomCoin for DefaultRando
         Ok(values)
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<H: ElementHasher> Serializable for DefaultRandomCoin<H> {
+    /// Serializes the current state of the coin and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.seed.write_into(target);
+        target.write_u64(self.counter);
+    }
+}
+
+impl<H: ElementHasher> Deserializable for DefaultRandomCoin<H> {
+    /// Reads the state of a coin from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if the state of the coin could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let seed = H::Digest::read_from(source)?;
+        let counter = source.read_u64()?;
+        Ok(Self { seed, counter })
+    }
+}
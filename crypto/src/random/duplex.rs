@@ -0,0 +1,340 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{errors::RandomCoinError, AlgebraicSponge, RandomCoin};
+use core::ops::Range;
+use math::{FieldElement, StarkField};
+use utils::{
+    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable,
+};
+
+// DUPLEX RANDOM COIN IMPLEMENTATION
+// ================================================================================================
+
+/// Pseudo-random element generator for finite fields based on a duplex sponge construction.
+///
+/// A random coin can be used to draw elements uniformly at random from the specified base field
+/// or from any extension of the base field.
+///
+/// Unlike [DefaultRandomCoin](super::DefaultRandomCoin), this coin does not use the hash function
+/// specified via the `H` type parameter as a black box. Instead, it operates directly on the state
+/// of the permutation underlying the hash function. The coin works roughly as follows:
+/// - The state of the coin consists of the permutation state. Field elements are absorbed into
+///   the rate portion of the state (overwriting its contents); once the rate portion is full, the
+///   permutation is applied.
+/// - To draw the next element, we apply the permutation if any elements have been absorbed since
+///   the last squeeze (or if all elements of the rate portion have already been drawn), and
+///   return the next unused element of the rate portion of the state. Thus, a single permutation
+///   yields as many base field elements as there are elements in the rate portion.
+/// - Digests (e.g., commitment roots) and out-of-domain evaluations are absorbed as field
+///   elements without hashing them first, and integers are absorbed as two 32-bit limbs.
+///
+/// This makes the coin cheaper to use with algebraic hash functions, and also simpler to
+/// arithmetize (e.g., for recursive proof verification).
+///
+/// Integers drawn via [draw_integers()](RandomCoin::draw_integers) are derived from the lower
+/// bits of the canonical representations of drawn base field elements. Thus, for a field of size
+/// `p`, the statistical distance of the drawn integers from uniform is at most `domain_size / p`.
+///
+/// # Examples
+/// ```
+/// # use winter_crypto::{RandomCoin, DuplexRandomCoin, hashers::Rp64_256};
+/// # use math::fields::f64::BaseElement;
+/// // initial elements for seeding the random coin
+/// let seed = &[BaseElement::new(1), BaseElement::new(2), BaseElement::new(3), BaseElement::new(4)];
+///
+/// // instantiate a random coin using Rescue Prime as the underlying permutation
+/// let mut coin = DuplexRandomCoin::<Rp64_256>::new(seed);
+///
+/// // should draw different elements each time
+/// let e1 = coin.draw::<BaseElement>().unwrap();
+/// let e2 = coin.draw::<BaseElement>().unwrap();
+/// assert_ne!(e1, e2);
+///
+/// // should draw same elements for the same seed
+/// let mut coin1 = DuplexRandomCoin::<Rp64_256>::new(seed);
+/// let mut coin2 = DuplexRandomCoin::<Rp64_256>::new(seed);
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_eq!(e1, e2);
+///
+/// // after reseeding should draw different elements
+/// coin2.reseed_with_int(42);
+/// let e1 = coin1.draw::<BaseElement>().unwrap();
+/// let e2 = coin2.draw::<BaseElement>().unwrap();
+/// assert_ne!(e1, e2);
+/// ```
+#[derive(Debug)]
+pub struct DuplexRandomCoin<H: AlgebraicSponge> {
+    state: Vec<H::BaseField>,
+    /// Number of elements absorbed into the rate portion since the last permutation.
+    num_absorbed: usize,
+    /// Number of elements of the rate portion which have not yet been drawn.
+    num_squeezable: usize,
+}
+
+impl<H: AlgebraicSponge> DuplexRandomCoin<H> {
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Absorbs the provided elements into the rate portion of the state, applying the permutation
+    /// whenever the rate portion is full.
+    fn absorb(&mut self, elements: &[H::BaseField]) {
+        let rate_width = H::RATE_RANGE.len();
+        for &element in elements {
+            if self.num_absorbed == rate_width {
+                H::apply_permutation(&mut self.state);
+                self.num_absorbed = 0;
+            }
+            self.state[H::RATE_RANGE.start + self.num_absorbed] = element;
+            self.num_absorbed += 1;
+        }
+
+        // outputs of the previous permutation must not be drawn after new elements were absorbed
+        self.num_squeezable = 0;
+    }
+
+    /// Returns the next unused element of the rate portion of the state, applying the permutation
+    /// first if needed.
+    fn squeeze(&mut self) -> H::BaseField {
+        if self.num_absorbed > 0 || self.num_squeezable == 0 {
+            H::apply_permutation(&mut self.state);
+            self.num_absorbed = 0;
+            self.num_squeezable = H::RATE_RANGE.len();
+        }
+
+        let element = self.state[H::RATE_RANGE.end - self.num_squeezable];
+        self.num_squeezable -= 1;
+        element
+    }
+
+    /// Returns an element derived from the current state of the coin without modifying the
+    /// state. The element is computed by absorbing a single `ONE` element into a copy of the
+    /// state and drawing from the copy; thus, it is independent of the elements which will be
+    /// drawn from the coin itself.
+    fn peek(&self) -> H::BaseField {
+        let mut coin = self.clone();
+        coin.absorb(&[H::BaseField::ONE]);
+        coin.squeeze()
+    }
+}
+
+impl<H: AlgebraicSponge> Clone for DuplexRandomCoin<H> {
+    fn clone(&self) -> Self {
+        Self {
+            state: self.state.clone(),
+            num_absorbed: self.num_absorbed,
+            num_squeezable: self.num_squeezable,
+        }
+    }
+}
+
+impl<B: StarkField, H: AlgebraicSponge<BaseField = B>> RandomCoin for DuplexRandomCoin<H> {
+    type BaseField = B;
+    type Hasher = H;
+
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new random coin instantiated with the provided `seed`.
+    ///
+    /// The first element of the capacity portion of the state is set to the number of elements in
+    /// the seed, and the seed is then absorbed into the rate portion of the state.
+    fn new(seed: &[Self::BaseField]) -> Self {
+        let mut state = vec![B::ZERO; H::STATE_WIDTH];
+        state[H::CAPACITY_RANGE.start] = B::from(seed.len() as u64);
+
+        let mut coin = Self {
+            state,
+            num_absorbed: 0,
+            num_squeezable: 0,
+        };
+        coin.absorb(seed);
+        coin
+    }
+
+    // RESEEDING
+    // --------------------------------------------------------------------------------------------
+
+    /// Reseeds the coin with the specified data by absorbing the elements of the digest into the
+    /// state of the coin.
+    fn reseed(&mut self, data: H::Digest) {
+        self.absorb(H::digest_as_elements(&data));
+    }
+
+    /// Reseeds the coin with the specified value by absorbing the lower and the upper 32 bits of
+    /// the value into the state of the coin as two field elements.
+    fn reseed_with_int(&mut self, value: u64) {
+        let limbs = [B::from(value as u32), B::from((value >> 32) as u32)];
+        self.absorb(&limbs);
+    }
+
+    /// Reseeds the coin with the specified field elements by absorbing them into the state of the
+    /// coin directly (i.e., without hashing them first).
+    fn reseed_with_elements<E>(&mut self, elements: &[E])
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        self.absorb(E::slice_as_base_elements(elements));
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of leading zeros in an element derived from the current state of the
+    /// coin if its canonical representation is interpreted as an integer in big-endian byte order.
+    ///
+    /// The element is derived from a copy of the state with a single `ONE` element absorbed into
+    /// it; this ensures that the element is not one of the elements drawn from the coin later on
+    /// (e.g., when drawing query positions after a proof-of-work check).
+    fn leading_zeros(&self) -> u32 {
+        element_head(self.peek()).trailing_zeros()
+    }
+
+    /// Reseeds a copy of the coin with the specified `value` and returns the number of leading
+    /// zeros for the copy (as defined for [leading_zeros()](RandomCoin::leading_zeros)).
+    fn check_leading_zeros(&self, value: u64) -> u32 {
+        let mut coin = self.clone();
+        coin.reseed_with_int(value);
+        coin.leading_zeros()
+    }
+
+    /// Returns the smallest value in the specified range for which
+    /// [check_leading_zeros()](RandomCoin::check_leading_zeros) returns at least
+    /// `min_leading_zeros`, or `None` if there is no such value in the range.
+    ///
+    /// A single copy of the coin is allocated for the entire range; for each value, the state of
+    /// the coin is copied into it before the value is absorbed.
+    fn find_nonce(&self, values: Range<u64>, min_leading_zeros: u32) -> Option<u64> {
+        let mut coin = self.clone();
+        values.into_iter().find(|&value| {
+            coin.state.copy_from_slice(&self.state);
+            coin.num_absorbed = self.num_absorbed;
+            coin.num_squeezable = self.num_squeezable;
+            coin.reseed_with_int(value);
+            coin.absorb(&[B::ONE]);
+            element_head(coin.squeeze()).trailing_zeros() >= min_leading_zeros
+        })
+    }
+
+    // DRAW METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the next pseudo-random field element.
+    ///
+    /// Elements in extension fields are built from `E::EXTENSION_DEGREE` consecutive base field
+    /// elements drawn from the coin. Thus, this method never fails.
+    fn draw<E: FieldElement<BaseField = B>>(&mut self) -> Result<E, RandomCoinError> {
+        let elements = (0..E::EXTENSION_DEGREE)
+            .map(|_| self.squeeze())
+            .collect::<Vec<_>>();
+        Ok(E::slice_from_base_elements(&elements)[0])
+    }
+
+    /// Returns a vector of unique integers selected from the range [0, domain_size).
+    ///
+    /// # Errors
+    /// Returns an error if the specified number of unique integers could not be generated
+    /// after 1000 draws from the coin.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `domain_size` is not a power of two.
+    /// - `num_values` is greater than or equal to `domain_size`.
+    fn draw_integers(
+        &mut self,
+        num_values: usize,
+        domain_size: usize,
+    ) -> Result<Vec<usize>, RandomCoinError> {
+        assert!(
+            domain_size.is_power_of_two(),
+            "domain size must be a power of two"
+        );
+        assert!(
+            num_values < domain_size,
+            "number of values must be smaller than domain size"
+        );
+
+        // determine how many bits are needed to represent valid values in the domain
+        let v_mask = (domain_size - 1) as u64;
+
+        // draw values from the coin until we get as many unique values as specified by num_queries
+        let mut values = Vec::new();
+        for _ in 0..1000 {
+            // convert the next element to integer and limit the integer to the number of bits
+            // which can fit into the specified domain
+            let value = (element_head(self.squeeze()) & v_mask) as usize;
+
+            if values.contains(&value) {
+                continue;
+            }
+            values.push(value);
+            if values.len() == num_values {
+                break;
+            }
+        }
+
+        if values.len() < num_values {
+            return Err(RandomCoinError::FailedToDrawIntegers(
+                num_values,
+                values.len(),
+                1000,
+            ));
+        }
+
+        Ok(values)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the lower 64 bits of the canonical representation of the provided element.
+fn element_head<B: StarkField>(element: B) -> u64 {
+    let bytes = element.to_bytes();
+    let mut head = [0; 8];
+    let len = bytes.len().min(8);
+    head[..len].copy_from_slice(&bytes[..len]);
+    u64::from_le_bytes(head)
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<H: AlgebraicSponge> Serializable for DuplexRandomCoin<H> {
+    /// Serializes the current state of the coin and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.state.write_into(target);
+        target.write_u8(self.num_absorbed as u8);
+        target.write_u8(self.num_squeezable as u8);
+    }
+}
+
+impl<H: AlgebraicSponge> Deserializable for DuplexRandomCoin<H> {
+    /// Reads the state of a coin from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if the state of the coin could not be read from the `source`, or if the
+    /// number of absorbed or squeezable elements exceeds the rate of the sponge.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let state = H::BaseField::read_batch_from(source, H::STATE_WIDTH)?;
+        let num_absorbed = source.read_u8()? as usize;
+        let num_squeezable = source.read_u8()? as usize;
+
+        let rate_width = H::RATE_RANGE.len();
+        if num_absorbed > rate_width || num_squeezable > rate_width {
+            return Err(DeserializationError::InvalidValue(
+                "number of absorbed or squeezable elements exceeds the rate of the sponge"
+                    .to_string(),
+            ));
+        }
+
+        Ok(Self {
+            state,
+            num_absorbed,
+            num_squeezable,
+        })
+    }
+}
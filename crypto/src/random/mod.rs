@@ -3,13 +3,20 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{errors::RandomCoinError, ElementHasher, Hasher};
+use crate::{errors::RandomCoinError, Digest, ElementHasher, Hasher};
+use core::{any::Any, ops::Range};
 use math::{FieldElement, StarkField};
 use utils::collections::Vec;
 
 mod default;
 pub use default::DefaultRandomCoin;
 
+mod duplex;
+pub use duplex::DuplexRandomCoin;
+
+#[cfg(test)]
+mod tests;
+
 // RANDOM COIN TRAIT
 // ================================================================================================
 
@@ -74,6 +81,46 @@ pub trait RandomCoin: Sync {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Reseeds the coin with the specified field elements.
+    ///
+    /// By default, the elements are hashed using the coin's hash function, and the coin is
+    /// reseeded with the resulting digest. Random coins which can absorb field elements directly
+    /// may override this method.
+    fn reseed_with_elements<E>(&mut self, elements: &[E])
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        self.reseed(Self::Hasher::hash_elements(elements));
+    }
+
+    /// Reseeds the coin with the specified digest which may have been computed by a hash function
+    /// different from the coin's hash function (e.g., a commitment built with a different hash
+    /// function).
+    ///
+    /// If the digest is of the same type as digests of the coin's hash function, the coin is
+    /// reseeded with the digest directly via [reseed()](RandomCoin::reseed). Otherwise, by
+    /// default, the serialized digest is hashed using the coin's hash function, and the coin is
+    /// reseeded with the resulting digest.
+    fn reseed_with_digest<D: Digest>(&mut self, digest: &D) {
+        match as_native_digest::<Self::Hasher, D>(digest) {
+            Some(&digest) => self.reseed(digest),
+            None => self.reseed(Self::Hasher::hash(&digest.to_bytes())),
+        }
+    }
+
+    /// Returns the smallest value in the specified range for which
+    /// [check_leading_zeros()](RandomCoin::check_leading_zeros) returns at least
+    /// `min_leading_zeros`, or `None` if there is no such value in the range.
+    ///
+    /// The default implementation checks the values one by one; random coins which can check
+    /// batches of values more efficiently (e.g., by reusing the state of the hash function
+    /// between values) may override this method.
+    fn find_nonce(&self, values: Range<u64>, min_leading_zeros: u32) -> Option<u64> {
+        values
+            .into_iter()
+            .find(|&value| self.check_leading_zeros(value) >= min_leading_zeros)
+    }
+
     /// Returns the next pair of pseudo-random field elements.
     ///
     /// # Errors
@@ -86,3 +133,11 @@ pub trait RandomCoin: Sync {
         Ok((self.draw()?, self.draw()?))
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the `digest` as a digest of the hash function `H` if both digests are of the same type.
+pub(crate) fn as_native_digest<H: Hasher, D: Digest>(digest: &D) -> Option<&H::Digest> {
+    (digest as &dyn Any).downcast_ref::<H::Digest>()
+}
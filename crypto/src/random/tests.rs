@@ -0,0 +1,242 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{DefaultRandomCoin, DuplexRandomCoin, RandomCoin};
+use crate::{
+    hashers::{Blake3_256, GriffinJive64_256, Rp64_256},
+    AlgebraicSponge, ElementHasher, Hasher,
+};
+use core::ops::Range;
+use math::fields::{f64::BaseElement, QuadExtension};
+use utils::{collections::Vec, Deserializable, Serializable};
+
+// DUPLEX RANDOM COIN
+// ================================================================================================
+
+#[test]
+fn duplex_coin_deterministic() {
+    let seed = seed_elements(5);
+    let mut coin1 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+    let mut coin2 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+
+    // draw more elements than fit into the rate portion of the state
+    let values1 = draw_many(&mut coin1, 20);
+    let values2 = draw_many(&mut coin2, 20);
+    assert_eq!(values1, values2);
+
+    // all drawn elements should be different
+    for (i, a) in values1.iter().enumerate() {
+        assert!(!values1[i + 1..].contains(a));
+    }
+
+    // seeds of different lengths result in different coins
+    let mut coin3 = DuplexRandomCoin::<Rp64_256>::new(&seed[..4]);
+    assert_ne!(values1, draw_many(&mut coin3, 20));
+}
+
+#[test]
+fn duplex_coin_reseed() {
+    let seed = seed_elements(4);
+    let mut coin1 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+    let mut coin2 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+
+    // reseeding with a digest and with the elements of the digest has the same effect
+    let digest = Rp64_256::hash_elements(&seed);
+    coin1.reseed(digest);
+    coin2.reseed_with_elements(Rp64_256::digest_as_elements(&digest));
+    let e1 = coin1.draw::<BaseElement>().unwrap();
+    assert_eq!(e1, coin2.draw::<BaseElement>().unwrap());
+    assert_ne!(
+        e1,
+        DuplexRandomCoin::<Rp64_256>::new(&seed)
+            .draw::<BaseElement>()
+            .unwrap()
+    );
+
+    // elements drawn after reseeding are different from the previously drawn ones
+    let e2 = coin1.draw::<BaseElement>().unwrap();
+    coin2.reseed_with_int(1);
+    assert_ne!(e2, coin2.draw::<BaseElement>().unwrap());
+
+    // elements in extension fields can be absorbed directly
+    let mut coin1 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+    let mut coin3 = DuplexRandomCoin::<Rp64_256>::new(&seed);
+    let ood = [QuadExtension::new(BaseElement::new(3), BaseElement::new(5))];
+    coin1.reseed_with_elements(&ood);
+    coin3.reseed_with_elements(&[BaseElement::new(3), BaseElement::new(5)]);
+    assert_eq!(
+        coin1.draw::<QuadExtension<BaseElement>>().unwrap(),
+        coin3.draw::<QuadExtension<BaseElement>>().unwrap()
+    );
+}
+
+#[test]
+fn duplex_coin_leading_zeros() {
+    let seed = seed_elements(4);
+    let coin = DuplexRandomCoin::<GriffinJive64_256>::new(&seed);
+
+    let mut nonce = 0;
+    while coin.check_leading_zeros(nonce) < 8 {
+        nonce += 1;
+    }
+
+    // checking leading zeros does not modify the coin
+    let mut coin2 = DuplexRandomCoin::<GriffinJive64_256>::new(&seed);
+    assert_eq!(draw_many(&mut coin2, 4), draw_many(&mut coin.clone(), 4));
+
+    // reseeding the coin with the nonce yields the same number of leading zeros
+    let mut coin = coin;
+    let expected = coin.check_leading_zeros(nonce);
+    coin.reseed_with_int(nonce);
+    assert_eq!(expected, coin.leading_zeros());
+    assert!(coin.leading_zeros() >= 8);
+}
+
+#[test]
+fn duplex_coin_draw_integers() {
+    let seed = seed_elements(4);
+    let mut coin = DuplexRandomCoin::<Rp64_256>::new(&seed);
+
+    let num_values = 40;
+    let domain_size = 64;
+    let values = coin.draw_integers(num_values, domain_size).unwrap();
+    assert_eq!(num_values, values.len());
+    for (i, value) in values.iter().enumerate() {
+        assert!(*value < domain_size);
+        assert!(!values[i + 1..].contains(value));
+    }
+
+    // the same integers are drawn from a coin with the same seed
+    let mut coin = DuplexRandomCoin::<Rp64_256>::new(&seed);
+    assert_eq!(values, coin.draw_integers(num_values, domain_size).unwrap());
+}
+
+#[test]
+fn duplex_coin_find_nonce() {
+    let coin = DuplexRandomCoin::<Rp64_256>::new(&seed_elements(4));
+    assert_eq!(
+        find_nonce_naive(&coin, 1..5000, 6),
+        coin.find_nonce(1..5000, 6)
+    );
+    assert_eq!(None, coin.find_nonce(1..100, 32));
+}
+
+#[test]
+fn duplex_coin_serialization() {
+    let mut coin1 = DuplexRandomCoin::<Rp64_256>::new(&seed_elements(5));
+    coin1.reseed_with_elements(&seed_elements(3));
+    draw_many(&mut coin1, 3);
+
+    // a coin read back from its serialized state draws the same elements as the original
+    let mut coin2 = DuplexRandomCoin::<Rp64_256>::read_from_bytes(&coin1.to_bytes()).unwrap();
+    assert_eq!(draw_many(&mut coin1, 10), draw_many(&mut coin2, 10));
+
+    // counters exceeding the rate of the sponge are rejected
+    let mut bytes = coin1.to_bytes();
+    let num_absorbed_idx = bytes.len() - 2;
+    bytes[num_absorbed_idx] = Rp64_256::RATE_RANGE.len() as u8 + 1;
+    assert!(DuplexRandomCoin::<Rp64_256>::read_from_bytes(&bytes).is_err());
+}
+
+// DEFAULT RANDOM COIN
+// ================================================================================================
+
+#[test]
+fn default_coin_reseed_with_elements() {
+    let seed = seed_elements(4);
+    let mut coin1 = DefaultRandomCoin::<Rp64_256>::new(&seed);
+    let mut coin2 = DefaultRandomCoin::<Rp64_256>::new(&seed);
+
+    // by default, reseeding with elements is the same as reseeding with their hash
+    coin1.reseed_with_elements(&seed);
+    coin2.reseed(Rp64_256::hash_elements(&seed));
+    assert_eq!(
+        coin1.draw::<BaseElement>().unwrap(),
+        coin2.draw::<BaseElement>().unwrap()
+    );
+}
+
+#[test]
+fn default_coin_find_nonce() {
+    let coin = DefaultRandomCoin::<Blake3_256<BaseElement>>::new(&seed_elements(4));
+    let nonce = coin.find_nonce(1..5000, 8);
+    assert!(nonce.is_some());
+    assert_eq!(find_nonce_naive(&coin, 1..5000, 8), nonce);
+
+    // values outside of the range are not checked
+    let nonce = nonce.unwrap();
+    assert_eq!(None, coin.find_nonce(nonce + 1..nonce + 1, 0));
+    assert_eq!(Some(nonce + 1), coin.find_nonce(nonce + 1..nonce + 2, 0));
+}
+
+#[test]
+fn default_coin_serialization() {
+    let mut coin1 = DefaultRandomCoin::<Blake3_256<BaseElement>>::new(&seed_elements(4));
+    draw_many(&mut coin1, 3);
+
+    // a coin read back from its serialized state draws the same elements as the original
+    let bytes = coin1.to_bytes();
+    let mut coin2 = DefaultRandomCoin::<Blake3_256<BaseElement>>::read_from_bytes(&bytes).unwrap();
+    assert_eq!(draw_many(&mut coin1, 10), draw_many(&mut coin2, 10));
+}
+
+#[test]
+fn default_coin_reseed_with_digest() {
+    let seed = seed_elements(4);
+    let mut coin1 = DefaultRandomCoin::<Blake3_256<BaseElement>>::new(&seed);
+    let mut coin2 = DefaultRandomCoin::<Blake3_256<BaseElement>>::new(&seed);
+
+    // for BLAKE3, reseeding with a digest of the same hash function is the same as reseeding
+    let digest = Blake3_256::<BaseElement>::hash(&[1, 2, 3]);
+    coin1.reseed(digest);
+    coin2.reseed_with_digest(&digest);
+    assert_eq!(
+        coin1.draw::<BaseElement>().unwrap(),
+        coin2.draw::<BaseElement>().unwrap()
+    );
+
+    // digests of other hash functions can also be used to reseed the coin
+    let digest = Rp64_256::hash_elements(&seed);
+    coin1.reseed_with_digest(&digest);
+    coin2.reseed_with_digest(&digest);
+    let e1 = coin1.draw::<BaseElement>().unwrap();
+    assert_eq!(e1, coin2.draw::<BaseElement>().unwrap());
+    coin2.reseed_with_digest(&Rp64_256::hash_elements(&seed[..3]));
+    assert_ne!(e1, coin2.draw::<BaseElement>().unwrap());
+}
+
+#[test]
+fn default_coin_reseed_with_native_digest() {
+    // for algebraic hash functions, reseeding with a digest of the coin's hash function must not
+    // hash the digest again
+    let seed = seed_elements(4);
+    let mut coin1 = DefaultRandomCoin::<Rp64_256>::new(&seed);
+    let mut coin2 = DefaultRandomCoin::<Rp64_256>::new(&seed);
+
+    let digest = Rp64_256::hash_elements(&seed);
+    coin1.reseed(digest);
+    coin2.reseed_with_digest(&digest);
+    assert_eq!(
+        coin1.draw::<BaseElement>().unwrap(),
+        coin2.draw::<BaseElement>().unwrap()
+    );
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn seed_elements(n: u64) -> Vec<BaseElement> {
+    (1..=n).map(BaseElement::new).collect()
+}
+
+fn draw_many<R: RandomCoin<BaseField = BaseElement>>(coin: &mut R, n: usize) -> Vec<BaseElement> {
+    (0..n).map(|_| coin.draw().unwrap()).collect()
+}
+
+fn find_nonce_naive<R: RandomCoin>(coin: &R, values: Range<u64>, min_zeros: u32) -> Option<u64> {
+    values
+        .into_iter()
+        .find(|&value| coin.check_leading_zeros(value) >= min_zeros)
+}
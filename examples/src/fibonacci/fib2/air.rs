@@ -6,7 +6,8 @@
 use super::{BaseElement, FieldElement, ProofOptions, TRACE_WIDTH};
 use crate::utils::are_equal;
 use winterfell::{
-    Air, AirContext, Assertion, EvaluationFrame, TraceInfo, TransitionConstraintDegree,
+    Air, AirContext, Assertion, EvaluationFrame, EvaluationFrameBatch, TraceInfo,
+    TransitionConstraintDegree,
 };
 
 // FIBONACCI AIR
@@ -39,6 +40,10 @@ impl Air for FibAir {
         &self.context
     }
 
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/fib2"
+    }
+
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
         frame: &EvaluationFrame<E>,
@@ -58,6 +63,23 @@ impl Air for FibAir {
         result[1] = are_equal(next[1], current[1] + next[0]);
     }
 
+    fn evaluate_transition_batch<E: FieldElement<BaseField = Self::BaseField>>(
+        &self,
+        frames: &EvaluationFrameBatch<E>,
+        _periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        // same constraints as in evaluate_transition(), but evaluated over contiguous column
+        // slices for all frames of the batch
+        let (current_0, current_1) = (frames.current_column(0), frames.current_column(1));
+        let (next_0, next_1) = (frames.next_column(0), frames.next_column(1));
+        let (result_0, result_1) = result.split_at_mut(frames.num_rows());
+        for i in 0..frames.num_rows() {
+            result_0[i] = are_equal(next_0[i], current_0[i] + current_1[i]);
+            result_1[i] = are_equal(next_1[i], current_1[i] + next_0[i]);
+        }
+    }
+
     fn get_assertions(&self) -> Vec<Assertion<Self::BaseField>> {
         // a valid Fibonacci sequence should start with two ones and terminate with
         // the expected result
@@ -4,6 +4,8 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{super::utils::build_proof_options, Blake3_256};
+use crate::Example;
+use winterfell::VerifierError;
 
 #[test]
 fn fib2_test_basic_proof_verification() {
@@ -23,6 +25,15 @@ fn fib2_test_basic_proof_verification_extension() {
     crate::tests::test_basic_proof_verification(fib);
 }
 
+#[test]
+fn fib2_test_basic_proof_verification_merkle_cap() {
+    let fib = Box::new(super::FibExample::<Blake3_256>::new(
+        16,
+        build_proof_options(false).with_merkle_cap_height(4),
+    ));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
 #[test]
 fn fib2_test_basic_proof_verification_fail() {
     let fib = Box::new(super::FibExample::<Blake3_256>::new(
@@ -31,3 +42,16 @@ fn fib2_test_basic_proof_verification_fail() {
     ));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib2_test_proof_verification_wrong_domain_separator() {
+    let fib = super::FibExample::<Blake3_256>::new(16, build_proof_options(false));
+    let mut proof = fib.prove();
+    proof.context = proof
+        .context
+        .with_domain_separator(b"winterfell/examples/mulfib2");
+    assert_eq!(
+        Err(VerifierError::InconsistentDomainSeparator),
+        fib.verify(proof)
+    );
+}
@@ -40,6 +40,10 @@ impl Air for Fib8Air {
         &self.context
     }
 
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/fib8"
+    }
+
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
         frame: &EvaluationFrame<E>,
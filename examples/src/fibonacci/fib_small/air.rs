@@ -39,6 +39,10 @@ impl Air for FibSmall {
         &self.context
     }
 
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/fib-small"
+    }
+
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
         frame: &EvaluationFrame<E>,
@@ -9,7 +9,7 @@ use core::marker::PhantomData;
 use log::debug;
 use std::time::Instant;
 use winterfell::{
-    crypto::{DefaultRandomCoin, ElementHasher},
+    crypto::{CommitmentHashers, DefaultRandomCoin},
     math::{fields::f64::BaseElement, FieldElement},
     ProofOptions, Prover, StarkProof, Trace, TraceTable, VerifierError,
 };
@@ -72,14 +72,14 @@ pub fn get_example(
     }
 }
 
-pub struct FibExample<H: ElementHasher> {
+pub struct FibExample<H: CommitmentHashers> {
     options: ProofOptions,
     sequence_length: usize,
     result: BaseElement,
     _hasher: PhantomData<H>,
 }
 
-impl<H: ElementHasher> FibExample<H> {
+impl<H: CommitmentHashers> FibExample<H> {
     pub fn new(sequence_length: usize, options: ProofOptions) -> Self {
         assert!(
             sequence_length.is_power_of_two(),
@@ -107,9 +107,9 @@ impl<H: ElementHasher> FibExample<H> {
 // EXAMPLE IMPLEMENTATION
 // ================================================================================================
 
-impl<H: ElementHasher> Example for FibExample<H>
+impl<H: CommitmentHashers> Example for FibExample<H>
 where
-    H: ElementHasher<BaseField = BaseElement>,
+    H: CommitmentHashers<BaseField = BaseElement>,
 {
     fn prove(&self) -> StarkProof {
         debug!(
@@ -139,11 +139,11 @@ where
     }
 
     fn verify(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        winterfell::verify::<FibSmall, H, DefaultRandomCoin<H>>(proof, self.result)
+        winterfell::verify::<FibSmall, H, DefaultRandomCoin<H::FriHasher>>(proof, self.result)
     }
 
     fn verify_with_wrong_inputs(&self, proof: StarkProof) -> Result<(), VerifierError> {
-        winterfell::verify::<FibSmall, H, DefaultRandomCoin<H>>(
+        winterfell::verify::<FibSmall, H, DefaultRandomCoin<H::FriHasher>>(
             proof,
             self.result + BaseElement::ONE,
         )
@@ -4,19 +4,20 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{
-    air::FibSmall, BaseElement, DefaultRandomCoin, ElementHasher, FieldElement, PhantomData,
+    air::FibSmall, BaseElement, CommitmentHashers, DefaultRandomCoin, FieldElement, PhantomData,
     ProofOptions, Prover, Trace, TraceTable, TRACE_WIDTH,
 };
 
 // FIBONACCI PROVER
 // ================================================================================================
 
-pub struct FibSmallProver<H: ElementHasher> {
+/// Fibonacci prover which uses the hash function of FRI layer commitments for the public coin.
+pub struct FibSmallProver<H: CommitmentHashers> {
     options: ProofOptions,
     _hasher: PhantomData<H>,
 }
 
-impl<H: ElementHasher> FibSmallProver<H> {
+impl<H: CommitmentHashers> FibSmallProver<H> {
     pub fn new(options: ProofOptions) -> Self {
         Self {
             options,
@@ -48,15 +49,15 @@ impl<H: ElementHasher> FibSmallProver<H> {
     }
 }
 
-impl<H: ElementHasher> Prover for FibSmallProver<H>
+impl<H: CommitmentHashers> Prover for FibSmallProver<H>
 where
-    H: ElementHasher<BaseField = BaseElement>,
+    H: CommitmentHashers<BaseField = BaseElement>,
 {
     type BaseField = BaseElement;
     type Air = FibSmall;
     type Trace = TraceTable<BaseElement>;
     type HashFn = H;
-    type RandomCoin = DefaultRandomCoin<Self::HashFn>;
+    type RandomCoin = DefaultRandomCoin<H::FriHasher>;
 
     fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
         let last_step = trace.length() - 1;
@@ -3,7 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{super::utils::build_proof_options, Rp64_256};
+use super::{
+    super::utils::build_proof_options, BaseElement, Blake3_256, DefaultRandomCoin, FieldElement,
+    ProofOptions, Prover, Rp64_256, StarkProof, TraceTable,
+};
+use core::{marker::PhantomData, mem::size_of};
+use std::sync::Mutex;
+use winterfell::{
+    crypto::{hashers::Blake3_192, DistinctHashers, DuplexRandomCoin, RandomCoin},
+    AggregateAir, AggregateInputs, AggregateProver, AggregateTrace, Air, AirContext, Assertion,
+    CancellationToken, DivergentConstraint, EvaluationFrame, FieldExtension, LowDegreeTest,
+    ProverError, ProverEvent, ProverObserver, ProverStep, Trace, TraceInfo, VerifierError,
+};
 
 #[test]
 fn fib_small_test_basic_proof_verification() {
@@ -23,6 +34,101 @@ fn fib_small_test_basic_proof_verification_extension() {
     crate::tests::test_basic_proof_verification(fib);
 }
 
+#[test]
+fn fib_small_test_basic_proof_verification_fri_folding_schedule() {
+    // the first two FRI layers are folded by 16 and 2, and the remaining layer by 4
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(
+        1024,
+        build_proof_options(false).with_fri_folding_schedule(&[16, 2]),
+    ));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib_small_test_fri_folding_schedule_past_remainder() {
+    // the LDE domain of 512 elements is folded into 32 and then into 2 elements, which is fewer
+    // than the blowup factor of 8
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 4, 0);
+    let prover = super::FibSmallProver::<Rp64_256>::new(
+        options.clone().with_fri_folding_schedule(&[16, 16]),
+    );
+    assert_eq!(
+        Err(ProverError::InvalidFriFoldingSchedule(512, 2)),
+        prover.prove(prover.build_trace(128))
+    );
+
+    // a schedule which folds the LDE domain into exactly the blowup factor can be used
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(
+        128,
+        options.with_fri_folding_schedule(&[16, 4]),
+    ));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib_small_test_basic_proof_verification_merkle_arity() {
+    // the LDE domain of 1024 elements and the FRI layers of 256 and 64 leaves are all powers of 4
+    let options = build_proof_options(false)
+        .with_merkle_arity(4)
+        .with_merkle_cap_height(2);
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(256, options.clone()));
+    crate::tests::test_basic_proof_verification(fib);
+
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(256, options));
+    crate::tests::test_basic_proof_verification_fail(fib);
+
+    // the LDE domain of 512 elements and the FRI layer of 64 leaves are powers of 8
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 8, 7).with_merkle_arity(8);
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(128, options));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib_small_test_merkle_arity_incompatible_domain() {
+    // the LDE domain of 512 elements is not a power of 4
+    let prover =
+        super::FibSmallProver::<Rp64_256>::new(build_proof_options(false).with_merkle_arity(4));
+    assert_eq!(
+        Err(ProverError::InvalidMerkleTreeArity(4, 512)),
+        prover.prove(prover.build_trace(128))
+    );
+
+    // the LDE domain of 1024 elements is a power of 4, but folding it by 8 results in a FRI layer
+    // of 128 leaves, which is not
+    let options = ProofOptions::new(28, 8, 0, FieldExtension::None, 8, 7).with_merkle_arity(4);
+    let prover = super::FibSmallProver::<Rp64_256>::new(options);
+    assert_eq!(
+        Err(ProverError::InvalidMerkleTreeArity(4, 128)),
+        prover.prove(prover.build_trace(256))
+    );
+}
+
+#[test]
+fn fib_small_test_basic_proof_verification_fri_grinding() {
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(
+        1024,
+        build_proof_options(false).with_fri_grinding_factor(8),
+    ));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
+#[test]
+fn fib_small_test_basic_proof_verification_stir() {
+    let options = build_proof_options(false).with_low_degree_test(LowDegreeTest::Stir);
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(1024, options.clone()));
+    crate::tests::test_basic_proof_verification(fib);
+
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(1024, options));
+    crate::tests::test_basic_proof_verification_fail(fib);
+}
+
+#[test]
+fn fib_small_test_basic_proof_verification_stir_extension() {
+    let options = build_proof_options(true).with_low_degree_test(LowDegreeTest::Stir);
+    let fib = Box::new(super::FibExample::<Rp64_256>::new(128, options));
+    crate::tests::test_basic_proof_verification(fib);
+}
+
 #[test]
 fn fib_small_test_basic_proof_verification_fail() {
     let fib = Box::new(super::FibExample::<Rp64_256>::new(
@@ -31,3 +137,413 @@ fn fib_small_test_basic_proof_verification_fail() {
     ));
     crate::tests::test_basic_proof_verification_fail(fib);
 }
+
+#[test]
+fn fib_small_test_basic_proof_verification_distinct_hashers() {
+    // BLAKE3 for trace and constraint commitments, Rescue Prime for FRI and the public coin
+    type Hashers = DistinctHashers<Blake3_256, Blake3_256, Rp64_256>;
+    let fib = Box::new(super::FibExample::<Hashers>::new(
+        128,
+        build_proof_options(false),
+    ));
+    crate::tests::test_basic_proof_verification(fib);
+
+    let fib = Box::new(super::FibExample::<Hashers>::new(
+        128,
+        build_proof_options(false),
+    ));
+    crate::tests::test_basic_proof_verification_fail(fib);
+}
+
+#[test]
+fn fib_small_test_basic_proof_verification_duplex_coin() {
+    let options = build_proof_options(true);
+    let prover = TestProver::<super::FibSmall, DuplexRandomCoin<Rp64_256>>::new(options);
+    let proof = prover.prove(prover.inner.build_trace(128)).unwrap();
+    let result = super::compute_fib_term(128);
+
+    // the proof is accepted only by a verifier which uses the same random coin
+    let verify_duplex = winterfell::verify::<super::FibSmall, Rp64_256, DuplexRandomCoin<Rp64_256>>;
+    assert!(verify_duplex(proof.clone(), result).is_ok());
+    assert!(verify_duplex(proof.clone(), result + BaseElement::ONE).is_err());
+    assert!(
+        winterfell::verify::<super::FibSmall, Rp64_256, DefaultRandomCoin<Rp64_256>>(proof, result)
+            .is_err()
+    );
+}
+
+#[test]
+fn fib_small_test_security_level_weak_coin_hash() {
+    let options = ProofOptions::new(64, 8, 0, FieldExtension::Quadratic, 4, 7);
+    let prover = super::FibSmallProver::<Rp64_256>::new(options);
+    let proof = prover.prove(prover.build_trace(128)).unwrap();
+
+    // the security level is limited by the hash function of the random coin, even if all
+    // commitments are built using a stronger hash function
+    type WeakCoin = DefaultRandomCoin<Blake3_192<BaseElement>>;
+    assert!(proof.security_level::<Rp64_256, DefaultRandomCoin<Rp64_256>>(true) > 96);
+    assert_eq!(96, proof.security_level::<Rp64_256, WeakCoin>(true));
+}
+
+#[test]
+fn fib_small_test_prover_observer() {
+    let prover = TestProver::<super::FibSmall>::new(build_proof_options(false));
+    let expected = prover.inner.prove(prover.inner.build_trace(128)).unwrap();
+
+    // observing the prover must not affect the proof
+    let proof = prover.prove(prover.inner.build_trace(128)).unwrap();
+    assert_eq!(expected.to_bytes(), proof.to_bytes());
+
+    // events must be emitted in the order in which the steps are executed
+    let events = prover.events.into_inner().unwrap();
+    let steps = events.iter().map(|event| event.step).collect::<Vec<_>>();
+    let (trace_length, ce_domain_size, lde_domain_size) = match steps[0] {
+        ProverStep::DomainPrepared {
+            trace_length,
+            ce_domain_size,
+            lde_domain_size,
+        } => (trace_length, ce_domain_size, lde_domain_size),
+        step => panic!("unexpected first step: {step:?}"),
+    };
+    assert_eq!(64, trace_length);
+    assert_eq!(512, lde_domain_size);
+    assert!(matches!(
+        &steps[1..],
+        [
+            ProverStep::TraceExtended {
+                num_columns: 2,
+                trace_length: 64,
+                lde_domain_size: 512,
+            },
+            ProverStep::TraceCommitted { tree_depth: 9 },
+            ProverStep::ConstraintsEvaluated { .. },
+            ProverStep::ConstraintColumnsCombined { .. },
+            ProverStep::CombinedEvaluationsInterpolated { .. },
+            ProverStep::CompositionPolySplit { .. },
+            ProverStep::CompositionPolyBuilt {
+                column_degree: 63,
+                ..
+            },
+            ProverStep::CompositionPolyEvaluated {
+                lde_domain_size: 512,
+                ..
+            },
+            ProverStep::ConstraintsCommitted { tree_depth: 9 },
+            ProverStep::DeepCompositionBuilt { degree: 63 },
+            ProverStep::DeepCompositionEvaluated {
+                lde_domain_size: 512
+            },
+            ProverStep::FriLayersBuilt { .. },
+            ProverStep::QueryPositionsDrawn { .. },
+            ProverStep::ProofBuilt { .. },
+        ]
+    ));
+    assert!(steps.iter().all(|step| match step {
+        ProverStep::ConstraintsEvaluated {
+            ce_domain_size: n, ..
+        }
+        | ProverStep::ConstraintColumnsCombined {
+            ce_domain_size: n, ..
+        }
+        | ProverStep::CombinedEvaluationsInterpolated { ce_domain_size: n } => *n == ce_domain_size,
+        _ => true,
+    }));
+
+    // the extended trace consists of trace polynomials and their evaluations over the LDE domain
+    assert_eq!(
+        (trace_length + lde_domain_size) * 2 * size_of::<BaseElement>(),
+        events[1].allocated_bytes
+    );
+    assert!(events.iter().all(|event| event.duration.is_some()));
+}
+
+#[test]
+fn fib_small_test_prover_cancellation() {
+    let options = build_proof_options(false);
+    let expected = super::FibSmallProver::<Rp64_256>::new(options.clone())
+        .prove(super::FibSmallProver::<Rp64_256>::new(options.clone()).build_trace(128))
+        .unwrap();
+
+    // a token which is never cancelled does not affect the proof
+    let prover = TestProver::<super::FibSmall>::new(options.clone());
+    let proof = prover.prove(prover.inner.build_trace(128)).unwrap();
+    assert_eq!(expected.to_bytes(), proof.to_bytes());
+
+    // proof generation stops if the token is cancelled before it starts, or in the middle of it
+    let prover = TestProver::<super::FibSmall>::new(options.clone());
+    prover.token.cancel();
+    let result = prover.prove(prover.inner.build_trace(128));
+    assert_eq!(Err(ProverError::Cancelled), result);
+
+    let prover = TestProver::<super::FibSmall>::new(options.clone())
+        .with_cancel_after(|step| matches!(step, ProverStep::TraceExtended { .. }));
+    let result = prover.prove(prover.inner.build_trace(128));
+    assert_eq!(Err(ProverError::Cancelled), result);
+
+    let prover =
+        TestProver::<super::FibSmall>::new(options.with_low_degree_test(LowDegreeTest::Stir))
+            .with_cancel_after(|step| matches!(step, ProverStep::DeepCompositionEvaluated { .. }));
+    let result = prover.prove(prover.inner.build_trace(128));
+    assert_eq!(Err(ProverError::Cancelled), result);
+}
+
+#[test]
+fn fib_small_test_prover_memory_budget() {
+    let options = build_proof_options(false);
+
+    // the memory budget is checked before any work is done
+    let prover = TestProver::<super::FibSmall>::new(options.clone()).with_memory_budget(1024);
+    let required = match prover.prove(prover.inner.build_trace(128)) {
+        Err(ProverError::MemoryBudgetExceeded(required, 1024)) => required,
+        result => panic!("unexpected result: {result:?}"),
+    };
+
+    // the estimate covers at least the extended trace and its Merkle tree
+    assert!(required > 512 * 2 * size_of::<BaseElement>() + 512 * 32);
+
+    // a proof is generated if the estimated memory fits into the budget
+    let prover = TestProver::<super::FibSmall>::new(options).with_memory_budget(required);
+    assert!(prover.prove(prover.inner.build_trace(128)).is_ok());
+}
+
+#[test]
+fn fib_small_test_prover_self_verification() {
+    // self-verification does not affect a valid proof
+    let options = build_proof_options(true);
+    let expected = super::FibSmallProver::<Rp64_256>::new(options.clone())
+        .prove(super::FibSmallProver::<Rp64_256>::new(options.clone()).build_trace(128))
+        .unwrap();
+
+    let prover = TestProver::<super::FibSmall>::new(options.clone()).with_self_verification();
+    let proof = prover.prove(prover.inner.build_trace(128)).unwrap();
+    assert_eq!(expected.to_bytes(), proof.to_bytes());
+
+    // a proof rejected by the verifier is reported together with the constraint which caused it
+    let prover = TestProver::<FaultyFibSmall>::new(options).with_self_verification();
+    let result = prover.prove(prover.inner.build_trace(128));
+    assert_eq!(
+        Err(ProverError::SelfVerificationFailed(
+            VerifierError::InconsistentOodConstraintEvaluations,
+            vec![DivergentConstraint::MainTransition(0)]
+        )),
+        result
+    );
+}
+
+#[test]
+#[cfg(feature = "concurrent")]
+fn fib_small_test_prover_thread_pool() {
+    use winterfell::rayon::ThreadPoolBuilder;
+
+    let options = build_proof_options(false);
+    let inner = super::FibSmallProver::<Rp64_256>::new(options.clone());
+    let expected = inner.prove(inner.build_trace(1024)).unwrap();
+
+    // proofs generated in pools of different sizes are identical, and all steps of proof
+    // generation are executed inside the pool
+    for num_threads in [1, 3] {
+        let prover = TestProver::<super::FibSmall>::new(options.clone());
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .unwrap();
+        let proof = prover
+            .prove_in_pool(prover.inner.build_trace(1024), &pool)
+            .unwrap();
+        assert_eq!(expected.to_bytes(), proof.to_bytes());
+
+        let observed = prover.num_threads.into_inner().unwrap();
+        assert!(!observed.is_empty());
+        assert!(observed.iter().all(|&n| n == num_threads));
+    }
+}
+
+// TEST PROVER
+// ================================================================================================
+
+/// Fibonacci prover which proves execution against the AIR `A` using the random coin `R`, and
+/// whose cancellation, memory budget, and self-verification can be configured by tests.
+///
+/// The prover records all events emitted during proof generation, together with the number of
+/// threads available to it at every step (when `concurrent` feature is enabled), and cancels its
+/// token as soon as it completes a step matching the `cancel_after` predicate.
+struct TestProver<A, R = DefaultRandomCoin<Rp64_256>> {
+    inner: super::FibSmallProver<Rp64_256>,
+    events: Mutex<Vec<ProverEvent>>,
+    #[cfg(feature = "concurrent")]
+    num_threads: Mutex<Vec<usize>>,
+    token: CancellationToken,
+    cancel_after: fn(&ProverStep) -> bool,
+    memory_budget: Option<usize>,
+    self_verification: bool,
+    _phantom: PhantomData<fn() -> (A, R)>,
+}
+
+impl<A, R> TestProver<A, R> {
+    fn new(options: ProofOptions) -> Self {
+        Self {
+            inner: super::FibSmallProver::new(options),
+            events: Mutex::new(Vec::new()),
+            #[cfg(feature = "concurrent")]
+            num_threads: Mutex::new(Vec::new()),
+            token: CancellationToken::new(),
+            cancel_after: |_| false,
+            memory_budget: None,
+            self_verification: false,
+            _phantom: PhantomData,
+        }
+    }
+
+    fn with_cancel_after(mut self, cancel_after: fn(&ProverStep) -> bool) -> Self {
+        self.cancel_after = cancel_after;
+        self
+    }
+
+    fn with_memory_budget(mut self, memory_budget: usize) -> Self {
+        self.memory_budget = Some(memory_budget);
+        self
+    }
+
+    fn with_self_verification(mut self) -> Self {
+        self.self_verification = true;
+        self
+    }
+}
+
+impl<A, R> ProverObserver for TestProver<A, R> {
+    fn on_event(&self, event: &ProverEvent) {
+        self.events.lock().unwrap().push(event.clone());
+        #[cfg(feature = "concurrent")]
+        self.num_threads
+            .lock()
+            .unwrap()
+            .push(winterfell::rayon::current_num_threads());
+        if (self.cancel_after)(&event.step) {
+            self.token.cancel();
+        }
+    }
+}
+
+impl<A, R> Prover for TestProver<A, R>
+where
+    A: Air<BaseField = BaseElement, PublicInputs = BaseElement>,
+    R: RandomCoin<BaseField = BaseElement>,
+{
+    type BaseField = BaseElement;
+    type Air = A;
+    type Trace = TraceTable<BaseElement>;
+    type HashFn = Rp64_256;
+    type RandomCoin = R;
+
+    fn get_pub_inputs(&self, trace: &Self::Trace) -> BaseElement {
+        self.inner.get_pub_inputs(trace)
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.inner.options()
+    }
+
+    fn observer(&self) -> &dyn ProverObserver {
+        self
+    }
+
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        Some(&self.token)
+    }
+
+    fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    fn self_verification(&self) -> bool {
+        self.self_verification
+    }
+}
+
+/// Fibonacci AIR whose first transition constraint does not hold when evaluated over an extension
+/// field. Since the prover evaluates constraints over the base field, and the verifier evaluates
+/// them at the out-of-domain point in the extension field, proofs for this AIR are rejected.
+struct FaultyFibSmall(super::FibSmall);
+
+impl Air for FaultyFibSmall {
+    type BaseField = BaseElement;
+    type PublicInputs = BaseElement;
+
+    fn new(trace_info: TraceInfo, pub_inputs: BaseElement, options: ProofOptions) -> Self {
+        Self(super::FibSmall::new(trace_info, pub_inputs, options))
+    }
+
+    fn context(&self) -> &AirContext<BaseElement> {
+        self.0.context()
+    }
+
+    fn domain_separator(&self) -> &[u8] {
+        self.0.domain_separator()
+    }
+
+    fn evaluate_transition<E: FieldElement<BaseField = BaseElement>>(
+        &self,
+        frame: &EvaluationFrame<E>,
+        periodic_values: &[E],
+        result: &mut [E],
+    ) {
+        self.0.evaluate_transition(frame, periodic_values, result);
+        if E::EXTENSION_DEGREE > 1 {
+            result[0] += E::ONE;
+        }
+    }
+
+    fn get_assertions(&self) -> Vec<Assertion<BaseElement>> {
+        self.0.get_assertions()
+    }
+}
+
+// AGGREGATE PROOFS
+// ================================================================================================
+
+#[test]
+fn fib_small_test_aggregate_proof_verification() {
+    let num_instances = 8;
+    let prover = AggregateProver::new(super::FibSmallProver::<Rp64_256>::new(build_proof_options(
+        false,
+    )));
+    let traces = (0..num_instances)
+        .map(|_| prover.inner().build_trace(128))
+        .collect();
+    let proof = prover.prove(AggregateTrace::new(traces).unwrap()).unwrap();
+
+    // the aggregated proof must be much smaller than separate proofs for all instances
+    let instance_proof = prover
+        .inner()
+        .prove(prover.inner().build_trace(128))
+        .unwrap();
+    let proof_size = proof.to_bytes().len();
+    assert!(proof_size < 2 * instance_proof.to_bytes().len());
+
+    let result = super::compute_fib_term(128);
+    let verify = |proof: StarkProof, results: Vec<BaseElement>| {
+        winterfell::verify::<AggregateAir<super::FibSmall>, Rp64_256, DefaultRandomCoin<Rp64_256>>(
+            proof,
+            AggregateInputs::new(results),
+        )
+    };
+    assert!(verify(proof.clone(), vec![result; num_instances]).is_ok());
+
+    // the proof must be rejected if public inputs of any instance are wrong
+    let mut results = vec![result; num_instances];
+    results[5] += BaseElement::ONE;
+    assert!(verify(proof, results).is_err());
+}
+
+#[test]
+fn fib_small_test_aggregate_trace_too_many_instances() {
+    // a trace of a single instance has two columns, and thus, at most 127 instances fit into the
+    // maximum trace width
+    let prover = super::FibSmallProver::<Rp64_256>::new(build_proof_options(false));
+    let traces = (0..128).map(|_| prover.build_trace(16)).collect::<Vec<_>>();
+    assert_eq!(127, traces[0].layout().max_aggregate_instances());
+    assert_eq!(
+        Some(ProverError::TooManyAggregateInstances(128, 127)),
+        AggregateTrace::new(traces).err()
+    );
+}
@@ -42,6 +42,10 @@ impl Air for MulFib2Air {
         &self.context
     }
 
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/mulfib2"
+    }
+
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
         frame: &EvaluationFrame<E>,
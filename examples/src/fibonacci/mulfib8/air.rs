@@ -48,6 +48,10 @@ impl Air for MulFib8Air {
         &self.context
     }
 
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/mulfib8"
+    }
+
     fn evaluate_transition<E: FieldElement + From<Self::BaseField>>(
         &self,
         frame: &EvaluationFrame<E>,
@@ -244,6 +244,10 @@ impl Air for LamportThresholdAir {
     fn context(&self) -> &AirContext<Self::BaseField> {
         &self.context
     }
+
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/lamport-threshold"
+    }
 }
 
 // HELPER FUNCTIONS
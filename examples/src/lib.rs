@@ -5,7 +5,10 @@
 
 use structopt::StructOpt;
 use winterfell::{
-    crypto::hashers::{GriffinJive64_256, Rp64_256, RpJive64_256},
+    crypto::{
+        hashers::{GriffinJive64_256, Rp64_256, RpJive64_256},
+        DefaultRandomCoin, ElementHasher,
+    },
     math::fields::f128::BaseElement,
     FieldExtension, ProofOptions, StarkProof, VerifierError,
 };
@@ -108,12 +111,12 @@ impl ExampleOptions {
     /// Returns security level of the input proof in bits.
     pub fn get_proof_security_level(&self, proof: &StarkProof, conjectured: bool) -> usize {
         let security_level = match self.hash_fn.as_str() {
-            "blake3_192" => proof.security_level::<Blake3_192>(conjectured),
-            "blake3_256" => proof.security_level::<Blake3_256>(conjectured),
-            "sha3_256" => proof.security_level::<Sha3_256>(conjectured),
-            "rp64_256" => proof.security_level::<Rp64_256>(conjectured),
-            "rp_jive64_256" => proof.security_level::<RpJive64_256>(conjectured),
-            "griffin_jive64_256" => proof.security_level::<GriffinJive64_256>(conjectured),
+            "blake3_192" => get_security_level::<Blake3_192>(proof, conjectured),
+            "blake3_256" => get_security_level::<Blake3_256>(proof, conjectured),
+            "sha3_256" => get_security_level::<Sha3_256>(proof, conjectured),
+            "rp64_256" => get_security_level::<Rp64_256>(proof, conjectured),
+            "rp_jive64_256" => get_security_level::<RpJive64_256>(proof, conjectured),
+            "griffin_jive64_256" => get_security_level::<GriffinJive64_256>(proof, conjectured),
             val => panic!("'{val}' is not a valid hash function option"),
         };
 
@@ -121,6 +124,12 @@ impl ExampleOptions {
     }
 }
 
+/// Returns security level of a proof generated using hash function `H` for all commitments and
+/// the default random coin instantiated with `H`.
+fn get_security_level<H: ElementHasher>(proof: &StarkProof, conjectured: bool) -> u32 {
+    proof.security_level::<H, DefaultRandomCoin<H>>(conjectured)
+}
+
 #[derive(StructOpt, Debug)]
 //#[structopt(about = "available examples")]
 pub enum ExampleType {
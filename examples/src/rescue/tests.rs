@@ -3,8 +3,10 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Blake3_256;
-use winterfell::{FieldExtension, ProofOptions};
+use super::{Blake3_256, RescueProver};
+use winterfell::{
+    math::fields::f128::BaseElement, FieldExtension, ProofOptions, Prover, ProverContext,
+};
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -33,6 +35,21 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_prover_context() {
+    // proofs generated with a shared context must be identical to proofs generated without one
+    let prover = RescueProver::<Blake3_256>::new(build_options(false));
+    let mut context = ProverContext::new();
+    for seed in [[1u128, 2], [3, 4]] {
+        let seed = seed.map(BaseElement::new);
+        let proof = prover
+            .prove_with_context(prover.build_trace(seed, 128), &mut context)
+            .unwrap();
+        assert_eq!(prover.prove(prover.build_trace(seed, 128)).unwrap(), proof);
+    }
+    assert_eq!(1, context.num_domains());
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
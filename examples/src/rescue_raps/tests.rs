@@ -3,8 +3,18 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::Blake3_256;
-use winterfell::{FieldExtension, ProofOptions};
+use super::{
+    Blake3_256, DefaultRandomCoin, PublicInputs, RescueRapsAir, RescueRapsExample,
+    RescueRapsProver, StarkProof,
+};
+use winterfell::{
+    math::{
+        fields::{f128::BaseElement, QuadExtension},
+        FieldElement,
+    },
+    AggregateAir, AggregateInputs, AggregateProver, AggregateTrace, FieldExtension, ProofOptions,
+    Prover, ProverContext, ProverStage, StagedProver,
+};
 
 #[test]
 fn rescue_test_basic_proof_verification() {
@@ -24,6 +34,15 @@ fn rescue_test_basic_proof_verification_extension() {
     crate::tests::test_basic_proof_verification(rescue_eg);
 }
 
+#[test]
+fn rescue_test_basic_proof_verification_merkle_cap() {
+    let rescue_eg = Box::new(super::RescueRapsExample::<Blake3_256>::new(
+        128,
+        build_options(false).with_merkle_cap_height(2),
+    ));
+    crate::tests::test_basic_proof_verification(rescue_eg);
+}
+
 #[test]
 fn rescue_test_basic_proof_verification_fail() {
     let rescue_eg = Box::new(super::RescueRapsExample::<Blake3_256>::new(
@@ -33,6 +52,91 @@ fn rescue_test_basic_proof_verification_fail() {
     crate::tests::test_basic_proof_verification_fail(rescue_eg);
 }
 
+#[test]
+fn rescue_test_staged_prover_checkpoints() {
+    let rescue_eg = RescueRapsExample::<Blake3_256>::new(32, build_options(true));
+    let prover = RescueRapsProver::<Blake3_256>::new(rescue_eg.options.clone());
+    let build_trace = || {
+        prover.build_trace(
+            &rescue_eg.seeds,
+            &rescue_eg.permuted_seeds,
+            rescue_eg.result,
+        )
+    };
+    let expected = prover.prove(build_trace()).unwrap();
+
+    // take a checkpoint after every stage and resume proof generation from it; the resulting
+    // proofs must be identical to the proof generated in one go
+    for num_stages in 0..=6 {
+        let mut context = ProverContext::new();
+        let mut trace = build_trace();
+        let checkpoint = {
+            let mut prover =
+                StagedProver::<_, QuadExtension<BaseElement>>::new(&prover, &trace, &mut context)
+                    .unwrap();
+            run_stages(&mut prover, &mut trace, 0..num_stages);
+            prover.to_checkpoint()
+        };
+
+        // a checkpoint cannot be resumed for different public inputs
+        let mut result = rescue_eg.result;
+        result[0][0] += BaseElement::ONE;
+        assert!(
+            StagedProver::<_, QuadExtension<BaseElement>>::from_checkpoint(
+                &prover,
+                PublicInputs { result },
+                &mut context,
+                &checkpoint,
+            )
+            .is_err()
+        );
+
+        // truncated checkpoints cannot be resumed
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        assert!(
+            StagedProver::<_, QuadExtension<BaseElement>>::from_checkpoint(
+                &prover,
+                pub_inputs,
+                &mut context,
+                &checkpoint[..checkpoint.len() - 1],
+            )
+            .is_err()
+        );
+
+        let pub_inputs = prover.get_pub_inputs(&trace);
+        let mut prover = StagedProver::<_, QuadExtension<BaseElement>>::from_checkpoint(
+            &prover,
+            pub_inputs,
+            &mut context,
+            &checkpoint,
+        )
+        .unwrap();
+        assert_eq!(num_stages, prover.stage() as usize);
+        run_stages(&mut prover, &mut trace, num_stages..6);
+        assert_eq!(ProverStage::LowDegreeProofBuilt, prover.stage());
+        assert_eq!(expected, prover.build_proof().unwrap());
+    }
+}
+
+fn run_stages(
+    prover: &mut StagedProver<RescueRapsProver<Blake3_256>, QuadExtension<BaseElement>>,
+    trace: &mut <RescueRapsProver<Blake3_256> as Prover>::Trace,
+    stages: core::ops::Range<usize>,
+) {
+    for stage in stages {
+        match stage {
+            0 => prover.commit_trace(trace),
+            1 => prover.commit_aux_trace(trace),
+            2 => prover.commit_constraints(),
+            3 => prover.evaluate_ood(),
+            4 => prover.evaluate_deep_composition(),
+            5 => prover.build_low_degree_proof(),
+            _ => unreachable!(),
+        }
+        .unwrap();
+    }
+}
+
 fn build_options(use_extension_field: bool) -> ProofOptions {
     let extension = if use_extension_field {
         FieldExtension::Quadratic
@@ -41,3 +145,39 @@ fn build_options(use_extension_field: bool) -> ProofOptions {
     };
     ProofOptions::new(28, 8, 0, extension, 4, 31)
 }
+
+#[test]
+fn rescue_test_aggregate_proof_verification() {
+    // instances have different seeds, and thus, different traces and public inputs
+    let options = build_options(true);
+    let examples = (0..4)
+        .map(|_| RescueRapsExample::<Blake3_256>::new(32, options.clone()))
+        .collect::<Vec<_>>();
+    let prover = AggregateProver::new(RescueRapsProver::<Blake3_256>::new(options));
+    let traces = examples
+        .iter()
+        .map(|eg| {
+            prover
+                .inner()
+                .build_trace(&eg.seeds, &eg.permuted_seeds, eg.result)
+        })
+        .collect();
+    let proof = prover.prove(AggregateTrace::new(traces).unwrap()).unwrap();
+
+    let mut results = examples.iter().map(|eg| eg.result).collect::<Vec<_>>();
+    let verify = |proof: StarkProof, results: &[[[BaseElement; 2]; 2]]| {
+        let pub_inputs = results
+            .iter()
+            .map(|&result| PublicInputs { result })
+            .collect();
+        winterfell::verify::<AggregateAir<RescueRapsAir>, Blake3_256, DefaultRandomCoin<Blake3_256>>(
+            proof,
+            AggregateInputs::new(pub_inputs),
+        )
+    };
+    assert!(verify(proof.clone(), &results).is_ok());
+
+    // the proof must be rejected if public inputs of any instance are wrong
+    results[2][0][0] += BaseElement::ONE;
+    assert!(verify(proof, &results).is_err());
+}
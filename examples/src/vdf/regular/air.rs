@@ -69,4 +69,8 @@ impl Air for VdfAir {
     fn context(&self) -> &AirContext<Self::BaseField> {
         &self.context
     }
+
+    fn domain_separator(&self) -> &[u8] {
+        b"winterfell/examples/vdf"
+    }
 }
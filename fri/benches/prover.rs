@@ -6,12 +6,18 @@
 use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
 use crypto::{hashers::Blake3_256, DefaultRandomCoin};
 use math::{fft, fields::f128::BaseElement, FieldElement};
-use rand_utils::rand_vector;
+use rand_utils::{rand_value, rand_vector};
 use std::time::Duration;
 use winter_fri::{DefaultProverChannel, FriOptions, FriProver};
 
 static BATCH_SIZES: [usize; 3] = [65536, 131072, 262144];
 static BLOWUP_FACTOR: usize = 8;
+static NUM_QUERIES: usize = 32;
+
+// Both benchmarks can be executed with and without `concurrent` feature enabled to measure the
+// speedup achieved by building and querying FRI layers in multiple threads, e.g.:
+//   cargo bench -p winter-fri --bench prover
+//   cargo bench -p winter-fri --bench prover --features concurrent
 
 pub fn build_layers(c: &mut Criterion) {
     let mut fri_group = c.benchmark_group("FRI prover");
@@ -36,7 +42,7 @@ pub fn build_layers(c: &mut Criterion) {
                             Blake3_256<BaseElement>,
                             DefaultRandomCoin<Blake3_256<BaseElement>>,
                         >::new(domain_size, 32);
-                        prover.build_layers(&mut channel, evaluations);
+                        prover.build_layers(&mut channel, evaluations).unwrap();
                         prover.reset();
                     },
                     BatchSize::LargeInput,
@@ -46,7 +52,44 @@ pub fn build_layers(c: &mut Criterion) {
     }
 }
 
-criterion_group!(fri_prover_group, build_layers);
+pub fn build_proof(c: &mut Criterion) {
+    let mut fri_group = c.benchmark_group("FRI prover");
+    fri_group.sample_size(10);
+    fri_group.measurement_time(Duration::from_secs(10));
+
+    // use folding factor 2 so that the number of layers queried in the query phase is large
+    let options = FriOptions::new(BLOWUP_FACTOR, 2, 255).with_merkle_cap_height(4);
+
+    for &domain_size in &BATCH_SIZES {
+        let evaluations = build_evaluations(domain_size);
+        let positions = (0..NUM_QUERIES)
+            .map(|_| (rand_value::<u64>() as usize) % domain_size)
+            .collect::<Vec<_>>();
+
+        fri_group.bench_with_input(
+            BenchmarkId::new("build_proof", domain_size),
+            &evaluations,
+            |b, e| {
+                b.iter_batched(
+                    || {
+                        let mut channel = DefaultProverChannel::<
+                            BaseElement,
+                            Blake3_256<BaseElement>,
+                            DefaultRandomCoin<Blake3_256<BaseElement>>,
+                        >::new(domain_size, NUM_QUERIES);
+                        let mut prover = FriProver::new(options.clone());
+                        prover.build_layers(&mut channel, e.clone()).unwrap();
+                        prover
+                    },
+                    |mut prover| prover.build_proof(&positions),
+                    BatchSize::LargeInput,
+                );
+            },
+        );
+    }
+}
+
+criterion_group!(fri_prover_group, build_layers, build_proof);
 criterion_main!(fri_prover_group);
 
 // HELPER FUNCTIONS
@@ -20,6 +20,15 @@ pub enum VerifierError {
     UnsupportedFoldingFactor(usize),
     /// Number of query positions does not match the number of provided evaluations.
     NumPositionEvaluationMismatch(usize, usize),
+    /// Number of polynomials for which evaluations were provided does not match the number of
+    /// polynomials in the FRI batch.
+    NumBatchedPolynomialsMismatch(usize, usize),
+    /// Degree of a batched polynomial does not match the degree bound of any of the FRI layers.
+    InvalidBatchedDegree(usize),
+    /// Number of proof-of-work nonces does not match the number of FRI layers.
+    NumLayerNoncesMismatch(usize, usize),
+    /// Proof-of-work nonce for one of the FRI layers did not meet the grinding requirement.
+    LayerProofOfWorkVerificationFailed(usize),
     /// Evaluations at queried positions did not match layer commitment made by the prover.
     LayerCommitmentMismatch,
     /// Degree-respecting projection was not performed correctly at one of the layers.
@@ -49,6 +58,18 @@ impl fmt::Display for VerifierError {
             Self::NumPositionEvaluationMismatch(num_positions, num_evaluations) => write!(f,
                 "the number of query positions must be the same as the number of polynomial evaluations, but {num_positions} and {num_evaluations} were provided"
             ),
+            Self::NumBatchedPolynomialsMismatch(expected, actual) => write!(f,
+                "expected evaluations of {expected} batched polynomials, but {actual} were provided"
+            ),
+            Self::InvalidBatchedDegree(degree) => {
+                write!(f, "batched polynomial of degree {degree} does not match the degree bound of any FRI layer")
+            }
+            Self::NumLayerNoncesMismatch(expected, actual) => {
+                write!(f, "expected {expected} FRI layer proof-of-work nonces, but {actual} were provided")
+            }
+            Self::LayerProofOfWorkVerificationFailed(layer) => {
+                write!(f, "proof-of-work verification failed at layer {layer}")
+            }
             Self::LayerCommitmentMismatch => {
                 write!(f, "FRI queries did not match layer commitment made by the prover")
             }
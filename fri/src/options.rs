@@ -4,6 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use math::StarkField;
+use utils::collections::Vec;
 
 // FRI OPTIONS
 // ================================================================================================
@@ -12,8 +13,12 @@ use math::StarkField;
 #[derive(Clone, PartialEq, Eq)]
 pub struct FriOptions {
     folding_factor: usize,
+    folding_schedule: Vec<usize>,
     remainder_max_degree: usize,
     blowup_factor: usize,
+    merkle_cap_height: usize,
+    merkle_arity: usize,
+    folding_grinding_factor: u32,
 }
 
 impl FriOptions {
@@ -29,18 +34,77 @@ impl FriOptions {
             blowup_factor.is_power_of_two(),
             "blowup factor must be a power of two, but was {blowup_factor}"
         );
-        assert!(
-            folding_factor == 2
-                || folding_factor == 4
-                || folding_factor == 8
-                || folding_factor == 16,
-            "folding factor {folding_factor} is not supported"
-        );
+        assert_folding_factor_supported(folding_factor);
         FriOptions {
             folding_factor,
+            folding_schedule: Vec::new(),
             remainder_max_degree,
             blowup_factor,
+            merkle_cap_height: 0,
+            merkle_arity: 2,
+            folding_grinding_factor: 0,
+        }
+    }
+
+    /// Updates the height of Merkle tree caps used to commit to FRI layers.
+    ///
+    /// When the cap height is `h`, the prover commits to all arity^h nodes at depth `h` of each
+    /// FRI layer tree (rather than to the tree root), and authentication paths for layer queries
+    /// stop at the cap. By default, cap height is set to 0.
+    pub fn with_merkle_cap_height(mut self, merkle_cap_height: usize) -> Self {
+        self.merkle_cap_height = merkle_cap_height;
+        self
+    }
+
+    /// Updates the arity of Merkle trees used to commit to FRI layers.
+    ///
+    /// The number of leaves in the tree of every FRI layer (i.e., the size of the layer domain
+    /// divided by the folding factor of the layer) must be a power of the arity. By default, the
+    /// trees are binary.
+    ///
+    /// # Panics
+    /// Panics if `merkle_arity` is not 2, 4, 8, or 16.
+    pub fn with_merkle_arity(mut self, merkle_arity: usize) -> Self {
+        assert!(
+            matches!(merkle_arity, 2 | 4 | 8 | 16),
+            "Merkle tree arity {merkle_arity} is not supported"
+        );
+        self.merkle_arity = merkle_arity;
+        self
+    }
+
+    /// Updates the number of bits of proof-of-work grinding performed before drawing the folding
+    /// challenge for each FRI layer.
+    ///
+    /// When the grinding factor is `g` and greater than 0, after committing to a layer the prover
+    /// searches for a nonce which, when absorbed into the public coin, results in at least `g`
+    /// leading zeros. The nonce is absorbed before the folding challenge α is drawn, and is
+    /// included in the proof. By default, the grinding factor is set to 0 (no grinding).
+    pub fn with_folding_grinding_factor(mut self, folding_grinding_factor: u32) -> Self {
+        self.folding_grinding_factor = folding_grinding_factor;
+        self
+    }
+
+    /// Updates the folding factors used for individual FRI layers.
+    ///
+    /// The i-th entry of the `folding_schedule` defines the factor by which the i-th FRI layer is
+    /// folded; layers past the end of the schedule are folded by the default folding factor. For
+    /// example, a schedule of `[16, 16, 8, 4]` folds the first two layers by 16, and the last two
+    /// layers by 8 and 4 respectively. By default, the schedule is empty.
+    ///
+    /// The schedule is not checked against the size of the evaluation domain here; a schedule
+    /// which folds a domain into fewer than `blowup_factor` elements cannot be used to prove
+    /// that a polynomial over this domain is of low degree (see
+    /// [remainder_domain_size()](FriOptions::remainder_domain_size)).
+    ///
+    /// # Panics
+    /// Panics if any of the factors in the `folding_schedule` is not 2, 4, 8, or 16.
+    pub fn with_folding_schedule(mut self, folding_schedule: &[usize]) -> Self {
+        for &folding_factor in folding_schedule {
+            assert_folding_factor_supported(folding_factor);
         }
+        self.folding_schedule = folding_schedule.to_vec();
+        self
     }
 
     /// Returns the offset by which the evaluation domain is shifted.
@@ -61,6 +125,23 @@ impl FriOptions {
         self.folding_factor
     }
 
+    /// Returns the folding factors for the FRI layers which are not folded by the default
+    /// folding factor.
+    pub fn folding_schedule(&self) -> &[usize] {
+        &self.folding_schedule
+    }
+
+    /// Returns the factor by which the FRI layer at the specified depth is folded.
+    ///
+    /// This is the factor specified for the layer in the folding schedule, or the default
+    /// folding factor if the schedule does not cover the layer.
+    pub fn layer_folding_factor(&self, layer_depth: usize) -> usize {
+        self.folding_schedule
+            .get(layer_depth)
+            .copied()
+            .unwrap_or(self.folding_factor)
+    }
+
     /// Returns maximum allowed remainder polynomial degree.
     ///
     /// In combination with `folding_factor` this property defines how many FRI layers are needed
@@ -78,17 +159,55 @@ impl FriOptions {
         self.blowup_factor
     }
 
+    /// Returns the height of Merkle tree caps used to commit to FRI layers.
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height
+    }
+
+    /// Returns the arity of Merkle trees used to commit to FRI layers.
+    pub fn merkle_arity(&self) -> usize {
+        self.merkle_arity
+    }
+
+    /// Returns the number of bits of proof-of-work grinding performed before drawing the folding
+    /// challenge for each FRI layer.
+    pub fn folding_grinding_factor(&self) -> u32 {
+        self.folding_grinding_factor
+    }
+
     /// Computes and return the number of FRI layers required for a domain of the specified size.
     ///
-    /// The number of layers for a given domain size is defined by the `folding_factor` and
-    /// `remainder_max_degree` and `blowup_factor` settings.
+    /// The number of layers for a given domain size is defined by the `folding_factor`,
+    /// `folding_schedule`, `remainder_max_degree`, and `blowup_factor` settings.
     pub fn num_fri_layers(&self, mut domain_size: usize) -> usize {
         let mut result = 0;
         let max_remainder_size = (self.remainder_max_degree + 1) * self.blowup_factor;
         while domain_size > max_remainder_size {
-            domain_size /= self.folding_factor;
+            domain_size /= self.layer_folding_factor(result);
             result += 1;
         }
         result
     }
+
+    /// Returns the size of the remainder domain (i.e., the domain of the last FRI layer) for an
+    /// evaluation domain of the specified size.
+    ///
+    /// The remainder domain must contain at least `blowup_factor` elements for the FRI protocol
+    /// to be executed over the evaluation domain. This may not be the case when the factors in
+    /// the folding schedule fold the domain past the maximum remainder size.
+    pub fn remainder_domain_size(&self, domain_size: usize) -> usize {
+        (0..self.num_fri_layers(domain_size)).fold(domain_size, |size, depth| {
+            size / self.layer_folding_factor(depth)
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn assert_folding_factor_supported(folding_factor: usize) {
+    assert!(
+        folding_factor == 2 || folding_factor == 4 || folding_factor == 8 || folding_factor == 16,
+        "folding factor {folding_factor} is not supported"
+    );
 }
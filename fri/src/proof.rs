@@ -3,6 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
+use crate::FriOptions;
 use crypto::{BatchMerkleProof, ElementHasher, Hasher};
 use math::FieldElement;
 use utils::{
@@ -23,7 +24,9 @@ use utils::{
 /// A proof consists of zero or more layers and a remainder polynomial. Each layer contains a set of
 /// polynomial evaluations at positions queried by the verifier as well as Merkle authentication
 /// paths for these evaluations (the Merkle paths are compressed into a batch Merkle proof). The
-/// remainder polynomial is given by its list of coefficients i.e. field elements.
+/// remainder polynomial is given by its list of coefficients i.e. field elements. When
+/// proof-of-work grinding is performed before drawing FRI folding challenges, the proof also
+/// contains the proof-of-work nonce for each FRI layer.
 ///
 /// All values in a proof are stored as vectors of bytes. Thus, the values must be parsed before
 /// they can be returned to the user. To do this, [parse_layers()](FriProof::parse_layers())
@@ -32,13 +35,15 @@ use utils::{
 pub struct FriProof {
     layers: Vec<FriProofLayer>,
     remainder: Vec<u8>,
+    pow_nonces: Vec<u64>,
     num_partitions: u8, // stored as power of 2
 }
 
 impl FriProof {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Creates a new FRI proof from the provided layers and remainder polynomial.
+    /// Creates a new FRI proof from the provided layers, remainder polynomial, and layer
+    /// proof-of-work nonces.
     ///
     /// # Panics
     /// Panics if:
@@ -47,6 +52,7 @@ impl FriProof {
     pub(crate) fn new<E: FieldElement>(
         layers: Vec<FriProofLayer>,
         remainder: Vec<E>,
+        pow_nonces: Vec<u64>,
         num_partitions: usize,
     ) -> Self {
         assert!(
@@ -69,6 +75,7 @@ impl FriProof {
         FriProof {
             layers,
             remainder: remainder.to_bytes(),
+            pow_nonces,
             num_partitions: num_partitions.trailing_zeros() as u8,
         }
     }
@@ -89,6 +96,14 @@ impl FriProof {
         self.remainder.len() / E::ELEMENT_BYTES
     }
 
+    /// Returns proof-of-work nonces found by the prover before drawing the folding challenge for
+    /// each FRI layer.
+    ///
+    /// The nonces are empty if no proof-of-work grinding was performed in the commit phase.
+    pub fn pow_nonces(&self) -> &[u64] {
+        &self.pow_nonces
+    }
+
     /// Returns the number of partitions used during proof generation.
     pub fn num_partitions(&self) -> usize {
         2usize.pow(self.num_partitions as u32)
@@ -96,10 +111,12 @@ impl FriProof {
 
     /// Returns the size of this proof in bytes.
     pub fn size(&self) -> usize {
-        // +1 for number of layers, +1 for remainder length, +1 for number of partitions
-        self.layers
-            .iter()
-            .fold(self.remainder.len() + 3, |acc, layer| acc + layer.size())
+        // +1 for number of layers, +1 for remainder length, +1 for number of nonces, +1 for
+        // number of partitions
+        self.layers.iter().fold(
+            self.remainder.len() + self.pow_nonces.len() * 8 + 4,
+            |acc, layer| acc + layer.size(),
+        )
     }
 
     // PARSING
@@ -109,19 +126,18 @@ impl FriProof {
     /// authentication paths for each query (grouped into batch Merkle proofs).
     ///
     /// # Panics
-    /// Panics if:
-    /// * `domain_size` is not a power of two.
-    /// * `folding_factor` is smaller than two or is not a power of two.
+    /// Panics if `domain_size` is not a power of two.
     ///
     /// # Errors
     /// Returns an error if:
-    /// * This proof is not consistent with the specified `domain_size` and `folding_factor`.
+    /// * This proof is not consistent with the specified `domain_size` and the folding factors
+    ///   and Merkle tree arity defined by the `options`.
     /// * Any of the layers could not be parsed successfully.
     #[allow(clippy::type_complexity)]
     pub fn parse_layers<H, E>(
         self,
         mut domain_size: usize,
-        folding_factor: usize,
+        options: &FriOptions,
     ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
     where
         E: FieldElement,
@@ -131,21 +147,21 @@ impl FriProof {
             domain_size.is_power_of_two(),
             "domain size must be a power of two"
         );
-        assert!(
-            folding_factor.is_power_of_two(),
-            "folding factor must be a power of two"
-        );
-        assert!(folding_factor > 1, "folding factor must be greater than 1");
 
         let mut layer_proofs = Vec::new();
         let mut layer_queries = Vec::new();
 
         // parse all layers
         for (i, layer) in self.layers.into_iter().enumerate() {
+            let folding_factor = options.layer_folding_factor(i);
             domain_size /= folding_factor;
-            let (qv, mp) = layer.parse(domain_size, folding_factor).map_err(|err| {
-                DeserializationError::InvalidValue(format!("failed to parse FRI layer {i}: {err}"))
-            })?;
+            let (qv, mp) = layer
+                .parse(domain_size, folding_factor, options.merkle_arity())
+                .map_err(|err| {
+                    DeserializationError::InvalidValue(format!(
+                        "failed to parse FRI layer {i}: {err}"
+                    ))
+                })?;
             layer_proofs.push(mp);
             layer_queries.push(qv);
         }
@@ -195,6 +211,12 @@ impl Serializable for FriProof {
         target.write_u16(self.remainder.len() as u16);
         target.write_bytes(&self.remainder);
 
+        // write proof-of-work nonces
+        target.write_u8(self.pow_nonces.len() as u8);
+        for &nonce in self.pow_nonces.iter() {
+            target.write_u64(nonce);
+        }
+
         // write number of partitions
         target.write_u8(self.num_partitions);
     }
@@ -214,12 +236,19 @@ impl Deserializable for FriProof {
         let num_remainder_bytes = source.read_u16()? as usize;
         let remainder = source.read_vec(num_remainder_bytes)?;
 
+        // read proof-of-work nonces
+        let num_nonces = source.read_u8()? as usize;
+        let pow_nonces = (0..num_nonces)
+            .map(|_| source.read_u64())
+            .collect::<Result<Vec<_>, _>>()?;
+
         // read number of partitions
         let num_partitions = source.read_u8()?;
 
         Ok(FriProof {
             layers,
             remainder,
+            pow_nonces,
             num_partitions,
         })
     }
@@ -276,12 +305,14 @@ impl FriProofLayer {
     /// # Errors
     /// Returns an error if:
     /// * This layer does not contain at least one query.
+    /// * `domain_size` is not a power of `merkle_arity`.
     /// * Parsing of any of the query values or the corresponding Merkle paths fails.
     /// * Not all bytes have been consumed while parsing this layer.
     pub fn parse<H, E>(
         self,
         domain_size: usize,
         folding_factor: usize,
+        merkle_arity: usize,
     ) -> Result<(Vec<E>, BatchMerkleProof<H>), DeserializationError>
     where
         E: FieldElement,
@@ -317,10 +348,18 @@ impl FriProofLayer {
             return Err(DeserializationError::UnconsumedBytes);
         }
 
-        // build batch Merkle proof
+        // build batch Merkle proof; the tree has one leaf per query
+        if !matches!(merkle_arity, 2 | 4 | 8 | 16)
+            || domain_size.ilog2() % merkle_arity.ilog2() != 0
+        {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of leaves ({domain_size}) must be a power of Merkle tree arity ({merkle_arity})"
+            )));
+        }
         let mut reader = SliceReader::new(&self.paths);
-        let tree_depth = domain_size.ilog2() as u8;
-        let merkle_proof = BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth)?;
+        let tree_depth = (domain_size.ilog2() / merkle_arity.ilog2()) as u8;
+        let merkle_proof =
+            BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth, merkle_arity)?;
         if reader.has_more_bytes() {
             return Err(DeserializationError::UnconsumedBytes);
         }
@@ -4,7 +4,7 @@
 // LICENSE file in the root directory of this source tree.
 
 use core::marker::PhantomData;
-use crypto::{ElementHasher, Hasher, RandomCoin};
+use crypto::{ElementHasher, Hasher, MerkleTree, RandomCoin};
 use math::FieldElement;
 use utils::collections::Vec;
 
@@ -32,9 +32,12 @@ pub trait ProverChannel<E: FieldElement> {
     /// value of the next FRI layer, and then putting each row of the matrix into a single
     /// leaf of the Merkle tree. Thus, the number of elements grouped into a single leaf is
     /// equal to the `folding_factor` used for FRI layer construction.
+    ///
+    /// Instead of the tree root, the commitment may also be a cap of the Merkle tree (i.e., all
+    /// nodes at a given depth of the tree); a cap consisting of a single node is the tree root.
     fn commit_fri_layer(
         &mut self,
-        layer_root: <<Self as ProverChannel<E>>::Hasher as Hasher>::Digest,
+        layer_commitment: &[<<Self as ProverChannel<E>>::Hasher as Hasher>::Digest],
     );
 
     /// Returns a random α drawn uniformly at random from the entire field.
@@ -45,6 +48,13 @@ pub trait ProverChannel<E: FieldElement> {
     /// prover, in the non-interactive version, the α is pseudo-randomly generated based on the
     /// values the prover previously wrote into the channel.
     fn draw_fri_alpha(&mut self) -> E;
+
+    /// Performs proof-of-work grinding before the next α is drawn, and returns the nonce found.
+    ///
+    /// The nonce must result in at least `grinding_factor` leading zeros when absorbed into the
+    /// state of the channel (see [RandomCoin::check_leading_zeros()]). The nonce is then
+    /// absorbed into the channel so that the next α depends on it.
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64;
 }
 
 // DEFAULT PROVER CHANNEL IMPLEMENTATION
@@ -58,12 +68,13 @@ pub struct DefaultProverChannel<E, H, R>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     public_coin: R,
-    commitments: Vec<H::Digest>,
+    commitments: Vec<Vec<H::Digest>>,
     domain_size: usize,
     num_queries: usize,
+    merkle_arity: usize,
     _field_element: PhantomData<E>,
 }
 
@@ -71,7 +82,7 @@ impl<E, H, R> DefaultProverChannel<E, H, R>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     /// Returns a new prover channel instantiated from the specified parameters.
     ///
@@ -97,10 +108,21 @@ where
             commitments: Vec::new(),
             domain_size,
             num_queries,
+            merkle_arity: 2,
             _field_element: PhantomData,
         }
     }
 
+    /// Updates the arity of Merkle trees used to commit to FRI layers.
+    ///
+    /// The arity is used to compute roots of FRI layer commitments, and must be the same as the
+    /// arity specified in the [FriOptions](crate::FriOptions) used by the FRI prover. By default,
+    /// the trees are assumed to be binary.
+    pub fn with_merkle_arity(mut self, merkle_arity: usize) -> Self {
+        self.merkle_arity = merkle_arity;
+        self
+    }
+
     /// Draws a set of positions at which the polynomial evaluations committed at the first FRI
     /// layer should be queried.
     ///
@@ -118,7 +140,7 @@ where
     }
 
     /// Returns a list of FRI layer commitments written by the prover into this channel.
-    pub fn layer_commitments(&self) -> &[H::Digest] {
+    pub fn layer_commitments(&self) -> &[Vec<H::Digest>] {
         &self.commitments
     }
 }
@@ -127,16 +149,27 @@ impl<E, H, R> ProverChannel<E> for DefaultProverChannel<E, H, R>
 where
     E: FieldElement,
     H: ElementHasher<BaseField = E::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     type Hasher = H;
 
-    fn commit_fri_layer(&mut self, layer_root: H::Digest) {
-        self.commitments.push(layer_root);
-        self.public_coin.reseed(layer_root);
+    fn commit_fri_layer(&mut self, layer_commitment: &[H::Digest]) {
+        let layer_root = MerkleTree::<H>::get_cap_root(layer_commitment, self.merkle_arity)
+            .expect("failed to compute root of a FRI layer commitment");
+        self.commitments.push(layer_commitment.to_vec());
+        self.public_coin.reseed_with_digest(&layer_root);
     }
 
     fn draw_fri_alpha(&mut self) -> E {
         self.public_coin.draw().expect("failed to draw FRI alpha")
     }
+
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = self
+            .public_coin
+            .find_nonce(1..u64::MAX, grinding_factor)
+            .expect("nonce not found");
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
 }
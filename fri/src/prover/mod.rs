@@ -12,7 +12,13 @@ use crate::{
 use core::marker::PhantomData;
 use crypto::{ElementHasher, Hasher, MerkleTree};
 use math::{fft, FieldElement, StarkField};
-use utils::{collections::Vec, flatten_vector_elements, group_slice_elements, transpose_slice};
+use utils::{
+    collections::Vec, flatten_vector_elements, group_slice_elements, iter, iter_mut,
+    transpose_slice, CancellationToken, Cancelled,
+};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 
 mod channel;
 pub use channel::{DefaultProverChannel, ProverChannel};
@@ -46,20 +52,23 @@ mod tests;
 /// During the commit phase, which is executed via [build_layers()](FriProver::build_layers())
 /// function, the prover repeatedly applies a degree-respecting projection (DRP) to `evaluations`
 /// (see [folding](crate::folding)). With every application of the DRP, the degree of the function
-/// *f* (and size of the domain over which it is evaluated) is reduced by the `folding_factor`
-/// until the remaining evaluations correspond to a polynomial, called remainder polynomial, with
-/// a number of coefficients less than or equal to `remainder_max_degree_plus_1`.
+/// *f* (and size of the domain over which it is evaluated) is reduced by the folding factor of
+/// the layer (see [FriOptions::layer_folding_factor()]) until the remaining evaluations
+/// correspond to a polynomial, called remainder polynomial, with a number of coefficients less
+/// than or equal to `remainder_max_degree_plus_1`.
 ///
 /// At each layer of reduction, the prover commits to the current set of evaluations. This is done
 /// by building a Merkle tree from the evaluations and sending the root of the tree to the verifier
 /// (via [ProverChannel]). The Merkle tree is build in such a way that all evaluations needed to
 /// compute a single value in the next FRI layer are grouped into the same leaf (the number of
 /// evaluations needed to compute a single element in the next FRI layer is equal to the
-/// `folding_factor`). This allows us to decommit all these values using a single Merkle
+/// layer's folding factor). This allows us to decommit all these values using a single Merkle
 /// authentication path.
 ///
 /// After committing to the set of evaluations at the current layer, the prover draws a random
-/// field element α from the channel, and uses it to build the next FRI layer. In the interactive
+/// field element α from the channel, and uses it to build the next FRI layer. If a folding
+/// grinding factor is specified in the [FriOptions], the prover first performs proof-of-work
+/// grinding via [ProverChannel::grind_fri_layer()]; the nonces found are included in the proof. In the interactive
 /// version of the protocol, the verifier draws α uniformly at random from the entire field and
 /// sends it to the prover. In the non-interactive version, α is pseudo-randomly generated based
 /// on the values the prover has written into the channel up to that point.
@@ -84,6 +93,12 @@ mod tests;
 ///
 /// After the proof is generated, the prover deletes all internally stored FRI layers.
 ///
+/// # Cancellation
+/// A [CancellationToken] can be attached to the prover via
+/// [with_cancellation_token()](FriProver::with_cancellation_token). The token is checked before
+/// every FRI layer is built, and once it has been cancelled, the commit phase is aborted, the
+/// prover is reset to a clean state, and [Cancelled] error is returned.
+///
 /// Calling [build_layers()](FriProver::build_layers()) when the internal state is dirty, or
 /// calling [build_proof()](FriProver::build_proof()) on a clean state will result in a panic.
 pub struct FriProver<B, E, C, H>
@@ -96,6 +111,8 @@ where
     options: FriOptions,
     layers: Vec<FriLayer<B, E, H>>,
     remainder_poly: FriRemainder<E>,
+    pow_nonces: Vec<u64>,
+    cancellation_token: Option<CancellationToken>,
     _channel: PhantomData<C>,
 }
 
@@ -125,14 +142,29 @@ where
             options,
             layers: Vec::new(),
             remainder_poly: FriRemainder(vec![]),
+            pow_nonces: Vec::new(),
+            cancellation_token: None,
             _channel: PhantomData,
         }
     }
 
+    /// Returns this prover with the specified cancellation token attached to it.
+    ///
+    /// Once the token has been cancelled, [build_layers()](FriProver::build_layers()) returns
+    /// before building the next FRI layer, and leaves the prover in a clean state. Thus, the
+    /// caller must check the token before proceeding to the query phase.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
     // ACCESSORS
     // --------------------------------------------------------------------------------------------
 
-    /// Returns folding factor for this prover.
+    /// Returns the default folding factor for this prover.
+    ///
+    /// Individual layers may be folded by different factors if a folding schedule has been
+    /// specified in the [FriOptions] for this prover.
     pub fn folding_factor(&self) -> usize {
         self.options.folding_factor()
     }
@@ -152,6 +184,7 @@ where
     pub fn reset(&mut self) {
         self.layers.clear();
         self.remainder_poly.0.clear();
+        self.pow_nonces.clear();
     }
 
     // COMMIT PHASE
@@ -167,27 +200,105 @@ where
     /// and the root of this tree is written into the channel. After this the prover draws a random
     /// field element α from the channel, and uses it in the next application of the DRP.
     ///
+    /// # Errors
+    /// Returns [Cancelled] if the cancellation token attached to this prover has been cancelled;
+    /// in such a case, the commit phase is aborted before building the next layer, and the prover
+    /// state is reset.
+    ///
     /// # Panics
     /// Panics if the prover state is dirty (the vector of layers is not empty).
-    pub fn build_layers(&mut self, channel: &mut C, mut evaluations: Vec<E>) {
+    pub fn build_layers(&mut self, channel: &mut C, evaluations: Vec<E>) -> Result<(), Cancelled> {
+        self.build_layers_batched(channel, vec![evaluations])
+    }
+
+    /// Executes the commit phase of the FRI protocol for a batch of functions evaluated over
+    /// domains of different sizes.
+    ///
+    /// The first element of `evaluations` defines the domain *D* over which the protocol is
+    /// executed. Every subsequent element contains evaluations of a function over a smaller
+    /// domain, and is folded into the FRI layer (or the remainder) with a domain of the same size:
+    /// right before the layer is committed to, the prover draws a random field element β from the
+    /// channel and adds the function evaluations multiplied by β to the layer evaluations. Thus,
+    /// the degree of each function is tested against the degree bound of the layer it is folded
+    /// into, and no degree adjustment is required.
+    ///
+    /// Each element of `evaluations` must be committed to by the caller before the commit phase
+    /// starts; when all evaluations are over the same domain, this is equivalent to computing a
+    /// random linear combination of them before calling [build_layers()](FriProver::build_layers).
+    ///
+    /// # Errors
+    /// Returns [Cancelled] if the cancellation token attached to this prover has been cancelled;
+    /// cancellation is handled in the same way as in [build_layers()](FriProver::build_layers).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The prover state is dirty (the vector of layers is not empty).
+    /// * `evaluations` is empty.
+    /// * The sizes of the domains in `evaluations` are not sorted in non-increasing order.
+    /// * Any of the domain sizes in `evaluations` does not match the domain size of some FRI
+    ///   layer or of the remainder.
+    pub fn build_layers_batched(
+        &mut self,
+        channel: &mut C,
+        evaluations: Vec<Vec<E>>,
+    ) -> Result<(), Cancelled> {
         assert!(
             self.layers.is_empty(),
             "a prior proof generation request has not been completed yet"
         );
+        assert!(!evaluations.is_empty(), "no evaluations were provided");
+        assert!(
+            evaluations.windows(2).all(|w| w[0].len() >= w[1].len()),
+            "evaluation domain sizes must be sorted in non-increasing order"
+        );
+
+        let mut batched_evaluations = evaluations.into_iter().peekable();
+        let mut evaluations = batched_evaluations.next().expect("no evaluations");
+
+        // reduce the degree by the folding factor of each layer until the remaining polynomial
+        // has small enough degree; the batched functions are folded in once the domain of the
+        // current layer matches their domain
+        for depth in 0..self.options.num_fri_layers(evaluations.len()) {
+            if self.is_cancelled() {
+                self.reset();
+                return Err(Cancelled);
+            }
 
-        // reduce the degree by folding_factor at each iteration until the remaining polynomial
-        // has small enough degree
-        for _ in 0..self.options.num_fri_layers(evaluations.len()) {
-            match self.folding_factor() {
+            while let Some(batched) =
+                batched_evaluations.next_if(|batched| batched.len() == evaluations.len())
+            {
+                fold_in_batched(channel, &mut evaluations, &batched);
+            }
+
+            let folding_factor = self.options.layer_folding_factor(depth);
+            match folding_factor {
                 2 => self.build_layer::<2>(channel, &mut evaluations),
                 4 => self.build_layer::<4>(channel, &mut evaluations),
                 8 => self.build_layer::<8>(channel, &mut evaluations),
                 16 => self.build_layer::<16>(channel, &mut evaluations),
-                _ => unimplemented!("folding factor {} is not supported", self.folding_factor()),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
             }
         }
 
+        while let Some(batched) =
+            batched_evaluations.next_if(|batched| batched.len() == evaluations.len())
+        {
+            fold_in_batched(channel, &mut evaluations, &batched);
+        }
+        assert!(
+            batched_evaluations.next().is_none(),
+            "batched evaluation domain sizes must match domain sizes of FRI layers"
+        );
+
         self.set_remainder(channel, &mut evaluations);
+        Ok(())
+    }
+
+    /// Returns true if the cancellation token attached to this prover has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .map_or(false, |token| token.is_cancelled())
     }
 
     /// Builds a single FRI layer by first committing to the `evaluations`, then drawing a random
@@ -200,8 +311,16 @@ where
         let transposed_evaluations = transpose_slice(evaluations);
         let hashed_evaluations = hash_values::<H, E, N>(&transposed_evaluations);
         let evaluation_tree =
-            MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct FRI layer tree");
-        channel.commit_fri_layer(*evaluation_tree.root());
+            MerkleTree::<H>::with_arity(hashed_evaluations, self.options.merkle_arity())
+                .expect("failed to construct FRI layer tree");
+        channel.commit_fri_layer(evaluation_tree.cap(self.options.merkle_cap_height()));
+
+        // perform proof-of-work grinding before drawing the folding challenge, if required
+        let grinding_factor = self.options.folding_grinding_factor();
+        if grinding_factor > 0 {
+            self.pow_nonces
+                .push(channel.grind_fri_layer(grinding_factor));
+        }
 
         // draw a pseudo-random coefficient from the channel, and use it in degree-respecting
         // projection to reduce the degree of evaluations by N
@@ -221,7 +340,7 @@ where
         let remainder_poly_size = evaluations.len() / self.options.blowup_factor();
         let remainder_poly = evaluations[..remainder_poly_size].to_vec();
         let commitment = <H as ElementHasher>::hash_elements(&remainder_poly);
-        channel.commit_fri_layer(commitment);
+        channel.commit_fri_layer(&[commitment]);
         self.remainder_poly = FriRemainder(remainder_poly);
     }
 
@@ -231,7 +350,7 @@ where
     ///
     /// For each of the provided `positions`, corresponding evaluations from each of the layers
     /// (excluding the remainder layer) are recorded into the proof together with Merkle
-    /// authentication paths from the root of layer commitment trees. For the remainder, we send
+    /// authentication paths from the caps of layer commitment trees. For the remainder, we send
     /// the whole remainder polynomial resulting from interpolating the remainder layer.
     ///
     /// # Panics
@@ -242,55 +361,78 @@ where
             "FRI layers have not been built yet"
         );
 
-        let mut layers = Vec::with_capacity(self.layers.len());
-
+        // determine a set of query positions for all FRI layers, except the last one; positions
+        // in each layer are derived from positions in the previous layer, and thus, have to be
+        // computed sequentially
+        let mut layer_positions = Vec::with_capacity(self.layers.len());
         if !self.layers.is_empty() {
             let mut positions = positions.to_vec();
             let mut domain_size = self.layers[0].evaluations.len();
-            let folding_factor = self.options.folding_factor();
-
-            // for all FRI layers, except the last one, record tree root, determine a set of query
-            // positions, and query the layer at these positions.
             for i in 0..self.layers.len() {
+                let folding_factor = self.options.layer_folding_factor(i);
                 positions = fold_positions(&positions, domain_size, folding_factor);
-
-                // sort of a static dispatch for folding_factor parameter
-                let proof_layer = match folding_factor {
-                    2 => query_layer::<B, E, H, 2>(&self.layers[i], &positions),
-                    4 => query_layer::<B, E, H, 4>(&self.layers[i], &positions),
-                    8 => query_layer::<B, E, H, 8>(&self.layers[i], &positions),
-                    16 => query_layer::<B, E, H, 16>(&self.layers[i], &positions),
-                    _ => unimplemented!("folding factor {} is not supported", folding_factor),
-                };
-
-                layers.push(proof_layer);
+                layer_positions.push(positions.clone());
                 domain_size /= folding_factor;
             }
         }
 
+        // query all layers at the determined positions; when `concurrent` feature is enabled,
+        // the layers are queried in multiple threads
+        let cap_height = self.options.merkle_cap_height();
+        let layers = iter!(self.layers)
+            .zip(iter!(layer_positions))
+            .enumerate()
+            .map(|(i, (layer, positions))| {
+                // sort of a static dispatch for folding_factor parameter
+                let folding_factor = self.options.layer_folding_factor(i);
+                match folding_factor {
+                    2 => query_layer::<B, E, H, 2>(layer, positions, cap_height),
+                    4 => query_layer::<B, E, H, 4>(layer, positions, cap_height),
+                    8 => query_layer::<B, E, H, 8>(layer, positions, cap_height),
+                    16 => query_layer::<B, E, H, 16>(layer, positions, cap_height),
+                    _ => unimplemented!("folding factor {} is not supported", folding_factor),
+                }
+            })
+            .collect::<Vec<_>>();
+
         // use the remaining polynomial values directly as proof
         let remainder = self.remainder_poly.0.clone();
+        let pow_nonces = self.pow_nonces.clone();
 
         // clear layers so that another proof can be generated
         self.reset();
 
-        FriProof::new(layers, remainder, 1)
+        FriProof::new(layers, remainder, pow_nonces, 1)
     }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Draws a random coefficient β from the channel and adds `batched` evaluations multiplied by β
+/// to `evaluations`.
+fn fold_in_batched<E: FieldElement, C: ProverChannel<E>>(
+    channel: &mut C,
+    evaluations: &mut [E],
+    batched: &[E],
+) {
+    let beta = channel.draw_fri_alpha();
+    iter_mut!(evaluations, 1024)
+        .zip(batched)
+        .for_each(|(evaluation, &value)| *evaluation += beta * value);
+}
+
 /// Builds a single proof layer by querying the evaluations of the passed in FRI layer at the
 /// specified positions.
 fn query_layer<B: StarkField, E: FieldElement<BaseField = B>, H: Hasher, const N: usize>(
     layer: &FriLayer<B, E, H>,
     positions: &[usize],
+    cap_height: usize,
 ) -> FriProofLayer {
     // build Merkle authentication paths for all query positions
     let proof = layer
         .tree
-        .prove_batch(positions)
+        .prove_batch_with_cap(positions, cap_height)
         .expect("failed to generate a Merkle proof for FRI layer queries");
 
     // build a list of polynomial evaluations at each position; since evaluations in FRI layers
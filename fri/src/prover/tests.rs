@@ -10,7 +10,9 @@ use crate::{
 };
 use crypto::{hashers::Blake3_256, DefaultRandomCoin, Hasher, RandomCoin};
 use math::{fft, fields::f128::BaseElement, FieldElement};
-use utils::{collections::Vec, Deserializable, Serializable, SliceReader};
+use utils::{
+    collections::Vec, CancellationToken, Cancelled, Deserializable, Serializable, SliceReader,
+};
 
 type Blake3 = Blake3_256<BaseElement>;
 
@@ -28,6 +30,7 @@ fn fri_folding_2() {
         lde_blowup_e,
         folding_factor_e,
         max_remainder_degree,
+        0,
     )
 }
 
@@ -42,9 +45,194 @@ fn fri_folding_4() {
         lde_blowup_e,
         folding_factor_e,
         max_remainder_degree,
+        0,
+    )
+}
+
+#[test]
+fn fri_folding_4_with_merkle_cap() {
+    let trace_length_e = 12;
+    let lde_blowup_e = 3;
+    let folding_factor_e = 2;
+    let max_remainder_degree = 7;
+    let merkle_cap_height = 4;
+    fri_prove_verify(
+        trace_length_e,
+        lde_blowup_e,
+        folding_factor_e,
+        max_remainder_degree,
+        merkle_cap_height,
     )
 }
 
+#[test]
+fn fri_folding_with_merkle_arity() {
+    // the number of leaves in every FRI layer tree is a power of the tree arity: for the domain
+    // of size 4096, these are 1024, 256, and 64 for 4-ary trees, and 512 and 64 for 8-ary trees
+    let options = FriOptions::new(8, 4, 7)
+        .with_merkle_arity(4)
+        .with_merkle_cap_height(1);
+    fri_prove_verify_with_options(1 << 9, options);
+
+    let options = FriOptions::new(8, 8, 7).with_merkle_arity(8);
+    fri_prove_verify_with_options(1 << 9, options);
+}
+
+#[test]
+fn fri_folding_schedule() {
+    // layers are folded by 16, 8, and 4, and then by the default folding factor of 2
+    let trace_length = 1 << 12;
+    let options = FriOptions::new(8, 2, 3).with_folding_schedule(&[16, 8, 4]);
+    assert_eq!(
+        4,
+        options.num_fri_layers(trace_length * options.blowup_factor())
+    );
+    assert_eq!(
+        32,
+        options.remainder_domain_size(trace_length * options.blowup_factor())
+    );
+    fri_prove_verify_with_options(trace_length, options)
+}
+
+#[test]
+fn fri_folding_schedule_past_remainder() {
+    // the second layer is folded into a domain which is smaller than the blowup factor, and thus,
+    // the schedule cannot be used for this domain
+    let options = FriOptions::new(8, 4, 0).with_folding_schedule(&[16, 16]);
+    assert_eq!(2, options.num_fri_layers(512));
+    assert_eq!(2, options.remainder_domain_size(512));
+
+    // the same schedule can be used for a larger domain
+    assert_eq!(8, options.remainder_domain_size(2048));
+}
+
+#[test]
+fn fri_batched() {
+    // polynomials of degree 1023 and 63 are folded into layers 1 and 3, and a polynomial of
+    // degree 3 is folded into the remainder
+    let options = FriOptions::new(8, 4, 7);
+    let degrees = [4095, 1023, 63, 3];
+    let evaluations = degrees
+        .iter()
+        .map(|&degree| build_evaluations(degree + 1, options.blowup_factor()))
+        .collect::<Vec<_>>();
+    let (proof, commitments, positions) = build_batched_proof(&options, evaluations.clone());
+
+    let result = verify_batched_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        &degrees,
+        &positions,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // make sure proof fails if the degree of a batched polynomial is too high
+    let mut invalid_evaluations = evaluations.clone();
+    invalid_evaluations[2] = build_evaluations(128, 4);
+    let (proof, commitments, positions) =
+        build_batched_proof(&options, invalid_evaluations.clone());
+    let result = verify_batched_proof(
+        proof,
+        commitments,
+        &invalid_evaluations,
+        &degrees,
+        &positions,
+        &options,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn fri_batched_invalid_degree() {
+    let options = FriOptions::new(8, 4, 7);
+    let degrees = [4095, 1023];
+    let evaluations = degrees
+        .iter()
+        .map(|&degree| build_evaluations(degree + 1, options.blowup_factor()))
+        .collect::<Vec<_>>();
+    let (proof, commitments, positions) = build_batched_proof(&options, evaluations.clone());
+
+    // the degree of a batched polynomial must match the degree bound of its layer
+    let result = verify_batched_proof(
+        proof,
+        commitments,
+        &evaluations,
+        &[4095, 1000],
+        &positions,
+        &options,
+    );
+    assert_eq!(Err(VerifierError::InvalidBatchedDegree(1000)), result);
+}
+
+#[test]
+fn fri_folding_4_with_grinding() {
+    let trace_length = 1 << 12;
+    let options = FriOptions::new(8, 4, 7).with_folding_grinding_factor(8);
+    fri_prove_verify_with_options(trace_length, options)
+}
+
+#[test]
+fn fri_grinding_verification_fail() {
+    let options = FriOptions::new(8, 4, 7).with_folding_grinding_factor(8);
+    let evaluations = vec![build_evaluations(4096, options.blowup_factor())];
+    let (proof, commitments, positions) = build_batched_proof(&options, evaluations.clone());
+    assert_eq!(options.num_fri_layers(4096 * 8), proof.pow_nonces().len());
+
+    // a nonce is expected for every layer when grinding is enabled, and none otherwise
+    let result = verify_batched_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        &[4095],
+        &positions,
+        &FriOptions::new(8, 4, 7),
+    );
+    assert_eq!(Err(VerifierError::NumLayerNoncesMismatch(0, 5)), result);
+
+    // nonces must satisfy the grinding factor expected by the verifier
+    let result = verify_batched_proof(
+        proof,
+        commitments,
+        &evaluations,
+        &[4095],
+        &positions,
+        &options.clone().with_folding_grinding_factor(32),
+    );
+    assert_eq!(
+        Err(VerifierError::LayerProofOfWorkVerificationFailed(0)),
+        result
+    );
+}
+
+#[test]
+fn fri_cancellation() {
+    let options = FriOptions::new(8, 4, 7);
+    let evaluations = build_evaluations(4096, options.blowup_factor());
+
+    // a token which has not been cancelled does not affect the commit phase
+    let token = CancellationToken::new();
+    let mut channel = build_prover_channel(4096, &options);
+    let mut prover = FriProver::new(options.clone()).with_cancellation_token(token.clone());
+    assert_eq!(
+        Ok(()),
+        prover.build_layers(&mut channel, evaluations.clone())
+    );
+    assert_eq!(options.num_fri_layers(4096 * 8), prover.num_layers());
+
+    // once the token is cancelled, no layers are built and the prover is left in a clean state
+    token.cancel();
+    let mut channel = build_prover_channel(4096, &options);
+    prover.reset();
+    assert_eq!(
+        Err(Cancelled),
+        prover.build_layers(&mut channel, evaluations)
+    );
+    assert_eq!(0, prover.num_layers());
+    assert!(channel.layer_commitments().is_empty());
+}
+
 // TEST UTILS
 // ================================================================================================
 
@@ -53,6 +241,7 @@ pub fn build_prover_channel(
     options: &FriOptions,
 ) -> DefaultProverChannel<BaseElement, Blake3, DefaultRandomCoin<Blake3>> {
     DefaultProverChannel::new(trace_length * options.blowup_factor(), 32)
+        .with_merkle_arity(options.merkle_arity())
 }
 
 pub fn build_evaluations(trace_length: usize, lde_blowup: usize) -> Vec<BaseElement> {
@@ -70,7 +259,7 @@ pub fn build_evaluations(trace_length: usize, lde_blowup: usize) -> Vec<BaseElem
 
 pub fn verify_proof(
     proof: FriProof,
-    commitments: Vec<<Blake3 as Hasher>::Digest>,
+    commitments: Vec<Vec<<Blake3 as Hasher>::Digest>>,
     evaluations: &[BaseElement],
     max_degree: usize,
     domain_size: usize,
@@ -89,7 +278,7 @@ pub fn verify_proof(
         proof,
         commitments,
         domain_size,
-        options.folding_factor(),
+        options,
     )
     .unwrap();
     let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
@@ -101,23 +290,79 @@ pub fn verify_proof(
     verifier.verify(&mut channel, &queried_evaluations, positions)
 }
 
+fn build_batched_proof(
+    options: &FriOptions,
+    evaluations: Vec<Vec<BaseElement>>,
+) -> (FriProof, Vec<Vec<<Blake3 as Hasher>::Digest>>, Vec<usize>) {
+    let trace_length = evaluations[0].len() / options.blowup_factor();
+    let mut channel = build_prover_channel(trace_length, options);
+    let mut prover = FriProver::new(options.clone());
+    prover
+        .build_layers_batched(&mut channel, evaluations)
+        .unwrap();
+    let positions = channel.draw_query_positions();
+    let proof = prover.build_proof(&positions);
+    (proof, channel.layer_commitments().to_vec(), positions)
+}
+
+fn verify_batched_proof(
+    proof: FriProof,
+    commitments: Vec<Vec<<Blake3 as Hasher>::Digest>>,
+    evaluations: &[Vec<BaseElement>],
+    max_degrees: &[usize],
+    positions: &[usize],
+    options: &FriOptions,
+) -> Result<(), VerifierError> {
+    let domain_size = evaluations[0].len();
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        commitments,
+        domain_size,
+        options,
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier = FriVerifier::new_batched(&mut channel, &mut coin, options.clone(), max_degrees)?;
+
+    // batched polynomials are queried at positions reduced modulo the size of their domain
+    let queried_evaluations = evaluations
+        .iter()
+        .map(|values| {
+            positions
+                .iter()
+                .map(|&p| values[p % values.len()])
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    verifier.verify_batched(&mut channel, &queried_evaluations, positions)
+}
+
 fn fri_prove_verify(
     trace_length_e: usize,
     lde_blowup_e: usize,
     folding_factor_e: usize,
     max_remainder_degree: usize,
+    merkle_cap_height: usize,
 ) {
     let trace_length = 1 << trace_length_e;
     let lde_blowup = 1 << lde_blowup_e;
     let folding_factor = 1 << folding_factor_e;
 
-    let options = FriOptions::new(lde_blowup, folding_factor, max_remainder_degree);
+    let options = FriOptions::new(lde_blowup, folding_factor, max_remainder_degree)
+        .with_merkle_cap_height(merkle_cap_height);
+    fri_prove_verify_with_options(trace_length, options)
+}
+
+fn fri_prove_verify_with_options(trace_length: usize, options: FriOptions) {
+    let lde_blowup = options.blowup_factor();
     let mut channel = build_prover_channel(trace_length, &options);
     let evaluations = build_evaluations(trace_length, lde_blowup);
 
     // instantiate the prover and generate the proof
     let mut prover = FriProver::new(options.clone());
-    prover.build_layers(&mut channel, evaluations.clone());
+    prover
+        .build_layers(&mut channel, evaluations.clone())
+        .unwrap();
     let positions = channel.draw_query_positions();
     let proof = prover.build_proof(&positions);
 
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use crate::{FriProof, VerifierError};
+use crate::{FriOptions, FriProof, VerifierError};
 use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
 use math::FieldElement;
 use utils::{collections::Vec, group_vector_elements, DeserializationError};
@@ -36,9 +36,19 @@ pub trait VerifierChannel<E: FieldElement> {
     /// from the entire field after each layer commitment is received. In the non-interactive
     /// version, the verifier can read all layer commitments at once, and then generate α values
     /// locally.
+    ///
+    /// Each layer commitment is a cap of the layer Merkle tree; a cap consisting of a single node
+    /// is the tree root.
     fn read_fri_layer_commitments(
         &mut self,
-    ) -> Vec<<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest>;
+    ) -> Vec<Vec<<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest>>;
+
+    /// Reads and removes from the channel proof-of-work nonces for all FRI layers.
+    ///
+    /// The nonce for a layer is absorbed into the public coin after the layer commitment and
+    /// before the folding challenge α for the layer is drawn. The returned vector is empty if the
+    /// prover did not perform proof-of-work grinding in the commit phase.
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64>;
 
     /// Reads and removes from the channel evaluations of the polynomial at the queried positions
     /// for the next FRI layer.
@@ -78,10 +88,10 @@ pub trait VerifierChannel<E: FieldElement> {
     fn read_layer_queries<const N: usize>(
         &mut self,
         positions: &[usize],
-        commitment: &<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest,
+        commitment: &[<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest],
     ) -> Result<Vec<[E; N]>, VerifierError> {
         let layer_proof = self.take_next_fri_layer_proof();
-        MerkleTree::<Self::Hasher>::verify_batch(commitment, positions, &layer_proof)
+        MerkleTree::<Self::Hasher>::verify_batch_with_cap(commitment, positions, &layer_proof)
             .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
 
         // TODO: make sure layer queries hash into leaves of layer proof
@@ -108,9 +118,10 @@ pub trait VerifierChannel<E: FieldElement> {
 /// Though this implementation is primarily intended for testing purposes, it can be used in
 /// production use cases as well.
 pub struct DefaultVerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
-    layer_commitments: Vec<H::Digest>,
+    layer_commitments: Vec<Vec<H::Digest>>,
     layer_proofs: Vec<BatchMerkleProof<H>>,
     layer_queries: Vec<Vec<E>>,
+    layer_nonces: Vec<u64>,
     remainder: Vec<E>,
     num_partitions: usize,
 }
@@ -126,20 +137,21 @@ where
     /// Returns an error if the specified `proof` could not be parsed correctly.
     pub fn new(
         proof: FriProof,
-        layer_commitments: Vec<H::Digest>,
+        layer_commitments: Vec<Vec<H::Digest>>,
         domain_size: usize,
-        folding_factor: usize,
+        options: &FriOptions,
     ) -> Result<Self, DeserializationError> {
         let num_partitions = proof.num_partitions();
+        let layer_nonces = proof.pow_nonces().to_vec();
 
         let remainder = proof.parse_remainder()?;
-        let (layer_queries, layer_proofs) =
-            proof.parse_layers::<H, E>(domain_size, folding_factor)?;
+        let (layer_queries, layer_proofs) = proof.parse_layers::<H, E>(domain_size, options)?;
 
         Ok(DefaultVerifierChannel {
             layer_commitments,
             layer_proofs,
             layer_queries,
+            layer_nonces,
             remainder,
             num_partitions,
         })
@@ -157,10 +169,14 @@ where
         self.num_partitions
     }
 
-    fn read_fri_layer_commitments(&mut self) -> Vec<H::Digest> {
+    fn read_fri_layer_commitments(&mut self) -> Vec<Vec<H::Digest>> {
         self.layer_commitments.drain(..).collect()
     }
 
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64> {
+        self.layer_nonces.drain(..).collect()
+    }
+
     fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
         self.layer_proofs.remove(0)
     }
@@ -6,8 +6,8 @@
 //! Contains an implementation of FRI verifier and associated components.
 
 use crate::{folding::fold_positions, utils::map_positions_to_indexes, FriOptions, VerifierError};
-use core::{convert::TryInto, marker::PhantomData, mem};
-use crypto::{ElementHasher, RandomCoin};
+use core::{convert::TryInto, marker::PhantomData};
+use crypto::{ElementHasher, MerkleTree, RandomCoin};
 use math::{polynom, FieldElement, StarkField};
 use utils::collections::Vec;
 
@@ -60,13 +60,14 @@ where
     E: FieldElement,
     C: VerifierChannel<E, Hasher = H>,
     H: ElementHasher<BaseField = E::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     max_poly_degree: usize,
     domain_size: usize,
     domain_generator: E::BaseField,
-    layer_commitments: Vec<H::Digest>,
+    layer_commitments: Vec<Vec<H::Digest>>,
     layer_alphas: Vec<E>,
+    batched_coefficients: Vec<(usize, E)>,
     options: FriOptions,
     num_partitions: usize,
     _channel: PhantomData<C>,
@@ -78,7 +79,7 @@ where
     E: FieldElement,
     C: VerifierChannel<E, Hasher = H>,
     H: ElementHasher<BaseField = E::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     /// Returns a new instance of FRI verifier created from the specified parameters.
     ///
@@ -88,8 +89,8 @@ where
     ///
     /// Creating a FRI verifier executes the commit phase of the FRI protocol from the verifier's
     /// perspective. Specifically, the verifier reads FRI layer commitments from the `channel`,
-    /// and for each commitment, updates the `public_coin` with this commitment and then draws
-    /// a random value α from the coin.
+    /// and for each commitment, updates the `public_coin` with the root of the committed Merkle
+    /// tree and then draws a random value α from the coin.
     ///
     /// The verifier stores layer commitments and corresponding α values in its internal state,
     /// and, thus, an instance of FRI verifier can be used to verify only a single proof.
@@ -97,7 +98,12 @@ where
     /// # Errors
     /// Returns an error if:
     /// * `max_poly_degree` is inconsistent with the number of FRI layers read from the channel
-    ///   and `folding_factor` specified in the `options` parameter.
+    ///   and the folding factors specified in the `options` parameter.
+    /// * The number of nodes in any of the layer commitments (Merkle tree caps) is not a power
+    ///   of two.
+    /// * The number of proof-of-work nonces read from the channel is inconsistent with the
+    ///   folding grinding factor specified in the `options` parameter, or any of the nonces does
+    ///   not result in the required number of leading zeros.
     /// * An error was encountered while drawing a random α value from the coin.
     pub fn new(
         channel: &mut C,
@@ -105,6 +111,39 @@ where
         options: FriOptions,
         max_poly_degree: usize,
     ) -> Result<Self, VerifierError> {
+        Self::new_batched(channel, public_coin, options, &[max_poly_degree])
+    }
+
+    /// Returns a new instance of FRI verifier for a batch of polynomials of different degrees.
+    ///
+    /// The first element of `max_poly_degrees` defines the highest degree of the polynomial for
+    /// which the protocol is executed (see [new()](FriVerifier::new)). Every subsequent element
+    /// specifies the highest degree of a polynomial which the prover folded into the FRI layer
+    /// (or the remainder) with a domain of the same size as the domain of the polynomial (see
+    /// [FriProver::build_layers_batched()](crate::FriProver::build_layers_batched)). For each such
+    /// polynomial, a random coefficient β is drawn from the `public_coin` right before the
+    /// commitment to the layer is absorbed.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Any of the conditions listed for [new()](FriVerifier::new) is not satisfied.
+    /// * Any of the batched polynomial degrees is not equal to the degree bound of the FRI layer
+    ///   with the matching domain size, or no such layer exists.
+    ///
+    /// # Panics
+    /// Panics if `max_poly_degrees` is empty.
+    pub fn new_batched(
+        channel: &mut C,
+        public_coin: &mut R,
+        options: FriOptions,
+        max_poly_degrees: &[usize],
+    ) -> Result<Self, VerifierError> {
+        assert!(
+            !max_poly_degrees.is_empty(),
+            "no polynomial degrees were provided"
+        );
+        let max_poly_degree = max_poly_degrees[0];
+
         // infer evaluation domain info
         let domain_size = max_poly_degree.next_power_of_two() * options.blowup_factor();
         let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
@@ -113,25 +152,82 @@ where
 
         // read layer commitments from the channel and use them to build a list of alphas
         let layer_commitments = channel.read_fri_layer_commitments();
+
+        // read proof-of-work nonces; a nonce is expected for every layer but the remainder layer
+        // if grinding is enabled
+        let layer_nonces = channel.read_fri_layer_nonces();
+        let grinding_factor = options.folding_grinding_factor();
+        let expected_num_nonces = if grinding_factor > 0 {
+            layer_commitments.len().saturating_sub(1)
+        } else {
+            0
+        };
+        if layer_nonces.len() != expected_num_nonces {
+            return Err(VerifierError::NumLayerNoncesMismatch(
+                expected_num_nonces,
+                layer_nonces.len(),
+            ));
+        }
+
         let mut layer_alphas = Vec::with_capacity(layer_commitments.len());
         let mut max_degree_plus_1 = max_poly_degree + 1;
+        let mut layer_domain_size = domain_size;
+        let mut batched_degrees = max_poly_degrees[1..].iter().copied().peekable();
+        let mut batched_coefficients = Vec::new();
         for (depth, commitment) in layer_commitments.iter().enumerate() {
-            public_coin.reseed(*commitment);
+            // draw coefficients for the polynomials which the prover folded into this layer
+            // right before committing to it
+            while let Some(degree) = batched_degrees.next_if(|&degree| {
+                degree.next_power_of_two() * options.blowup_factor() == layer_domain_size
+            }) {
+                if degree + 1 != max_degree_plus_1 {
+                    return Err(VerifierError::InvalidBatchedDegree(degree));
+                }
+                let beta = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
+                batched_coefficients.push((depth, beta));
+            }
+
+            // layer commitments are Merkle tree caps; the coin is reseeded with the tree root
+            let layer_root = MerkleTree::<H>::get_cap_root(commitment, options.merkle_arity())
+                .map_err(|_| VerifierError::LayerCommitmentMismatch)?;
+            public_coin.reseed_with_digest(&layer_root);
+
+            // the remainder layer is not folded, and thus, the prover does not draw an alpha for
+            // it; drawing it here would make the state of the coin diverge from the prover's for
+            // random coins whose state changes on draws
+            if depth == layer_commitments.len() - 1 {
+                break;
+            }
+
+            // make sure the prover performed proof-of-work grinding before drawing alpha
+            if grinding_factor > 0 {
+                let nonce = layer_nonces[depth];
+                if public_coin.check_leading_zeros(nonce) < grinding_factor {
+                    return Err(VerifierError::LayerProofOfWorkVerificationFailed(depth));
+                }
+                public_coin.reseed_with_int(nonce);
+            }
+
             let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
             layer_alphas.push(alpha);
 
             // make sure the degree can be reduced by the folding factor at all layers
             // but the remainder layer
-            if depth != layer_commitments.len() - 1
-                && max_degree_plus_1 % options.folding_factor() != 0
-            {
+            let folding_factor = options.layer_folding_factor(depth);
+            if max_degree_plus_1 % folding_factor != 0 {
                 return Err(VerifierError::DegreeTruncation(
                     max_degree_plus_1 - 1,
-                    options.folding_factor(),
+                    folding_factor,
                     depth,
                 ));
             }
-            max_degree_plus_1 /= options.folding_factor();
+            max_degree_plus_1 /= folding_factor;
+            layer_domain_size /= folding_factor;
+        }
+
+        // make sure all batched polynomials were folded into some layer
+        if let Some(degree) = batched_degrees.next() {
+            return Err(VerifierError::InvalidBatchedDegree(degree));
         }
 
         Ok(FriVerifier {
@@ -140,6 +236,7 @@ where
             domain_generator,
             layer_commitments,
             layer_alphas,
+            batched_coefficients,
             options,
             num_partitions,
             _channel: PhantomData,
@@ -207,106 +304,145 @@ where
         evaluations: &[E],
         positions: &[usize],
     ) -> Result<(), VerifierError> {
-        if evaluations.len() != positions.len() {
-            return Err(VerifierError::NumPositionEvaluationMismatch(
-                positions.len(),
-                evaluations.len(),
-            ));
-        }
-
-        // static dispatch for folding factor parameter
-        let folding_factor = self.options.folding_factor();
-        match folding_factor {
-            2 => self.verify_generic::<2>(channel, evaluations, positions),
-            4 => self.verify_generic::<4>(channel, evaluations, positions),
-            8 => self.verify_generic::<8>(channel, evaluations, positions),
-            16 => self.verify_generic::<16>(channel, evaluations, positions),
-            _ => Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
-        }
+        self.verify_batched(channel, &[evaluations.to_vec()], positions)
     }
 
-    /// This is the actual implementation of the verification procedure described above, but it
-    /// also takes folding factor as a generic parameter N.
-    fn verify_generic<const N: usize>(
+    /// Executes the query phase of the FRI protocol for a batch of polynomials of different
+    /// degrees.
+    ///
+    /// The first element of `evaluations` contains evaluations of the polynomial at the first FRI
+    /// layer at the specified `positions` (see [verify()](FriVerifier::verify)). Every subsequent
+    /// element contains evaluations of a batched polynomial (in the order in which the degrees
+    /// of these polynomials were provided to [new_batched()](FriVerifier::new_batched)); the i-th
+    /// of these evaluations must be taken at position `positions[i] % n` of the polynomial's
+    /// evaluation domain, where `n` is the size of this domain.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of elements in `evaluations` is not equal to the number of polynomials this
+    ///   verifier was instantiated for.
+    /// * The length of any element of `evaluations` is not equal to the length of `positions`.
+    /// * Any of the conditions listed for [verify()](FriVerifier::verify) is not satisfied.
+    pub fn verify_batched(
         &self,
         channel: &mut C,
-        evaluations: &[E],
+        evaluations: &[Vec<E>],
         positions: &[usize],
     ) -> Result<(), VerifierError> {
-        // pre-compute roots of unity used in computing x coordinates in the folded domain
-        let folding_roots = (0..N)
-            .map(|i| {
-                self.domain_generator
-                    .exp_vartime(((self.domain_size / N * i) as u64).into())
-            })
-            .collect::<Vec<_>>();
+        if evaluations.len() != self.batched_coefficients.len() + 1 {
+            return Err(VerifierError::NumBatchedPolynomialsMismatch(
+                self.batched_coefficients.len() + 1,
+                evaluations.len(),
+            ));
+        }
+        for batched in evaluations.iter() {
+            if batched.len() != positions.len() {
+                return Err(VerifierError::NumPositionEvaluationMismatch(
+                    positions.len(),
+                    batched.len(),
+                ));
+            }
+        }
 
         // 1 ----- verify the recursive components of the FRI proof -----------------------------------
+        let query_positions = positions;
         let mut domain_generator = self.domain_generator;
         let mut domain_size = self.domain_size;
         let mut max_degree_plus_1 = self.max_poly_degree + 1;
         let mut positions = positions.to_vec();
-        let mut evaluations = evaluations.to_vec();
-
-        for depth in 0..self.options.num_fri_layers(self.domain_size) {
-            // determine which evaluations were queried in the folded layer
-            let mut folded_positions =
-                fold_positions(&positions, domain_size, self.options.folding_factor());
-            // determine where these evaluations are in the commitment Merkle tree
-            let position_indexes = map_positions_to_indexes(
-                &folded_positions,
-                domain_size,
-                self.options.folding_factor(),
-                self.num_partitions,
-            );
-            // read query values from the specified indexes in the Merkle tree
-            let layer_commitment = self.layer_commitments[depth];
-            // TODO: add layer depth to the potential error message
-            let layer_values = channel.read_layer_queries(&position_indexes, &layer_commitment)?;
-            let query_values =
-                get_query_values::<E, N>(&layer_values, &positions, &folded_positions, domain_size);
-            if evaluations != query_values {
-                return Err(VerifierError::InvalidLayerFolding(depth));
+        let mut evaluations_iter = evaluations.iter();
+        let mut evaluations = evaluations_iter.next().expect("no evaluations").clone();
+        let mut batched = evaluations_iter
+            .zip(self.batched_coefficients.iter())
+            .peekable();
+
+        let num_fri_layers = self.options.num_fri_layers(self.domain_size);
+        for depth in 0..num_fri_layers {
+            // fold in the polynomials which were folded into this layer by the prover
+            while let Some((values, &(_, beta))) =
+                batched.next_if(|(_, &(batched_depth, _))| batched_depth == depth)
+            {
+                fold_in_batched_queries(
+                    &mut evaluations,
+                    &positions,
+                    domain_size,
+                    beta,
+                    values,
+                    query_positions,
+                );
             }
 
-            // build a set of x coordinates for each row polynomial
-            #[rustfmt::skip]
-            let xs = folded_positions.iter().map(|&i| {
-                let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
-                folding_roots.iter()
-                    .map(|&r| E::from(xe * r))
-                    .collect::<Vec<_>>().try_into().unwrap()
-            })
-            .collect::<Vec<_>>();
-
-            // interpolate x and y values into row polynomials
-            let row_polys = polynom::interpolate_batch(&xs, &layer_values);
-
-            // calculate the pseudo-random value used for linear combination in layer folding
-            let alpha = self.layer_alphas[depth];
-
-            // check that when the polynomials are evaluated at alpha, the result is equal to
-            // the corresponding column value
-            evaluations = row_polys.iter().map(|p| polynom::eval(p, alpha)).collect();
+            // static dispatch for folding factor parameter
+            let folding_factor = self.options.layer_folding_factor(depth);
+            let (folded_positions, folded_evaluations) = match folding_factor {
+                2 => self.verify_layer::<2>(
+                    channel,
+                    depth,
+                    domain_generator,
+                    domain_size,
+                    &positions,
+                    &evaluations,
+                )?,
+                4 => self.verify_layer::<4>(
+                    channel,
+                    depth,
+                    domain_generator,
+                    domain_size,
+                    &positions,
+                    &evaluations,
+                )?,
+                8 => self.verify_layer::<8>(
+                    channel,
+                    depth,
+                    domain_generator,
+                    domain_size,
+                    &positions,
+                    &evaluations,
+                )?,
+                16 => self.verify_layer::<16>(
+                    channel,
+                    depth,
+                    domain_generator,
+                    domain_size,
+                    &positions,
+                    &evaluations,
+                )?,
+                _ => return Err(VerifierError::UnsupportedFoldingFactor(folding_factor)),
+            };
 
             // make sure next degree reduction does not result in degree truncation
-            if max_degree_plus_1 % N != 0 {
+            if max_degree_plus_1 % folding_factor != 0 {
                 return Err(VerifierError::DegreeTruncation(
                     max_degree_plus_1 - 1,
-                    N,
+                    folding_factor,
                     depth,
                 ));
             }
 
             // update variables for the next iteration of the loop
-            domain_generator = domain_generator.exp_vartime((N as u32).into());
-            max_degree_plus_1 /= N;
-            domain_size /= N;
-            mem::swap(&mut positions, &mut folded_positions);
+            domain_generator = domain_generator.exp_vartime((folding_factor as u32).into());
+            max_degree_plus_1 /= folding_factor;
+            domain_size /= folding_factor;
+            positions = folded_positions;
+            evaluations = folded_evaluations;
         }
 
         // 2 ----- verify the remainder polynomial of the FRI proof -------------------------------
 
+        // fold in the polynomials which were folded into the remainder by the prover
+        while let Some((values, &(_, beta))) =
+            batched.next_if(|(_, &(batched_depth, _))| batched_depth == num_fri_layers)
+        {
+            fold_in_batched_queries(
+                &mut evaluations,
+                &positions,
+                domain_size,
+                beta,
+                values,
+                query_positions,
+            );
+        }
+
         // read the remainder polynomial from the channel and make sure it agrees with the evaluations
         // from the previous layer.
         let remainder_poly = channel.read_remainder()?;
@@ -329,10 +465,90 @@ where
 
         Ok(())
     }
+
+    /// Verifies the queries against the FRI layer at the specified `depth` which is folded by
+    /// factor N.
+    ///
+    /// Returns the folded positions together with the evaluations of the next FRI layer at these
+    /// positions.
+    fn verify_layer<const N: usize>(
+        &self,
+        channel: &mut C,
+        depth: usize,
+        domain_generator: E::BaseField,
+        domain_size: usize,
+        positions: &[usize],
+        evaluations: &[E],
+    ) -> Result<(Vec<usize>, Vec<E>), VerifierError> {
+        // pre-compute roots of unity used in computing x coordinates in the folded domain
+        let folding_roots = (0..N)
+            .map(|i| domain_generator.exp_vartime(((domain_size / N * i) as u64).into()))
+            .collect::<Vec<_>>();
+
+        // determine which evaluations were queried in the folded layer
+        let folded_positions = fold_positions(positions, domain_size, N);
+        // determine where these evaluations are in the commitment Merkle tree
+        let position_indexes =
+            map_positions_to_indexes(&folded_positions, domain_size, N, self.num_partitions);
+        // read query values from the specified indexes in the Merkle tree
+        let layer_commitment = &self.layer_commitments[depth];
+        // TODO: add layer depth to the potential error message
+        let layer_values = channel.read_layer_queries(&position_indexes, layer_commitment)?;
+        let query_values =
+            get_query_values::<E, N>(&layer_values, positions, &folded_positions, domain_size);
+        if evaluations != query_values {
+            return Err(VerifierError::InvalidLayerFolding(depth));
+        }
+
+        // build a set of x coordinates for each row polynomial
+        #[rustfmt::skip]
+        let xs = folded_positions.iter().map(|&i| {
+            let xe = domain_generator.exp_vartime((i as u64).into()) * self.options.domain_offset();
+            folding_roots.iter()
+                .map(|&r| E::from(xe * r))
+                .collect::<Vec<_>>().try_into().unwrap()
+        })
+        .collect::<Vec<_>>();
+
+        // interpolate x and y values into row polynomials
+        let row_polys = polynom::interpolate_batch(&xs, &layer_values);
+
+        // calculate the pseudo-random value used for linear combination in layer folding
+        let alpha = self.layer_alphas[depth];
+
+        // evaluate the row polynomials at alpha; the results are the values of the next FRI layer
+        // at the folded positions
+        let folded_evaluations = row_polys.iter().map(|p| polynom::eval(p, alpha)).collect();
+
+        Ok((folded_positions, folded_evaluations))
+    }
 }
 
 // HELPER FUNCTIONS
 // ================================================================================================
+
+/// Adds evaluations of a batched polynomial multiplied by `beta` to `evaluations` at the current
+/// `positions` in a domain of the specified size.
+///
+/// Evaluations of the batched polynomial are provided for the original `query_positions`; the
+/// evaluation at a current position is the one at a query position which reduces to it modulo
+/// the domain size.
+fn fold_in_batched_queries<E: FieldElement>(
+    evaluations: &mut [E],
+    positions: &[usize],
+    domain_size: usize,
+    beta: E,
+    batched: &[E],
+    query_positions: &[usize],
+) {
+    for (evaluation, &position) in evaluations.iter_mut().zip(positions) {
+        let idx = query_positions
+            .iter()
+            .position(|&p| p % domain_size == position)
+            .expect("folded position must be derived from a query position");
+        *evaluation += beta * batched[idx];
+    }
+}
 fn get_query_values<E: FieldElement, const N: usize>(
     values: &[[E; N]],
     positions: &[usize],
@@ -3,7 +3,7 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{ExtensibleField, ExtensionOf, FieldElement};
+use super::{sqrt, ExtensibleField, ExtensionOf, FieldElement};
 use core::{
     convert::TryFrom,
     fmt,
@@ -110,6 +110,19 @@ impl<B: ExtensibleField<2>> FieldElement for QuadExtension<B> {
         Self(result[0], result[1])
     }
 
+    fn legendre(&self) -> i8 {
+        // an element is a square in the extension field if and only if its norm is a square in
+        // the base field
+        let x = [self.0, self.1];
+        let norm = <B as ExtensibleField<2>>::mul(x, <B as ExtensibleField<2>>::frobenius(x));
+        debug_assert_eq!(norm[1], B::ZERO, "norm must be in the base field");
+        norm[0].legendre()
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        sqrt::sqrt_ext(*self)
+    }
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -321,7 +334,7 @@ impl<B: ExtensibleField<2>> From<u8> for QuadExtension<B> {
     }
 }
 
-impl<'a, B: ExtensibleField<2>> TryFrom<&'a [u8]> for QuadExtension<B> {
+impl<B: ExtensibleField<2>> TryFrom<&[u8]> for QuadExtension<B> {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
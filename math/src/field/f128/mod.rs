@@ -10,7 +10,7 @@
 //! significant thought given to performance, and the implementations of most operations are
 //! sub-optimal as well.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{sqrt, ExtensibleField, FieldElement, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -83,6 +83,14 @@ impl FieldElement for BaseElement {
         BaseElement(self.0)
     }
 
+    fn legendre(&self) -> i8 {
+        sqrt::legendre_base(*self)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        sqrt::sqrt_base(*self)
+    }
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -362,7 +370,7 @@ impl From<[u8; 16]> for BaseElement {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
+impl TryFrom<&[u8]> for BaseElement {
     type Error = String;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
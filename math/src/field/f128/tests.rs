@@ -7,8 +7,10 @@ use super::{
     AsBytes, BaseElement, ByteReader, Deserializable, DeserializationError, FieldElement,
     StarkField, Vec, M,
 };
-use crate::field::{ExtensionOf, QuadExtension};
-use core::convert::TryFrom;
+use crate::field::{
+    sqrt::{check_sqrt, check_sqrt_base_cases},
+    ExtensionOf, QuadExtension,
+};
 use num_bigint::BigUint;
 use rand_utils::{rand_value, rand_vector};
 use utils::SliceReader;
@@ -146,6 +148,20 @@ fn test_g_is_2_exp_40_root() {
     assert_eq!(g.exp(1u128 << 40), BaseElement::ONE);
 }
 
+// SQUARE ROOTS
+// ================================================================================================
+
+#[test]
+fn sqrt() {
+    check_sqrt_base_cases::<BaseElement>();
+
+    // test random values
+    let x: Vec<BaseElement> = rand_vector(100);
+    for a in x {
+        check_sqrt(a);
+    }
+}
+
 // FIELD EXTENSIONS
 // ================================================================================================
 
@@ -159,6 +175,21 @@ fn quad_mul_base() {
     assert_eq!(expected, a.mul_base(b0));
 }
 
+#[test]
+fn quad_sqrt() {
+    // all base field elements are squares in the quadratic extension
+    let g = <QuadExtension<BaseElement>>::from(BaseElement::GENERATOR);
+    assert_eq!(1, g.legendre());
+    assert_eq!(g, g.sqrt().unwrap().square());
+
+    for _ in 0..10 {
+        check_sqrt(<QuadExtension<BaseElement>>::new(
+            rand_value(),
+            rand_value(),
+        ));
+    }
+}
+
 // SERIALIZATION / DESERIALIZATION
 // ================================================================================================
 
@@ -267,6 +298,6 @@ impl BaseElement {
         let bytes = value.to_bytes_le();
         let mut buffer = [0u8; 16];
         buffer[0..bytes.len()].copy_from_slice(&bytes);
-        BaseElement::try_from(buffer).unwrap()
+        BaseElement::from(buffer)
     }
 }
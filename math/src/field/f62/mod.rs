@@ -9,7 +9,7 @@
 //! fast modular arithmetic including branchless multiplication and addition. Base elements are
 //! stored in the Montgomery form using `u64` as the backing type.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{sqrt, ExtensibleField, FieldElement, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -118,6 +118,14 @@ impl FieldElement for BaseElement {
         BaseElement(self.0)
     }
 
+    fn legendre(&self) -> i8 {
+        sqrt::legendre_base(*self)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        sqrt::sqrt_base(*self)
+    }
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -457,7 +465,7 @@ impl From<[u8; 8]> for BaseElement {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
+impl TryFrom<&[u8]> for BaseElement {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
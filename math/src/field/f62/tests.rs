@@ -4,7 +4,10 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{AsBytes, BaseElement, DeserializationError, FieldElement, Serializable, StarkField};
-use crate::field::{CubeExtension, ExtensionOf, QuadExtension};
+use crate::field::{
+    sqrt::{check_sqrt, check_sqrt_base_cases},
+    CubeExtension, ExtensionOf, QuadExtension,
+};
 use core::convert::TryFrom;
 use num_bigint::BigUint;
 use proptest::prelude::*;
@@ -224,6 +227,15 @@ fn get_root_of_unity() {
     assert_eq!(BaseElement::ONE, root_38.exp(1u64 << 38));
 }
 
+// SQUARE ROOTS
+// ------------------------------------------------------------------------------------------------
+
+#[test]
+fn sqrt() {
+    check_sqrt_base_cases::<BaseElement>();
+    check_sqrt(rand_value::<BaseElement>());
+}
+
 // SERIALIZATION AND DESERIALIZATION
 // ------------------------------------------------------------------------------------------------
 
@@ -380,6 +392,12 @@ proptest! {
         assert_eq!((v % super::M as u128) as u64, e.as_int());
     }
 
+    #[test]
+    fn sqrt_proptest(a in any::<u64>()) {
+        let a = BaseElement::from(a);
+        check_sqrt(a);
+    }
+
     // QUADRATIC EXTENSION
     // --------------------------------------------------------------------------------------------
     #[test]
@@ -395,6 +413,12 @@ proptest! {
         prop_assert_eq!(expected, a * b);
     }
 
+    #[test]
+    fn quad_sqrt_proptest(a0 in any::<u64>(), a1 in any::<u64>()) {
+        let a = QuadExtension::<BaseElement>::new(BaseElement::from(a0), BaseElement::from(a1));
+        check_sqrt(a);
+    }
+
     // CUBIC EXTENSION
     // --------------------------------------------------------------------------------------------
     #[test]
@@ -409,4 +433,10 @@ proptest! {
         };
         prop_assert_eq!(expected, a * b);
     }
+
+    #[test]
+    fn cube_sqrt_proptest(a0 in any::<u64>(), a1 in any::<u64>(), a2 in any::<u64>()) {
+        let a = CubeExtension::<BaseElement>::new(BaseElement::from(a0), BaseElement::from(a1), BaseElement::from(a2));
+        check_sqrt(a);
+    }
 }
@@ -14,7 +14,7 @@
 //!   and shifts.
 //! * $8$ is the 64th root of unity which opens up potential for optimized FFT implementations.
 
-use super::{ExtensibleField, FieldElement, StarkField};
+use super::{sqrt, ExtensibleField, FieldElement, StarkField};
 use core::{
     convert::{TryFrom, TryInto},
     fmt::{Debug, Display, Formatter},
@@ -167,6 +167,14 @@ impl FieldElement for BaseElement {
         Self(self.0)
     }
 
+    fn legendre(&self) -> i8 {
+        sqrt::legendre_base(*self)
+    }
+
+    fn sqrt(&self) -> Option<Self> {
+        sqrt::sqrt_base(*self)
+    }
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -557,7 +565,7 @@ impl From<[u8; 8]> for BaseElement {
     }
 }
 
-impl<'a> TryFrom<&'a [u8]> for BaseElement {
+impl TryFrom<&[u8]> for BaseElement {
     type Error = DeserializationError;
 
     /// Converts a slice of bytes into a field element; returns error if the value encoded in bytes
@@ -10,5 +10,7 @@ pub mod f128;
 pub mod f62;
 pub mod f64;
 
+mod sqrt;
+
 mod extensions;
 pub use extensions::{CubeExtension, QuadExtension};
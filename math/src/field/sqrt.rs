@@ -0,0 +1,254 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Helper functions for computing Legendre symbols and square roots in STARK fields and their
+//! extensions.
+//!
+//! Square roots are computed using the Tonelli-Shanks algorithm. For base fields, the exponents
+//! involved fit into `PositiveInteger` type of the field; for extension fields, the order of the
+//! multiplicative group exceeds the size of `PositiveInteger`, and thus exponentiation is done
+//! with exponents represented as little-endian vectors of 64-bit limbs.
+//!
+//! None of the functions in this module are constant-time.
+
+use super::{FieldElement, StarkField};
+use utils::collections::Vec;
+
+// BASE FIELDS
+// ================================================================================================
+
+/// Returns the Legendre symbol of `a` in the base field `B`.
+///
+/// This is computed via Euler's criterion as a^((p - 1) / 2), and the result is 1 if `a` is a
+/// non-zero square, -1 if `a` is not a square, and 0 if `a` is zero.
+pub fn legendre_base<B: StarkField>(a: B) -> i8 {
+    if a == B::ZERO {
+        return 0;
+    }
+
+    // since the modulus is odd, (p - 1) / 2 = p >> 1
+    let symbol = a.exp_vartime(B::MODULUS >> 1);
+    if symbol == B::ONE {
+        1
+    } else {
+        debug_assert_eq!(
+            symbol,
+            -B::ONE,
+            "Euler's criterion must evaluate to 1 or -1"
+        );
+        -1
+    }
+}
+
+/// Returns a square root of `a` in the base field `B`, or None if `a` is not a square.
+///
+/// Let p - 1 = t * 2^s with t odd. The Tonelli-Shanks algorithm requires a primitive root of
+/// unity of order 2^s, which is exactly [StarkField::TWO_ADIC_ROOT_OF_UNITY].
+pub fn sqrt_base<B: StarkField>(a: B) -> Option<B> {
+    if a == B::ZERO {
+        return Some(a);
+    }
+    if legendre_base(a) != 1 {
+        return None;
+    }
+
+    // t = p >> s, and since t is odd, (t - 1) / 2 = t >> 1
+    let t_minus_one_half = B::MODULUS >> (B::TWO_ADICITY + 1);
+    let w = a.exp_vartime(t_minus_one_half);
+
+    tonelli_shanks(a, w, B::TWO_ADIC_ROOT_OF_UNITY, B::TWO_ADICITY)
+}
+
+// EXTENSION FIELDS
+// ================================================================================================
+
+/// Returns a square root of `a` in an extension field `E`, or None if `a` is not a square.
+///
+/// The order of the multiplicative group of the extension field is q - 1, where q = p^k and k is
+/// the extension degree. We decompose q - 1 = t * 2^s with t odd, find a quadratic non-residue z
+/// in the field, and run the Tonelli-Shanks algorithm with c = z^t.
+pub fn sqrt_ext<E: FieldElement>(a: E) -> Option<E> {
+    if a == E::ZERO {
+        return Some(a);
+    }
+    if a.legendre() != 1 {
+        return None;
+    }
+
+    // compute q - 1 for q = p^k; since q is odd, q - 1 is just q with the lowest bit cleared
+    let modulus = modulus_limbs::<E::BaseField>();
+    let mut order = modulus.clone();
+    for _ in 1..E::EXTENSION_DEGREE {
+        order = mul_limbs(&order, &modulus);
+    }
+    order[0] &= !1;
+
+    // decompose q - 1 into t * 2^s
+    let s = trailing_zeros_limbs(&order);
+    let t = shr_limbs(&order, s);
+
+    let z = find_non_residue::<E>();
+    let c = exp_limbs(z, &t);
+    let w = exp_limbs(a, &shr_limbs(&t, 1));
+
+    tonelli_shanks(a, w, c, s)
+}
+
+/// Returns the smallest element of the form (i, 1, 0, ..., 0) which is a quadratic non-residue
+/// in the extension field `E`.
+fn find_non_residue<E: FieldElement>() -> E {
+    let mut base_elements = vec![E::BaseField::ZERO; E::EXTENSION_DEGREE];
+    base_elements[1] = E::BaseField::ONE;
+    loop {
+        let candidate = E::slice_from_base_elements(&base_elements)[0];
+        if candidate.legendre() == -1 {
+            return candidate;
+        }
+        base_elements[0] += E::BaseField::ONE;
+    }
+}
+
+// TONELLI-SHANKS
+// ================================================================================================
+
+/// Runs the main loop of the Tonelli-Shanks algorithm.
+///
+/// Here, `w` must be equal to a^((t - 1) / 2), and `c` must be a primitive root of unity of
+/// order 2^`s`, where t * 2^s is the order of the multiplicative group of the field.
+fn tonelli_shanks<E: FieldElement>(a: E, w: E, c: E, s: u32) -> Option<E> {
+    // x = a^((t + 1) / 2), b = a^t
+    let mut x = a * w;
+    let mut b = x * w;
+    let mut z = c;
+    let mut m = s;
+
+    while b != E::ONE {
+        // find the smallest i such that b^(2^i) = 1
+        let mut i = 0;
+        let mut b2 = b;
+        while b2 != E::ONE {
+            b2 = b2.square();
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+
+        // y = z^(2^(m - i - 1))
+        let mut y = z;
+        for _ in 0..(m - i - 1) {
+            y = y.square();
+        }
+
+        z = y.square();
+        x *= y;
+        b *= z;
+        m = i;
+    }
+
+    Some(x)
+}
+
+// MULTI-LIMB INTEGER HELPERS
+// ================================================================================================
+
+/// Returns the modulus of the field `B` as a vector of little-endian 64-bit limbs.
+fn modulus_limbs<B: StarkField>() -> Vec<u64> {
+    B::get_modulus_le_bytes()
+        .chunks(8)
+        .map(|chunk| {
+            let mut bytes = [0u8; 8];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(bytes)
+        })
+        .collect()
+}
+
+/// Returns a product of two integers represented as vectors of little-endian 64-bit limbs.
+fn mul_limbs(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut result = vec![0u64; a.len() + b.len()];
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &bj) in b.iter().enumerate() {
+            let t = (ai as u128) * (bj as u128) + (result[i + j] as u128) + carry;
+            result[i + j] = t as u64;
+            carry = t >> 64;
+        }
+        result[i + b.len()] = carry as u64;
+    }
+    result
+}
+
+/// Returns the number of trailing zero bits in an integer represented as a vector of
+/// little-endian 64-bit limbs.
+fn trailing_zeros_limbs(a: &[u64]) -> u32 {
+    let mut result = 0;
+    for &limb in a {
+        if limb != 0 {
+            return result + limb.trailing_zeros();
+        }
+        result += 64;
+    }
+    result
+}
+
+/// Shifts an integer represented as a vector of little-endian 64-bit limbs right by `n` bits.
+fn shr_limbs(a: &[u64], n: u32) -> Vec<u64> {
+    let limb_shift = (n / 64) as usize;
+    let bit_shift = n % 64;
+    let mut result = vec![0u64; a.len()];
+    for i in limb_shift..a.len() {
+        let mut limb = a[i] >> bit_shift;
+        if bit_shift != 0 && i + 1 < a.len() {
+            limb |= a[i + 1] << (64 - bit_shift);
+        }
+        result[i - limb_shift] = limb;
+    }
+    result
+}
+
+/// Exponentiates `base` by an integer represented as a vector of little-endian 64-bit limbs.
+fn exp_limbs<E: FieldElement>(base: E, power: &[u64]) -> E {
+    let mut result = E::ONE;
+    for &limb in power.iter().rev() {
+        for i in (0..64).rev() {
+            result = result.square();
+            if (limb >> i) & 1 == 1 {
+                result *= base;
+            }
+        }
+    }
+    result
+}
+
+// TEST HELPERS
+// ================================================================================================
+
+/// Checks square root and Legendre symbol computations for the zero, the one, and the generator
+/// of the multiplicative group of the base field `B`.
+#[cfg(test)]
+pub fn check_sqrt_base_cases<B: StarkField>() {
+    assert_eq!(Some(B::ZERO), B::ZERO.sqrt());
+    assert_eq!(0, B::ZERO.legendre());
+    assert_eq!(1, B::ONE.legendre());
+
+    // the generator of the multiplicative group is never a square
+    assert_eq!(-1, B::GENERATOR.legendre());
+    assert!(!B::GENERATOR.is_square());
+    assert_eq!(None, B::GENERATOR.sqrt());
+}
+
+/// Checks that a square root of `a` squared is either `a` or `-a`, and that `a` has a square root
+/// if and only if its Legendre symbol is not -1.
+#[cfg(test)]
+pub fn check_sqrt<E: FieldElement>(a: E) {
+    let r = a.square().sqrt().expect("a square must have a square root");
+    assert!(r == a || r == -a);
+
+    match a.sqrt() {
+        Some(r) => assert_eq!(a, r.square()),
+        None => assert_eq!(-1, a.legendre()),
+    }
+}
@@ -158,6 +158,29 @@ pub trait FieldElement:
     #[must_use]
     fn conjugate(&self) -> Self;
 
+    // SQUARE ROOTS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the Legendre symbol of this field element; that is, 1 if the element is a non-zero
+    /// square, -1 if the element is not a square, and 0 if the element is ZERO.
+    ///
+    /// This function is not constant-time.
+    fn legendre(&self) -> i8;
+
+    /// Returns true if this field element is a square (ZERO is considered to be a square).
+    ///
+    /// This function is not constant-time.
+    fn is_square(&self) -> bool {
+        self.legendre() >= 0
+    }
+
+    /// Returns a square root of this field element, or None if the element is not a square.
+    ///
+    /// If `r` is returned, `-r` is also a square root of this element; no guarantees are made
+    /// about which of the two roots is returned. This function is not constant-time.
+    #[must_use]
+    fn sqrt(&self) -> Option<Self>;
+
     // BASE ELEMENT CONVERSIONS
     // --------------------------------------------------------------------------------------------
 
@@ -14,6 +14,7 @@
 //!
 //! * Basic arithmetic operations: addition, multiplication, subtraction, division, inversion.
 //! * Drawing random and pseudo-random elements from the field.
+//! * Computing Legendre symbols and square roots (using the Tonelli-Shanks algorithm).
 //! * Computing roots of unity of a given order.
 //!
 //! Currently, there are two implementations of finite fields:
@@ -12,6 +12,10 @@
 //! - Synthetic polynomial division for efficient division by polynomials of the form
 //!   `x`^`a` - `b`.
 //!
+//! It also provides an owned [Polynomial] type which wraps these functions and supports the
+//! usual arithmetic operators as well as FFT-based evaluation, composition, and formal
+//! derivatives.
+//!
 //! In the context of this module any slice of field elements is considered to be a polynomial
 //! in reverse coefficient form. A few examples:
 //!
@@ -28,6 +32,9 @@ use crate::{field::FieldElement, utils::batch_inversion};
 use core::mem;
 use utils::{collections::Vec, group_vector_elements};
 
+mod polynomial;
+pub use polynomial::Polynomial;
+
 #[cfg(test)]
 mod tests;
 
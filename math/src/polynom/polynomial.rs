@@ -0,0 +1,444 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{eval, eval_many, remove_leading_zeros};
+use crate::{fft, field::FieldElement};
+use core::{
+    cmp,
+    ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+};
+use utils::collections::Vec;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Polynomials with fewer coefficients than this are multiplied using the schoolbook algorithm;
+/// larger polynomials are multiplied using FFT.
+const MIN_FFT_MUL_SIZE: usize = 64;
+
+// POLYNOMIAL
+// ================================================================================================
+
+/// An owned polynomial with coefficients in the field `E`.
+///
+/// Coefficients are stored in reverse order (i.e., the constant coefficient comes first), which
+/// is consistent with how polynomials are represented by the rest of the [polynom](super) module.
+/// Leading ZERO coefficients are always removed; thus, a zero polynomial has no coefficients.
+///
+/// Polynomials support the usual arithmetic operators (`+`, `-`, `*`, `/`, `%`) both for owned
+/// values and for references. Division discards the remainder, while the `%` operator returns
+/// the remainder only.
+///
+/// # Examples
+/// ```
+/// # use winter_math::polynom::Polynomial;
+/// # use winter_math::{fields::{f128::BaseElement}, FieldElement};
+/// // p(x) = x + 1
+/// let p = Polynomial::new(vec![BaseElement::ONE, BaseElement::ONE]);
+/// // q(x) = x - 1
+/// let q = Polynomial::new(vec![-BaseElement::ONE, BaseElement::ONE]);
+///
+/// // p(x) * q(x) = x^2 - 1
+/// let r = &p * &q;
+/// assert_eq!(2, r.degree());
+/// assert_eq!(BaseElement::new(8), r.eval(BaseElement::new(3)));
+///
+/// assert_eq!(p, &r / &q);
+/// assert!((&r % &q).is_zero());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Polynomial<E: FieldElement> {
+    coefficients: Vec<E>,
+}
+
+impl<E: FieldElement> Polynomial<E> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new polynomial instantiated from the provided coefficients.
+    ///
+    /// Coefficients are expected to be in reverse order (i.e., the constant coefficient comes
+    /// first). Leading ZERO coefficients are removed.
+    pub fn new(mut coefficients: Vec<E>) -> Self {
+        let len = coefficients
+            .iter()
+            .rposition(|&c| c != E::ZERO)
+            .map_or(0, |i| i + 1);
+        coefficients.truncate(len);
+        Self { coefficients }
+    }
+
+    /// Returns a zero polynomial.
+    pub fn zero() -> Self {
+        Self {
+            coefficients: Vec::new(),
+        }
+    }
+
+    /// Returns a constant polynomial p(x) = `value`.
+    pub fn constant(value: E) -> Self {
+        Self::new(vec![value])
+    }
+
+    /// Returns a polynomial p(x) = `coefficient` * x^`degree`.
+    pub fn monomial(degree: usize, coefficient: E) -> Self {
+        let mut coefficients = E::zeroed_vector(degree + 1);
+        coefficients[degree] = coefficient;
+        Self::new(coefficients)
+    }
+
+    /// Returns a polynomial interpolated from the provided X and Y coordinates using Lagrange
+    /// interpolation.
+    ///
+    /// # Panics
+    /// Panics if number of X and Y coordinates is not the same.
+    pub fn interpolate(xs: &[E], ys: &[E]) -> Self {
+        Self::new(super::interpolate(xs, ys, true))
+    }
+
+    /// Returns a polynomial interpolated from its evaluations over a multiplicative subgroup of
+    /// size `evaluations.len()` shifted by `domain_offset`.
+    ///
+    /// The interpolation is done using the iFFT algorithm. To interpolate from evaluations over
+    /// the subgroup itself, `domain_offset` should be set to ONE.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * Length of `evaluations` is not a power of two.
+    /// * The base field does not contain a multiplicative subgroup of size `evaluations.len()`.
+    /// * `domain_offset` is ZERO.
+    pub fn from_evaluations(mut evaluations: Vec<E>, domain_offset: E::BaseField) -> Self {
+        let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(evaluations.len());
+        fft::interpolate_poly_with_offset(&mut evaluations, &inv_twiddles, domain_offset);
+        Self::new(evaluations)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the coefficients of this polynomial in reverse order (i.e., the constant
+    /// coefficient comes first).
+    pub fn coefficients(&self) -> &[E] {
+        &self.coefficients
+    }
+
+    /// Consumes this polynomial and returns its coefficients in reverse order.
+    pub fn into_coefficients(self) -> Vec<E> {
+        self.coefficients
+    }
+
+    /// Returns the number of coefficients in this polynomial; this is equal to the degree of the
+    /// polynomial plus one, except for a zero polynomial which has no coefficients.
+    pub fn num_coefficients(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    /// Returns the degree of this polynomial. The degree of a zero polynomial is 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    /// Returns true if this is a zero polynomial.
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// Returns the leading coefficient of this polynomial, or ZERO for a zero polynomial.
+    pub fn leading_coefficient(&self) -> E {
+        self.coefficients.last().copied().unwrap_or(E::ZERO)
+    }
+
+    // EVALUATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Evaluates this polynomial at the point `x`.
+    pub fn eval<F>(&self, x: F) -> F
+    where
+        F: FieldElement + From<E>,
+    {
+        eval(&self.coefficients, x)
+    }
+
+    /// Evaluates this polynomial at all points in `xs`.
+    pub fn eval_many<F>(&self, xs: &[F]) -> Vec<F>
+    where
+        F: FieldElement + From<E>,
+    {
+        eval_many(&self.coefficients, xs)
+    }
+
+    /// Evaluates this polynomial over a multiplicative subgroup of size `domain_size` shifted by
+    /// `domain_offset` using the FFT algorithm.
+    ///
+    /// To evaluate the polynomial over the subgroup itself, `domain_offset` should be set to ONE.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `domain_size` is not a power of two.
+    /// * `domain_size` is smaller than the number of coefficients in this polynomial.
+    /// * The base field does not contain a multiplicative subgroup of size `domain_size`.
+    /// * `domain_offset` is ZERO.
+    pub fn evaluate_over_domain(&self, domain_size: usize, domain_offset: E::BaseField) -> Vec<E> {
+        assert!(
+            domain_size.is_power_of_two(),
+            "domain size must be a power of 2, but was {domain_size}"
+        );
+        assert!(
+            domain_size >= self.coefficients.len(),
+            "domain size ({}) must be at least the number of coefficients ({})",
+            domain_size,
+            self.coefficients.len()
+        );
+        assert_ne!(
+            domain_offset,
+            E::BaseField::ZERO,
+            "domain offset cannot be zero"
+        );
+
+        // pad the coefficients to the smallest power of two which is at least 2, so that we can
+        // use FFT-based evaluation
+        let poly_size = cmp::max(self.coefficients.len().next_power_of_two(), 2);
+        let poly_size = cmp::min(poly_size, domain_size);
+        let mut coefficients = self.coefficients.clone();
+        coefficients.resize(poly_size, E::ZERO);
+
+        if poly_size == 1 {
+            // this can happen only when domain size is 1
+            return coefficients;
+        }
+
+        let twiddles = fft::get_twiddles::<E::BaseField>(poly_size);
+        fft::evaluate_poly_with_offset(
+            &coefficients,
+            &twiddles,
+            domain_offset,
+            domain_size / poly_size,
+        )
+    }
+
+    // POLYNOMIAL OPERATIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the quotient and the remainder of dividing this polynomial by `divisor`.
+    ///
+    /// # Panics
+    /// Panics if `divisor` is a zero polynomial.
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "cannot divide polynomial by zero");
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return (Self::zero(), self.clone());
+        }
+
+        let quotient = Self::new(super::div(&self.coefficients, &divisor.coefficients));
+        let remainder = self - &(&quotient * divisor);
+        (quotient, remainder)
+    }
+
+    /// Returns a composition of this polynomial with `other`; that is, if this polynomial is
+    /// p(x) and `other` is q(x), returns p(q(x)).
+    pub fn compose(&self, other: &Self) -> Self {
+        // Horner evaluation of p at q(x)
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Self::zero(), |acc, &coeff| {
+                &(&acc * other) + &Self::constant(coeff)
+            })
+    }
+
+    /// Returns the formal derivative of this polynomial.
+    pub fn derivative(&self) -> Self {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &coeff)| coeff.mul_base(E::BaseField::from(i as u64)))
+            .collect();
+        Self::new(coefficients)
+    }
+
+    /// Returns this polynomial multiplied by the scalar `k`.
+    pub fn mul_by_scalar(&self, k: E) -> Self {
+        Self::new(super::mul_by_scalar(&self.coefficients, k))
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns a product of polynomials `a` and `b` computed using FFT.
+fn fft_mul<E: FieldElement>(a: &[E], b: &[E]) -> Vec<E> {
+    let result_len = a.len() + b.len() - 1;
+    let domain_size = result_len.next_power_of_two();
+    let twiddles = fft::get_twiddles::<E::BaseField>(domain_size);
+
+    let mut a_evaluations = a.to_vec();
+    a_evaluations.resize(domain_size, E::ZERO);
+    fft::evaluate_poly(&mut a_evaluations, &twiddles);
+
+    let mut b_evaluations = b.to_vec();
+    b_evaluations.resize(domain_size, E::ZERO);
+    fft::evaluate_poly(&mut b_evaluations, &twiddles);
+
+    for (a, b) in a_evaluations.iter_mut().zip(b_evaluations) {
+        *a *= b;
+    }
+
+    let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(domain_size);
+    fft::interpolate_poly(&mut a_evaluations, &inv_twiddles);
+    a_evaluations.truncate(result_len);
+    a_evaluations
+}
+
+// CONVERSIONS
+// ================================================================================================
+
+impl<E: FieldElement> From<Vec<E>> for Polynomial<E> {
+    fn from(coefficients: Vec<E>) -> Self {
+        Self::new(coefficients)
+    }
+}
+
+impl<E: FieldElement> From<&[E]> for Polynomial<E> {
+    fn from(coefficients: &[E]) -> Self {
+        Self::new(remove_leading_zeros(coefficients))
+    }
+}
+
+impl<E: FieldElement> From<Polynomial<E>> for Vec<E> {
+    fn from(poly: Polynomial<E>) -> Self {
+        poly.coefficients
+    }
+}
+
+// OVERLOADED OPERATORS
+// ================================================================================================
+
+impl<E: FieldElement> Add for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn add(self, rhs: Self) -> Polynomial<E> {
+        Polynomial::new(super::add(&self.coefficients, &rhs.coefficients))
+    }
+}
+
+impl<E: FieldElement> Add for Polynomial<E> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<E: FieldElement> AddAssign<&Polynomial<E>> for Polynomial<E> {
+    fn add_assign(&mut self, rhs: &Self) {
+        *self = &*self + rhs;
+    }
+}
+
+impl<E: FieldElement> Sub for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn sub(self, rhs: Self) -> Polynomial<E> {
+        Polynomial::new(super::sub(&self.coefficients, &rhs.coefficients))
+    }
+}
+
+impl<E: FieldElement> Sub for Polynomial<E> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<E: FieldElement> SubAssign<&Polynomial<E>> for Polynomial<E> {
+    fn sub_assign(&mut self, rhs: &Self) {
+        *self = &*self - rhs;
+    }
+}
+
+impl<E: FieldElement> Mul for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn mul(self, rhs: Self) -> Polynomial<E> {
+        if self.is_zero() || rhs.is_zero() {
+            return Polynomial::zero();
+        }
+
+        let coefficients =
+            if cmp::min(self.coefficients.len(), rhs.coefficients.len()) < MIN_FFT_MUL_SIZE {
+                super::mul(&self.coefficients, &rhs.coefficients)
+            } else {
+                fft_mul(&self.coefficients, &rhs.coefficients)
+            };
+        Polynomial::new(coefficients)
+    }
+}
+
+impl<E: FieldElement> Mul for Polynomial<E> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl<E: FieldElement> MulAssign<&Polynomial<E>> for Polynomial<E> {
+    fn mul_assign(&mut self, rhs: &Self) {
+        *self = &*self * rhs;
+    }
+}
+
+impl<E: FieldElement> Div for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn div(self, rhs: Self) -> Polynomial<E> {
+        self.div_rem(rhs).0
+    }
+}
+
+impl<E: FieldElement> Div for Polynomial<E> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        &self / &rhs
+    }
+}
+
+impl<E: FieldElement> Rem for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn rem(self, rhs: Self) -> Polynomial<E> {
+        self.div_rem(rhs).1
+    }
+}
+
+impl<E: FieldElement> Rem for Polynomial<E> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self {
+        &self % &rhs
+    }
+}
+
+impl<E: FieldElement> Neg for &Polynomial<E> {
+    type Output = Polynomial<E>;
+
+    fn neg(self) -> Polynomial<E> {
+        Polynomial {
+            coefficients: self.coefficients.iter().map(|&c| -c).collect(),
+        }
+    }
+}
+
+impl<E: FieldElement> Neg for Polynomial<E> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        -&self
+    }
+}
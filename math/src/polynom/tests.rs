@@ -3,11 +3,12 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::remove_leading_zeros;
+use super::{remove_leading_zeros, Polynomial};
 use crate::{
-    field::{f128::BaseElement, FieldElement, StarkField},
+    field::{f128::BaseElement, FieldElement, QuadExtension, StarkField},
     utils::get_power_series,
 };
+use rand_utils::{rand_value, rand_vector};
 use utils::collections::Vec;
 
 #[test]
@@ -256,3 +257,126 @@ fn syn_div() {
     let result = super::syn_div(&poly, 4, root.exp(4));
     assert_eq!(poly, remove_leading_zeros(&super::mul(&result, &z_poly)));
 }
+
+// POLYNOMIAL TYPE
+// ================================================================================================
+
+#[test]
+fn polynomial_new() {
+    let p = Polynomial::new(vec![
+        BaseElement::ONE,
+        BaseElement::new(2),
+        BaseElement::ZERO,
+    ]);
+    assert_eq!(2, p.num_coefficients());
+    assert_eq!(1, p.degree());
+    assert_eq!(BaseElement::new(2), p.leading_coefficient());
+
+    let p = Polynomial::new(vec![BaseElement::ZERO, BaseElement::ZERO]);
+    assert!(p.is_zero());
+    assert_eq!(Polynomial::zero(), p);
+    assert_eq!(0, p.degree());
+}
+
+#[test]
+fn polynomial_arithmetic() {
+    let a = Polynomial::new(rand_vector::<BaseElement>(10));
+    let b = Polynomial::new(rand_vector::<BaseElement>(7));
+    let x: BaseElement = rand_value();
+
+    assert_eq!(a.eval(x) + b.eval(x), (&a + &b).eval(x));
+    assert_eq!(a.eval(x) - b.eval(x), (&a - &b).eval(x));
+    assert_eq!(a.eval(x) * b.eval(x), (&a * &b).eval(x));
+    assert_eq!(-a.eval(x), (-&a).eval(x));
+    assert!((&a - &a).is_zero());
+
+    // large polynomials are multiplied using FFT
+    let c = Polynomial::new(rand_vector::<BaseElement>(100));
+    let d = Polynomial::new(rand_vector::<BaseElement>(200));
+    let expected = Polynomial::new(super::mul(c.coefficients(), d.coefficients()));
+    assert_eq!(expected, &c * &d);
+
+    let mut e = a.clone();
+    e += &b;
+    e -= &b;
+    e *= &b;
+    assert_eq!(&a * &b, e);
+}
+
+#[test]
+fn polynomial_div_rem() {
+    let a = Polynomial::new(rand_vector::<BaseElement>(20));
+    let b = Polynomial::new(rand_vector::<BaseElement>(7));
+
+    let (q, r) = a.div_rem(&b);
+    assert!(r.degree() < b.degree());
+    assert_eq!(a, &(&q * &b) + &r);
+    assert_eq!(q, &a / &b);
+    assert_eq!(r, &a % &b);
+
+    // dividing by a polynomial of higher degree results in zero quotient
+    let (q, r) = b.div_rem(&a);
+    assert!(q.is_zero());
+    assert_eq!(b, r);
+}
+
+#[test]
+fn polynomial_evaluate_over_domain() {
+    let p = Polynomial::new(rand_vector::<BaseElement>(12));
+    let domain_size = 64_usize;
+    let offset = BaseElement::GENERATOR;
+
+    let g = BaseElement::get_root_of_unity(domain_size.ilog2());
+    let domain = get_power_series(g, domain_size);
+    let shifted_domain = domain.iter().map(|&x| x * offset).collect::<Vec<_>>();
+
+    let evaluations = p.evaluate_over_domain(domain_size, offset);
+    assert_eq!(p.eval_many(&shifted_domain), evaluations);
+    assert_eq!(p, Polynomial::from_evaluations(evaluations, offset));
+
+    let evaluations = p.evaluate_over_domain(domain_size, BaseElement::ONE);
+    assert_eq!(p.eval_many(&domain), evaluations);
+}
+
+#[test]
+fn polynomial_compose() {
+    let p = Polynomial::new(rand_vector::<BaseElement>(5));
+    let q = Polynomial::new(rand_vector::<BaseElement>(4));
+    let x: BaseElement = rand_value();
+
+    let r = p.compose(&q);
+    assert_eq!(12, r.degree());
+    assert_eq!(p.eval(q.eval(x)), r.eval(x));
+}
+
+#[test]
+fn polynomial_derivative() {
+    // p(x) = 4 * x^3 + 3 * x^2 + 2 * x + 1
+    let p = Polynomial::new((1_u32..5).map(BaseElement::from).collect());
+
+    // p'(x) = 12 * x^2 + 6 * x + 2
+    let expected = Polynomial::new(vec![
+        BaseElement::new(2),
+        BaseElement::new(6),
+        BaseElement::new(12),
+    ]);
+    assert_eq!(expected, p.derivative());
+    assert!(Polynomial::constant(BaseElement::ONE)
+        .derivative()
+        .is_zero());
+}
+
+#[test]
+fn polynomial_in_extension_field() {
+    type E = QuadExtension<BaseElement>;
+
+    let a = Polynomial::new(rand_vector::<E>(10));
+    let b = Polynomial::new(rand_vector::<E>(70));
+    let x: E = rand_value();
+
+    assert_eq!(a.eval(x) * b.eval(x), (&a * &b).eval(x));
+
+    let offset = BaseElement::GENERATOR;
+    let evaluations = b.evaluate_over_domain(128, offset);
+    assert_eq!(b, Polynomial::from_evaluations(evaluations, offset));
+}
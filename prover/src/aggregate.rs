@@ -0,0 +1,253 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    CancellationToken, ColMatrix, CompositionPoly, ConstraintCommitment, ConstraintEvaluationMode,
+    ConstraintHasher, Prover, ProverError, ProverObserver, RowMatrix, StarkDomain, Trace,
+    TraceHasher,
+};
+use air::{AggregateAir, AggregateInputs, Air, EvaluationFrame, ProofOptions, TraceLayout};
+use crypto::MerkleTree;
+use math::FieldElement;
+use utils::collections::Vec;
+
+// AGGREGATE TRACE
+// ================================================================================================
+
+/// Execution trace of a set of instances of a computation which are proven together in a single
+/// STARK proof via [AggregateProver].
+///
+/// Each segment of the aggregated trace consists of the corresponding segments of all instances
+/// placed side by side, such that the i-th instance occupies the i-th group of columns in every
+/// segment (see [AggregateAir] for details).
+pub struct AggregateTrace<T: Trace> {
+    instances: Vec<T>,
+    layout: TraceLayout,
+    main_segment: ColMatrix<T::BaseField>,
+}
+
+impl<T: Trace> AggregateTrace<T> {
+    /// Returns an aggregated trace built from execution traces of the provided instances.
+    ///
+    /// Since the total width of the aggregated trace cannot exceed
+    /// [TraceInfo::MAX_TRACE_WIDTH](air::TraceInfo::MAX_TRACE_WIDTH) columns, at most
+    /// [TraceLayout::max_aggregate_instances()] instances can be aggregated into a single trace.
+    ///
+    /// # Errors
+    /// Returns an error if the number of instances is greater than
+    /// [TraceLayout::max_aggregate_instances()] for the layout of the provided traces.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * `instances` is empty.
+    /// * The provided traces have different layouts, lengths, or metadata.
+    pub fn new(instances: Vec<T>) -> Result<Self, ProverError> {
+        assert!(
+            !instances.is_empty(),
+            "at least one instance must be specified"
+        );
+        let first = &instances[0];
+        for trace in instances.iter().skip(1) {
+            assert_eq!(
+                first.layout(),
+                trace.layout(),
+                "all instances must have the same trace layout"
+            );
+            assert_eq!(
+                first.length(),
+                trace.length(),
+                "all instances must have the same trace length"
+            );
+            assert_eq!(
+                first.meta(),
+                trace.meta(),
+                "all instances must have the same trace metadata"
+            );
+        }
+
+        let max_instances = first.layout().max_aggregate_instances();
+        if instances.len() > max_instances {
+            return Err(ProverError::TooManyAggregateInstances(
+                instances.len(),
+                max_instances,
+            ));
+        }
+        let layout = first.layout().aggregate(instances.len());
+
+        let main_segment = ColMatrix::new(
+            instances
+                .iter()
+                .flat_map(|trace| trace.main_segment().columns().map(|column| column.to_vec()))
+                .collect(),
+        );
+
+        Ok(Self {
+            instances,
+            layout,
+            main_segment,
+        })
+    }
+
+    /// Returns execution traces of all aggregated instances.
+    pub fn instances(&self) -> &[T] {
+        &self.instances
+    }
+}
+
+impl<T: Trace> Trace for AggregateTrace<T> {
+    type BaseField = T::BaseField;
+
+    fn layout(&self) -> &TraceLayout {
+        &self.layout
+    }
+
+    fn length(&self) -> usize {
+        self.main_segment.num_rows()
+    }
+
+    fn meta(&self) -> &[u8] {
+        self.instances[0].meta()
+    }
+
+    fn main_segment(&self) -> &ColMatrix<Self::BaseField> {
+        &self.main_segment
+    }
+
+    /// Builds the next auxiliary trace segment for every instance using the same random elements,
+    /// and places the resulting segments side by side.
+    fn build_aux_segment<E: FieldElement<BaseField = Self::BaseField>>(
+        &mut self,
+        aux_segments: &[ColMatrix<E>],
+        rand_elements: &[E],
+    ) -> Option<ColMatrix<E>> {
+        let num_instances = self.instances.len();
+        let mut columns = Vec::new();
+        for (i, trace) in self.instances.iter_mut().enumerate() {
+            // extract the columns of previously built segments which belong to this instance
+            let instance_segments = aux_segments
+                .iter()
+                .map(|segment| {
+                    let width = segment.num_cols() / num_instances;
+                    ColMatrix::new(
+                        (i * width..(i + 1) * width)
+                            .map(|column| segment.get_column(column).to_vec())
+                            .collect(),
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let segment = trace.build_aux_segment(&instance_segments, rand_elements)?;
+            columns.extend(segment.into_columns());
+        }
+        Some(ColMatrix::new(columns))
+    }
+
+    fn read_main_frame(&self, row_idx: usize, frame: &mut EvaluationFrame<Self::BaseField>) {
+        let next_row_idx = (row_idx + 1) % self.length();
+        self.main_segment
+            .read_row_into(row_idx, frame.current_mut());
+        self.main_segment
+            .read_row_into(next_row_idx, frame.next_mut());
+    }
+}
+
+// AGGREGATE PROVER
+// ================================================================================================
+
+/// A prover which proves a set of instances of the computation described by the prover `P` in a
+/// single STARK proof.
+///
+/// Execution traces of all instances are combined into an [AggregateTrace], and the resulting
+/// proof attests to correct execution of all instances against the [AggregateAir] built from the
+/// AIR of `P`. The proof can be verified against public inputs of all instances via
+/// [AggregateInputs].
+///
+/// Proof options, constraint evaluation mode, observer, cancellation token, memory budget, and
+/// commitment building are inherited from `P`.
+pub struct AggregateProver<P: Prover> {
+    prover: P,
+}
+
+impl<P: Prover> AggregateProver<P> {
+    /// Returns a new aggregate prover which proves instances of the computation described by
+    /// the provided prover.
+    pub fn new(prover: P) -> Self {
+        Self { prover }
+    }
+
+    /// Returns the prover for a single instance of the computation.
+    pub fn inner(&self) -> &P {
+        &self.prover
+    }
+}
+
+impl<P: Prover> Prover for AggregateProver<P> {
+    type BaseField = P::BaseField;
+    type Air = AggregateAir<P::Air>;
+    type Trace = AggregateTrace<P::Trace>;
+    type HashFn = P::HashFn;
+    type RandomCoin = P::RandomCoin;
+
+    fn get_pub_inputs(
+        &self,
+        trace: &Self::Trace,
+    ) -> AggregateInputs<<P::Air as Air>::PublicInputs> {
+        AggregateInputs::new(
+            trace
+                .instances()
+                .iter()
+                .map(|trace| self.prover.get_pub_inputs(trace))
+                .collect(),
+        )
+    }
+
+    fn options(&self) -> &ProofOptions {
+        self.prover.options()
+    }
+
+    fn constraint_evaluation_mode(&self) -> ConstraintEvaluationMode {
+        self.prover.constraint_evaluation_mode()
+    }
+
+    fn observer(&self) -> &dyn ProverObserver {
+        self.prover.observer()
+    }
+
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        self.prover.cancellation_token()
+    }
+
+    fn memory_budget(&self) -> Option<usize> {
+        self.prover.memory_budget()
+    }
+
+    fn self_verification(&self) -> bool {
+        self.prover.self_verification()
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn build_trace_commitment<E>(
+        &self,
+        trace: &ColMatrix<E>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<(RowMatrix<E>, MerkleTree<TraceHasher<Self>>, ColMatrix<E>), ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        self.prover.build_trace_commitment(trace, domain)
+    }
+
+    fn build_constraint_commitment<E>(
+        &self,
+        composition_poly: &CompositionPoly<E>,
+        domain: &StarkDomain<Self::BaseField>,
+    ) -> Result<ConstraintCommitment<E, ConstraintHasher<Self>>, ProverError>
+    where
+        E: FieldElement<BaseField = Self::BaseField>,
+    {
+        self.prover
+            .build_constraint_commitment(composition_poly, domain)
+    }
+}
@@ -4,99 +4,126 @@
 // LICENSE file in the root directory of this source tree.
 
 use air::{
-    proof::{Commitments, Context, OodFrame, Queries, StarkProof},
+    proof::{Commitments, Context, LowDegreeProof, OodFrame, Queries, StarkProof},
     Air, ConstraintCompositionCoefficients, DeepCompositionCoefficients,
 };
 use core::marker::PhantomData;
-use crypto::{ElementHasher, RandomCoin};
-use fri::{self, FriProof};
+use crypto::{CommitmentHashers, ElementHasher, Hasher, MerkleTree, RandomCoin};
 use math::{FieldElement, ToElements};
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
-#[cfg(feature = "concurrent")]
-use utils::iterators::*;
+use crate::grinding;
 
 // TYPES AND INTERFACES
 // ================================================================================================
 
-pub struct ProverChannel<'a, A, E, H, R>
+pub struct ProverChannel<E, H, R>
 where
-    A: Air,
-    E: FieldElement<BaseField = A::BaseField>,
-    H: ElementHasher<BaseField = A::BaseField>,
-    R: RandomCoin<BaseField = E::BaseField, Hasher = H>,
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
-    air: &'a A,
     public_coin: R,
     context: Context,
     commitments: Commitments,
     ood_frame: OodFrame,
     pow_nonce: u64,
     _field_element: PhantomData<E>,
+    _hashers: PhantomData<H>,
 }
 
 // PROVER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<'a, A, E, H, R> ProverChannel<'a, A, E, H, R>
+impl<E, H, R> ProverChannel<E, H, R>
 where
-    A: Air,
-    E: FieldElement<BaseField = A::BaseField>,
-    H: ElementHasher<BaseField = A::BaseField>,
-    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates a new prover channel for the specified `air` and public inputs.
-    pub fn new(air: &'a A, mut pub_inputs_elements: Vec<A::BaseField>) -> Self {
-        let context = Context::new::<A::BaseField>(air.trace_info(), air.options().clone());
+    pub fn new<A>(air: &A, mut pub_inputs_elements: Vec<E::BaseField>) -> Self
+    where
+        A: Air<BaseField = E::BaseField>,
+    {
+        let context = build_proof_context(air);
 
-        // build a seed for the public coin; the initial seed is a hash of the proof context and
-        // the public inputs, but as the protocol progresses, the coin will be reseeded with the
-        // info sent to the verifier
+        // build a seed for the public coin; the initial seed is a hash of the proof context
+        // (which includes the domain separator of the AIR) and the public inputs, but as the
+        // protocol progresses, the coin will be reseeded with the info sent to the verifier
         let mut coin_seed_elements = context.to_elements();
         coin_seed_elements.append(&mut pub_inputs_elements);
 
         ProverChannel {
-            air,
             public_coin: RandomCoin::new(&coin_seed_elements),
             context,
             commitments: Commitments::default(),
             ood_frame: OodFrame::default(),
             pow_nonce: 0,
             _field_element: PhantomData,
+            _hashers: PhantomData,
         }
     }
 
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the proof context of this channel.
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /// Returns the arity of Merkle trees used to commit to the trace, constraint evaluations, and
+    /// FRI layers.
+    fn merkle_arity(&self) -> usize {
+        self.context.options().merkle_arity()
+    }
+
     // COMMITMENT METHODS
     // --------------------------------------------------------------------------------------------
 
     /// Commits the prover the extended execution trace.
-    pub fn commit_trace(&mut self, trace_root: H::Digest) {
-        self.commitments.add::<H>(&trace_root);
-        self.public_coin.reseed(trace_root);
+    ///
+    /// The commitment is a cap of the trace Merkle tree; the public coin is reseeded with the
+    /// root of the tree computed from this cap.
+    pub fn commit_trace(&mut self, trace_cap: &[<H::TraceHasher as Hasher>::Digest]) {
+        self.commitments.add::<H::TraceHasher>(trace_cap);
+        let trace_root = get_cap_root::<H::TraceHasher>(trace_cap, self.merkle_arity());
+        self.public_coin.reseed_with_digest(&trace_root);
     }
 
     /// Commits the prover to the evaluations of the constraint composition polynomial.
-    pub fn commit_constraints(&mut self, constraint_root: H::Digest) {
-        self.commitments.add::<H>(&constraint_root);
-        self.public_coin.reseed(constraint_root);
+    ///
+    /// The commitment is a cap of the constraint evaluation Merkle tree; the public coin is
+    /// reseeded with the root of the tree computed from this cap.
+    pub fn commit_constraints(
+        &mut self,
+        constraint_cap: &[<H::ConstraintHasher as Hasher>::Digest],
+    ) {
+        self.commitments.add::<H::ConstraintHasher>(constraint_cap);
+        let constraint_root =
+            get_cap_root::<H::ConstraintHasher>(constraint_cap, self.merkle_arity());
+        self.public_coin.reseed_with_digest(&constraint_root);
     }
 
     /// Saves the evaluations of trace polynomials over the out-of-domain evaluation frame. This
-    /// also reseeds the public coin with the hashes of the evaluation frame states.
+    /// also reseeds the public coin with the evaluation frame states.
     pub fn send_ood_trace_states(&mut self, trace_states: &[Vec<E>]) {
         self.ood_frame.set_trace_states(trace_states);
         for trace_state in trace_states {
-            self.public_coin.reseed(H::hash_elements(trace_state));
+            self.public_coin.reseed_with_elements(trace_state);
         }
     }
 
     /// Saves the evaluations of constraint composition polynomial columns at the out-of-domain
-    /// point. This also reseeds the public coin wit the hash of the evaluations.
+    /// point. This also reseeds the public coin with the evaluations.
     pub fn send_ood_constraint_evaluations(&mut self, evaluations: &[E]) {
         self.ood_frame.set_constraint_evaluations(evaluations);
-        self.public_coin.reseed(H::hash_elements(evaluations));
+        self.public_coin.reseed_with_elements(evaluations);
     }
 
     // PUBLIC COIN METHODS
@@ -106,18 +133,29 @@ where
     /// the specified index.
     ///
     /// The elements are drawn from the public coin uniformly at random.
-    pub fn get_aux_trace_segment_rand_elements(&mut self, aux_segment_idx: usize) -> Vec<E> {
-        self.air
-            .get_aux_trace_segment_random_elements(aux_segment_idx, &mut self.public_coin)
+    pub fn get_aux_trace_segment_rand_elements<A>(
+        &mut self,
+        air: &A,
+        aux_segment_idx: usize,
+    ) -> Vec<E>
+    where
+        A: Air<BaseField = E::BaseField>,
+    {
+        air.get_aux_trace_segment_random_elements(aux_segment_idx, &mut self.public_coin)
             .expect("failed to draw random elements for an auxiliary trace segment")
     }
 
     /// Returns a set of coefficients for constructing a constraint composition polynomial.
     ///
     /// The coefficients are drawn from the public coin uniformly at random.
-    pub fn get_constraint_composition_coeffs(&mut self) -> ConstraintCompositionCoefficients<E> {
-        self.air
-            .get_constraint_composition_coefficients(&mut self.public_coin)
+    pub fn get_constraint_composition_coeffs<A>(
+        &mut self,
+        air: &A,
+    ) -> ConstraintCompositionCoefficients<E>
+    where
+        A: Air<BaseField = E::BaseField>,
+    {
+        air.get_constraint_composition_coefficients(&mut self.public_coin)
             .expect("failed to draw composition coefficients")
     }
 
@@ -129,9 +167,11 @@ where
     /// Returns a set of coefficients for constructing a DEEP composition polynomial.
     ///
     /// The coefficients are drawn from the public coin uniformly at random.
-    pub fn get_deep_composition_coeffs(&mut self) -> DeepCompositionCoefficients<E> {
-        self.air
-            .get_deep_composition_coefficients(&mut self.public_coin)
+    pub fn get_deep_composition_coeffs<A>(&mut self, air: &A) -> DeepCompositionCoefficients<E>
+    where
+        A: Air<BaseField = E::BaseField>,
+    {
+        air.get_deep_composition_coefficients(&mut self.public_coin)
             .expect("failed to draw DEEP composition coefficients")
     }
 
@@ -150,19 +190,12 @@ where
     /// Determines a nonce, which when hashed with the current seed of the public coin results
     /// in a new seed with the number of leading zeros equal to the grinding_factor specified
     /// in the proof options.
+    ///
+    /// The smallest such nonce is selected, and thus, the nonce does not depend on the number of
+    /// threads used to search for it.
     pub fn grind_query_seed(&mut self) {
         let grinding_factor = self.context.options().grinding_factor();
-
-        #[cfg(not(feature = "concurrent"))]
-        let nonce = (1..u64::MAX)
-            .find(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
-            .expect("nonce not found");
-
-        #[cfg(feature = "concurrent")]
-        let nonce = (1..u64::MAX)
-            .into_par_iter()
-            .find_any(|&nonce| self.public_coin.check_leading_zeros(nonce) >= grinding_factor)
-            .expect("nonce not found");
+        let nonce = grinding::find_pow_nonce(&self.public_coin, grinding_factor);
 
         self.pow_nonce = nonce;
         self.public_coin.reseed_with_int(nonce);
@@ -176,7 +209,7 @@ where
         self,
         trace_queries: Vec<Queries>,
         constraint_queries: Queries,
-        fri_proof: FriProof,
+        low_degree_proof: LowDegreeProof,
     ) -> StarkProof {
         StarkProof {
             context: self.context,
@@ -184,7 +217,7 @@ where
             ood_frame: self.ood_frame,
             trace_queries,
             constraint_queries,
-            fri_proof,
+            low_degree_proof,
             pow_nonce: self.pow_nonce,
         }
     }
@@ -193,23 +226,134 @@ where
 // FRI PROVER CHANNEL IMPLEMENTATION
 // ================================================================================================
 
-impl<'a, A, E, H, R> fri::ProverChannel<E> for ProverChannel<'a, A, E, H, R>
+impl<E, H, R> fri::ProverChannel<E> for ProverChannel<E, H, R>
 where
-    A: Air,
-    E: FieldElement<BaseField = A::BaseField>,
-    H: ElementHasher<BaseField = A::BaseField>,
-    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
 {
-    type Hasher = H;
+    type Hasher = H::FriHasher;
 
     /// Commits the prover to a FRI layer.
-    fn commit_fri_layer(&mut self, layer_root: H::Digest) {
-        self.commitments.add::<H>(&layer_root);
-        self.public_coin.reseed(layer_root);
+    fn commit_fri_layer(&mut self, layer_cap: &[<H::FriHasher as Hasher>::Digest]) {
+        self.commitments.add::<H::FriHasher>(layer_cap);
+        let layer_root = get_cap_root::<H::FriHasher>(layer_cap, self.merkle_arity());
+        self.public_coin.reseed_with_digest(&layer_root);
     }
 
     /// Returns a new alpha drawn from the public coin.
     fn draw_fri_alpha(&mut self) -> E {
         self.public_coin.draw().expect("failed to draw FRI alpha")
     }
+
+    /// Determines a nonce which, when absorbed into the public coin, results in the specified
+    /// number of leading zeros, and absorbs the nonce into the public coin.
+    fn grind_fri_layer(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = grinding::find_pow_nonce(&self.public_coin, grinding_factor);
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
+}
+
+// STIR PROVER CHANNEL IMPLEMENTATION
+// ================================================================================================
+
+impl<E, H, R> stir::ProverChannel<E> for ProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    type Hasher = H::FriHasher;
+
+    /// Commits the prover to a STIR oracle; STIR oracle trees are always binary.
+    fn commit_stir_oracle(&mut self, oracle_cap: &[<H::FriHasher as Hasher>::Digest]) {
+        self.commitments.add::<H::FriHasher>(oracle_cap);
+        let oracle_root = get_cap_root::<H::FriHasher>(oracle_cap, 2);
+        self.public_coin.reseed_with_digest(&oracle_root);
+    }
+
+    /// Reseeds the public coin with the specified elements.
+    fn send_stir_elements(&mut self, elements: &[E]) {
+        self.public_coin.reseed_with_elements(elements);
+    }
+
+    /// Returns a new STIR challenge drawn from the public coin.
+    fn draw_stir_challenge(&mut self) -> E {
+        self.public_coin
+            .draw()
+            .expect("failed to draw STIR challenge")
+    }
+
+    /// Determines a nonce which, when absorbed into the public coin, results in the specified
+    /// number of leading zeros, and absorbs the nonce into the public coin.
+    fn grind_stir_round(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = grinding::find_pow_nonce(&self.public_coin, grinding_factor);
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
+
+    /// Returns a set of STIR query positions drawn from the public coin.
+    fn draw_stir_query_positions(&mut self, num_queries: usize, domain_size: usize) -> Vec<usize> {
+        self.public_coin
+            .draw_integers(num_queries, domain_size)
+            .expect("failed to draw query position")
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E, H, R> Serializable for ProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField> + Serializable,
+{
+    /// Serializes the current state of the channel (including the state of the public coin) and
+    /// writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.context.write_into(target);
+        self.public_coin.write_into(target);
+        self.commitments.write_into(target);
+        self.ood_frame.write_into(target);
+        target.write_u64(self.pow_nonce);
+    }
+}
+
+impl<E, H, R> Deserializable for ProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField> + Deserializable,
+{
+    /// Reads the state of a channel from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if the state of a channel could not be read from the `source`.
+    fn read_from<S: ByteReader>(source: &mut S) -> Result<Self, DeserializationError> {
+        Ok(ProverChannel {
+            context: Context::read_from(source)?,
+            public_coin: R::read_from(source)?,
+            commitments: Commitments::read_from(source)?,
+            ood_frame: OodFrame::read_from(source)?,
+            pow_nonce: source.read_u64()?,
+            _field_element: PhantomData,
+            _hashers: PhantomData,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Computes the root of a Merkle tree of the specified arity from the specified tree cap.
+fn get_cap_root<H: ElementHasher>(cap: &[H::Digest], arity: usize) -> H::Digest {
+    MerkleTree::<H>::get_cap_root(cap, arity).expect("failed to compute root of a Merkle tree cap")
+}
+
+/// Builds the proof context for the specified AIR.
+pub fn build_proof_context<A: Air>(air: &A) -> Context {
+    Context::new::<A::BaseField>(air.trace_info(), air.options().clone())
+        .with_domain_separator(air.domain_separator())
 }
@@ -7,7 +7,9 @@ use super::RowMatrix;
 use air::proof::Queries;
 use crypto::{ElementHasher, MerkleTree};
 use math::FieldElement;
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 // CONSTRAINT COMMITMENT
 // ================================================================================================
@@ -38,9 +40,9 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
         }
     }
 
-    /// Returns the root of the commitment Merkle tree.
-    pub fn root(&self) -> H::Digest {
-        *self.commitment.root()
+    /// Returns the cap of the specified height of the commitment Merkle tree.
+    pub fn cap(&self, cap_height: usize) -> &[H::Digest] {
+        self.commitment.cap(cap_height)
     }
 
     /// Returns the depth of the commitment Merkle tree.
@@ -50,12 +52,12 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
     }
 
     /// Returns constraint evaluations at the specified positions along with Merkle authentication
-    /// paths from the root of the commitment to these evaluations.
-    pub fn query(self, positions: &[usize]) -> Queries {
+    /// paths from the commitment cap of the specified height to these evaluations.
+    pub fn query(self, positions: &[usize], cap_height: usize) -> Queries {
         // build Merkle authentication paths to the leaves specified by positions
         let merkle_proof = self
             .commitment
-            .prove_batch(positions)
+            .prove_batch_with_cap(positions, cap_height)
             .expect("failed to generate a Merkle proof for constraint queries");
 
         // determine a set of evaluations corresponding to each position
@@ -68,3 +70,42 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintComm
         Queries::new(merkle_proof, evaluations)
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> Serializable
+    for ConstraintCommitment<E, H>
+{
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.evaluations.write_into(target);
+        self.commitment.write_into(target);
+    }
+}
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> Deserializable
+    for ConstraintCommitment<E, H>
+{
+    /// Reads a constraint evaluation commitment from the specified `source` and returns the
+    /// result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid constraint commitment could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let evaluations = RowMatrix::<E>::read_from(source)?;
+        let commitment = MerkleTree::<H>::read_from(source)?;
+        if evaluations.num_rows() != commitment.leaves().len() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of leaves in constraint commitment must be {}, but was {}",
+                evaluations.num_rows(),
+                commitment.leaves().len()
+            )));
+        }
+
+        Ok(Self {
+            evaluations,
+            commitment,
+        })
+    }
+}
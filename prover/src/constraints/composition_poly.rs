@@ -5,7 +5,13 @@
 
 use super::ColMatrix;
 use math::{polynom, FieldElement};
-use utils::{collections::Vec, uninit_vector};
+use utils::{
+    collections::Vec, iter_mut, uninit_vector, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable,
+};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
 
 // COMPOSITION POLYNOMIAL
 // ================================================================================================
@@ -85,6 +91,27 @@ impl<E: FieldElement> CompositionPoly<E> {
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement> Serializable for CompositionPoly<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.data.write_into(target);
+    }
+}
+
+impl<E: FieldElement> Deserializable for CompositionPoly<E> {
+    /// Reads a composition polynomial from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a composition polynomial could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let data = ColMatrix::read_from(source)?;
+        Ok(Self { data })
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
@@ -92,6 +119,8 @@ impl<E: FieldElement> CompositionPoly<E> {
 /// in such a way that each resulting column has the same degree. For example, a polynomial
 /// a * x^3 + b * x^2 + c * x + d, can be rewritten as: (b * x^2 + d) + x * (a * x^2 + c), and then
 /// the two columns will be: (b * x^2 + d) and (a * x^2 + c).
+///
+/// When `concurrent` feature is enabled, each column is filled in multiple threads.
 fn transpose<E: FieldElement>(coefficients: Vec<E>, num_columns: usize) -> Vec<Vec<E>> {
     let column_len = coefficients.len() / num_columns;
 
@@ -101,11 +130,10 @@ fn transpose<E: FieldElement>(coefficients: Vec<E>, num_columns: usize) -> Vec<V
             .collect::<Vec<_>>()
     };
 
-    // TODO: implement multi-threaded version
-    for (i, coeff) in coefficients.into_iter().enumerate() {
-        let row_idx = i / num_columns;
-        let col_idx = i % num_columns;
-        result[col_idx][row_idx] = coeff;
+    for (col_idx, column) in result.iter_mut().enumerate() {
+        iter_mut!(column, 1024)
+            .enumerate()
+            .for_each(|(row_idx, coeff)| *coeff = coefficients[row_idx * num_columns + col_idx]);
     }
 
     result
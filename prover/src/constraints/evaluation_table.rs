@@ -4,8 +4,12 @@
 // LICENSE file in the root directory of this source tree.
 
 use super::{CompositionPoly, ConstraintDivisor, ProverError, StarkDomain};
+use crate::{
+    observer::{matrix_bytes, StepTimer},
+    ProverObserver, ProverStep,
+};
 use math::{batch_inversion, fft, FieldElement, StarkField};
-use utils::{batch_iter_mut, collections::Vec, iter_mut, uninit_vector};
+use utils::{batch_iter_mut, collections::Vec, uninit_vector};
 
 #[cfg(debug_assertions)]
 use air::TransitionConstraints;
@@ -18,13 +22,33 @@ use utils::iterators::*;
 
 const MIN_FRAGMENT_SIZE: usize = 16;
 
+// CONSTRAINT EVALUATION MODE
+// ================================================================================================
+
+/// Defines how constraint evaluations are accumulated into the constraint composition polynomial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstraintEvaluationMode {
+    /// Merged constraint evaluations are recorded into a table with one column per constraint
+    /// divisor over the entire constraint evaluation domain; the columns are divided by their
+    /// divisors and combined only after all constraints have been evaluated.
+    Table,
+    /// Merged constraint evaluations are divided by their divisors in fixed-size chunks of the
+    /// constraint evaluation domain as soon as they are computed, and are accumulated directly
+    /// into evaluations of the constraint composition polynomial. This reduces the memory needed
+    /// to hold constraint evaluations roughly by a factor equal to the number of distinct
+    /// constraint divisors.
+    Streaming,
+}
+
 // CONSTRAINT EVALUATION TABLE
 // ================================================================================================
 
 pub struct ConstraintEvaluationTable<'a, E: FieldElement> {
     evaluations: Vec<Vec<E>>,
     divisors: Vec<ConstraintDivisor<E::BaseField>>,
+    divisor_offset_exps: Vec<E::BaseField>,
     domain: &'a StarkDomain<E::BaseField>,
+    mode: ConstraintEvaluationMode,
 
     #[cfg(debug_assertions)]
     main_transition_evaluations: Vec<Vec<E::BaseField>>,
@@ -37,19 +61,25 @@ pub struct ConstraintEvaluationTable<'a, E: FieldElement> {
 impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
-    /// Returns a new constraint evaluation table with number of columns equal to the number of
-    /// specified divisors, and number of rows equal to the size of constraint evaluation domain.
+    /// Returns a new constraint evaluation table with number of rows equal to the size of
+    /// constraint evaluation domain.
+    ///
+    /// In [ConstraintEvaluationMode::Table] mode, the number of columns is equal to the number of
+    /// specified divisors; in [ConstraintEvaluationMode::Streaming] mode, the table consists of a
+    /// single column into which divided constraint evaluations are accumulated.
     #[cfg(not(debug_assertions))]
     pub fn new(
         domain: &'a StarkDomain<E::BaseField>,
         divisors: Vec<ConstraintDivisor<E::BaseField>>,
+        mode: ConstraintEvaluationMode,
     ) -> Self {
-        let num_columns = divisors.len();
         let num_rows = domain.ce_domain_size();
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: build_evaluation_columns(divisors.len(), num_rows, mode),
+            divisor_offset_exps: get_divisor_offset_exps(&divisors, domain),
             divisors,
             domain,
+            mode,
         }
     }
 
@@ -60,9 +90,9 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     pub fn new(
         domain: &'a StarkDomain<E::BaseField>,
         divisors: Vec<ConstraintDivisor<E::BaseField>>,
+        mode: ConstraintEvaluationMode,
         transition_constraints: &TransitionConstraints<E>,
     ) -> Self {
-        let num_columns = divisors.len();
         let num_rows = domain.ce_domain_size();
         let num_tm_columns = transition_constraints.num_main_constraints();
         let num_ta_columns = transition_constraints.num_aux_constraints();
@@ -73,9 +103,11 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             build_transition_constraint_degrees(transition_constraints, domain.trace_length());
 
         ConstraintEvaluationTable {
-            evaluations: uninit_matrix(num_columns, num_rows),
+            evaluations: build_evaluation_columns(divisors.len(), num_rows, mode),
+            divisor_offset_exps: get_divisor_offset_exps(&divisors, domain),
             divisors,
             domain,
+            mode,
             main_transition_evaluations: uninit_matrix(num_tm_columns, num_rows),
             aux_transition_evaluations: uninit_matrix(num_ta_columns, num_rows),
             expected_transition_degrees,
@@ -91,9 +123,13 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         self.evaluations[0].len()
     }
 
-    /// Returns number of columns in this table. The first column always contains the value of
+    /// Returns number of columns in this table.
+    ///
+    /// In [ConstraintEvaluationMode::Table] mode, the first column always contains the value of
     /// combined transition constraint evaluations; the remaining columns contain values of
-    /// assertion constraint evaluations combined based on common divisors.
+    /// assertion constraint evaluations combined based on common divisors. In
+    /// [ConstraintEvaluationMode::Streaming] mode, the table contains a single column of
+    /// evaluations of the constraint composition polynomial.
     #[allow(dead_code)]
     pub fn num_columns(&self) -> usize {
         self.evaluations.len()
@@ -104,7 +140,7 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
 
     /// Break the table into the number of specified fragments. All fragments can be updated
     /// independently - e.g. in different threads.
-    pub fn fragments(&mut self, num_fragments: usize) -> Vec<EvaluationTableFragment<E>> {
+    pub fn fragments(&mut self, num_fragments: usize) -> Vec<EvaluationTableFragment<'_, E>> {
         let fragment_size = self.num_rows() / num_fragments;
         assert!(
             fragment_size >= MIN_FRAGMENT_SIZE,
@@ -131,6 +167,12 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
                     EvaluationTableFragment {
                         offset: i * fragment_size,
                         evaluations,
+                        divisors: &self.divisors,
+                        divisor_offset_exps: &self.divisor_offset_exps,
+                        domain: self.domain,
+                        mode: self.mode,
+                        divisor_values: Vec::new(),
+                        scratch: Vec::new(),
                         tm_evaluations,
                         ta_evaluations,
                     }
@@ -145,6 +187,12 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
             .map(|(i, evaluations)| EvaluationTableFragment {
                 offset: i * fragment_size,
                 evaluations,
+                divisors: &self.divisors,
+                divisor_offset_exps: &self.divisor_offset_exps,
+                domain: self.domain,
+                mode: self.mode,
+                divisor_values: Vec::new(),
+                scratch: Vec::new(),
             })
             .collect();
 
@@ -156,30 +204,89 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
     /// Divides constraint evaluation columns by their respective divisor (in evaluation form),
     /// combines the results into a single column, and interpolates this column into a composition
     /// polynomial in coefficient form.
-    pub fn into_poly(self) -> Result<CompositionPoly<E>, ProverError> {
-        // allocate memory for the combined polynomial
-        let mut combined_poly = E::zeroed_vector(self.num_rows());
-
-        // iterate over all columns of the constraint evaluation table, divide each column
-        // by the evaluations of its corresponding divisor, and add all resulting evaluations
-        // together into a single vector
-        for (column, divisor) in self.evaluations.into_iter().zip(self.divisors.iter()) {
-            // in debug mode, make sure post-division degree of each column matches the expected
-            // degree
-            #[cfg(debug_assertions)]
-            validate_column_degree(&column, divisor, self.domain, column.len() - 1)?;
+    ///
+    /// In [ConstraintEvaluationMode::Streaming] mode, the division and combination have already
+    /// been performed during constraint evaluation, and thus, the only column of the table is
+    /// interpolated directly.
+    ///
+    /// Completion of each step of this procedure is reported to the specified `observer`.
+    ///
+    /// When `concurrent` feature is enabled, all steps of this procedure are performed in
+    /// multiple threads.
+    pub fn into_poly(
+        self,
+        observer: &dyn ProverObserver,
+    ) -> Result<CompositionPoly<E>, ProverError> {
+        let num_rows = self.num_rows();
+        let mut combined_poly = match self.mode {
+            ConstraintEvaluationMode::Table => {
+                // in debug mode, make sure post-division degree of each column matches the
+                // expected degree
+                #[cfg(debug_assertions)]
+                for (column, divisor) in self.evaluations.iter().zip(self.divisors.iter()) {
+                    validate_column_degree(column, divisor, self.domain, column.len() - 1)?;
+                }
 
-            // divide the column by the divisor and accumulate the result into combined_poly
-            acc_column(column, divisor, self.domain, &mut combined_poly);
-        }
+                // divide all columns of the constraint evaluation table by the evaluations of
+                // their corresponding divisors, and add all resulting evaluations together into
+                // a single vector
+                let timer = StepTimer::start();
+                let combined_poly = combine_columns(&self.evaluations, &self.divisors, self.domain);
+                observer.on_event(&timer.event(
+                    ProverStep::ConstraintColumnsCombined {
+                        num_columns: self.evaluations.len(),
+                        ce_domain_size: num_rows,
+                    },
+                    matrix_bytes::<E>(num_rows, 1),
+                ));
+                combined_poly
+            }
+            ConstraintEvaluationMode::Streaming => self
+                .evaluations
+                .into_iter()
+                .next()
+                .expect("no constraint evaluation column"),
+        };
 
         // at this point, combined_poly contains evaluations of the combined constraint polynomial;
         // we interpolate this polynomial to transform it into coefficient form.
-        let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(combined_poly.len());
-        fft::interpolate_poly_with_offset(&mut combined_poly, &inv_twiddles, self.domain.offset());
+        let timer = StepTimer::start();
+        fft::interpolate_poly_with_offset(
+            &mut combined_poly,
+            self.domain.ce_inv_twiddles(),
+            self.domain.offset(),
+        );
+        observer.on_event(&timer.event(
+            ProverStep::CombinedEvaluationsInterpolated {
+                ce_domain_size: num_rows,
+            },
+            0,
+        ));
+
+        // in debug mode, make sure the degree of the combined polynomial matches the expected
+        // degree; in table mode this has already been checked for each column individually
+        #[cfg(debug_assertions)]
+        if self.mode == ConstraintEvaluationMode::Streaming
+            && math::polynom::degree_of(&combined_poly) != num_rows - 1
+        {
+            return Err(ProverError::MismatchedConstraintPolynomialDegree(
+                num_rows - 1,
+                math::polynom::degree_of(&combined_poly),
+            ));
+        }
+
+        // break the combined polynomial into columns of degree trace_length - 1
+        let timer = StepTimer::start();
+        let composition_poly = CompositionPoly::new(combined_poly, self.domain.trace_length());
+        observer.on_event(&timer.event(
+            ProverStep::CompositionPolySplit {
+                num_columns: composition_poly.num_columns(),
+                column_degree: composition_poly.column_degree(),
+            },
+            matrix_bytes::<E>(self.domain.trace_length(), composition_poly.num_columns()),
+        ));
 
-        let trace_length = self.domain.trace_length();
-        Ok(CompositionPoly::new(combined_poly, trace_length))
+        Ok(composition_poly)
     }
 
     // DEBUG HELPERS
@@ -201,18 +308,18 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
         // determine max transition constraint degree
         let mut actual_degrees = Vec::with_capacity(self.expected_transition_degrees.len());
         let mut max_degree = 0;
-        let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
+        let inv_twiddles = self.domain.ce_inv_twiddles();
 
         // first process transition constraint evaluations for the main trace segment
         for evaluations in self.main_transition_evaluations.iter() {
-            let degree = get_transition_poly_degree(evaluations, &inv_twiddles, &div_values);
+            let degree = get_transition_poly_degree(evaluations, inv_twiddles, &div_values);
             actual_degrees.push(degree);
             max_degree = core::cmp::max(max_degree, degree);
         }
 
         // then process transition constraint evaluations for auxiliary trace segments
         for evaluations in self.aux_transition_evaluations.iter() {
-            let degree = get_transition_poly_degree(evaluations, &inv_twiddles, &div_values);
+            let degree = get_transition_poly_degree(evaluations, inv_twiddles, &div_values);
             actual_degrees.push(degree);
             max_degree = core::cmp::max(max_degree, degree);
         }
@@ -243,6 +350,12 @@ impl<'a, E: FieldElement> ConstraintEvaluationTable<'a, E> {
 pub struct EvaluationTableFragment<'a, E: FieldElement> {
     offset: usize,
     evaluations: Vec<&'a mut [E]>,
+    divisors: &'a [ConstraintDivisor<E::BaseField>],
+    divisor_offset_exps: &'a [E::BaseField],
+    domain: &'a StarkDomain<E::BaseField>,
+    mode: ConstraintEvaluationMode,
+    divisor_values: Vec<E::BaseField>,
+    scratch: Vec<E::BaseField>,
 
     #[cfg(debug_assertions)]
     tm_evaluations: Vec<&'a mut [E::BaseField]>,
@@ -261,15 +374,73 @@ impl<'a, E: FieldElement> EvaluationTableFragment<'a, E> {
         self.evaluations[0].len()
     }
 
-    /// Returns the number of columns in every evaluation row.
-    pub fn num_columns(&self) -> usize {
-        self.evaluations.len()
+    /// Returns the number of constraint divisors; this is the number of merged constraint
+    /// evaluations expected for every row.
+    pub fn num_divisors(&self) -> usize {
+        self.divisors.len()
     }
 
-    /// Updates a single row in the fragment with provided data.
-    pub fn update_row(&mut self, row_idx: usize, row_data: &[E]) {
-        for (column, &value) in self.evaluations.iter_mut().zip(row_data) {
-            column[row_idx] = value;
+    /// Updates a batch of consecutive rows starting at `row_offset` with the provided data.
+    ///
+    /// The data is expected to be in column-major form and to contain one column of merged
+    /// constraint evaluations per constraint divisor. In [ConstraintEvaluationMode::Streaming]
+    /// mode, each column is divided by its divisor, and the results are added to the values of
+    /// the combined column of the fragment.
+    pub fn update_rows(&mut self, row_offset: usize, data: &[E]) {
+        let num_rows = data.len() / self.num_divisors();
+        let columns = data.chunks(num_rows);
+
+        match self.mode {
+            ConstraintEvaluationMode::Table => {
+                for (column, values) in self.evaluations.iter_mut().zip(columns) {
+                    column[row_offset..row_offset + num_rows].copy_from_slice(values);
+                }
+            }
+            ConstraintEvaluationMode::Streaming => {
+                let step = self.offset + row_offset;
+                let result = &mut self.evaluations[0][row_offset..row_offset + num_rows];
+                self.divisor_values.resize(num_rows, E::BaseField::ZERO);
+                self.scratch.resize(num_rows, E::BaseField::ZERO);
+
+                for ((divisor, &offset_exp), values) in self
+                    .divisors
+                    .iter()
+                    .zip(self.divisor_offset_exps)
+                    .zip(columns)
+                {
+                    // compute z = 1 / (x^a - b) for all rows of the batch
+                    let (a, b) = divisor.numerator()[0];
+                    for (i, z) in self.divisor_values.iter_mut().enumerate() {
+                        *z = self
+                            .domain
+                            .get_ce_x_power_at(step + i, a as u64, offset_exp)
+                            - b;
+                    }
+                    serial_batch_inversion(&mut self.divisor_values, &mut self.scratch);
+
+                    // divide the values by the divisor in the same way as combine_columns() does
+                    if divisor.exemptions().is_empty() {
+                        for ((acc_value, &value), &z) in result
+                            .iter_mut()
+                            .zip(values)
+                            .zip(self.divisor_values.iter())
+                        {
+                            *acc_value += value.mul_base(z);
+                        }
+                    } else {
+                        for (i, ((acc_value, &value), &z)) in result
+                            .iter_mut()
+                            .zip(values)
+                            .zip(self.divisor_values.iter())
+                            .enumerate()
+                        {
+                            let x = self.domain.get_ce_x_at(step + i);
+                            let e = divisor.evaluate_exemptions_at(x);
+                            *acc_value += value.mul_base(z * e);
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -298,6 +469,59 @@ fn uninit_matrix<E: FieldElement>(num_cols: usize, num_rows: usize) -> Vec<Vec<E
     unsafe { (0..num_cols).map(|_| uninit_vector(num_rows)).collect() }
 }
 
+/// Allocates memory for the columns of a constraint evaluation table.
+///
+/// In streaming mode, a single column is allocated; this column is initialized with zeros as
+/// divided constraint evaluations are accumulated into it.
+fn build_evaluation_columns<E: FieldElement>(
+    num_divisors: usize,
+    num_rows: usize,
+    mode: ConstraintEvaluationMode,
+) -> Vec<Vec<E>> {
+    match mode {
+        ConstraintEvaluationMode::Table => uninit_matrix(num_divisors, num_rows),
+        ConstraintEvaluationMode::Streaming => vec![E::zeroed_vector(num_rows)],
+    }
+}
+
+/// Returns s^a for each of the specified divisors, where s is the domain offset and a is the
+/// degree of the divisor's numerator.
+fn get_divisor_offset_exps<B: StarkField>(
+    divisors: &[ConstraintDivisor<B>],
+    domain: &StarkDomain<B>,
+) -> Vec<B> {
+    divisors
+        .iter()
+        .map(|divisor| {
+            let numerator = divisor.numerator();
+            assert_eq!(numerator.len(), 1, "complex divisors are not yet supported");
+            domain.offset().exp((numerator[0].0 as u64).into())
+        })
+        .collect()
+}
+
+/// Inverts all values in place using `scratch` as temporary storage; `scratch` must have the
+/// same length as `values`.
+///
+/// Unlike [batch_inversion], this does not handle zero values; divisor numerators never evaluate
+/// to zero over the constraint evaluation domain because the domain is offset from the trace
+/// domain.
+fn serial_batch_inversion<B: StarkField>(values: &mut [B], scratch: &mut [B]) {
+    let mut last = B::ONE;
+    for (product, &value) in scratch.iter_mut().zip(values.iter()) {
+        *product = last;
+        last *= value;
+    }
+
+    last = last.inv();
+
+    for (value, &product) in values.iter_mut().zip(scratch.iter()).rev() {
+        let inverse = last * product;
+        last *= *value;
+        *value = inverse;
+    }
+}
+
 /// Breaks the source data into a mutable set of fragments such that each fragment has the same
 /// number of columns as the source data, and the number of rows equal to `num_fragments`
 /// parameter.
@@ -320,57 +544,67 @@ fn make_fragments<E: FieldElement>(
     result
 }
 
-#[allow(clippy::many_single_char_names)]
-fn acc_column<E: FieldElement>(
-    column: Vec<E>,
-    divisor: &ConstraintDivisor<E::BaseField>,
+/// Divides each column by the evaluations of its corresponding divisor, and combines the results
+/// into a single column.
+///
+/// The result is computed in batches of consecutive rows; for each batch, all columns are divided
+/// and accumulated before moving on to the next batch. When `concurrent` feature is enabled, the
+/// batches are processed in multiple threads.
+fn combine_columns<E: FieldElement>(
+    columns: &[Vec<E>],
+    divisors: &[ConstraintDivisor<E::BaseField>],
     domain: &StarkDomain<E::BaseField>,
-    result: &mut [E],
-) {
-    let numerator = divisor.numerator();
-    assert_eq!(numerator.len(), 1, "complex divisors are not yet supported");
-
-    // compute inverse evaluations of the divisor's numerator, which has the form (x^a - b)
-    let z = get_inv_evaluation(divisor, domain);
-
-    // divide column values by the divisor; for boundary constraints this computed simply as
-    // multiplication of column value by the inverse of divisor numerator; for transition
-    // constraints, it is computed similarly, but the result is also multiplied by the divisor's
-    // denominator (exclusion point).
-    if divisor.exemptions().is_empty() {
-        // the column represents merged evaluations of boundary constraints, and divisor has the
-        // form of (x^a - b); thus to divide the column by the divisor, we compute: value * z,
-        // where z = 1 / (x^a - 1) and has already been computed above.
-        iter_mut!(result, 1024)
-            .zip(column)
-            .enumerate()
-            .for_each(|(i, (acc_value, value))| {
-                // determine which value of z corresponds to the current domain point
-                let z = z[i % z.len()];
-                // compute value * z and add it to the result
-                *acc_value += value.mul_base(z);
-            });
-    } else {
-        // the column represents merged evaluations of transition constraints, and divisor has the
-        // form of (x^a - 1) / e(x), where e(x) describes the exemption points; thus, to divide
-        // the column by the divisor, we compute: value * e(x) * z, where z = 1 / (x^a - 1) and has
-        // already been computed above.
-        batch_iter_mut!(
-            result,
-            128, // min batch size
-            |batch: &mut [E], batch_offset: usize| {
-                for (i, acc_value) in batch.iter_mut().enumerate() {
-                    // compute value of e(x) and compute next value of x
-                    let x = domain.get_ce_x_at(batch_offset + i);
-                    let e = divisor.evaluate_exemptions_at(x);
-                    // determine which value of z corresponds to the current domain point
-                    let z = z[i % z.len()];
-                    // compute value * e(x) * z and add it to the result
-                    *acc_value += column[batch_offset + i].mul_base(z * e);
+) -> Vec<E> {
+    // compute inverse evaluations of each divisor's numerator, which has the form (x^a - b)
+    let inv_evaluations = divisors
+        .iter()
+        .map(|divisor| get_inv_evaluation(divisor, domain))
+        .collect::<Vec<_>>();
+
+    let mut result = E::zeroed_vector(domain.ce_domain_size());
+    batch_iter_mut!(
+        &mut result,
+        128, // min batch size
+        |batch: &mut [E], batch_offset: usize| {
+            for ((column, divisor), z) in columns.iter().zip(divisors).zip(&inv_evaluations) {
+                let column = &column[batch_offset..batch_offset + batch.len()];
+
+                // divide column values by the divisor; for boundary constraints this computed
+                // simply as multiplication of column value by the inverse of divisor numerator;
+                // for transition constraints, it is computed similarly, but the result is also
+                // multiplied by the divisor's denominator (exclusion point).
+                if divisor.exemptions().is_empty() {
+                    // the column represents merged evaluations of boundary constraints, and
+                    // divisor has the form of (x^a - b); thus to divide the column by the divisor,
+                    // we compute: value * z, where z = 1 / (x^a - b) and has already been computed
+                    // above.
+                    for (i, (acc_value, &value)) in batch.iter_mut().zip(column).enumerate() {
+                        // determine which value of z corresponds to the current domain point
+                        let z = z[(batch_offset + i) % z.len()];
+                        // compute value * z and add it to the result
+                        *acc_value += value.mul_base(z);
+                    }
+                } else {
+                    // the column represents merged evaluations of transition constraints, and
+                    // divisor has the form of (x^a - 1) / e(x), where e(x) describes the exemption
+                    // points; thus, to divide the column by the divisor, we compute:
+                    // value * e(x) * z, where z = 1 / (x^a - 1) and has already been computed
+                    // above.
+                    for (i, (acc_value, &value)) in batch.iter_mut().zip(column).enumerate() {
+                        // compute value of e(x)
+                        let x = domain.get_ce_x_at(batch_offset + i);
+                        let e = divisor.evaluate_exemptions_at(x);
+                        // determine which value of z corresponds to the current domain point
+                        let z = z[(batch_offset + i) % z.len()];
+                        // compute value * e(x) * z and add it to the result
+                        *acc_value += value.mul_base(z * e);
+                    }
                 }
             }
-        );
-    }
+        }
+    );
+
+    result
 }
 
 /// Computes evaluations of the divisor's numerator over the domain of the specified size and offset.
@@ -516,3 +750,77 @@ fn evaluate_divisor<E: FieldElement>(
         .map(|x| E::from(divisor.evaluate_at(x)))
         .collect()
 }
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{ConstraintEvaluationMode, ConstraintEvaluationTable};
+    use crate::{tests::MockAir, NoopObserver, StarkDomain};
+    use air::{Air, Assertion, ConstraintDivisor};
+    use math::{fields::f128::BaseElement, polynom, FieldElement};
+    use rand_utils::rand_vector;
+    use utils::collections::Vec;
+
+    #[test]
+    fn streaming_mode_matches_table_mode() {
+        let trace_length = 32;
+        let assertions = vec![
+            Assertion::single(0, 0, BaseElement::ONE),
+            Assertion::single(1, trace_length - 1, BaseElement::ONE),
+            Assertion::periodic(2, 1, 8, BaseElement::ONE),
+        ];
+        let air = MockAir::with_assertions(assertions, trace_length);
+        let domain = StarkDomain::new(&air);
+        let num_rows = domain.ce_domain_size();
+
+        // build a transition divisor followed by one divisor per assertion
+        let coefficients = vec![(BaseElement::ONE, BaseElement::ONE)];
+        let transition_constraints = air.get_transition_constraints(&coefficients);
+        let mut divisors = vec![transition_constraints.divisor().clone()];
+        for assertion in air.get_assertions() {
+            divisors.push(ConstraintDivisor::from_assertion(&assertion, trace_length));
+        }
+
+        // for each divisor, build evaluations of a random polynomial of degree num_rows - 1
+        // multiplied by the divisor; the data is in column-major form
+        let mut data = Vec::new();
+        for divisor in divisors.iter() {
+            let poly: Vec<BaseElement> = rand_vector(num_rows);
+            for step in 0..num_rows {
+                let x = domain.get_ce_x_at(step);
+                data.push(divisor.evaluate_at(x) * polynom::eval(&poly, x));
+            }
+        }
+
+        // record the data into tables in both modes in batches of 8 rows across 2 fragments
+        let build_poly = |mode: ConstraintEvaluationMode| {
+            #[cfg(not(debug_assertions))]
+            let mut table = ConstraintEvaluationTable::new(&domain, divisors.clone(), mode);
+            #[cfg(debug_assertions)]
+            let mut table = ConstraintEvaluationTable::new(
+                &domain,
+                divisors.clone(),
+                mode,
+                &transition_constraints,
+            );
+
+            for mut fragment in table.fragments(2) {
+                for row_offset in (0..fragment.num_rows()).step_by(8) {
+                    let step = fragment.offset() + row_offset;
+                    let batch = data
+                        .chunks(num_rows)
+                        .flat_map(|column| column[step..step + 8].iter().copied())
+                        .collect::<Vec<_>>();
+                    fragment.update_rows(row_offset, &batch);
+                }
+            }
+            table.into_poly(&NoopObserver).unwrap().into_columns()
+        };
+
+        let expected = build_poly(ConstraintEvaluationMode::Table);
+        let actual = build_poly(ConstraintEvaluationMode::Streaming);
+        assert_eq!(expected, actual);
+    }
+}
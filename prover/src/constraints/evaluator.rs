@@ -5,14 +5,15 @@
 
 use super::{
     super::TraceLde, evaluation_table::EvaluationTableFragment, BoundaryConstraints,
-    ConstraintEvaluationTable, PeriodicValueTable, StarkDomain,
+    ConstraintEvaluationMode, ConstraintEvaluationTable, PeriodicValueTable, ProverError,
+    StarkDomain,
 };
 use air::{
-    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrame,
+    Air, AuxTraceRandElements, ConstraintCompositionCoefficients, EvaluationFrameBatch,
     TransitionConstraints,
 };
 use math::FieldElement;
-use utils::iter_mut;
+use utils::{iter_mut, CancellationToken};
 
 #[cfg(feature = "concurrent")]
 use utils::{iterators::*, rayon};
@@ -23,6 +24,11 @@ use utils::{iterators::*, rayon};
 #[cfg(feature = "concurrent")]
 const MIN_CONCURRENT_DOMAIN_SIZE: usize = 8192;
 
+/// Number of consecutive steps of the constraint evaluation domain for which transition
+/// constraints are evaluated in a single batch; must be a power of two. This is also the size of
+/// the chunks in which constraint evaluations are divided by their divisors in streaming mode.
+const EVALUATION_BATCH_SIZE: usize = 64;
+
 // CONSTRAINT EVALUATOR
 // ================================================================================================
 
@@ -31,7 +37,7 @@ pub struct ConstraintEvaluator<'a, A: Air, E: FieldElement<BaseField = A::BaseFi
     boundary_constraints: BoundaryConstraints<E>,
     transition_constraints: TransitionConstraints<E>,
     aux_rand_elements: AuxTraceRandElements<E>,
-    periodic_values: PeriodicValueTable<E::BaseField>,
+    periodic_values: &'a PeriodicValueTable<E::BaseField>,
 }
 
 impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<'a, A, E> {
@@ -39,8 +45,12 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
     // --------------------------------------------------------------------------------------------
     /// Returns a new evaluator which can be used to evaluate transition and boundary constraints
     /// over extended execution trace.
+    ///
+    /// `periodic_values` is expected to be the table of periodic column values built for the
+    /// specified `air`.
     pub fn new(
         air: &'a A,
+        periodic_values: &'a PeriodicValueTable<E::BaseField>,
         aux_rand_elements: AuxTraceRandElements<E>,
         composition_coefficients: ConstraintCompositionCoefficients<E>,
     ) -> Self {
@@ -49,9 +59,6 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
         let transition_constraints =
             air.get_transition_constraints(&composition_coefficients.transition);
 
-        // build periodic value table
-        let periodic_values = PeriodicValueTable::new(air);
-
         // build boundary constraint groups; these will be used to evaluate and compose boundary
         // constraint evaluations.
         let boundary_constraints =
@@ -71,11 +78,20 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
     /// Evaluates constraints against the provided extended execution trace. Constraints are
     /// evaluated over a constraint evaluation domain. This is an optimization because constraint
     /// evaluation domain can be many times smaller than the full LDE domain.
+    ///
+    /// The `mode` parameter determines whether the returned table contains merged constraint
+    /// evaluations for each divisor, or evaluations of the composition polynomial accumulated
+    /// while constraints are being evaluated.
+    ///
+    /// If a cancellation `token` is provided, it is checked before every batch of steps is
+    /// evaluated, and [ProverError::Cancelled] is returned once the token has been cancelled.
     pub fn evaluate(
         self,
         trace: &TraceLde<E>,
         domain: &'a StarkDomain<E::BaseField>,
-    ) -> ConstraintEvaluationTable<'a, E> {
+        mode: ConstraintEvaluationMode,
+        token: Option<&CancellationToken>,
+    ) -> Result<ConstraintEvaluationTable<'a, E>, ProverError> {
         assert_eq!(
             trace.trace_len(),
             domain.lde_domain_size(),
@@ -92,10 +108,14 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
         // memory to hold all transition constraint evaluations (before they are merged into a
         // single value) so that we can check their degrees later
         #[cfg(not(debug_assertions))]
-        let mut evaluation_table = ConstraintEvaluationTable::<E>::new(domain, divisors);
+        let mut evaluation_table = ConstraintEvaluationTable::<E>::new(domain, divisors, mode);
         #[cfg(debug_assertions)]
-        let mut evaluation_table =
-            ConstraintEvaluationTable::<E>::new(domain, divisors, &self.transition_constraints);
+        let mut evaluation_table = ConstraintEvaluationTable::<E>::new(
+            domain,
+            divisors,
+            mode,
+            &self.transition_constraints,
+        );
 
         // when `concurrent` feature is enabled, break the evaluation table into multiple fragments
         // to evaluate them into multiple threads; unless the constraint evaluation domain is small,
@@ -117,18 +137,23 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
         let mut fragments = evaluation_table.fragments(num_fragments);
         iter_mut!(fragments).for_each(|fragment| {
             if self.air.trace_info().is_multi_segment() {
-                self.evaluate_fragment_full(trace, domain, fragment);
+                self.evaluate_fragment_full(trace, domain, fragment, token);
             } else {
-                self.evaluate_fragment_main(trace, domain, fragment);
+                self.evaluate_fragment_main(trace, domain, fragment, token);
             }
         });
 
+        // fragments are evaluated only partially if the token was cancelled during evaluation
+        if token.map_or(false, CancellationToken::is_cancelled) {
+            return Err(ProverError::Cancelled);
+        }
+
         // when in debug mode, make sure expected transition constraint degrees align with
         // actual degrees we got during constraint evaluation
         #[cfg(debug_assertions)]
         evaluation_table.validate_transition_degrees();
 
-        evaluation_table
+        Ok(evaluation_table)
     }
 
     // EVALUATION HELPERS
@@ -137,51 +162,87 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
     /// Evaluates constraints for a single fragment of the evaluation table.
     ///
     /// This evaluates constraints only over the main segment of the execution trace.
+    ///
+    /// Transition constraints are evaluated over batches of consecutive steps; for each batch,
+    /// evaluation frames and periodic values are read into contiguous buffers, and are passed
+    /// to [Air::evaluate_transition_batch()]. Evaluation stops early if the cancellation `token`
+    /// is cancelled.
     fn evaluate_fragment_main(
         &self,
         trace: &TraceLde<E>,
         domain: &StarkDomain<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
+        token: Option<&CancellationToken>,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step;
-        let mut main_frame = EvaluationFrame::new(trace.main_trace_width());
-        let mut evaluations = vec![E::ZERO; fragment.num_columns()];
+        // initialize buffers to hold trace values and evaluation results for each batch of steps;
+        // fragment sizes and batch size are powers of two, and thus, each fragment consists of
+        // a whole number of batches
+        let batch_size = fragment.num_rows().min(EVALUATION_BATCH_SIZE);
+        let mut main_frames = EvaluationFrameBatch::new(trace.main_trace_width(), batch_size);
+        let mut periodic_values =
+            vec![E::BaseField::ZERO; self.periodic_values.width() * batch_size];
+        let mut t_batch_evaluations =
+            vec![E::BaseField::ZERO; self.num_main_transition_constraints() * batch_size];
         let mut t_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
+        let mut evaluations = vec![E::ZERO; fragment.num_divisors()];
+        let mut batch_evaluations = vec![E::ZERO; fragment.num_divisors() * batch_size];
 
         // this will be used to convert steps in constraint evaluation domain to steps in
         // LDE domain
         let lde_shift = domain.ce_to_lde_blowup().trailing_zeros();
 
-        for i in 0..fragment.num_rows() {
-            let step = i + fragment.offset();
-
-            // update evaluation frame buffer with data from the execution trace; this will
-            // read current and next rows from the trace into the buffer; data in the trace
-            // table is extended over the LDE domain, so, we need to convert step in constraint
-            // evaluation domain, into a step in LDE domain, in case these domains are different
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
-
-            // evaluate transition constraints and save the merged result the first slot of the
-            // evaluations buffer
-            evaluations[0] =
-                self.evaluate_main_transition(&main_frame, domain, step, &mut t_evaluations);
-
-            // when in debug mode, save transition constraint evaluations
-            #[cfg(debug_assertions)]
-            fragment.update_transition_evaluations(i, &t_evaluations, &[]);
-
-            // evaluate boundary constraints; the results go into remaining slots of the
-            // evaluations buffer
-            let main_state = main_frame.current();
-            self.boundary_constraints.evaluate_main(
-                main_state,
-                domain,
-                step,
-                &mut evaluations[1..],
+        for batch_offset in (0..fragment.num_rows()).step_by(batch_size) {
+            if token.map_or(false, CancellationToken::is_cancelled) {
+                return;
+            }
+            let batch_step = batch_offset + fragment.offset();
+
+            // update evaluation frame buffers with data from the execution trace; this will
+            // read current and next rows for all steps of the batch; data in the trace table is
+            // extended over the LDE domain, so, we need to convert steps in constraint evaluation
+            // domain, into steps in LDE domain, in case these domains are different
+            trace.read_main_trace_batch_into(
+                batch_step << lde_shift,
+                1 << lde_shift,
+                &mut main_frames,
+            );
+
+            // evaluate transition constraints for all steps of the batch
+            self.evaluate_main_transition_batch(
+                &main_frames,
+                batch_step,
+                &mut periodic_values,
+                &mut t_batch_evaluations,
             );
 
-            // record the result in the evaluation table
-            fragment.update_row(i, &evaluations);
+            for j in 0..batch_size {
+                let step = batch_step + j;
+
+                // merge transition constraint evaluations at this step and save the result into
+                // the first slot of the evaluations buffer
+                read_batch_row(&t_batch_evaluations, j, batch_size, &mut t_evaluations);
+                evaluations[0] = self.merge_main_transition(&t_evaluations, domain, step);
+
+                // when in debug mode, save transition constraint evaluations
+                #[cfg(debug_assertions)]
+                fragment.update_transition_evaluations(batch_offset + j, &t_evaluations, &[]);
+
+                // evaluate boundary constraints; the results go into remaining slots of the
+                // evaluations buffer
+                let main_state = trace.get_main_segment().row(step << lde_shift);
+                self.boundary_constraints.evaluate_main(
+                    main_state,
+                    domain,
+                    step,
+                    &mut evaluations[1..],
+                );
+
+                // record the result in the buffer of evaluations for the batch
+                write_batch_row(&evaluations, j, batch_size, &mut batch_evaluations);
+            }
+
+            // record evaluations for all steps of the batch in the evaluation table
+            fragment.update_rows(batch_offset, &batch_evaluations);
         }
     }
 
@@ -189,131 +250,192 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
     ///
     /// This evaluates constraints only over all segments of the execution trace (i.e. main segment
     /// and all auxiliary segments).
+    ///
+    /// Similarly to [evaluate_fragment_main()](Self::evaluate_fragment_main), transition
+    /// constraints are evaluated over batches of consecutive steps, and evaluation stops early if
+    /// the cancellation `token` is cancelled.
     fn evaluate_fragment_full(
         &self,
         trace: &TraceLde<E>,
         domain: &StarkDomain<A::BaseField>,
         fragment: &mut EvaluationTableFragment<E>,
+        token: Option<&CancellationToken>,
     ) {
-        // initialize buffers to hold trace values and evaluation results at each step
-        let mut main_frame = EvaluationFrame::new(trace.main_trace_width());
-        let mut aux_frame = EvaluationFrame::new(trace.aux_trace_width());
+        // initialize buffers to hold trace values and evaluation results for each batch of steps
+        let batch_size = fragment.num_rows().min(EVALUATION_BATCH_SIZE);
+        let mut main_frames = EvaluationFrameBatch::new(trace.main_trace_width(), batch_size);
+        let mut aux_frames = EvaluationFrameBatch::new(trace.aux_trace_width(), batch_size);
+        let mut periodic_values =
+            vec![E::BaseField::ZERO; self.periodic_values.width() * batch_size];
+        let mut tm_batch_evaluations =
+            vec![E::BaseField::ZERO; self.num_main_transition_constraints() * batch_size];
+        let mut ta_batch_evaluations =
+            vec![E::ZERO; self.num_aux_transition_constraints() * batch_size];
         let mut tm_evaluations = vec![E::BaseField::ZERO; self.num_main_transition_constraints()];
         let mut ta_evaluations = vec![E::ZERO; self.num_aux_transition_constraints()];
-        let mut evaluations = vec![E::ZERO; fragment.num_columns()];
+        let mut evaluations = vec![E::ZERO; fragment.num_divisors()];
+        let mut batch_evaluations = vec![E::ZERO; fragment.num_divisors() * batch_size];
 
         // this will be used to convert steps in constraint evaluation domain to steps in
         // LDE domain
         let lde_shift = domain.ce_to_lde_blowup().trailing_zeros();
 
-        for i in 0..fragment.num_rows() {
-            let step = i + fragment.offset();
+        for batch_offset in (0..fragment.num_rows()).step_by(batch_size) {
+            if token.map_or(false, CancellationToken::is_cancelled) {
+                return;
+            }
+            let batch_step = batch_offset + fragment.offset();
 
             // read both the main and the auxiliary evaluation frames from the trace
-            trace.read_main_trace_frame_into(step << lde_shift, &mut main_frame);
-            trace.read_aux_trace_frame_into(step << lde_shift, &mut aux_frame);
-
-            // evaluate transition constraints and save the merged result the first slot of the
-            // evaluations buffer; we evaluate and compose constraints in the same function, we
-            // can just add up the results of evaluating main and auxiliary constraints.
-            evaluations[0] =
-                self.evaluate_main_transition(&main_frame, domain, step, &mut tm_evaluations);
-            evaluations[0] += self.evaluate_aux_transition(
-                &main_frame,
-                &aux_frame,
-                domain,
-                step,
-                &mut ta_evaluations,
+            trace.read_main_trace_batch_into(
+                batch_step << lde_shift,
+                1 << lde_shift,
+                &mut main_frames,
+            );
+            trace.read_aux_trace_batch_into(
+                batch_step << lde_shift,
+                1 << lde_shift,
+                &mut aux_frames,
             );
 
-            // when in debug mode, save transition constraint evaluations
-            #[cfg(debug_assertions)]
-            fragment.update_transition_evaluations(i, &tm_evaluations, &ta_evaluations);
-
-            // evaluate boundary constraints; the results go into remaining slots of the
-            // evaluations buffer
-            let main_state = main_frame.current();
-            let aux_state = aux_frame.current();
-            self.boundary_constraints.evaluate_all(
-                main_state,
-                aux_state,
-                domain,
-                step,
-                &mut evaluations[1..],
+            // evaluate main and auxiliary transition constraints for all steps of the batch
+            self.evaluate_main_transition_batch(
+                &main_frames,
+                batch_step,
+                &mut periodic_values,
+                &mut tm_batch_evaluations,
+            );
+            self.evaluate_aux_transition_batch(
+                &main_frames,
+                &aux_frames,
+                &periodic_values,
+                &mut ta_batch_evaluations,
             );
 
-            // record the result in the evaluation table
-            fragment.update_row(i, &evaluations);
+            for j in 0..batch_size {
+                let step = batch_step + j;
+
+                // merge transition constraint evaluations at this step and save the result into
+                // the first slot of the evaluations buffer; since all transition constraints have
+                // the same divisor, we can just add up the results of merging main and auxiliary
+                // constraints.
+                read_batch_row(&tm_batch_evaluations, j, batch_size, &mut tm_evaluations);
+                read_batch_row(&ta_batch_evaluations, j, batch_size, &mut ta_evaluations);
+                evaluations[0] = self.merge_main_transition(&tm_evaluations, domain, step);
+                evaluations[0] += self.merge_aux_transition(&ta_evaluations, domain, step);
+
+                // when in debug mode, save transition constraint evaluations
+                #[cfg(debug_assertions)]
+                fragment.update_transition_evaluations(
+                    batch_offset + j,
+                    &tm_evaluations,
+                    &ta_evaluations,
+                );
+
+                // evaluate boundary constraints; the results go into remaining slots of the
+                // evaluations buffer
+                let main_state = trace.get_main_segment().row(step << lde_shift);
+                let aux_state = trace.get_aux_segment(0).row(step << lde_shift);
+                self.boundary_constraints.evaluate_all(
+                    main_state,
+                    aux_state,
+                    domain,
+                    step,
+                    &mut evaluations[1..],
+                );
+
+                // record the result in the buffer of evaluations for the batch
+                write_batch_row(&evaluations, j, batch_size, &mut batch_evaluations);
+            }
+
+            // record evaluations for all steps of the batch in the evaluation table
+            fragment.update_rows(batch_offset, &batch_evaluations);
         }
     }
 
     // TRANSITION CONSTRAINT EVALUATORS
     // --------------------------------------------------------------------------------------------
 
-    /// Evaluates transition constraints of the main execution trace at the specified step of the
-    /// constraint evaluation domain.
+    /// Evaluates transition constraints of the main execution trace for a batch of consecutive
+    /// steps of the constraint evaluation domain starting at `step`.
     ///
-    /// `x` is the corresponding domain value at the specified step. That is, x = s * g^step,
-    /// where g is the generator of the constraint evaluation domain, and s is the domain offset.
-    #[rustfmt::skip]
-    fn evaluate_main_transition(
+    /// Periodic values for the steps of the batch are read into `periodic_values`, and the
+    /// evaluations are written into `evaluations`; both buffers are in column-major form.
+    fn evaluate_main_transition_batch(
         &self,
-        main_frame: &EvaluationFrame<E::BaseField>,
-        domain: &StarkDomain<A::BaseField>,
+        main_frames: &EvaluationFrameBatch<E::BaseField>,
         step: usize,
+        periodic_values: &mut [E::BaseField],
         evaluations: &mut [E::BaseField],
-    ) -> E {
+    ) {
         // TODO: use a more efficient way to zero out memory
         evaluations.fill(E::BaseField::ZERO);
 
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
+        // get periodic values for all steps of the batch
+        self.periodic_values.read_batch_into(step, periodic_values);
 
         // evaluate transition constraints over the main segment of the execution trace and save
         // the results into evaluations buffer
-        self.air.evaluate_transition(main_frame, periodic_values, evaluations);
-
-        // merge transition constraint evaluations into a single value and return it;
-        // we can do this here because all transition constraints have the same divisor.
-        self.transition_constraints.main_constraints().iter().fold(E::ZERO, |result, group| {
-            let (power, offset_exp) = (group.degree_adjustment(), group.domain_offset_exp());
-            let xp = domain.get_ce_x_power_at(step, power, offset_exp);
-            result + group.merge_evaluations(evaluations, xp)
-        })
+        self.air
+            .evaluate_transition_batch(main_frames, periodic_values, evaluations);
     }
 
-    /// Evaluates all transition constraints (i.e., for main and auxiliary trace segments) at the
-    /// specified step of the constraint evaluation domain.
+    /// Evaluates transition constraints of the auxiliary trace segments for a batch of
+    /// consecutive steps of the constraint evaluation domain.
     ///
-    /// `x` is the corresponding domain value at the specified step. That is, x = s * g^step,
-    /// where g is the generator of the constraint evaluation domain, and s is the domain offset.
-    #[rustfmt::skip]
-    fn evaluate_aux_transition(
+    /// Periodic values for the steps of the batch are expected to be in `periodic_values`
+    /// already, and the evaluations are written into `evaluations`; both buffers are in
+    /// column-major form.
+    fn evaluate_aux_transition_batch(
         &self,
-        main_frame: &EvaluationFrame<E::BaseField>,
-        aux_frame: &EvaluationFrame<E>,
-        domain: &StarkDomain<A::BaseField>,
-        step: usize,
+        main_frames: &EvaluationFrameBatch<E::BaseField>,
+        aux_frames: &EvaluationFrameBatch<E>,
+        periodic_values: &[E::BaseField],
         evaluations: &mut [E],
-    ) -> E {
+    ) {
         // TODO: use a more efficient way to zero out memory
         evaluations.fill(E::ZERO);
 
-        // get periodic values at the evaluation step
-        let periodic_values = self.periodic_values.get_row(step);
-
         // evaluate transition constraints over auxiliary trace segments and save the results into
         // evaluations buffer
-        self.air.evaluate_aux_transition(
-            main_frame,
-            aux_frame,
+        self.air.evaluate_aux_transition_batch(
+            main_frames,
+            aux_frames,
             periodic_values,
             &self.aux_rand_elements,
             evaluations,
         );
+    }
+
+    /// Merges evaluations of transition constraints of the main execution trace at the specified
+    /// step of the constraint evaluation domain into a single value.
+    ///
+    /// We can do this because all transition constraints have the same divisor.
+    #[rustfmt::skip]
+    fn merge_main_transition(
+        &self,
+        evaluations: &[E::BaseField],
+        domain: &StarkDomain<A::BaseField>,
+        step: usize,
+    ) -> E {
+        self.transition_constraints.main_constraints().iter().fold(E::ZERO, |result, group| {
+            let (power, offset_exp) = (group.degree_adjustment(), group.domain_offset_exp());
+            let xp = domain.get_ce_x_power_at(step, power, offset_exp);
+            result + group.merge_evaluations(evaluations, xp)
+        })
+    }
 
-        // merge transition constraint evaluations into a single value and return it;
-        // we can do this here because all transition constraints have the same divisor.
+    /// Merges evaluations of transition constraints of the auxiliary trace segments at the
+    /// specified step of the constraint evaluation domain into a single value.
+    ///
+    /// We can do this because all transition constraints have the same divisor.
+    #[rustfmt::skip]
+    fn merge_aux_transition(
+        &self,
+        evaluations: &[E],
+        domain: &StarkDomain<A::BaseField>,
+        step: usize,
+    ) -> E {
         self.transition_constraints.aux_constraints().iter().fold(E::ZERO, |result, group| {
             let (power, offset_exp) = (group.degree_adjustment(), group.domain_offset_exp());
             let xp = domain.get_ce_x_power_at(step, power, offset_exp);
@@ -335,3 +457,23 @@ impl<'a, A: Air, E: FieldElement<BaseField = A::BaseField>> ConstraintEvaluator<
         self.transition_constraints.num_aux_constraints()
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Copies values of the specified row from column-major `batch` with `num_rows` rows into
+/// `result`.
+#[inline(always)]
+fn read_batch_row<E: Copy>(batch: &[E], row: usize, num_rows: usize, result: &mut [E]) {
+    for (i, value) in result.iter_mut().enumerate() {
+        *value = batch[i * num_rows + row];
+    }
+}
+
+/// Copies `values` into the specified row of column-major `batch` with `num_rows` rows.
+#[inline(always)]
+fn write_batch_row<E: Copy>(values: &[E], row: usize, num_rows: usize, batch: &mut [E]) {
+    for (i, &value) in values.iter().enumerate() {
+        batch[i * num_rows + row] = value;
+    }
+}
@@ -9,7 +9,7 @@ mod boundary;
 use boundary::BoundaryConstraints;
 
 mod periodic_table;
-use periodic_table::PeriodicValueTable;
+pub(crate) use periodic_table::PeriodicValueTable;
 
 mod evaluator;
 pub use evaluator::ConstraintEvaluator;
@@ -18,7 +18,7 @@ mod composition_poly;
 pub use composition_poly::CompositionPoly;
 
 mod evaluation_table;
-pub use evaluation_table::ConstraintEvaluationTable;
+pub use evaluation_table::{ConstraintEvaluationMode, ConstraintEvaluationTable};
 
 mod commitment;
 pub use commitment::ConstraintCommitment;
@@ -81,12 +81,26 @@ impl<B: StarkField> PeriodicValueTable<B> {
         self.width == 0
     }
 
-    pub fn get_row(&self, ce_step: usize) -> &[B] {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Reads values of all periodic columns for `result.len() / width` consecutive steps of the
+    /// constraint evaluation domain starting at `ce_step` into `result`.
+    ///
+    /// The values are written in column-major form: values of the i-th periodic column are
+    /// written into `result[i * n..(i + 1) * n]`, where `n` is the number of steps.
+    pub fn read_batch_into(&self, ce_step: usize, result: &mut [B]) {
         if self.is_empty() {
-            &[]
-        } else {
-            let start = (ce_step % self.length) * self.width;
-            &self.values[start..start + self.width]
+            return;
+        }
+
+        let num_steps = result.len() / self.width;
+        for (i, column) in result.chunks_mut(num_steps).enumerate() {
+            for (j, value) in column.iter_mut().enumerate() {
+                let row = (ce_step + j) % self.length;
+                *value = self.values[row * self.width + i];
+            }
         }
     }
 }
@@ -142,15 +156,25 @@ mod tests {
             })
             .collect::<Vec<_>>();
 
-        // build actual values by recording rows of the table at each step of ce_domain
-        let mut actual = vec![Vec::new(), Vec::new()];
-        for i in 0..air.ce_domain_size() {
-            let row = table.get_row(i);
-            actual[0].push(row[0]);
-            actual[1].push(row[1]);
-        }
+        // build actual values by reading all steps of ce_domain in a single batch
+        let ce_domain_size = air.ce_domain_size();
+        let mut batch = vec![BaseElement::ZERO; 2 * ce_domain_size];
+        table.read_batch_into(0, &mut batch);
+        let actual = batch
+            .chunks(ce_domain_size)
+            .map(|column| column.to_vec())
+            .collect::<Vec<_>>();
 
         assert_eq!(expected, actual);
+
+        // batches which cross the end of the table wrap around to its beginning
+        let mut batch = vec![BaseElement::ZERO; 2 * 8];
+        table.read_batch_into(table.length - 4, &mut batch);
+        for j in 0..8 {
+            let step = (table.length - 4 + j) % ce_domain_size;
+            assert_eq!(expected[0][step], batch[j]);
+            assert_eq!(expected[1][step], batch[8 + j]);
+        }
     }
 
     fn build_ce_domain(domain_size: usize, domain_offset: BaseElement) -> Vec<BaseElement> {
@@ -0,0 +1,196 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{constraints::PeriodicValueTable, StarkDomain};
+use air::Air;
+use math::StarkField;
+use utils::{
+    collections::{BTreeMap, Vec},
+    Serializable,
+};
+
+// PROVER CONTEXT
+// ================================================================================================
+
+/// A cache of data structures which depend only on the shape of a computation and thus can be
+/// re-used across proofs of computations of the same shape.
+///
+/// For every distinct combination of trace length, constraint evaluation domain size, LDE
+/// domain size, and domain offset, the context holds a [StarkDomain] (including twiddles and
+/// inverse twiddles for the trace and constraint evaluation domains), as well as extended values
+/// of the periodic columns of the most recently proven computation of this shape.
+///
+/// Cached domains are never evicted: the context grows by one domain for every new shape of a
+/// computation proven with it. Thus, when proving computations of many different shapes, the
+/// context should be cleared via [clear()](ProverContext::clear) as needed.
+///
+/// A context can be passed to [Prover::prove_with_context()](crate::Prover::prove_with_context)
+/// so that repeated proofs of same-sized traces skip domain setup. The data is cached for a
+/// single base field `B`; the cached structures do not depend on the field extension used to
+/// generate a proof.
+pub struct ProverContext<B: StarkField> {
+    domains: BTreeMap<DomainKey, CachedDomain<B>>,
+}
+
+impl<B: StarkField> ProverContext<B> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new empty prover context.
+    pub fn new() -> Self {
+        Self {
+            domains: BTreeMap::new(),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of distinct domains cached in this context.
+    pub fn num_domains(&self) -> usize {
+        self.domains.len()
+    }
+
+    /// Returns true if this context holds no cached domains.
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// Removes all cached data from this context.
+    pub fn clear(&mut self) {
+        self.domains.clear();
+    }
+
+    // CACHE ACCESS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns cached domain data for the computation described by the specified AIR.
+    ///
+    /// If the context does not hold a domain matching the AIR, the domain is built and added to
+    /// the context. Similarly, the periodic value table is re-built if periodic columns of the
+    /// AIR differ from the ones for which the cached table was built.
+    pub(crate) fn get_or_build<A: Air<BaseField = B>>(&mut self, air: &A) -> &CachedDomain<B> {
+        let key = DomainKey::new(air);
+        let periodic_columns = air.get_periodic_column_values();
+
+        let cached = self
+            .domains
+            .entry(key)
+            .or_insert_with(|| CachedDomain::new(air, periodic_columns.clone()));
+
+        if cached.periodic_columns != periodic_columns {
+            cached.periodic_values = PeriodicValueTable::new(air);
+            cached.periodic_columns = periodic_columns;
+        }
+
+        cached
+    }
+}
+
+impl<B: StarkField> Default for ProverContext<B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// CACHED DOMAIN
+// ================================================================================================
+
+/// Data cached in a [ProverContext] for computations of a single shape.
+pub(crate) struct CachedDomain<B: StarkField> {
+    domain: StarkDomain<B>,
+    periodic_columns: Vec<Vec<B>>,
+    periodic_values: PeriodicValueTable<B>,
+}
+
+impl<B: StarkField> CachedDomain<B> {
+    fn new<A: Air<BaseField = B>>(air: &A, periodic_columns: Vec<Vec<B>>) -> Self {
+        Self {
+            domain: StarkDomain::new(air),
+            periodic_columns,
+            periodic_values: PeriodicValueTable::new(air),
+        }
+    }
+
+    /// Returns the STARK domain for the computation.
+    pub fn domain(&self) -> &StarkDomain<B> {
+        &self.domain
+    }
+
+    /// Returns the table of extended periodic column values for the computation.
+    pub fn periodic_values(&self) -> &PeriodicValueTable<B> {
+        &self.periodic_values
+    }
+}
+
+// DOMAIN KEY
+// ================================================================================================
+
+/// Describes the shape of a computation for the purposes of domain caching.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct DomainKey {
+    trace_length: usize,
+    ce_domain_size: usize,
+    lde_domain_size: usize,
+    domain_offset: Vec<u8>,
+}
+
+impl DomainKey {
+    fn new<A: Air>(air: &A) -> Self {
+        Self {
+            trace_length: air.trace_length(),
+            ce_domain_size: air.ce_domain_size(),
+            lde_domain_size: air.lde_domain_size(),
+            domain_offset: air.domain_offset().to_bytes(),
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::ProverContext;
+    use crate::tests::MockAir;
+    use math::{fields::f128::BaseElement, FieldElement, StarkField};
+
+    #[test]
+    fn prover_context_caching() {
+        let mut context = ProverContext::<BaseElement>::new();
+        assert!(context.is_empty());
+
+        // domains of the same shape are built only once
+        let air = MockAir::with_trace_length(32);
+        let domain_ptr = context.get_or_build(&air).domain() as *const _;
+        let air = MockAir::with_trace_length(32);
+        assert_eq!(domain_ptr, context.get_or_build(&air).domain() as *const _);
+        assert_eq!(1, context.num_domains());
+
+        // a computation of a different shape gets a separate domain
+        let air = MockAir::with_trace_length(64);
+        assert_eq!(64, context.get_or_build(&air).domain().trace_length());
+        assert_eq!(2, context.num_domains());
+
+        // periodic values are re-built when periodic columns change
+        let column = vec![BaseElement::ONE, BaseElement::ZERO];
+        let air = MockAir::with_periodic_columns(vec![column], 32);
+        let cached = context.get_or_build(&air);
+        assert_eq!(1, cached.periodic_values().width());
+        assert_eq!(domain_ptr, cached.domain() as *const _);
+        assert_eq!(2, context.num_domains());
+
+        // a computation of the same shape over a shifted domain gets a separate domain
+        let offset = BaseElement::GENERATOR.square();
+        let air = MockAir::with_domain_offset(offset, 32);
+        assert_eq!(offset, context.get_or_build(&air).domain().offset());
+        assert_eq!(3, context.num_domains());
+        let air = MockAir::with_trace_length(32);
+        assert_eq!(domain_ptr, context.get_or_build(&air).domain() as *const _);
+        assert_eq!(3, context.num_domains());
+
+        context.clear();
+        assert!(context.is_empty());
+    }
+}
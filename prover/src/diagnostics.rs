@@ -0,0 +1,299 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{Prover, ProverError, Trace, TracePolyTable};
+use air::{
+    proof::StarkProof, Air, Assertion, AuxTraceRandElements, ConstraintDivisor, EvaluationFrame,
+};
+use math::{fft, polynom, FieldElement, StarkField};
+use utils::collections::Vec;
+
+// DIVERGENT CONSTRAINT
+// ================================================================================================
+
+/// A constraint which is not consistent with the execution trace at the out-of-domain point.
+///
+/// A transition constraint diverges if its evaluation at the out-of-domain point, divided by the
+/// transition constraint divisor, is different from the value at this point of the polynomial
+/// interpolated from such quotients over the constraint evaluation domain. This happens when the
+/// constraint does not hold on the execution trace, or when the actual degree of the constraint
+/// is greater than the degree declared by the AIR. An assertion diverges if it does not hold on
+/// the execution trace.
+///
+/// Indexes of transition constraints follow the order of evaluations computed by
+/// [Air::evaluate_transition()] and [Air::evaluate_aux_transition()], and indexes of assertions
+/// follow the order of assertions returned by [Air::get_assertions()] and
+/// [Air::get_aux_assertions()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DivergentConstraint {
+    /// A transition constraint against the main trace segment.
+    MainTransition(usize),
+    /// A transition constraint against auxiliary trace segments.
+    AuxTransition(usize),
+    /// An assertion against the main trace segment.
+    MainAssertion(usize),
+    /// An assertion against auxiliary trace segments.
+    AuxAssertion(usize),
+}
+
+// OOD SNAPSHOT
+// ================================================================================================
+
+/// Data retained by the prover to diagnose a proof which failed self-verification.
+pub(crate) struct OodSnapshot<E: FieldElement> {
+    pub trace_polys: TracePolyTable<E>,
+    pub aux_rand_elements: AuxTraceRandElements<E>,
+    pub z: E,
+}
+
+// SELF-VERIFICATION
+// ================================================================================================
+
+/// Verifies the `proof` generated by the `prover` for the specified `trace`.
+///
+/// If the verification fails, constraints which diverge at the out-of-domain point are
+/// determined from the trace polynomials in the `snapshot`, and are returned in the error
+/// together with the verifier error.
+pub(crate) fn verify_proof<P, E>(
+    prover: &P,
+    trace: &P::Trace,
+    proof: &StarkProof,
+    snapshot: OodSnapshot<E>,
+) -> Result<(), ProverError>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    let pub_inputs = prover.get_pub_inputs(trace);
+    verifier::verify::<P::Air, P::HashFn, P::RandomCoin>(proof.clone(), pub_inputs).map_err(|err| {
+        let pub_inputs = prover.get_pub_inputs(trace);
+        let air = P::Air::new(trace.get_info(), pub_inputs, prover.options().clone());
+        ProverError::SelfVerificationFailed(err, find_divergent_constraints(&air, &snapshot))
+    })
+}
+
+// DIAGNOSTICS
+// ================================================================================================
+
+/// Returns a list of constraints of the `air` which diverge at the out-of-domain point.
+fn find_divergent_constraints<A, E>(air: &A, snapshot: &OodSnapshot<E>) -> Vec<DivergentConstraint>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    let trace_length = air.trace_length();
+    let main_width = air.trace_layout().main_trace_width();
+    let aux_width = air.trace_layout().aux_trace_width();
+    let divisor = ConstraintDivisor::<A::BaseField>::from_transition(
+        trace_length,
+        air.context().num_transition_exemptions(),
+    );
+
+    // compute quotients of transition constraints at the out-of-domain point directly from the
+    // out-of-domain evaluation frame
+    let z = snapshot.z;
+    let ood_frame = snapshot.trace_polys.get_ood_frame(z);
+    let main_frame = EvaluationFrame::from_rows(
+        ood_frame[0][..main_width].to_vec(),
+        ood_frame[1][..main_width].to_vec(),
+    );
+    let periodic_values = get_periodic_values(air, z);
+    let z_divisor = divisor.evaluate_at(z);
+
+    let mut main_ood = E::zeroed_vector(air.context().num_main_transition_constraints());
+    air.evaluate_transition(&main_frame, &periodic_values, &mut main_ood);
+    let mut aux_ood = E::zeroed_vector(air.context().num_aux_transition_constraints());
+    if aux_width > 0 {
+        let aux_frame = EvaluationFrame::from_rows(
+            ood_frame[0][main_width..].to_vec(),
+            ood_frame[1][main_width..].to_vec(),
+        );
+        air.evaluate_aux_transition(
+            &main_frame,
+            &aux_frame,
+            &periodic_values,
+            &snapshot.aux_rand_elements,
+            &mut aux_ood,
+        );
+    }
+
+    // compute the same quotients by interpolating them over the constraint evaluation domain
+    let (main_quotients, aux_quotients) = interpolate_transition_quotients(air, snapshot, &divisor);
+
+    let mut result = Vec::new();
+    for (i, (&value, quotient)) in main_ood.iter().zip(main_quotients.iter()).enumerate() {
+        if value / z_divisor != polynom::eval(quotient, z) {
+            result.push(DivergentConstraint::MainTransition(i));
+        }
+    }
+    for (i, (&value, quotient)) in aux_ood.iter().zip(aux_quotients.iter()).enumerate() {
+        if value / z_divisor != polynom::eval(quotient, z) {
+            result.push(DivergentConstraint::AuxTransition(i));
+        }
+    }
+
+    // check assertions against the execution trace recovered from trace polynomials
+    let twiddles = fft::get_twiddles::<A::BaseField>(trace_length);
+    let main_trace = snapshot
+        .trace_polys
+        .main_trace_polys()
+        .map(|poly| evaluate_poly(poly, &twiddles))
+        .collect::<Vec<_>>();
+    let aux_trace = snapshot
+        .trace_polys
+        .aux_trace_polys()
+        .map(|poly| evaluate_poly(poly, &twiddles))
+        .collect::<Vec<_>>();
+
+    for (i, assertion) in air.get_assertions().iter().enumerate() {
+        if !is_satisfied(assertion, &main_trace, trace_length) {
+            result.push(DivergentConstraint::MainAssertion(i));
+        }
+    }
+    let aux_assertions = air.get_aux_assertions(&snapshot.aux_rand_elements);
+    for (i, assertion) in aux_assertions.iter().enumerate() {
+        if !is_satisfied(assertion, &aux_trace, trace_length) {
+            result.push(DivergentConstraint::AuxAssertion(i));
+        }
+    }
+
+    result
+}
+
+/// Evaluates transition constraints of the `air` over the constraint evaluation domain, divides
+/// them by the transition constraint `divisor`, and returns polynomials interpolated from the
+/// resulting quotients of the main and auxiliary transition constraints.
+fn interpolate_transition_quotients<A, E>(
+    air: &A,
+    snapshot: &OodSnapshot<E>,
+    divisor: &ConstraintDivisor<A::BaseField>,
+) -> (Vec<Vec<A::BaseField>>, Vec<Vec<E>>)
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    let trace_length = air.trace_length();
+    let ce_domain_size = air.ce_domain_size();
+    let ce_blowup = ce_domain_size / trace_length;
+    let offset = air.domain_offset();
+
+    // extend trace polynomials over the constraint evaluation domain
+    let twiddles = fft::get_twiddles::<A::BaseField>(trace_length);
+    let main_trace = snapshot
+        .trace_polys
+        .main_trace_polys()
+        .map(|poly| fft::evaluate_poly_with_offset(poly, &twiddles, offset, ce_blowup))
+        .collect::<Vec<_>>();
+    let aux_trace = snapshot
+        .trace_polys
+        .aux_trace_polys()
+        .map(|poly| fft::evaluate_poly_with_offset(poly, &twiddles, offset, ce_blowup))
+        .collect::<Vec<_>>();
+
+    // evaluate transition constraint quotients at every point of the domain; the next row of
+    // the trace is located `ce_blowup` steps ahead in the constraint evaluation domain
+    let num_main = air.context().num_main_transition_constraints();
+    let num_aux = air.context().num_aux_transition_constraints();
+    let mut main_quotients = vec![Vec::with_capacity(ce_domain_size); num_main];
+    let mut aux_quotients = vec![Vec::with_capacity(ce_domain_size); num_aux];
+
+    let mut main_frame = EvaluationFrame::new(main_trace.len());
+    // an evaluation frame cannot be empty; if there are no auxiliary segments, the auxiliary
+    // frame is never read
+    let mut aux_frame = EvaluationFrame::new(aux_trace.len().max(1));
+    let mut main_evaluations = A::BaseField::zeroed_vector(num_main);
+    let mut aux_evaluations = E::zeroed_vector(num_aux);
+
+    let g = A::BaseField::get_root_of_unity(ce_domain_size.ilog2());
+    let mut x = offset;
+    for step in 0..ce_domain_size {
+        let next_step = (step + ce_blowup) % ce_domain_size;
+        read_row(&main_trace, step, main_frame.current_mut());
+        read_row(&main_trace, next_step, main_frame.next_mut());
+        read_row(&aux_trace, step, aux_frame.current_mut());
+        read_row(&aux_trace, next_step, aux_frame.next_mut());
+        let periodic_values = get_periodic_values(air, x);
+        let x_divisor = divisor.evaluate_at(x).inv();
+
+        // constraint evaluations may be accumulated into the result, and thus, it must be reset
+        main_evaluations.fill(A::BaseField::ZERO);
+        air.evaluate_transition(&main_frame, &periodic_values, &mut main_evaluations);
+        for (quotients, &value) in main_quotients.iter_mut().zip(main_evaluations.iter()) {
+            quotients.push(value * x_divisor);
+        }
+
+        if num_aux > 0 {
+            aux_evaluations.fill(E::ZERO);
+            air.evaluate_aux_transition(
+                &main_frame,
+                &aux_frame,
+                &periodic_values,
+                &snapshot.aux_rand_elements,
+                &mut aux_evaluations,
+            );
+            for (quotients, &value) in aux_quotients.iter_mut().zip(aux_evaluations.iter()) {
+                quotients.push(value * E::from(x_divisor));
+            }
+        }
+
+        x *= g;
+    }
+
+    // interpolate the quotients into polynomials
+    let inv_twiddles = fft::get_inv_twiddles::<A::BaseField>(ce_domain_size);
+    for quotients in main_quotients.iter_mut() {
+        fft::interpolate_poly_with_offset(quotients, &inv_twiddles, offset);
+    }
+    for quotients in aux_quotients.iter_mut() {
+        fft::interpolate_poly_with_offset(quotients, &inv_twiddles, offset);
+    }
+
+    (main_quotients, aux_quotients)
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns values of periodic columns of the `air` at the specified point `x`.
+fn get_periodic_values<A, E>(air: &A, x: E) -> Vec<E>
+where
+    A: Air,
+    E: FieldElement<BaseField = A::BaseField>,
+{
+    air.get_periodic_column_polys()
+        .iter()
+        .map(|poly| {
+            let num_cycles = air.trace_length() / poly.len();
+            polynom::eval(poly, x.exp_vartime((num_cycles as u32).into()))
+        })
+        .collect()
+}
+
+/// Returns evaluations of the polynomial `poly` over the trace domain.
+fn evaluate_poly<E: FieldElement>(poly: &[E], twiddles: &[E::BaseField]) -> Vec<E> {
+    let mut evaluations = poly.to_vec();
+    fft::evaluate_poly(&mut evaluations, twiddles);
+    evaluations
+}
+
+/// Copies values at the specified `step` of all `columns` into the `row`.
+fn read_row<E: FieldElement>(columns: &[Vec<E>], step: usize, row: &mut [E]) {
+    for (value, column) in row.iter_mut().zip(columns.iter()) {
+        *value = column[step];
+    }
+}
+
+/// Returns true if the `assertion` holds on the trace described by the specified `columns`.
+fn is_satisfied<E: FieldElement>(
+    assertion: &Assertion<E>,
+    columns: &[Vec<E>],
+    trace_length: usize,
+) -> bool {
+    let mut satisfied = true;
+    assertion.apply(trace_length, |step, value| {
+        satisfied &= columns[assertion.column()][step] == value;
+    });
+    satisfied
+}
@@ -15,9 +15,17 @@ pub struct StarkDomain<B: StarkField> {
     /// vector is half the length of the trace domain size.
     trace_twiddles: Vec<B>,
 
+    /// Twiddles which can be used to interpolate polynomials over the trace domain. Length of
+    /// this vector is half the length of the trace domain size.
+    trace_inv_twiddles: Vec<B>,
+
     /// [g^i for i in (0..ce_domain_size)] where g is the constraint evaluation domain generator.
     ce_domain: Vec<B>,
 
+    /// Twiddles which can be used to interpolate polynomials over the constraint evaluation
+    /// domain. Length of this vector is half the length of the constraint evaluation domain size.
+    ce_inv_twiddles: Vec<B>,
+
     /// LDE domain size / constraint evaluation domain size
     ce_to_lde_blowup: usize,
 
@@ -37,14 +45,18 @@ impl<B: StarkField> StarkDomain<B> {
     /// Returns a new STARK domain initialized with the provided `context`.
     pub fn new<A: Air<BaseField = B>>(air: &A) -> Self {
         let trace_twiddles = fft::get_twiddles(air.trace_length());
+        let trace_inv_twiddles = fft::get_inv_twiddles(air.trace_length());
 
         // build constraint evaluation domain
         let domain_gen = B::get_root_of_unity(air.ce_domain_size().ilog2());
         let ce_domain = get_power_series(domain_gen, air.ce_domain_size());
+        let ce_inv_twiddles = fft::get_inv_twiddles(air.ce_domain_size());
 
         StarkDomain {
             trace_twiddles,
+            trace_inv_twiddles,
             ce_domain,
+            ce_inv_twiddles,
             ce_to_lde_blowup: air.lde_domain_size() / air.ce_domain_size(),
             ce_domain_mod_mask: air.ce_domain_size() - 1,
             domain_offset: air.domain_offset(),
@@ -63,13 +75,18 @@ impl<B: StarkField> StarkDomain<B> {
             "blowup factor must be a power of 2"
         );
 
+        let trace_inv_twiddles = fft::get_inv_twiddles(trace_twiddles.len() * 2);
+
         let ce_domain_size = trace_twiddles.len() * blowup_factor * 2;
         let domain_gen = B::get_root_of_unity(ce_domain_size.ilog2());
         let ce_domain = get_power_series(domain_gen, ce_domain_size);
+        let ce_inv_twiddles = fft::get_inv_twiddles(ce_domain_size);
 
         StarkDomain {
             trace_twiddles,
+            trace_inv_twiddles,
             ce_domain,
+            ce_inv_twiddles,
             ce_to_lde_blowup: 1,
             ce_domain_mod_mask: ce_domain_size - 1,
             domain_offset,
@@ -89,6 +106,11 @@ impl<B: StarkField> StarkDomain<B> {
         &self.trace_twiddles
     }
 
+    /// Returns twiddles which can be used to interpolate trace polynomials.
+    pub fn trace_inv_twiddles(&self) -> &[B] {
+        &self.trace_inv_twiddles
+    }
+
     /// Returns blowup factor from trace to constraint evaluation domain.
     pub fn trace_to_ce_blowup(&self) -> usize {
         self.ce_domain_size() / self.trace_length()
@@ -113,6 +135,12 @@ impl<B: StarkField> StarkDomain<B> {
         B::get_root_of_unity(self.ce_domain_size().ilog2())
     }
 
+    /// Returns twiddles which can be used to interpolate polynomials over the constraint
+    /// evaluation domain.
+    pub fn ce_inv_twiddles(&self) -> &[B] {
+        &self.ce_inv_twiddles
+    }
+
     /// Returns blowup factor from constraint evaluation to LDE domain.
     pub fn ce_to_lde_blowup(&self) -> usize {
         self.ce_to_lde_blowup
@@ -5,7 +5,10 @@
 
 //! Contains common error types for prover and verifier.
 
+use super::DivergentConstraint;
 use core::fmt;
+use utils::{collections::Vec, Cancelled};
+use verifier::VerifierError;
 
 // PROVER ERROR
 // ================================================================================================
@@ -21,6 +24,29 @@ pub enum ProverError {
     /// This error occurs when the base field specified by the AIR does not support field extension
     /// of degree specified by proof options.
     UnsupportedFieldExtension(usize),
+    /// This error occurs when proof generation is cancelled via the prover's cancellation token.
+    Cancelled,
+    /// This error occurs when the estimated amount of memory required to generate a proof
+    /// exceeds the prover's memory budget. The first value is the estimated number of bytes, and
+    /// the second value is the budget.
+    MemoryBudgetExceeded(usize, usize),
+    /// This error occurs when the generated proof is rejected by the verifier during
+    /// self-verification. The error returned by the verifier is accompanied by the list of
+    /// constraints which are not consistent with the execution trace at the out-of-domain point.
+    SelfVerificationFailed(VerifierError, Vec<DivergentConstraint>),
+    /// This error occurs when the number of instances combined into an aggregated trace is
+    /// greater than the number of instances whose traces fit into the maximum trace width. The
+    /// first value is the number of instances, and the second value is the maximum.
+    TooManyAggregateInstances(usize, usize),
+    /// This error occurs when the FRI folding schedule specified by proof options folds the LDE
+    /// domain into a remainder domain which is smaller than the blowup factor. The first value is
+    /// the size of the LDE domain, and the second value is the size of the remainder domain.
+    InvalidFriFoldingSchedule(usize, usize),
+    /// This error occurs when the number of leaves in a Merkle tree committing to the trace,
+    /// constraint evaluations, or a FRI layer is not a power of the Merkle tree arity specified
+    /// by proof options. The first value is the arity, and the second value is the number of
+    /// leaves.
+    InvalidMerkleTreeArity(usize, usize),
 }
 
 impl fmt::Display for ProverError {
@@ -36,6 +62,30 @@ impl fmt::Display for ProverError {
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the specified base field")
             }
+            Self::Cancelled => {
+                write!(f, "proof generation was cancelled")
+            }
+            Self::MemoryBudgetExceeded(required, budget) => {
+                write!(f, "proof generation requires an estimated {required} bytes of memory, but the memory budget is {budget} bytes")
+            }
+            Self::SelfVerificationFailed(err, divergent) => {
+                write!(f, "the generated proof failed verification: {err}; divergent constraints: {divergent:?}")
+            }
+            Self::TooManyAggregateInstances(num_instances, max) => {
+                write!(f, "cannot aggregate {num_instances} instances; at most {max} instances of this trace fit into a single trace")
+            }
+            Self::InvalidFriFoldingSchedule(lde_domain_size, remainder_domain_size) => {
+                write!(f, "FRI folding schedule folds the LDE domain of size {lde_domain_size} into a remainder domain of size {remainder_domain_size}, which is smaller than the blowup factor")
+            }
+            Self::InvalidMerkleTreeArity(arity, num_leaves) => {
+                write!(f, "a Merkle tree of arity {arity} cannot be built over {num_leaves} leaves; the number of leaves must be a power of the arity")
+            }
         }
     }
 }
+
+impl From<Cancelled> for ProverError {
+    fn from(_: Cancelled) -> Self {
+        Self::Cancelled
+    }
+}
@@ -0,0 +1,122 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Proof-of-work grinding for query seeds.
+//!
+//! The nonce found by [find_pow_nonce()] is always the smallest valid nonce. Thus, it depends only
+//! on the state of the public coin and the grinding factor, and not on the number of threads used
+//! to search for it (or on how the threads were scheduled). This makes generated proofs fully
+//! reproducible.
+
+use crypto::RandomCoin;
+
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "concurrent")]
+use utils::iterators::*;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of nonces checked by a single thread before the results of all threads are combined.
+pub const NONCE_BATCH_SIZE: u64 = 1 << 12;
+
+// GRINDING
+// ================================================================================================
+
+/// Returns the smallest nonce greater than zero which, when absorbed into the provided public
+/// coin, results in at least `grinding_factor` leading zeros (as defined by
+/// [RandomCoin::check_leading_zeros()]).
+///
+/// Nonces are checked in batches of [NONCE_BATCH_SIZE] via [RandomCoin::find_nonce()]. When the
+/// `concurrent` feature is enabled, consecutive batches are checked in parallel, and the first
+/// batch containing a valid nonce determines the result.
+///
+/// # Panics
+/// Panics if a valid nonce could not be found.
+pub fn find_pow_nonce<R: RandomCoin>(coin: &R, grinding_factor: u32) -> u64 {
+    #[cfg(not(feature = "concurrent"))]
+    let nonce = coin.find_nonce(1..u64::MAX, grinding_factor);
+
+    #[cfg(feature = "concurrent")]
+    let nonce = {
+        let num_batches = (utils::rayon::current_num_threads() * 4) as u64;
+        let round_size = num_batches * NONCE_BATCH_SIZE;
+        (0..(u64::MAX - 1) / round_size).find_map(|round| {
+            let round_start = 1 + round * round_size;
+            (0..num_batches).into_par_iter().find_map_first(|batch| {
+                let batch_start = round_start + batch * NONCE_BATCH_SIZE;
+                coin.find_nonce(batch_start..batch_start + NONCE_BATCH_SIZE, grinding_factor)
+            })
+        })
+    };
+
+    nonce.expect("nonce not found")
+}
+
+/// Returns the expected number of nonces which need to be checked to find a nonce for the
+/// specified grinding factor.
+pub fn expected_pow_attempts(grinding_factor: u32) -> u64 {
+    2u64.saturating_pow(grinding_factor)
+}
+
+/// Returns an estimate of the time needed by [find_pow_nonce()] to find a nonce for the provided
+/// public coin and the specified grinding factor.
+///
+/// The estimate is computed by timing a single batch of nonce checks for the provided coin and
+/// scaling it to the [expected number of attempts](expected_pow_attempts) (divided by the number
+/// of available threads when the `concurrent` feature is enabled). The actual time may differ
+/// significantly from the estimate since the number of attempts is geometrically distributed.
+#[cfg(feature = "std")]
+pub fn estimate_pow_time<R: RandomCoin>(coin: &R, grinding_factor: u32) -> Duration {
+    // no nonce can produce u32::MAX leading zeros, and thus, the entire batch is checked
+    let now = Instant::now();
+    let _ = coin.find_nonce(1..1 + NONCE_BATCH_SIZE, u32::MAX);
+    let batch_time = now.elapsed().as_secs_f64();
+
+    #[cfg(not(feature = "concurrent"))]
+    let num_threads = 1;
+    #[cfg(feature = "concurrent")]
+    let num_threads = utils::rayon::current_num_threads();
+
+    let num_batches = expected_pow_attempts(grinding_factor) as f64 / NONCE_BATCH_SIZE as f64;
+    Duration::from_secs_f64(batch_time * num_batches / num_threads as f64)
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{expected_pow_attempts, find_pow_nonce};
+    use crypto::{hashers::Blake3_256, DefaultRandomCoin, RandomCoin};
+    use math::fields::f128::BaseElement;
+
+    type Coin = DefaultRandomCoin<Blake3_256<BaseElement>>;
+
+    #[test]
+    fn find_smallest_pow_nonce() {
+        let coin = Coin::new(&[BaseElement::new(1), BaseElement::new(2)]);
+        let grinding_factor = 10;
+
+        let nonce = find_pow_nonce(&coin, grinding_factor);
+        assert!(coin.check_leading_zeros(nonce) >= grinding_factor);
+        for value in 1..nonce {
+            assert!(coin.check_leading_zeros(value) < grinding_factor);
+        }
+
+        // the same nonce is found for a coin with the same state
+        let coin = Coin::new(&[BaseElement::new(1), BaseElement::new(2)]);
+        assert_eq!(nonce, find_pow_nonce(&coin, grinding_factor));
+    }
+
+    #[test]
+    fn expected_attempts() {
+        assert_eq!(1, expected_pow_attempts(0));
+        assert_eq!(1 << 20, expected_pow_attempts(20));
+        assert_eq!(u64::MAX, expected_pow_attempts(64));
+    }
+}
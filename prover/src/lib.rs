@@ -12,7 +12,8 @@
 //! When the crate is compiled with `concurrent` feature enabled, proof generation will be
 //! performed in multiple threads (usually, as many threads as there are logical cores on the
 //! machine). The number of threads can be configured via `RAYON_NUM_THREADS` environment
-//! variable.
+//! variable. Alternatively, a proof can be generated in a dedicated thread pool via
+//! `Prover::prove_in_pool()`.
 //!
 //! # Usage
 //! To generate a proof that a computation was executed correctly, you'll need to do the
@@ -44,53 +45,65 @@
 extern crate alloc;
 
 pub use air::{
-    proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    proof::StarkProof, AggregateAir, AggregateInputs, Air, AirContext, Assertion,
+    AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup,
+    ConstraintCompositionCoefficients, ConstraintDivisor, DeepCompositionCoefficients,
+    EvaluationFrame, EvaluationFrameBatch, FieldExtension, LowDegreeTest, ProofOptions, TraceInfo,
     TraceLayout, TransitionConstraintDegree, TransitionConstraintGroup,
 };
 pub use utils::{
-    iterators, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
-    SliceReader,
+    iterators, ByteReader, ByteWriter, CancellationToken, Deserializable, DeserializationError,
+    Serializable, SliceReader,
 };
 
-use fri::FriProver;
-use utils::collections::Vec;
+#[cfg(feature = "concurrent")]
+pub use utils::rayon;
 
 pub use math;
 use math::{
-    fft::infer_degree,
     fields::{CubeExtension, QuadExtension},
-    ExtensibleField, FieldElement, StarkField, ToElements,
+    ExtensibleField, FieldElement, StarkField,
 };
 
 pub use crypto;
-use crypto::{ElementHasher, MerkleTree, RandomCoin};
-
-#[cfg(feature = "std")]
-use log::debug;
-#[cfg(feature = "std")]
-use std::time::Instant;
+use crypto::{CommitmentHashers, MerkleTree, RandomCoin};
 
 mod domain;
 pub use domain::StarkDomain;
 
+mod context;
+pub use context::ProverContext;
+
 pub mod matrix;
 pub use matrix::{ColMatrix, RowMatrix};
 
 mod constraints;
-use constraints::ConstraintEvaluator;
-pub use constraints::{CompositionPoly, ConstraintCommitment};
+pub use constraints::{CompositionPoly, ConstraintCommitment, ConstraintEvaluationMode};
 
 mod composer;
-use composer::DeepCompositionPoly;
 
 mod trace;
 pub use trace::{Trace, TraceTable, TraceTableFragment};
 use trace::{TraceCommitment, TraceLde, TracePolyTable};
 
 mod channel;
-use channel::ProverChannel;
+
+mod stages;
+pub use stages::{ProverStage, StagedProver};
+
+mod aggregate;
+pub use aggregate::{AggregateProver, AggregateTrace};
+
+mod observer;
+#[cfg(feature = "std")]
+pub use observer::LogObserver;
+use observer::{matrix_bytes, tree_bytes, StepTimer};
+pub use observer::{NoopObserver, ProverEvent, ProverObserver, ProverStep};
+
+pub mod grinding;
+
+mod diagnostics;
+pub use diagnostics::DivergentConstraint;
 
 mod errors;
 pub use errors::ProverError;
@@ -98,6 +111,15 @@ pub use errors::ProverError;
 #[cfg(test)]
 pub mod tests;
 
+// TYPE ALIASES
+// ================================================================================================
+
+/// Hash function used by the prover `P` to commit to the extended execution trace.
+type TraceHasher<P> = <<P as Prover>::HashFn as CommitmentHashers>::TraceHasher;
+
+/// Hash function used by the prover `P` to commit to the constraint evaluations.
+type ConstraintHasher<P> = <<P as Prover>::HashFn as CommitmentHashers>::ConstraintHasher;
+
 // PROVER
 // ================================================================================================
 
@@ -130,11 +152,19 @@ pub trait Prover {
     /// Execution trace of the computation described by this prover.
     type Trace: Trace<BaseField = Self::BaseField>;
 
-    /// Hash function to be used.
-    type HashFn: ElementHasher<BaseField = Self::BaseField>;
+    /// Hash functions to be used for trace, constraint, and FRI layer commitments.
+    ///
+    /// This can be a single [ElementHasher](crypto::ElementHasher), in which case the same hash
+    /// function is used for all commitments, or [DistinctHashers](crypto::DistinctHashers) which
+    /// allows specifying a different hash function for each commitment.
+    type HashFn: CommitmentHashers<BaseField = Self::BaseField>;
 
     /// PRNG to be used for generating random field elements.
-    type RandomCoin: RandomCoin<BaseField = Self::BaseField, Hasher = Self::HashFn>;
+    ///
+    /// The hash function of the PRNG does not need to be the same as any of the commitment hash
+    /// functions; commitments are absorbed into the PRNG via
+    /// [RandomCoin::reseed_with_digest()].
+    type RandomCoin: RandomCoin<BaseField = Self::BaseField>;
 
     // REQUIRED METHODS
     // --------------------------------------------------------------------------------------------
@@ -155,6 +185,70 @@ pub trait Prover {
     // PROVIDED METHODS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns [ConstraintEvaluationMode] which this prover uses to evaluate constraints.
+    ///
+    /// By default, [ConstraintEvaluationMode::Table] is used. Provers for AIRs with many distinct
+    /// constraint divisors (e.g., many assertions against different steps) can return
+    /// [ConstraintEvaluationMode::Streaming] to reduce peak memory usage during constraint
+    /// evaluation. The mode does not affect the generated proof.
+    fn constraint_evaluation_mode(&self) -> ConstraintEvaluationMode {
+        ConstraintEvaluationMode::Table
+    }
+
+    /// Returns [ProverObserver] which receives events emitted by this prover as it completes
+    /// individual steps of proof generation.
+    ///
+    /// By default, [LogObserver] is used when the crate is compiled with `std` feature, and
+    /// [NoopObserver] is used otherwise. Observers do not affect the generated proof.
+    fn observer(&self) -> &dyn ProverObserver {
+        #[cfg(feature = "std")]
+        return &LogObserver;
+        #[cfg(not(feature = "std"))]
+        return &NoopObserver;
+    }
+
+    /// Returns [CancellationToken] via which proof generation by this prover can be cancelled.
+    ///
+    /// The token is checked between stages of proof generation, as well as inside long-running
+    /// loops (i.e., between LDE segments, batches of constraint evaluation steps, and FRI layers
+    /// or STIR rounds). Once the token has been cancelled, [Prover::prove()] returns
+    /// [ProverError::Cancelled] as soon as the next check is reached.
+    ///
+    /// By default, no token is used, and proof generation cannot be cancelled.
+    fn cancellation_token(&self) -> Option<&CancellationToken> {
+        None
+    }
+
+    /// Returns the maximum number of bytes this prover is allowed to allocate for a single proof.
+    ///
+    /// Before any work is done, the amount of memory required for the extended execution trace,
+    /// the constraint evaluation table, the composition polynomial, the low-degree test, and the
+    /// Merkle trees committing to them is estimated from the [AirContext] of the computation.
+    /// If the estimate exceeds the budget, [Prover::prove()] returns
+    /// [ProverError::MemoryBudgetExceeded]. The estimate does not include temporary allocations
+    /// and the memory occupied by the execution trace itself.
+    ///
+    /// By default, the memory budget is not limited.
+    fn memory_budget(&self) -> Option<usize> {
+        None
+    }
+
+    /// Returns true if proofs generated by this prover should be verified before being returned.
+    ///
+    /// When enabled, [Prover::prove()] runs the verifier against every generated proof. If the
+    /// verification fails, [ProverError::SelfVerificationFailed] is returned; in addition to the
+    /// verifier error, it contains the list of [DivergentConstraint]s, i.e., transition
+    /// constraints whose out-of-domain evaluations are not consistent with their evaluations over
+    /// the constraint evaluation domain (e.g., because the constraint degree declared in the AIR is
+    /// too low), and assertions which do not hold on the execution trace. Self-verification is not
+    /// performed when proof generation is driven by a [StagedProver] directly.
+    ///
+    /// By default, self-verification is disabled. Enabling it adds the time needed to verify the
+    /// proof, and the memory needed to retain a copy of trace polynomials until the proof is built.
+    fn self_verification(&self) -> bool {
+        false
+    }
+
     /// Returns a STARK proof attesting to a correct execution of a computation defined by the
     /// provided trace.
     ///
@@ -162,23 +256,83 @@ pub trait Prover {
     /// the computation described by [Self::Air](Prover::Air) and generated using some set of
     /// secret and public inputs. Public inputs must match the value returned from
     /// [Self::get_pub_inputs()](Prover::get_pub_inputs) for the provided trace.
-    #[rustfmt::skip]
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The field extension specified in proof options is not supported for the base field.
+    /// * The proof generation was cancelled via [Prover::cancellation_token()].
+    /// * The memory required to generate the proof exceeds [Prover::memory_budget()].
+    /// * The FRI folding schedule specified in proof options folds the LDE domain of the trace
+    ///   into a remainder domain which is smaller than the blowup factor.
+    /// * The LDE domain or a FRI layer domain cannot be committed to by a Merkle tree of the arity
+    ///   specified in proof options.
+    /// * The constraint composition polynomial could not be built from constraint evaluations.
+    /// * [Prover::self_verification()] is enabled and the generated proof failed verification.
     fn prove(&self, trace: Self::Trace) -> Result<StarkProof, ProverError> {
+        self.prove_with_context(trace, &mut ProverContext::new())
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, using threads of the specified thread `pool`.
+    ///
+    /// This is equivalent to [Prover::prove()], but all work which is parallelized when
+    /// `concurrent` feature is enabled (i.e., trace extension, constraint evaluation, Merkle tree
+    /// construction, the low-degree test, and proof-of-work grinding) is executed by threads of
+    /// the `pool` instead of the global thread pool. Thus, provers running in the same process
+    /// can be confined to separate pools, and the number of threads used by a prover can be
+    /// limited by building a pool with the desired number of threads via
+    /// [ThreadPoolBuilder::num_threads()](rayon::ThreadPoolBuilder::num_threads).
+    ///
+    /// The generated proof does not depend on the number of threads in the pool; it is identical
+    /// to the proof generated by [Prover::prove()] for the same trace. To generate a proof with a
+    /// [ProverContext] or a [StagedProver] in a dedicated pool, the respective calls can be
+    /// wrapped into [ThreadPool::install()](rayon::ThreadPool::install).
+    ///
+    /// # Errors
+    /// Returns the same errors as [Prover::prove()].
+    #[cfg(feature = "concurrent")]
+    fn prove_in_pool(
+        &self,
+        trace: Self::Trace,
+        pool: &rayon::ThreadPool,
+    ) -> Result<StarkProof, ProverError>
+    where
+        Self: Sync,
+        Self::Trace: Send,
+    {
+        pool.install(|| self.prove(trace))
+    }
+
+    /// Returns a STARK proof attesting to a correct execution of a computation defined by the
+    /// provided trace, re-using domains cached in the specified `context`.
+    ///
+    /// This is equivalent to [Prover::prove()], but the [StarkDomain] (i.e., twiddles, inverse
+    /// twiddles, and the constraint evaluation domain) and extended periodic column values are
+    /// taken from the `context` if it contains them for a computation of the same shape; otherwise
+    /// they are built and added to the `context`. Thus, when generating many proofs for traces of
+    /// the same size, passing the same context to all of them skips domain setup for all but the
+    /// first proof. The generated proof does not depend on the state of the `context`.
+    #[rustfmt::skip]
+    fn prove_with_context(
+        &self,
+        trace: Self::Trace,
+        context: &mut ProverContext<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError> {
         // figure out which version of the generic proof generation procedure to run. this is a sort
         // of static dispatch for selecting two generic parameter: extension field and hash function.
         match self.options().field_extension() {
-            FieldExtension::None => self.generate_proof::<Self::BaseField>(trace),
+            FieldExtension::None => self.generate_proof::<Self::BaseField>(trace, context),
             FieldExtension::Quadratic => {
                 if !<QuadExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(2));
                 }
-                self.generate_proof::<QuadExtension<Self::BaseField>>(trace)
+                self.generate_proof::<QuadExtension<Self::BaseField>>(trace, context)
             }
             FieldExtension::Cubic => {
                 if !<CubeExtension<Self::BaseField>>::is_supported() {
                     return Err(ProverError::UnsupportedFieldExtension(3));
                 }
-                self.generate_proof::<CubeExtension<Self::BaseField>>(trace)
+                self.generate_proof::<CubeExtension<Self::BaseField>>(trace, context)
             }
         }
     }
@@ -190,264 +344,49 @@ pub trait Prover {
     /// execution `trace` is valid against this prover's AIR.
     /// TODO: make this function un-callable externally?
     #[doc(hidden)]
-    fn generate_proof<E>(&self, mut trace: Self::Trace) -> Result<StarkProof, ProverError>
+    fn generate_proof<E>(
+        &self,
+        mut trace: Self::Trace,
+        context: &mut ProverContext<Self::BaseField>,
+    ) -> Result<StarkProof, ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
     {
-        // 0 ----- instantiate AIR and prover channel ---------------------------------------------
+        // 0 ----- instantiate AIR, prover channel, and computation domain ------------------------
+        let mut prover = StagedProver::<Self, E>::new(self, &trace, context)?;
 
-        // serialize public inputs; these will be included in the seed for the public coin
-        let pub_inputs = self.get_pub_inputs(&trace);
-        let pub_inputs_elements = pub_inputs.to_elements();
+        // 1 ----- commit to the execution trace --------------------------------------------------
+        prover.commit_trace(&trace)?;
+        prover.commit_aux_trace(&mut trace)?;
 
-        // create an instance of AIR for the provided parameters. this takes a generic description
-        // of the computation (provided via AIR type), and creates a description of a specific
-        // execution of the computation for the provided public inputs.
-        let air = Self::Air::new(trace.get_info(), pub_inputs, self.options().clone());
+        // when self-verification is enabled, retain the randomness used to build auxiliary trace
+        // segments to be able to diagnose constraints which failed verification
+        let aux_rand_elements = match self.self_verification() {
+            true => prover.aux_rand_elements().cloned(),
+            false => None,
+        };
 
-        // create a channel which is used to simulate interaction between the prover and the
-        // verifier; the channel will be used to commit to values and to draw randomness that
-        // should come from the verifier.
-        let mut channel = ProverChannel::<Self::Air, E, Self::HashFn, Self::RandomCoin>::new(
-            &air,
-            pub_inputs_elements,
-        );
+        // 2 ----- evaluate constraints and commit to the constraint evaluations ------------------
+        prover.commit_constraints()?;
 
-        // 1 ----- Commit to the execution trace --------------------------------------------------
+        // 3 ----- evaluate trace and constraint polynomials at an out-of-domain point ------------
+        prover.evaluate_ood()?;
+        let ood_snapshot =
+            aux_rand_elements.map(|rand_elements| prover.ood_snapshot(rand_elements));
 
-        // build computation domain; this is used later for polynomial evaluations
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let domain = StarkDomain::new(&air);
-        #[cfg(feature = "std")]
-        debug!(
-            "Built domain of 2^{} elements in {} ms",
-            domain.lde_domain_size().ilog2(),
-            now.elapsed().as_millis()
-        );
-
-        // extend the main execution trace and build a Merkle tree from the extended trace
-        let (main_trace_lde, main_trace_tree, main_trace_polys) =
-            self.build_trace_commitment::<Self::BaseField>(trace.main_segment(), &domain);
-
-        // commit to the LDE of the main trace by writing the root of its Merkle tree into
-        // the channel
-        channel.commit_trace(*main_trace_tree.root());
-
-        // initialize trace commitment and trace polynomial table structs with the main trace
-        // data; for multi-segment traces these structs will be used as accumulators of all
-        // trace segments
-        let mut trace_commitment = TraceCommitment::new(
-            main_trace_lde,
-            main_trace_tree,
-            domain.trace_to_lde_blowup(),
-        );
-        let mut trace_polys = TracePolyTable::new(main_trace_polys);
-
-        // build auxiliary trace segments (if any), and append the resulting segments to trace
-        // commitment and trace polynomial table structs
-        let mut aux_trace_segments = Vec::new();
-        let mut aux_trace_rand_elements = AuxTraceRandElements::new();
-        for i in 0..trace.layout().num_aux_segments() {
-            #[cfg(feature = "std")]
-            let now = Instant::now();
-
-            // draw a set of random elements required to build an auxiliary trace segment
-            let rand_elements = channel.get_aux_trace_segment_rand_elements(i);
-
-            // build the trace segment
-            let aux_segment = trace
-                .build_aux_segment(&aux_trace_segments, &rand_elements)
-                .expect("failed build auxiliary trace segment");
-            #[cfg(feature = "std")]
-            debug!(
-                "Built auxiliary trace segment of {} columns and 2^{} steps in {} ms",
-                aux_segment.num_cols(),
-                aux_segment.num_rows().ilog2(),
-                now.elapsed().as_millis()
-            );
-
-            // extend the auxiliary trace segment and build a Merkle tree from the extended trace
-            let (aux_segment_lde, aux_segment_tree, aux_segment_polys) =
-                self.build_trace_commitment::<E>(&aux_segment, &domain);
-
-            // commit to the LDE of the extended auxiliary trace segment  by writing the root of
-            // its Merkle tree into the channel
-            channel.commit_trace(*aux_segment_tree.root());
-
-            // append the segment to the trace commitment and trace polynomial table structs
-            trace_commitment.add_segment(aux_segment_lde, aux_segment_tree);
-            trace_polys.add_aux_segment(aux_segment_polys);
-            aux_trace_rand_elements.add_segment_elements(rand_elements);
-            aux_trace_segments.push(aux_segment);
-        }
+        // 4 ----- build DEEP composition polynomial and evaluate it over the LDE domain ----------
+        prover.evaluate_deep_composition()?;
 
-        // make sure the specified trace (including auxiliary segments) is valid against the AIR.
-        // This checks validity of both, assertions and state transitions. We do this in debug
-        // mode only because this is a very expensive operation.
-        #[cfg(debug_assertions)]
-        trace.validate(&air, &aux_trace_segments, &aux_trace_rand_elements);
-
-        // 2 ----- evaluate constraints -----------------------------------------------------------
-        // evaluate constraints specified by the AIR over the constraint evaluation domain, and
-        // compute random linear combinations of these evaluations using coefficients drawn from
-        // the channel; this step evaluates only constraint numerators, thus, only constraints with
-        // identical denominators are merged together. the results are saved into a constraint
-        // evaluation table where each column contains merged evaluations of constraints with
-        // identical denominators.
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let constraint_coeffs = channel.get_constraint_composition_coeffs();
-        let evaluator = ConstraintEvaluator::new(&air, aux_trace_rand_elements, constraint_coeffs);
-        let constraint_evaluations = evaluator.evaluate(trace_commitment.trace_table(), &domain);
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated constraints over domain of 2^{} elements in {} ms",
-            constraint_evaluations.num_rows().ilog2(),
-            now.elapsed().as_millis()
-        );
-
-        // 3 ----- commit to constraint evaluations -----------------------------------------------
-
-        // first, build constraint composition polynomial from the constraint evaluation table:
-        // - divide all constraint evaluation columns by their respective divisors
-        // - combine them into a single column of evaluations,
-        // - interpolate the column into a polynomial in coefficient form
-        // - "break" the polynomial into a set of column polynomials each of degree equal to
-        //   trace_length - 1
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let composition_poly = constraint_evaluations.into_poly()?;
-        #[cfg(feature = "std")]
-        debug!(
-            "Converted constraint evaluations into {} composition polynomial columns of degree {} in {} ms",
-            composition_poly.num_columns(),
-            composition_poly.column_degree(),
-            now.elapsed().as_millis()
-        );
-
-        // then, build a commitment to the evaluations of the composition polynomial columns
-        let constraint_commitment =
-            self.build_constraint_commitment::<E>(&composition_poly, &domain);
-
-        // then, commit to the evaluations of constraints by writing the root of the constraint
-        // Merkle tree into the channel
-        channel.commit_constraints(constraint_commitment.root());
-
-        // 4 ----- build DEEP composition polynomial ----------------------------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-
-        // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
-        // from the base field or from an extension field defined by E.
-        //
-        // The purpose of sampling from the extension field here (instead of the base field) is to
-        // increase security. Soundness is limited by the size of the field that the random point
-        // is drawn from, and we can potentially save on performance by only drawing this point
-        // from an extension field, rather than increasing the size of the field overall.
-        let z = channel.get_ood_point();
-
-        // evaluate trace and constraint polynomials at the OOD point z, and send the results to
-        // the verifier. the trace polynomials are actually evaluated over two points: z and z * g,
-        // where g is the generator of the trace domain.
-        let ood_trace_states = trace_polys.get_ood_frame(z);
-        channel.send_ood_trace_states(&ood_trace_states);
-
-        let ood_evaluations = composition_poly.evaluate_at(z);
-        channel.send_ood_constraint_evaluations(&ood_evaluations);
-
-        // draw random coefficients to use during DEEP polynomial composition, and use them to
-        // initialize the DEEP composition polynomial
-        let deep_coefficients = channel.get_deep_composition_coeffs();
-        let mut deep_composition_poly = DeepCompositionPoly::new(z, deep_coefficients);
-
-        // combine all trace polynomials together and merge them into the DEEP composition
-        // polynomial
-        deep_composition_poly.add_trace_polys(trace_polys, ood_trace_states);
-
-        // merge columns of constraint composition polynomial into the DEEP composition polynomial;
-        deep_composition_poly.add_composition_poly(composition_poly, ood_evaluations);
-
-        // raise the degree of the DEEP composition polynomial by one to make sure it is equal to
-        // trace_length - 1
-        deep_composition_poly.adjust_degree();
+        // 5 ----- build low-degree proof for the DEEP composition polynomial ---------------------
+        prover.build_low_degree_proof()?;
 
-        #[cfg(feature = "std")]
-        debug!(
-            "Built DEEP composition polynomial of degree {} in {} ms",
-            deep_composition_poly.degree(),
-            now.elapsed().as_millis()
-        );
-
-        // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
-        // degree
-        assert_eq!(domain.trace_length() - 1, deep_composition_poly.degree());
-
-        // 5 ----- evaluate DEEP composition polynomial over LDE domain ---------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let deep_evaluations = deep_composition_poly.evaluate(&domain);
-        // we check the following condition in debug mode only because infer_degree is an expensive
-        // operation
-        debug_assert_eq!(
-            domain.trace_length() - 1,
-            infer_degree(&deep_evaluations, domain.offset())
-        );
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {} ms",
-            domain.lde_domain_size().ilog2(),
-            now.elapsed().as_millis()
-        );
-
-        // 6 ----- compute FRI layers for the composition polynomial ------------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let mut fri_prover = FriProver::new(air.options().to_fri_options());
-        fri_prover.build_layers(&mut channel, deep_evaluations);
-        #[cfg(feature = "std")]
-        debug!(
-            "Computed {} FRI layers from composition polynomial evaluations in {} ms",
-            fri_prover.num_layers(),
-            now.elapsed().as_millis()
-        );
-
-        // 7 ----- determine query positions ------------------------------------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-
-        // apply proof-of-work to the query seed
-        channel.grind_query_seed();
-
-        // generate pseudo-random query positions
-        let query_positions = channel.get_query_positions();
-        #[cfg(feature = "std")]
-        debug!(
-            "Determined {} query positions in {} ms",
-            query_positions.len(),
-            now.elapsed().as_millis()
-        );
+        // 6 ----- query commitments and build proof object ---------------------------------------
+        let proof = prover.build_proof()?;
 
-        // 8 ----- build proof object -------------------------------------------------------------
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-
-        // generate FRI proof
-        let fri_proof = fri_prover.build_proof(&query_positions);
-
-        // query the execution trace at the selected position; for each query, we need the
-        // state of the trace at that position + Merkle authentication path
-        let trace_queries = trace_commitment.query(&query_positions);
-
-        // query the constraint commitment at the selected positions; for each query, we need just
-        // a Merkle authentication path. this is because constraint evaluations for each step are
-        // merged into a single value and Merkle authentication paths contain these values already
-        let constraint_queries = constraint_commitment.query(&query_positions);
-
-        // build the proof object
-        let proof = channel.build_proof(trace_queries, constraint_queries, fri_proof);
-        #[cfg(feature = "std")]
-        debug!("Built proof object in {} ms", now.elapsed().as_millis());
+        // 7 ----- verify the proof if self-verification is enabled -------------------------------
+        if let Some(ood_snapshot) = ood_snapshot {
+            diagnostics::verify_proof(self, &trace, &proof, ood_snapshot)?;
+        }
 
         Ok(proof)
     }
@@ -461,42 +400,44 @@ pub trait Prover {
     ///
     /// Trace commitment is computed by hashing each row of the extended execution trace, and then
     /// building a Merkle tree from the resulting hashes.
+    ///
+    /// # Errors
+    /// Returns [ProverError::Cancelled] if the prover's cancellation token is cancelled while the
+    /// trace is being extended.
+    #[allow(clippy::type_complexity)]
     fn build_trace_commitment<E>(
         &self,
         trace: &ColMatrix<E>,
         domain: &StarkDomain<Self::BaseField>,
-    ) -> (RowMatrix<E>, MerkleTree<Self::HashFn>, ColMatrix<E>)
+    ) -> Result<(RowMatrix<E>, MerkleTree<TraceHasher<Self>>, ColMatrix<E>), ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
     {
         // extend the execution trace
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let trace_polys = trace.interpolate_columns();
-        let trace_lde =
-            RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(&trace_polys, domain);
-        #[cfg(feature = "std")]
-        debug!(
-            "Extended execution trace of {} columns from 2^{} to 2^{} steps ({}x blowup) in {} ms",
-            trace_lde.num_cols(),
-            trace_polys.num_rows().ilog2(),
-            trace_lde.num_rows().ilog2(),
-            domain.trace_to_lde_blowup(),
-            now.elapsed().as_millis()
-        );
+        let timer = StepTimer::start();
+        let trace_polys = trace.interpolate_columns_over(domain);
+        let trace_lde = evaluate_polys_over(&trace_polys, domain, self.cancellation_token())?;
+        self.observer().on_event(&timer.event(
+            ProverStep::TraceExtended {
+                num_columns: trace_lde.num_cols(),
+                trace_length: trace_polys.num_rows(),
+                lde_domain_size: trace_lde.num_rows(),
+            },
+            matrix_bytes::<E>(trace_polys.num_rows(), trace_polys.num_cols())
+                + matrix_bytes::<E>(trace_lde.num_rows(), trace_lde.num_cols()),
+        ));
 
         // build trace commitment
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let trace_tree = trace_lde.commit_to_rows();
-        #[cfg(feature = "std")]
-        debug!(
-            "Computed execution trace commitment (Merkle tree of depth {}) in {} ms",
-            trace_tree.depth(),
-            now.elapsed().as_millis()
-        );
-
-        (trace_lde, trace_tree, trace_polys)
+        let timer = StepTimer::start();
+        let trace_tree = trace_lde.commit_to_rows(self.options().merkle_arity());
+        self.observer().on_event(&timer.event(
+            ProverStep::TraceCommitted {
+                tree_depth: trace_tree.depth(),
+            },
+            tree_bytes::<TraceHasher<Self>>(trace_tree.leaves().len()),
+        ));
+
+        Ok((trace_lde, trace_tree, trace_polys))
     }
 
     /// Evaluates constraint composition polynomial over the LDE domain and builds a commitment
@@ -507,40 +448,65 @@ pub trait Prover {
     ///
     /// The commitment is computed by hashing each row in the evaluation matrix, and then building
     /// a Merkle tree from the resulting hashes.
+    ///
+    /// # Errors
+    /// Returns [ProverError::Cancelled] if the prover's cancellation token is cancelled while the
+    /// composition polynomial columns are being evaluated.
     fn build_constraint_commitment<E>(
         &self,
         composition_poly: &CompositionPoly<E>,
         domain: &StarkDomain<Self::BaseField>,
-    ) -> ConstraintCommitment<E, Self::HashFn>
+    ) -> Result<ConstraintCommitment<E, ConstraintHasher<Self>>, ProverError>
     where
         E: FieldElement<BaseField = Self::BaseField>,
     {
         // evaluate composition polynomial columns over the LDE domain
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let composed_evaluations = RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(
-            composition_poly.data(),
-            domain,
-        );
-        #[cfg(feature = "std")]
-        debug!(
-            "Evaluated {} composition polynomial columns over LDE domain (2^{} elements) in {} ms",
-            composed_evaluations.num_cols(),
-            composed_evaluations.num_rows().ilog2(),
-            now.elapsed().as_millis()
-        );
+        let timer = StepTimer::start();
+        let composed_evaluations =
+            evaluate_polys_over(composition_poly.data(), domain, self.cancellation_token())?;
+        self.observer().on_event(&timer.event(
+            ProverStep::CompositionPolyEvaluated {
+                num_columns: composed_evaluations.num_cols(),
+                lde_domain_size: composed_evaluations.num_rows(),
+            },
+            matrix_bytes::<E>(
+                composed_evaluations.num_rows(),
+                composed_evaluations.num_cols(),
+            ),
+        ));
 
         // build constraint evaluation commitment
-        #[cfg(feature = "std")]
-        let now = Instant::now();
-        let commitment = composed_evaluations.commit_to_rows();
+        let timer = StepTimer::start();
+        let commitment = composed_evaluations.commit_to_rows(self.options().merkle_arity());
+        let num_leaves = commitment.leaves().len();
         let constraint_commitment = ConstraintCommitment::new(composed_evaluations, commitment);
-        #[cfg(feature = "std")]
-        debug!(
-            "Computed constraint evaluation commitment (Merkle tree of depth {}) in {} ms",
-            constraint_commitment.tree_depth(),
-            now.elapsed().as_millis()
-        );
-        constraint_commitment
+        self.observer().on_event(&timer.event(
+            ProverStep::ConstraintsCommitted {
+                tree_depth: constraint_commitment.tree_depth(),
+            },
+            tree_bytes::<ConstraintHasher<Self>>(num_leaves),
+        ));
+        Ok(constraint_commitment)
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Evaluates the provided polynomials over the LDE domain, checking the cancellation `token` (if
+/// any) between segments of [DEFAULT_SEGMENT_WIDTH] polynomials.
+fn evaluate_polys_over<E: FieldElement>(
+    polys: &ColMatrix<E>,
+    domain: &StarkDomain<E::BaseField>,
+    token: Option<&CancellationToken>,
+) -> Result<RowMatrix<E>, ProverError> {
+    match token {
+        Some(token) => {
+            RowMatrix::try_evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(polys, domain, token)
+                .ok_or(ProverError::Cancelled)
+        }
+        None => Ok(RowMatrix::evaluate_polys_over::<DEFAULT_SEGMENT_WIDTH>(
+            polys, domain,
+        )),
     }
 }
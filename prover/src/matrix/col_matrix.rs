@@ -6,8 +6,11 @@
 use crate::StarkDomain;
 use core::{iter::FusedIterator, slice};
 use crypto::{ElementHasher, MerkleTree};
-use math::{fft, polynom, FieldElement};
-use utils::{batch_iter_mut, collections::Vec, iter, iter_mut, uninit_vector};
+use math::{fft, polynom, polynom::Polynomial, FieldElement};
+use utils::{
+    batch_iter_mut, collections::Vec, iter, iter_mut, uninit_vector, ByteReader, ByteWriter,
+    Deserializable, DeserializationError, Serializable,
+};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
@@ -68,6 +71,35 @@ impl<E: FieldElement> ColMatrix<E> {
         Self { columns }
     }
 
+    /// Returns a new [Matrix] in which each column contains coefficients of one of the provided
+    /// polynomials.
+    ///
+    /// The number of rows in the matrix is set to the smallest power of two which is sufficient
+    /// to hold coefficients of all polynomials (but no smaller than 2). Columns of polynomials
+    /// with fewer coefficients are padded with ZEROs.
+    ///
+    /// # Panics
+    /// Panics if the provided list of polynomials is empty.
+    pub fn from_polys(polys: &[Polynomial<E>]) -> Self {
+        let num_rows = polys
+            .iter()
+            .map(|poly| poly.num_coefficients())
+            .max()
+            .expect("a matrix must contain at least one column")
+            .next_power_of_two()
+            .max(2);
+
+        let columns = polys
+            .iter()
+            .map(|poly| {
+                let mut column = poly.coefficients().to_vec();
+                column.resize(num_rows, E::ZERO);
+                column
+            })
+            .collect();
+        Self::new(columns)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -171,12 +203,12 @@ impl<E: FieldElement> ColMatrix<E> {
     // --------------------------------------------------------------------------------------------
 
     /// Returns an iterator over the columns of this matrix.
-    pub fn columns(&self) -> ColumnIter<E> {
+    pub fn columns(&self) -> ColumnIter<'_, E> {
         ColumnIter::new(self)
     }
 
     /// Returns a mutable iterator over the columns of this matrix.
-    pub fn columns_mut(&mut self) -> ColumnIterMut<E> {
+    pub fn columns_mut(&mut self) -> ColumnIterMut<'_, E> {
         ColumnIterMut::new(self)
     }
 
@@ -195,14 +227,25 @@ impl<E: FieldElement> ColMatrix<E> {
     ///   coefficients of a degree `num_rows - 1` polynomial.
     pub fn interpolate_columns(&self) -> Self {
         let inv_twiddles = fft::get_inv_twiddles::<E::BaseField>(self.num_rows());
-        let columns = iter!(self.columns)
-            .map(|evaluations| {
-                let mut column = evaluations.clone();
-                fft::interpolate_poly(&mut column, &inv_twiddles);
-                column
-            })
-            .collect();
-        Self { columns }
+        self.interpolate_columns_with_twiddles(&inv_twiddles)
+    }
+
+    /// Interpolates columns of the matrix into polynomials in coefficient form using the
+    /// interpolation twiddles of the trace domain defined by the specified [StarkDomain].
+    ///
+    /// This is equivalent to [interpolate_columns()](Self::interpolate_columns) but avoids
+    /// re-computing the twiddles.
+    ///
+    /// # Panics
+    /// Panics if the number of rows in this matrix is not equal to the trace length of the
+    /// `domain`.
+    pub fn interpolate_columns_over(&self, domain: &StarkDomain<E::BaseField>) -> Self {
+        assert_eq!(
+            self.num_rows(),
+            domain.trace_length(),
+            "number of rows must be equal to the trace length of the domain"
+        );
+        self.interpolate_columns_with_twiddles(domain.trace_inv_twiddles())
     }
 
     /// Interpolates columns of the matrix into polynomials in coefficient form and returns the
@@ -245,6 +288,14 @@ impl<E: FieldElement> ColMatrix<E> {
         Self { columns }
     }
 
+    /// Returns a polynomial whose coefficients are contained in the column at the specified index.
+    ///
+    /// # Panics
+    /// Panics if `col_idx` is out of bounds for this matrix.
+    pub fn get_column_poly(&self, col_idx: usize) -> Polynomial<E> {
+        Polynomial::from(self.get_column(col_idx))
+    }
+
     /// Evaluates polynomials contained in the columns of this matrix at a single point `x`.
     pub fn evaluate_columns_at<F>(&self, x: F) -> Vec<F>
     where
@@ -260,11 +311,14 @@ impl<E: FieldElement> ColMatrix<E> {
     ///
     /// The commitment is built as follows:
     /// * Each row of the matrix is hashed into a single digest of the specified hash function.
-    /// * The resulting values are used to built a binary Merkle tree such that each row digest
-    ///   becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the
+    /// * The resulting values are used to built a Merkle tree of the specified arity such that
+    ///   each row digest becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the
     ///   number of rows in the matrix.
     /// * The resulting Merkle tree is return as the commitment to the entire matrix.
-    pub fn commit_to_rows<H>(&self) -> MerkleTree<H>
+    ///
+    /// # Panics
+    /// Panics if the number of rows in the matrix is not a power of `merkle_arity`.
+    pub fn commit_to_rows<H>(&self, merkle_arity: usize) -> MerkleTree<H>
     where
         H: ElementHasher<BaseField = E::BaseField>,
     {
@@ -287,7 +341,8 @@ impl<E: FieldElement> ColMatrix<E> {
         );
 
         // build Merkle tree out of hashed rows
-        MerkleTree::new(row_hashes).expect("failed to construct trace Merkle tree")
+        MerkleTree::with_arity(row_hashes, merkle_arity)
+            .expect("failed to construct trace Merkle tree")
     }
 
     // CONVERSIONS
@@ -299,6 +354,71 @@ impl<E: FieldElement> ColMatrix<E> {
     pub fn into_columns(self) -> Vec<Vec<E>> {
         self.columns
     }
+
+    /// Returns the columns of this matrix as a list of polynomials, interpreting each column as
+    /// polynomial coefficients.
+    pub fn into_polys(self) -> Vec<Polynomial<E>> {
+        self.columns.into_iter().map(Polynomial::new).collect()
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Interpolates columns of the matrix into polynomials in coefficient form using the
+    /// specified inverse twiddles.
+    fn interpolate_columns_with_twiddles(&self, inv_twiddles: &[E::BaseField]) -> Self {
+        let columns = iter!(self.columns)
+            .map(|evaluations| {
+                let mut column = evaluations.clone();
+                fft::interpolate_poly(&mut column, inv_twiddles);
+                column
+            })
+            .collect();
+        Self { columns }
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement> Serializable for ColMatrix<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.num_cols() as u32);
+        target.write_u64(self.num_rows() as u64);
+        for column in self.columns.iter() {
+            column.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement> Deserializable for ColMatrix<E> {
+    /// Reads a column-major matrix from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a matrix could not be read from the `source`, or if the matrix read
+    /// from the `source` does not satisfy restrictions imposed on [ColMatrix] contents.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_cols = source.read_u32()? as usize;
+        let num_rows = source.read_u64()? as usize;
+
+        if num_cols == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a matrix must contain at least one column".into(),
+            ));
+        }
+        if num_rows <= 1 || !num_rows.is_power_of_two() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of rows in a matrix must be a power of two greater than one, but was {num_rows}"
+            )));
+        }
+
+        let mut columns = Vec::with_capacity(num_cols);
+        for _ in 0..num_cols {
+            columns.push(E::read_batch_from(source, num_rows)?);
+        }
+        Ok(Self { columns })
+    }
 }
 
 // COLUMN ITERATOR
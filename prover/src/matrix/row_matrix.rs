@@ -8,7 +8,10 @@ use crate::StarkDomain;
 use crypto::{ElementHasher, MerkleTree};
 use math::{fft, FieldElement, StarkField};
 use utils::collections::Vec;
-use utils::{batch_iter_mut, flatten_vector_elements, uninit_vector};
+use utils::{
+    batch_iter_mut, flatten_vector_elements, uninit_vector, ByteReader, ByteWriter,
+    CancellationToken, Deserializable, DeserializationError, Serializable,
+};
 
 #[cfg(feature = "concurrent")]
 use utils::iterators::*;
@@ -83,6 +86,29 @@ impl<E: FieldElement> RowMatrix<E> {
         polys: &ColMatrix<E>,
         domain: &StarkDomain<E::BaseField>,
     ) -> Self {
+        Self::evaluate_polys_over_with::<N>(polys, domain, None)
+            .expect("polynomial evaluation was cancelled")
+    }
+
+    /// Same as [evaluate_polys_over()](RowMatrix::evaluate_polys_over), but checks the provided
+    /// cancellation `token` before evaluating every batch of `N` polynomials.
+    ///
+    /// Returns `None` if the token has been cancelled before all polynomials were evaluated.
+    pub fn try_evaluate_polys_over<const N: usize>(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+        token: &CancellationToken,
+    ) -> Option<Self> {
+        Self::evaluate_polys_over_with::<N>(polys, domain, Some(token))
+    }
+
+    /// Evaluates the provided polynomials over the specified [StarkDomain], checking the
+    /// cancellation `token` (if any) before evaluating every batch of `N` polynomials.
+    fn evaluate_polys_over_with<const N: usize>(
+        polys: &ColMatrix<E>,
+        domain: &StarkDomain<E::BaseField>,
+        token: Option<&CancellationToken>,
+    ) -> Option<Self> {
         assert!(N > 0, "batch size N must be greater than zero");
 
         // pre-compute offsets for each row
@@ -91,10 +117,10 @@ impl<E: FieldElement> RowMatrix<E> {
             get_evaluation_offsets::<E>(poly_size, domain.trace_to_lde_blowup(), domain.offset());
 
         // build matrix segments by evaluating all polynomials
-        let segments = build_segments::<E, N>(polys, domain.trace_twiddles(), &offsets);
+        let segments = try_build_segments::<E, N>(polys, domain.trace_twiddles(), &offsets, token)?;
 
         // transpose data in individual segments into a single row-major matrix
-        Self::from_segments(segments, polys.num_base_cols())
+        Some(Self::from_segments(segments, polys.num_base_cols()))
     }
 
     /// Returns a new [RowMatrix] instantiated from the specified matrix segments.
@@ -175,11 +201,14 @@ impl<E: FieldElement> RowMatrix<E> {
     ///
     /// The commitment is built as follows:
     /// * Each row of the matrix is hashed into a single digest of the specified hash function.
-    /// * The resulting values are used to build a binary Merkle tree such that each row digest
-    ///   becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the
+    /// * The resulting values are used to build a Merkle tree of the specified arity such that
+    ///   each row digest becomes a leaf in the tree. Thus, the number of leaves in the tree is equal to the
     ///   number of rows in the matrix.
     /// * The resulting Merkle tree is returned as the commitment to the entire matrix.
-    pub fn commit_to_rows<H>(&self) -> MerkleTree<H>
+    ///
+    /// # Panics
+    /// Panics if the number of rows in the matrix is not a power of `merkle_arity`.
+    pub fn commit_to_rows<H>(&self, merkle_arity: usize) -> MerkleTree<H>
     where
         H: ElementHasher<BaseField = E::BaseField>,
     {
@@ -198,7 +227,55 @@ impl<E: FieldElement> RowMatrix<E> {
         );
 
         // build Merkle tree out of hashed rows
-        MerkleTree::new(row_hashes).expect("failed to construct trace Merkle tree")
+        MerkleTree::with_arity(row_hashes, merkle_arity)
+            .expect("failed to construct trace Merkle tree")
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement> Serializable for RowMatrix<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.row_width as u64);
+        target.write_u64(self.elements_per_row as u64);
+        target.write_u64(self.num_rows() as u64);
+        self.data.write_into(target);
+    }
+}
+
+impl<E: FieldElement> Deserializable for RowMatrix<E> {
+    /// Reads a row-major matrix from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid matrix could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let row_width = source.read_u64()? as usize;
+        let elements_per_row = source.read_u64()? as usize;
+        let num_rows = source.read_u64()? as usize;
+
+        if row_width == 0 || elements_per_row > row_width {
+            return Err(DeserializationError::InvalidValue(format!(
+                "invalid matrix row width {row_width} for {elements_per_row} elements per row"
+            )));
+        }
+        if elements_per_row % E::EXTENSION_DEGREE != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "elements per row must be a multiple of {}, but was {elements_per_row}",
+                E::EXTENSION_DEGREE
+            )));
+        }
+        let num_elements = num_rows.checked_mul(row_width).ok_or_else(|| {
+            DeserializationError::InvalidValue(format!("matrix of {num_rows} rows is too large"))
+        })?;
+
+        let data = E::BaseField::read_batch_from(source, num_elements)?;
+        Ok(Self {
+            data,
+            row_width,
+            elements_per_row,
+        })
     }
 }
 
@@ -257,6 +334,18 @@ pub fn build_segments<E: FieldElement, const N: usize>(
     twiddles: &[E::BaseField],
     offsets: &[E::BaseField],
 ) -> Vec<Segment<E::BaseField, N>> {
+    try_build_segments(polys, twiddles, offsets, None).expect("segment building was cancelled")
+}
+
+/// Returns matrix segments constructed by evaluating polynomials in the specified matrix over the
+/// domain defined by twiddles and offsets, or `None` if the cancellation token (if any) has been
+/// cancelled before all segments were built.
+fn try_build_segments<E: FieldElement, const N: usize>(
+    polys: &ColMatrix<E>,
+    twiddles: &[E::BaseField],
+    offsets: &[E::BaseField],
+    token: Option<&CancellationToken>,
+) -> Option<Vec<Segment<E::BaseField, N>>> {
     assert!(N > 0, "batch size N must be greater than zero");
     debug_assert_eq!(polys.num_rows(), twiddles.len() * 2);
     debug_assert_eq!(offsets.len() % polys.num_rows(), 0);
@@ -268,7 +357,10 @@ pub fn build_segments<E: FieldElement, const N: usize>(
     };
 
     (0..num_segments)
-        .map(|i| Segment::new(polys, i * N, offsets, twiddles))
+        .map(|i| match token {
+            Some(token) if token.is_cancelled() => None,
+            _ => Some(Segment::new(polys, i * N, offsets, twiddles)),
+        })
         .collect()
 }
 
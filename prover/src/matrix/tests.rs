@@ -4,11 +4,15 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::{
-    math::{fields::f64::BaseElement, get_power_series, polynom, StarkField},
+    math::{
+        fields::f64::BaseElement, get_power_series, polynom, polynom::Polynomial, FieldElement,
+        StarkField,
+    },
     ColMatrix, RowMatrix,
 };
+use math::fields::QuadExtension;
 use rand_utils::rand_vector;
-use utils::collections::Vec;
+use utils::{collections::Vec, Deserializable, Serializable};
 
 #[test]
 fn test_eval_poly_with_offset_matrix() {
@@ -39,6 +43,54 @@ fn test_eval_poly_with_offset_matrix() {
     }
 }
 
+#[test]
+fn test_col_matrix_polys() {
+    let polys = vec![
+        Polynomial::new(rand_vector::<BaseElement>(5)),
+        Polynomial::new(rand_vector::<BaseElement>(8)),
+        Polynomial::constant(BaseElement::ONE),
+    ];
+
+    let matrix = ColMatrix::from_polys(&polys);
+    assert_eq!(3, matrix.num_cols());
+    assert_eq!(8, matrix.num_rows());
+    assert_eq!(BaseElement::ZERO, matrix.get(0, 7));
+
+    let x = BaseElement::new(11);
+    let expected = polys.iter().map(|p| p.eval(x)).collect::<Vec<_>>();
+    assert_eq!(expected, matrix.evaluate_columns_at(x));
+
+    assert_eq!(polys[1], matrix.get_column_poly(1));
+    assert_eq!(polys, matrix.into_polys());
+}
+
+#[test]
+fn test_matrix_serialization() {
+    type QuadElement = QuadExtension<BaseElement>;
+
+    let columns: Vec<Vec<QuadElement>> = (0..3).map(|_| rand_vector(16)).collect();
+    let col_matrix = ColMatrix::new(columns);
+    let bytes = col_matrix.to_bytes();
+    let col_matrix2 = ColMatrix::<QuadElement>::read_from_bytes(&bytes).unwrap();
+    assert_eq!(
+        col_matrix.columns().collect::<Vec<_>>(),
+        col_matrix2.columns().collect::<Vec<_>>()
+    );
+    assert!(ColMatrix::<QuadElement>::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+    // rows of this matrix are padded, and thus, row width differs from the number of elements
+    let row_matrix = RowMatrix::evaluate_polys::<4>(&col_matrix, 2);
+    let row_matrix2 = RowMatrix::<QuadElement>::read_from_bytes(&row_matrix.to_bytes()).unwrap();
+    assert_eq!(row_matrix.num_cols(), row_matrix2.num_cols());
+    assert_eq!(row_matrix.data(), row_matrix2.data());
+    assert_eq!(row_matrix.row(5), row_matrix2.row(5));
+
+    // a matrix of quadratic extension elements cannot have an odd number of elements per row
+    let row_matrix =
+        RowMatrix::<BaseElement>::evaluate_polys::<1>(&ColMatrix::new(vec![rand_vector(4)]), 2);
+    assert!(RowMatrix::<QuadElement>::read_from_bytes(&row_matrix.to_bytes()).is_err());
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
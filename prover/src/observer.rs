@@ -0,0 +1,247 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{mem::size_of, time::Duration};
+use crypto::Hasher;
+
+#[cfg(feature = "std")]
+use log::debug;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+// PROVER OBSERVER
+// ================================================================================================
+
+/// Receives structured events emitted by the prover as it completes individual steps of proof
+/// generation.
+///
+/// An observer is supplied to the prover via [Prover::observer()](crate::Prover::observer). The
+/// observer is invoked synchronously from the thread driving proof generation, and thus, should
+/// return quickly; observers which need to record events can do so via interior mutability.
+///
+/// Two observers are provided by this crate: [NoopObserver] which ignores all events, and
+/// [LogObserver] (available only with `std` feature) which writes events into the log at `debug`
+/// level.
+pub trait ProverObserver {
+    /// Invoked after the prover completes a step of proof generation described by the `event`.
+    fn on_event(&self, event: &ProverEvent);
+}
+
+/// A [ProverObserver] which ignores all events.
+///
+/// This is the default observer when the crate is compiled without `std` feature.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl ProverObserver for NoopObserver {
+    fn on_event(&self, _event: &ProverEvent) {}
+}
+
+/// A [ProverObserver] which writes a human-readable description of every event into the log at
+/// `debug` level.
+///
+/// This is the default observer when the crate is compiled with `std` feature.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogObserver;
+
+#[cfg(feature = "std")]
+impl ProverObserver for LogObserver {
+    #[rustfmt::skip]
+    fn on_event(&self, event: &ProverEvent) {
+        let ms = event.duration.unwrap_or_default().as_millis();
+        match event.step {
+            ProverStep::DomainPrepared { lde_domain_size, .. } => {
+                debug!("Prepared domain of 2^{} elements in {ms} ms", lde_domain_size.ilog2())
+            }
+            ProverStep::AuxTraceSegmentBuilt { num_columns, num_rows } => {
+                debug!("Built auxiliary trace segment of {num_columns} columns and 2^{} steps in {ms} ms", num_rows.ilog2())
+            }
+            ProverStep::TraceExtended { num_columns, trace_length, lde_domain_size } => {
+                debug!(
+                    "Extended execution trace of {num_columns} columns from 2^{} to 2^{} steps ({}x blowup) in {ms} ms",
+                    trace_length.ilog2(),
+                    lde_domain_size.ilog2(),
+                    lde_domain_size / trace_length
+                )
+            }
+            ProverStep::TraceCommitted { tree_depth } => {
+                debug!("Computed execution trace commitment (Merkle tree of depth {tree_depth}) in {ms} ms")
+            }
+            ProverStep::ConstraintsEvaluated { ce_domain_size, .. } => {
+                debug!("Evaluated constraints over domain of 2^{} elements in {ms} ms", ce_domain_size.ilog2())
+            }
+            ProverStep::ConstraintColumnsCombined { num_columns, .. } => {
+                debug!("Divided {num_columns} constraint evaluation columns by their divisors and combined them in {ms} ms")
+            }
+            ProverStep::CombinedEvaluationsInterpolated { ce_domain_size } => {
+                debug!("Interpolated combined constraint evaluations over domain of 2^{} elements in {ms} ms", ce_domain_size.ilog2())
+            }
+            ProverStep::CompositionPolySplit { num_columns, .. } => {
+                debug!("Split composition polynomial into {num_columns} columns in {ms} ms")
+            }
+            ProverStep::CompositionPolyBuilt { num_columns, column_degree } => {
+                debug!("Converted constraint evaluations into {num_columns} composition polynomial columns of degree {column_degree} in {ms} ms")
+            }
+            ProverStep::CompositionPolyEvaluated { num_columns, lde_domain_size } => {
+                debug!("Evaluated {num_columns} composition polynomial columns over LDE domain (2^{} elements) in {ms} ms", lde_domain_size.ilog2())
+            }
+            ProverStep::ConstraintsCommitted { tree_depth } => {
+                debug!("Computed constraint evaluation commitment (Merkle tree of depth {tree_depth}) in {ms} ms")
+            }
+            ProverStep::DeepCompositionBuilt { degree } => {
+                debug!("Built DEEP composition polynomial of degree {degree} in {ms} ms")
+            }
+            ProverStep::DeepCompositionEvaluated { lde_domain_size } => {
+                debug!("Evaluated DEEP composition polynomial over LDE domain (2^{} elements) in {ms} ms", lde_domain_size.ilog2())
+            }
+            ProverStep::FriLayersBuilt { num_layers } => {
+                debug!("Computed {num_layers} FRI layers from composition polynomial evaluations in {ms} ms")
+            }
+            ProverStep::QueryPositionsDrawn { num_queries } => {
+                debug!("Determined {num_queries} query positions in {ms} ms")
+            }
+            ProverStep::StirRoundsBuilt { num_rounds } => {
+                debug!("Executed {num_rounds} STIR rounds for composition polynomial evaluations in {ms} ms")
+            }
+            ProverStep::ProofBuilt { .. } => debug!("Built proof object in {ms} ms"),
+        }
+    }
+}
+
+// PROVER EVENT
+// ================================================================================================
+
+/// Describes a completed step of proof generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProverEvent {
+    /// The completed step together with the dimensions of the data it operated on.
+    pub step: ProverStep,
+    /// Time it took to complete the step; this is `None` when the crate is compiled without
+    /// `std` feature.
+    pub duration: Option<Duration>,
+    /// Estimated number of bytes allocated for the data produced by the step (e.g., extended
+    /// trace, Merkle tree nodes). Temporary allocations made during the step are not included.
+    pub allocated_bytes: usize,
+}
+
+/// A step of proof generation.
+///
+/// Steps are listed in the order in which they are executed by the prover. Steps related to the
+/// execution trace are executed once for the main trace segment and once for every auxiliary
+/// trace segment, and steps related to the low-degree test depend on the low-degree test
+/// specified in proof options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverStep {
+    /// The STARK domain of the computation was built or retrieved from the prover context.
+    DomainPrepared {
+        trace_length: usize,
+        ce_domain_size: usize,
+        lde_domain_size: usize,
+    },
+    /// An auxiliary trace segment was built.
+    AuxTraceSegmentBuilt { num_columns: usize, num_rows: usize },
+    /// A segment of the execution trace was interpolated and extended over the LDE domain.
+    TraceExtended {
+        num_columns: usize,
+        trace_length: usize,
+        lde_domain_size: usize,
+    },
+    /// A Merkle tree was built from rows of an extended trace segment.
+    TraceCommitted { tree_depth: usize },
+    /// Constraints were evaluated over the constraint evaluation domain and merged into columns
+    /// of the constraint evaluation table.
+    ConstraintsEvaluated {
+        num_columns: usize,
+        ce_domain_size: usize,
+    },
+    /// Constraint evaluation columns were divided by their divisors and combined into a single
+    /// column. This step is skipped in [ConstraintEvaluationMode::Streaming](crate::ConstraintEvaluationMode::Streaming)
+    /// mode.
+    ConstraintColumnsCombined {
+        num_columns: usize,
+        ce_domain_size: usize,
+    },
+    /// Combined constraint evaluations were interpolated into a polynomial in coefficient form.
+    CombinedEvaluationsInterpolated { ce_domain_size: usize },
+    /// The combined constraint polynomial was split into composition polynomial columns.
+    CompositionPolySplit {
+        num_columns: usize,
+        column_degree: usize,
+    },
+    /// The constraint composition polynomial was built from the constraint evaluation table;
+    /// this step encompasses the three preceding steps.
+    CompositionPolyBuilt {
+        num_columns: usize,
+        column_degree: usize,
+    },
+    /// Composition polynomial columns were evaluated over the LDE domain.
+    CompositionPolyEvaluated {
+        num_columns: usize,
+        lde_domain_size: usize,
+    },
+    /// A Merkle tree was built from rows of composition polynomial evaluations.
+    ConstraintsCommitted { tree_depth: usize },
+    /// The DEEP composition polynomial was built.
+    DeepCompositionBuilt { degree: usize },
+    /// The DEEP composition polynomial was evaluated over the LDE domain.
+    DeepCompositionEvaluated { lde_domain_size: usize },
+    /// FRI layers were built from evaluations of the DEEP composition polynomial.
+    FriLayersBuilt { num_layers: usize },
+    /// Query positions were drawn after applying proof-of-work to the query seed.
+    QueryPositionsDrawn { num_queries: usize },
+    /// STIR rounds were executed for evaluations of the DEEP composition polynomial.
+    StirRoundsBuilt { num_rounds: usize },
+    /// Trace and constraint commitments were queried, and the proof object was assembled.
+    ProofBuilt { num_queries: usize },
+}
+
+// STEP TIMER
+// ================================================================================================
+
+/// Measures duration of a prover step when the crate is compiled with `std` feature.
+pub(crate) struct StepTimer {
+    #[cfg(feature = "std")]
+    start: Instant,
+}
+
+impl StepTimer {
+    /// Starts a new timer.
+    pub fn start() -> Self {
+        Self {
+            #[cfg(feature = "std")]
+            start: Instant::now(),
+        }
+    }
+
+    /// Returns an event for the specified step with the duration measured since this timer was
+    /// started.
+    pub fn event(&self, step: ProverStep, allocated_bytes: usize) -> ProverEvent {
+        #[cfg(feature = "std")]
+        let duration = Some(self.start.elapsed());
+        #[cfg(not(feature = "std"))]
+        let duration = None;
+
+        ProverEvent {
+            step,
+            duration,
+            allocated_bytes,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of bytes occupied by a matrix with the specified dimensions.
+pub(crate) fn matrix_bytes<E>(num_rows: usize, num_cols: usize) -> usize {
+    num_rows * num_cols * size_of::<E>()
+}
+
+/// Returns an upper bound on the number of bytes occupied by leaves and internal nodes of a
+/// Merkle tree with the specified number of leaves.
+pub(crate) fn tree_bytes<H: Hasher>(num_leaves: usize) -> usize {
+    2 * num_leaves * size_of::<H::Digest>()
+}
@@ -0,0 +1,1194 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{
+    channel::{build_proof_context, ProverChannel},
+    composer::DeepCompositionPoly,
+    constraints::ConstraintEvaluator,
+    context::CachedDomain,
+    diagnostics::OodSnapshot,
+    observer::{matrix_bytes, tree_bytes, StepTimer},
+    CompositionPoly, ConstraintCommitment, ConstraintEvaluationMode, ConstraintHasher, Prover,
+    ProverContext, ProverError, ProverObserver, ProverStep, StarkDomain, Trace, TraceCommitment,
+    TraceHasher, TracePolyTable,
+};
+use air::{
+    proof::{LowDegreeProof, StarkProof},
+    Air, AuxTraceRandElements, LowDegreeTest, ProofOptions,
+};
+use crypto::{CommitmentHashers, RandomCoin};
+use fri::FriProver;
+use math::{fft::infer_degree, FieldElement, ToElements};
+use stir::StirProver;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, CancellationToken, Deserializable,
+    DeserializationError, Serializable, SliceReader,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the checkpoint format written by [StagedProver::write_checkpoint_into()].
+const CHECKPOINT_VERSION: u8 = 1;
+
+// PROVER STAGE
+// ================================================================================================
+
+/// The most recently completed stage of proof generation in a [StagedProver].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum ProverStage {
+    /// The AIR and the prover channel have been instantiated, but nothing has been committed to.
+    Initialized = 0,
+    /// The main segment of the execution trace has been extended and committed to.
+    TraceCommitted = 1,
+    /// Auxiliary segments of the execution trace (if any) have been built, extended, and
+    /// committed to.
+    AuxTraceCommitted = 2,
+    /// Constraints have been evaluated, and evaluations of the resulting constraint composition
+    /// polynomial have been committed to.
+    ConstraintsCommitted = 3,
+    /// Trace polynomials and constraint composition polynomial have been evaluated at the
+    /// out-of-domain point.
+    OodEvaluated = 4,
+    /// The DEEP composition polynomial has been built and evaluated over the LDE domain.
+    DeepEvaluated = 5,
+    /// A low-degree proof for the DEEP composition polynomial has been built, and the query
+    /// positions have been determined.
+    LowDegreeProofBuilt = 6,
+}
+
+impl Serializable for ProverStage {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(*self as u8);
+    }
+}
+
+impl Deserializable for ProverStage {
+    /// Reads a prover stage from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if the value does not correspond to a valid prover stage.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        match source.read_u8()? {
+            0 => Ok(Self::Initialized),
+            1 => Ok(Self::TraceCommitted),
+            2 => Ok(Self::AuxTraceCommitted),
+            3 => Ok(Self::ConstraintsCommitted),
+            4 => Ok(Self::OodEvaluated),
+            5 => Ok(Self::DeepEvaluated),
+            6 => Ok(Self::LowDegreeProofBuilt),
+            value => Err(DeserializationError::InvalidValue(format!(
+                "value {value} cannot be deserialized as a prover stage"
+            ))),
+        }
+    }
+}
+
+// STAGED PROVER
+// ================================================================================================
+
+/// Generates a STARK proof one protocol stage at a time.
+///
+/// [Prover::prove()] executes all stages of the protocol in a single call. A staged prover
+/// exposes the same stages as separate methods which must be invoked in the following order:
+///
+/// 1. [commit_trace()](StagedProver::commit_trace) - extends the main segment of the execution
+///    trace and commits to it.
+/// 2. [commit_aux_trace()](StagedProver::commit_aux_trace) - builds, extends, and commits to
+///    auxiliary trace segments (if any).
+/// 3. [commit_constraints()](StagedProver::commit_constraints) - evaluates constraints and
+///    commits to the resulting constraint composition polynomial.
+/// 4. [evaluate_ood()](StagedProver::evaluate_ood) - evaluates trace and constraint composition
+///    polynomials at an out-of-domain point.
+/// 5. [evaluate_deep_composition()](StagedProver::evaluate_deep_composition) - builds the DEEP
+///    composition polynomial and evaluates it over the LDE domain.
+/// 6. [build_low_degree_proof()](StagedProver::build_low_degree_proof) - builds a FRI or STIR
+///    proof for the DEEP composition polynomial and determines query positions.
+/// 7. [build_proof()](StagedProver::build_proof) - queries trace and constraint commitments and
+///    assembles the [StarkProof].
+///
+/// The resulting proof is identical to the proof generated by [Prover::prove()] for the same
+/// trace. Between any two stages, custom logic can be executed, and the state of the prover can
+/// be written into a checkpoint via [write_checkpoint_into()](StagedProver::write_checkpoint_into).
+/// A prover can be re-created from a checkpoint via
+/// [read_checkpoint_from()](StagedProver::read_checkpoint_from) to resume proof generation, for
+/// example, after a crash.
+///
+/// Every stage checks the prover's [cancellation token](Prover::cancellation_token) and returns
+/// [ProverError::Cancelled] if the token has been cancelled before or during the stage; in such a
+/// case, the state of the prover is unspecified, and the prover should be discarded.
+///
+/// The field in which the protocol is executed is specified by the `E` type parameter, and must
+/// match the field extension specified in the prover's [ProofOptions].
+pub struct StagedProver<'a, P, E>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    prover: &'a P,
+    air: P::Air,
+    domain: &'a CachedDomain<P::BaseField>,
+    channel: ProverChannel<E, P::HashFn, P::RandomCoin>,
+    pub_inputs_elements: Vec<P::BaseField>,
+    stage: ProverStage,
+    trace_commitment: Option<TraceCommitment<E, TraceHasher<P>>>,
+    trace_polys: Option<TracePolyTable<E>>,
+    aux_rand_elements: Option<AuxTraceRandElements<E>>,
+    composition_poly: Option<CompositionPoly<E>>,
+    constraint_commitment: Option<ConstraintCommitment<E, ConstraintHasher<P>>>,
+    ood_evaluations: Option<OodEvaluations<E>>,
+    deep_evaluations: Option<Vec<E>>,
+    low_degree_proof: Option<LowDegreeProof>,
+    query_positions: Option<Vec<usize>>,
+}
+
+impl<'a, P, E> StagedProver<'a, P, E>
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new staged prover for the computation defined by the provided trace.
+    ///
+    /// This instantiates the AIR and the prover channel, and retrieves the STARK domain for the
+    /// computation from the `context` (building it if needed). The trace itself is not processed
+    /// until [commit_trace()](StagedProver::commit_trace) is called.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The prover's cancellation token has been cancelled.
+    /// * The estimated amount of memory required to generate the proof exceeds the prover's
+    ///   [memory budget](Prover::memory_budget).
+    /// * The FRI folding schedule specified by proof options folds the LDE domain into a remainder
+    ///   domain which is smaller than the blowup factor.
+    /// * The number of leaves in the trace, constraint evaluation, or FRI layer Merkle trees is
+    ///   not a power of the Merkle tree arity specified by proof options.
+    ///
+    /// # Panics
+    /// Panics if the extension degree of `E` is different from the degree of the field extension
+    /// specified in the prover's [ProofOptions].
+    pub fn new(
+        prover: &'a P,
+        trace: &P::Trace,
+        context: &'a mut ProverContext<P::BaseField>,
+    ) -> Result<Self, ProverError> {
+        check_cancelled(prover.cancellation_token())?;
+        let options = prover.options();
+        assert_eq!(
+            E::EXTENSION_DEGREE,
+            options.field_extension().degree() as usize,
+            "extension degree of the proof field must match the field extension in proof options"
+        );
+
+        // serialize public inputs; these will be included in the seed for the public coin
+        let pub_inputs = prover.get_pub_inputs(trace);
+        let pub_inputs_elements = pub_inputs.to_elements();
+
+        // create an instance of AIR for the provided parameters. this takes a generic description
+        // of the computation (provided via AIR type), and creates a description of a specific
+        // execution of the computation for the provided public inputs.
+        let air = P::Air::new(trace.get_info(), pub_inputs, options.clone());
+
+        // make sure the FRI folding schedule does not fold the LDE domain past the remainder
+        if options.low_degree_test() == LowDegreeTest::Fri {
+            let fri_options = options.to_fri_options();
+            let lde_domain_size = air.lde_domain_size();
+            let remainder_domain_size = fri_options.remainder_domain_size(lde_domain_size);
+            if remainder_domain_size < fri_options.blowup_factor() {
+                return Err(ProverError::InvalidFriFoldingSchedule(
+                    lde_domain_size,
+                    remainder_domain_size,
+                ));
+            }
+        }
+
+        // make sure Merkle trees of the specified arity can be built over the LDE domain and all
+        // FRI layers; STIR oracle trees are always binary
+        let merkle_arity = options.merkle_arity();
+        let lde_domain_size = air.lde_domain_size();
+        check_merkle_tree_leaves(merkle_arity, lde_domain_size)?;
+        if options.low_degree_test() == LowDegreeTest::Fri {
+            let fri_options = options.to_fri_options();
+            let mut fri_layer_domain_size = lde_domain_size;
+            for i in 0..fri_options.num_fri_layers(lde_domain_size) {
+                fri_layer_domain_size /= fri_options.layer_folding_factor(i);
+                check_merkle_tree_leaves(merkle_arity, fri_layer_domain_size)?;
+            }
+        }
+
+        // make sure the data structures built during proof generation fit into the memory budget
+        if let Some(budget) = prover.memory_budget() {
+            let required = estimate_memory_usage::<P, E>(&air, prover.constraint_evaluation_mode());
+            if required > budget {
+                return Err(ProverError::MemoryBudgetExceeded(required, budget));
+            }
+        }
+
+        // create a channel which is used to simulate interaction between the prover and the
+        // verifier; the channel will be used to commit to values and to draw randomness that
+        // should come from the verifier.
+        let channel = ProverChannel::new(&air, pub_inputs_elements.clone());
+
+        // build computation domain (or retrieve it from the context if it was built for a
+        // computation of the same shape before); this is used later for polynomial evaluations
+        let timer = StepTimer::start();
+        let domain = context.get_or_build(&air);
+        let stark_domain = domain.domain();
+        // the domain holds trace twiddles and inverse twiddles (trace_length / 2 elements each),
+        // the constraint evaluation domain, and its inverse twiddles (ce_domain_size / 2 elements)
+        prover.observer().on_event(&timer.event(
+            ProverStep::DomainPrepared {
+                trace_length: stark_domain.trace_length(),
+                ce_domain_size: stark_domain.ce_domain_size(),
+                lde_domain_size: stark_domain.lde_domain_size(),
+            },
+            matrix_bytes::<P::BaseField>(
+                stark_domain.trace_length() + stark_domain.ce_domain_size() * 3 / 2,
+                1,
+            ),
+        ));
+
+        Ok(Self::from_parts(
+            prover,
+            air,
+            domain,
+            channel,
+            pub_inputs_elements,
+        ))
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the most recently completed stage of proof generation.
+    pub fn stage(&self) -> ProverStage {
+        self.stage
+    }
+
+    /// Returns the AIR of the computation for which the proof is being generated.
+    pub fn air(&self) -> &P::Air {
+        &self.air
+    }
+
+    /// Returns the STARK domain of the computation for which the proof is being generated.
+    pub fn domain(&self) -> &StarkDomain<P::BaseField> {
+        self.domain.domain()
+    }
+
+    /// Returns the constraint composition polynomial if constraints have been committed to, but
+    /// the DEEP composition polynomial has not been built yet.
+    pub fn composition_poly(&self) -> Option<&CompositionPoly<E>> {
+        self.composition_poly.as_ref()
+    }
+
+    /// Returns evaluations of the DEEP composition polynomial over the LDE domain if they have
+    /// been computed, but the low-degree proof has not been built yet.
+    pub fn deep_evaluations(&self) -> Option<&[E]> {
+        self.deep_evaluations.as_deref()
+    }
+
+    /// Returns positions at which trace and constraint commitments will be queried if the
+    /// low-degree proof has already been built.
+    pub fn query_positions(&self) -> Option<&[usize]> {
+        self.query_positions.as_deref()
+    }
+
+    /// Returns random elements used to build auxiliary trace segments if auxiliary trace
+    /// segments have been committed to, but constraints have not been evaluated yet.
+    pub(crate) fn aux_rand_elements(&self) -> Option<&AuxTraceRandElements<E>> {
+        self.aux_rand_elements.as_ref()
+    }
+
+    /// Returns a snapshot of trace polynomials and the out-of-domain point used to diagnose a
+    /// proof which failed self-verification.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::OodEvaluated] stage.
+    pub(crate) fn ood_snapshot(
+        &self,
+        aux_rand_elements: AuxTraceRandElements<E>,
+    ) -> OodSnapshot<E> {
+        self.assert_stage(ProverStage::OodEvaluated);
+        OodSnapshot {
+            trace_polys: self.trace_polys.clone().expect("no trace polynomials"),
+            aux_rand_elements,
+            z: self.ood_evaluations.as_ref().expect("no OOD evaluations").z,
+        }
+    }
+
+    // PROTOCOL STAGES
+    // --------------------------------------------------------------------------------------------
+
+    /// Extends the main segment of the execution trace and commits to the extended trace.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * This prover is not at [ProverStage::Initialized] stage.
+    /// * The shape of the provided trace is different from the trace used to instantiate this
+    ///   prover.
+    pub fn commit_trace(&mut self, trace: &P::Trace) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::Initialized);
+        self.check_cancelled()?;
+        assert_eq!(
+            self.air.trace_info(),
+            &trace.get_info(),
+            "trace info must match the trace used to instantiate the prover"
+        );
+        let domain = self.domain.domain();
+
+        // extend the main execution trace and build a Merkle tree from the extended trace
+        let (main_trace_lde, main_trace_tree, main_trace_polys) = self
+            .prover
+            .build_trace_commitment::<P::BaseField>(trace.main_segment(), domain)?;
+
+        // commit to the LDE of the main trace by writing the cap of its Merkle tree into
+        // the channel
+        self.channel
+            .commit_trace(main_trace_tree.cap(self.merkle_cap_height()));
+
+        // initialize trace commitment and trace polynomial table structs with the main trace
+        // data; for multi-segment traces these structs will be used as accumulators of all
+        // trace segments
+        self.trace_commitment = Some(TraceCommitment::new(
+            main_trace_lde,
+            main_trace_tree,
+            domain.trace_to_lde_blowup(),
+        ));
+        self.trace_polys = Some(TracePolyTable::new(main_trace_polys));
+        self.stage = ProverStage::TraceCommitted;
+        Ok(())
+    }
+
+    /// Builds auxiliary trace segments (if any), extends them, and commits to the extended
+    /// segments.
+    ///
+    /// In debug mode, this also checks that the trace (including auxiliary segments) is valid
+    /// against the AIR.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::TraceCommitted] stage.
+    pub fn commit_aux_trace(&mut self, trace: &mut P::Trace) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::TraceCommitted);
+        let token = self.prover.cancellation_token();
+        let domain = self.domain.domain();
+        let merkle_cap_height = self.merkle_cap_height();
+        let trace_commitment = self.trace_commitment.as_mut().expect("no trace commitment");
+        let trace_polys = self.trace_polys.as_mut().expect("no trace polynomials");
+
+        // build auxiliary trace segments (if any), and append the resulting segments to trace
+        // commitment and trace polynomial table structs
+        let mut aux_trace_segments = Vec::new();
+        let mut aux_trace_rand_elements = AuxTraceRandElements::new();
+        for i in 0..trace.layout().num_aux_segments() {
+            check_cancelled(token)?;
+            let timer = StepTimer::start();
+
+            // draw a set of random elements required to build an auxiliary trace segment
+            let rand_elements = self
+                .channel
+                .get_aux_trace_segment_rand_elements(&self.air, i);
+
+            // build the trace segment
+            let aux_segment = trace
+                .build_aux_segment(&aux_trace_segments, &rand_elements)
+                .expect("failed build auxiliary trace segment");
+            self.prover.observer().on_event(&timer.event(
+                ProverStep::AuxTraceSegmentBuilt {
+                    num_columns: aux_segment.num_cols(),
+                    num_rows: aux_segment.num_rows(),
+                },
+                matrix_bytes::<E>(aux_segment.num_rows(), aux_segment.num_cols()),
+            ));
+
+            // extend the auxiliary trace segment and build a Merkle tree from the extended trace
+            let (aux_segment_lde, aux_segment_tree, aux_segment_polys) = self
+                .prover
+                .build_trace_commitment::<E>(&aux_segment, domain)?;
+
+            // commit to the LDE of the extended auxiliary trace segment by writing the cap of
+            // its Merkle tree into the channel
+            self.channel
+                .commit_trace(aux_segment_tree.cap(merkle_cap_height));
+
+            // append the segment to the trace commitment and trace polynomial table structs
+            trace_commitment.add_segment(aux_segment_lde, aux_segment_tree);
+            trace_polys.add_aux_segment(aux_segment_polys);
+            aux_trace_rand_elements.add_segment_elements(rand_elements);
+            aux_trace_segments.push(aux_segment);
+        }
+
+        // make sure the specified trace (including auxiliary segments) is valid against the AIR.
+        // This checks validity of both, assertions and state transitions. We do this in debug
+        // mode only because this is a very expensive operation.
+        #[cfg(debug_assertions)]
+        trace.validate(&self.air, &aux_trace_segments, &aux_trace_rand_elements);
+
+        self.aux_rand_elements = Some(aux_trace_rand_elements);
+        self.stage = ProverStage::AuxTraceCommitted;
+        Ok(())
+    }
+
+    /// Evaluates constraints over the constraint evaluation domain, builds the constraint
+    /// composition polynomial from the evaluations, and commits to the evaluations of this
+    /// polynomial over the LDE domain.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The prover's cancellation token has been cancelled.
+    /// * The constraint composition polynomial could not be built from the constraint
+    ///   evaluations.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::AuxTraceCommitted] stage.
+    pub fn commit_constraints(&mut self) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::AuxTraceCommitted);
+        self.check_cancelled()?;
+        let domain = self.domain.domain();
+        let trace_commitment = self.trace_commitment.as_ref().expect("no trace commitment");
+        let aux_rand_elements = self.aux_rand_elements.take().expect("no aux rand elements");
+
+        // evaluate constraints specified by the AIR over the constraint evaluation domain, and
+        // compute random linear combinations of these evaluations using coefficients drawn from
+        // the channel; this step evaluates only constraint numerators, thus, only constraints with
+        // identical denominators are merged together. the results are saved into a constraint
+        // evaluation table where each column contains merged evaluations of constraints with
+        // identical denominators. in streaming mode, merged evaluations are divided by their
+        // denominators on the fly, and the table contains a single combined column instead.
+        let timer = StepTimer::start();
+        let constraint_coeffs = self.channel.get_constraint_composition_coeffs(&self.air);
+        let evaluator = ConstraintEvaluator::new(
+            &self.air,
+            self.domain.periodic_values(),
+            aux_rand_elements,
+            constraint_coeffs,
+        );
+        let constraint_evaluations = evaluator.evaluate(
+            trace_commitment.trace_table(),
+            domain,
+            self.prover.constraint_evaluation_mode(),
+            self.prover.cancellation_token(),
+        )?;
+        self.prover.observer().on_event(&timer.event(
+            ProverStep::ConstraintsEvaluated {
+                num_columns: constraint_evaluations.num_columns(),
+                ce_domain_size: constraint_evaluations.num_rows(),
+            },
+            matrix_bytes::<E>(
+                constraint_evaluations.num_rows(),
+                constraint_evaluations.num_columns(),
+            ),
+        ));
+
+        // first, build constraint composition polynomial from the constraint evaluation table:
+        // - divide all constraint evaluation columns by their respective divisors
+        // - combine them into a single column of evaluations,
+        // - interpolate the column into a polynomial in coefficient form
+        // - "break" the polynomial into a set of column polynomials each of degree equal to
+        //   trace_length - 1
+        let timer = StepTimer::start();
+        let composition_poly = constraint_evaluations.into_poly(self.prover.observer())?;
+        self.prover.observer().on_event(&timer.event(
+            ProverStep::CompositionPolyBuilt {
+                num_columns: composition_poly.num_columns(),
+                column_degree: composition_poly.column_degree(),
+            },
+            matrix_bytes::<E>(domain.trace_length(), composition_poly.num_columns()),
+        ));
+
+        // then, build a commitment to the evaluations of the composition polynomial columns
+        let constraint_commitment = self
+            .prover
+            .build_constraint_commitment::<E>(&composition_poly, domain)?;
+
+        // then, commit to the evaluations of constraints by writing the cap of the constraint
+        // Merkle tree into the channel
+        self.channel
+            .commit_constraints(constraint_commitment.cap(self.merkle_cap_height()));
+
+        self.composition_poly = Some(composition_poly);
+        self.constraint_commitment = Some(constraint_commitment);
+        self.stage = ProverStage::ConstraintsCommitted;
+        Ok(())
+    }
+
+    /// Draws an out-of-domain point z, and evaluates trace polynomials and the constraint
+    /// composition polynomial at this point.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::ConstraintsCommitted] stage.
+    pub fn evaluate_ood(&mut self) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::ConstraintsCommitted);
+        self.check_cancelled()?;
+        let trace_polys = self.trace_polys.as_ref().expect("no trace polynomials");
+        let composition_poly = self.composition_poly.as_ref().expect("no composition poly");
+
+        // draw an out-of-domain point z. Depending on the type of E, the point is drawn either
+        // from the base field or from an extension field defined by E.
+        //
+        // The purpose of sampling from the extension field here (instead of the base field) is to
+        // increase security. Soundness is limited by the size of the field that the random point
+        // is drawn from, and we can potentially save on performance by only drawing this point
+        // from an extension field, rather than increasing the size of the field overall.
+        let z = self.channel.get_ood_point();
+
+        // evaluate trace and constraint polynomials at the OOD point z, and send the results to
+        // the verifier. the trace polynomials are actually evaluated over two points: z and z * g,
+        // where g is the generator of the trace domain.
+        let trace_states = trace_polys.get_ood_frame(z);
+        self.channel.send_ood_trace_states(&trace_states);
+
+        let constraint_evaluations = composition_poly.evaluate_at(z);
+        self.channel
+            .send_ood_constraint_evaluations(&constraint_evaluations);
+
+        self.ood_evaluations = Some(OodEvaluations {
+            z,
+            trace_states,
+            constraint_evaluations,
+        });
+        self.stage = ProverStage::OodEvaluated;
+        Ok(())
+    }
+
+    /// Builds the DEEP composition polynomial and evaluates it over the LDE domain.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::OodEvaluated] stage.
+    pub fn evaluate_deep_composition(&mut self) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::OodEvaluated);
+        self.check_cancelled()?;
+        let domain = self.domain.domain();
+        let trace_polys = self.trace_polys.take().expect("no trace polynomials");
+        let composition_poly = self.composition_poly.take().expect("no composition poly");
+        let ood = self.ood_evaluations.take().expect("no OOD evaluations");
+
+        let timer = StepTimer::start();
+
+        // draw random coefficients to use during DEEP polynomial composition, and use them to
+        // initialize the DEEP composition polynomial
+        let deep_coefficients = self.channel.get_deep_composition_coeffs(&self.air);
+        let mut deep_composition_poly = DeepCompositionPoly::new(ood.z, deep_coefficients);
+
+        // combine all trace polynomials together and merge them into the DEEP composition
+        // polynomial
+        deep_composition_poly.add_trace_polys(trace_polys, ood.trace_states);
+
+        // merge columns of constraint composition polynomial into the DEEP composition polynomial;
+        deep_composition_poly.add_composition_poly(composition_poly, ood.constraint_evaluations);
+
+        // raise the degree of the DEEP composition polynomial by one to make sure it is equal to
+        // trace_length - 1
+        deep_composition_poly.adjust_degree();
+
+        self.prover.observer().on_event(&timer.event(
+            ProverStep::DeepCompositionBuilt {
+                degree: deep_composition_poly.degree(),
+            },
+            matrix_bytes::<E>(domain.trace_length(), 1),
+        ));
+
+        // make sure the degree of the DEEP composition polynomial is equal to trace polynomial
+        // degree
+        assert_eq!(domain.trace_length() - 1, deep_composition_poly.degree());
+
+        // evaluate DEEP composition polynomial over LDE domain
+        let timer = StepTimer::start();
+        let deep_evaluations = deep_composition_poly.evaluate(domain);
+        // we check the following condition in debug mode only because infer_degree is an expensive
+        // operation
+        debug_assert_eq!(
+            domain.trace_length() - 1,
+            infer_degree(&deep_evaluations, domain.offset())
+        );
+        self.prover.observer().on_event(&timer.event(
+            ProverStep::DeepCompositionEvaluated {
+                lde_domain_size: domain.lde_domain_size(),
+            },
+            matrix_bytes::<E>(deep_evaluations.len(), 1),
+        ));
+
+        self.deep_evaluations = Some(deep_evaluations);
+        self.stage = ProverStage::DeepEvaluated;
+        Ok(())
+    }
+
+    /// Builds a low-degree proof for the DEEP composition polynomial using the low-degree test
+    /// specified in proof options, and determines positions at which trace and constraint
+    /// commitments should be queried.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::DeepEvaluated] stage.
+    pub fn build_low_degree_proof(&mut self) -> Result<(), ProverError> {
+        self.assert_stage(ProverStage::DeepEvaluated);
+        self.check_cancelled()?;
+        let deep_evaluations = self.deep_evaluations.take().expect("no DEEP evaluations");
+
+        let options = self.air.options();
+        let observer = self.prover.observer();
+        let token = self.prover.cancellation_token();
+        let (low_degree_proof, query_positions) = match options.low_degree_test() {
+            LowDegreeTest::Fri => build_fri_proof(
+                &mut self.channel,
+                options,
+                deep_evaluations,
+                observer,
+                token,
+            )?,
+            LowDegreeTest::Stir => build_stir_proof(
+                &mut self.channel,
+                options,
+                deep_evaluations,
+                observer,
+                token,
+            )?,
+        };
+
+        self.low_degree_proof = Some(low_degree_proof);
+        self.query_positions = Some(query_positions);
+        self.stage = ProverStage::LowDegreeProofBuilt;
+        Ok(())
+    }
+
+    /// Queries trace and constraint commitments at the previously determined positions, and
+    /// builds the STARK proof.
+    ///
+    /// # Panics
+    /// Panics if this prover is not at [ProverStage::LowDegreeProofBuilt] stage.
+    pub fn build_proof(mut self) -> Result<StarkProof, ProverError> {
+        self.assert_stage(ProverStage::LowDegreeProofBuilt);
+        self.check_cancelled()?;
+        let trace_commitment = self.trace_commitment.take().expect("no trace commitment");
+        let constraint_commitment = self.constraint_commitment.take().expect("no commitment");
+        let low_degree_proof = self.low_degree_proof.take().expect("no low-degree proof");
+        let query_positions = self.query_positions.take().expect("no query positions");
+        let merkle_cap_height = self.merkle_cap_height();
+
+        let timer = StepTimer::start();
+
+        // query the execution trace at the selected position; for each query, we need the
+        // state of the trace at that position + Merkle authentication path
+        let trace_queries = trace_commitment.query(&query_positions, merkle_cap_height);
+
+        // query the constraint commitment at the selected positions; for each query, we need just
+        // a Merkle authentication path. this is because constraint evaluations for each step are
+        // merged into a single value and Merkle authentication paths contain these values already
+        let constraint_queries = constraint_commitment.query(&query_positions, merkle_cap_height);
+
+        // build the proof object
+        let proof = self
+            .channel
+            .build_proof(trace_queries, constraint_queries, low_degree_proof);
+        self.prover.observer().on_event(&timer.event(
+            ProverStep::ProofBuilt {
+                num_queries: query_positions.len(),
+            },
+            0,
+        ));
+
+        Ok(proof)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new prover at [ProverStage::Initialized] stage assembled from the provided parts.
+    fn from_parts(
+        prover: &'a P,
+        air: P::Air,
+        domain: &'a CachedDomain<P::BaseField>,
+        channel: ProverChannel<E, P::HashFn, P::RandomCoin>,
+        pub_inputs_elements: Vec<P::BaseField>,
+    ) -> Self {
+        Self {
+            prover,
+            air,
+            domain,
+            channel,
+            pub_inputs_elements,
+            stage: ProverStage::Initialized,
+            trace_commitment: None,
+            trace_polys: None,
+            aux_rand_elements: None,
+            composition_poly: None,
+            constraint_commitment: None,
+            ood_evaluations: None,
+            deep_evaluations: None,
+            low_degree_proof: None,
+            query_positions: None,
+        }
+    }
+
+    /// Returns the height of Merkle tree caps used in commitments.
+    fn merkle_cap_height(&self) -> usize {
+        self.air.options().merkle_cap_height()
+    }
+
+    /// Returns [ProverError::Cancelled] if the prover's cancellation token has been cancelled.
+    fn check_cancelled(&self) -> Result<(), ProverError> {
+        check_cancelled(self.prover.cancellation_token())
+    }
+
+    /// Panics if this prover is not at the specified stage.
+    fn assert_stage(&self, expected: ProverStage) {
+        assert_eq!(
+            self.stage, expected,
+            "expected prover to be at {expected:?} stage, but was at {:?} stage",
+            self.stage
+        );
+    }
+}
+
+// CHECKPOINTS
+// ================================================================================================
+
+impl<'a, P, E> StagedProver<'a, P, E>
+where
+    P: Prover + ?Sized,
+    P::RandomCoin: Serializable + Deserializable,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    /// Serializes the current state of this prover and writes the resulting bytes into the
+    /// `target`.
+    ///
+    /// The checkpoint contains the state of the prover channel (including the state of the public
+    /// coin) and the data produced by the completed stages which is needed by subsequent stages
+    /// (e.g., trace LDE and its Merkle trees). It does not contain the execution trace itself;
+    /// if the checkpoint is taken before [ProverStage::AuxTraceCommitted] stage, the trace will
+    /// need to be provided to the resumed prover again.
+    pub fn write_checkpoint_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(CHECKPOINT_VERSION);
+        self.stage.write_into(target);
+        target.write_u32(self.pub_inputs_elements.len() as u32);
+        self.pub_inputs_elements.write_into(target);
+        self.channel.write_into(target);
+
+        // the set of values present in a checkpoint is fully defined by the prover stage
+        if let Some(trace_commitment) = &self.trace_commitment {
+            trace_commitment.write_into(target);
+        }
+        if let Some(trace_polys) = &self.trace_polys {
+            trace_polys.write_into(target);
+        }
+        if let Some(aux_rand_elements) = &self.aux_rand_elements {
+            aux_rand_elements.write_into(target);
+        }
+        if let Some(composition_poly) = &self.composition_poly {
+            composition_poly.write_into(target);
+        }
+        if let Some(constraint_commitment) = &self.constraint_commitment {
+            constraint_commitment.write_into(target);
+        }
+        if let Some(ood_evaluations) = &self.ood_evaluations {
+            ood_evaluations.write_into(target);
+        }
+        if let Some(deep_evaluations) = &self.deep_evaluations {
+            target.write_u64(deep_evaluations.len() as u64);
+            deep_evaluations.write_into(target);
+        }
+        if let Some(low_degree_proof) = &self.low_degree_proof {
+            low_degree_proof.write_into(target);
+        }
+        if let Some(query_positions) = &self.query_positions {
+            target.write_u32(query_positions.len() as u32);
+            for &position in query_positions.iter() {
+                target.write_u64(position as u64);
+            }
+        }
+    }
+
+    /// Returns the current state of this prover serialized into a vector of bytes.
+    pub fn to_checkpoint(&self) -> Vec<u8> {
+        let mut result = Vec::new();
+        self.write_checkpoint_into(&mut result);
+        result
+    }
+
+    /// Reads a checkpoint from the specified `source` and returns a prover which resumes proof
+    /// generation from the stage at which the checkpoint was taken.
+    ///
+    /// The AIR of the computation is re-built from the trace info stored in the checkpoint and
+    /// the provided `pub_inputs`; the STARK domain is retrieved from the `context` (or built and
+    /// added to it).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * A valid checkpoint could not be read from the `source`.
+    /// * The checkpoint was taken for different public inputs, proof options, or field.
+    /// * The checkpoint was taken for a computation described by a different AIR.
+    pub fn read_checkpoint_from<R: ByteReader>(
+        prover: &'a P,
+        pub_inputs: <P::Air as Air>::PublicInputs,
+        context: &'a mut ProverContext<P::BaseField>,
+        source: &mut R,
+    ) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != CHECKPOINT_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "checkpoint version {version} is not supported"
+            )));
+        }
+        let stage = ProverStage::read_from(source)?;
+
+        // make sure the checkpoint was taken for the same public inputs
+        let num_pub_inputs_elements = source.read_u32()? as usize;
+        let pub_inputs_elements = P::BaseField::read_batch_from(source, num_pub_inputs_elements)?;
+        if pub_inputs.to_elements() != pub_inputs_elements {
+            return Err(DeserializationError::InvalidValue(
+                "checkpoint was taken for different public inputs".into(),
+            ));
+        }
+
+        // make sure the checkpoint was taken with the same proof options and field
+        let channel = ProverChannel::<E, P::HashFn, P::RandomCoin>::read_from(source)?;
+        let options = prover.options();
+        if channel.context().options() != options {
+            return Err(DeserializationError::InvalidValue(
+                "checkpoint was taken with different proof options".into(),
+            ));
+        }
+        if E::EXTENSION_DEGREE != options.field_extension().degree() as usize {
+            return Err(DeserializationError::InvalidValue(format!(
+                "checkpoint cannot be resumed in a field of extension degree {}",
+                E::EXTENSION_DEGREE
+            )));
+        }
+
+        // re-build the AIR and make sure that it describes the same computation as the one for
+        // which the checkpoint was taken
+        let trace_info = channel.context().get_trace_info();
+        let air = P::Air::new(trace_info, pub_inputs, options.clone());
+        if build_proof_context(&air) != *channel.context() {
+            return Err(DeserializationError::InvalidValue(
+                "checkpoint was taken for a different computation".into(),
+            ));
+        }
+
+        let domain = context.get_or_build(&air);
+        let mut prover = Self::from_parts(prover, air, domain, channel, pub_inputs_elements);
+        prover.stage = stage;
+
+        // read the values present in the checkpoint at the specified stage
+        use ProverStage::*;
+        if stage >= TraceCommitted {
+            prover.trace_commitment = Some(TraceCommitment::read_from(source)?);
+        }
+        if (TraceCommitted..=OodEvaluated).contains(&stage) {
+            prover.trace_polys = Some(TracePolyTable::read_from(source)?);
+        }
+        if stage == AuxTraceCommitted {
+            prover.aux_rand_elements = Some(AuxTraceRandElements::read_from(source)?);
+        }
+        if (ConstraintsCommitted..=OodEvaluated).contains(&stage) {
+            prover.composition_poly = Some(CompositionPoly::read_from(source)?);
+        }
+        if stage >= ConstraintsCommitted {
+            prover.constraint_commitment = Some(ConstraintCommitment::read_from(source)?);
+        }
+        if stage == OodEvaluated {
+            prover.ood_evaluations = Some(OodEvaluations::read_from(source)?);
+        }
+        if stage == DeepEvaluated {
+            let num_evaluations = source.read_u64()? as usize;
+            prover.deep_evaluations = Some(E::read_batch_from(source, num_evaluations)?);
+        }
+        if stage == LowDegreeProofBuilt {
+            prover.low_degree_proof = Some(LowDegreeProof::read_from(source)?);
+            let num_positions = source.read_u32()? as usize;
+            let mut query_positions = Vec::with_capacity(num_positions);
+            for _ in 0..num_positions {
+                query_positions.push(source.read_u64()? as usize);
+            }
+            prover.query_positions = Some(query_positions);
+        }
+
+        prover.validate_checkpoint_state()?;
+        Ok(prover)
+    }
+
+    /// Returns a prover which resumes proof generation from the checkpoint contained in the
+    /// provided `bytes`.
+    ///
+    /// See [read_checkpoint_from()](StagedProver::read_checkpoint_from) for details.
+    pub fn from_checkpoint(
+        prover: &'a P,
+        pub_inputs: <P::Air as Air>::PublicInputs,
+        context: &'a mut ProverContext<P::BaseField>,
+        bytes: &[u8],
+    ) -> Result<Self, DeserializationError> {
+        Self::read_checkpoint_from(prover, pub_inputs, context, &mut SliceReader::new(bytes))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an error if the dimensions of values read from a checkpoint are inconsistent with
+    /// the STARK domain of the computation.
+    fn validate_checkpoint_state(&self) -> Result<(), DeserializationError> {
+        let domain = self.domain.domain();
+        let invalid = |name: &str| {
+            DeserializationError::InvalidValue(format!(
+                "{name} in the checkpoint is inconsistent with the computation domain"
+            ))
+        };
+
+        if let Some(trace_commitment) = &self.trace_commitment {
+            let trace_lde = trace_commitment.trace_table();
+            if trace_lde.trace_len() != domain.lde_domain_size()
+                || trace_lde.main_trace_width() != self.air.trace_layout().main_trace_width()
+            {
+                return Err(invalid("trace commitment"));
+            }
+        }
+        if let Some(trace_polys) = &self.trace_polys {
+            if trace_polys.poly_size() != domain.trace_length() {
+                return Err(invalid("trace polynomial table"));
+            }
+        }
+        if let Some(composition_poly) = &self.composition_poly {
+            if composition_poly.column_len() != domain.trace_length() {
+                return Err(invalid("constraint composition polynomial"));
+            }
+        }
+        if let Some(constraint_commitment) = &self.constraint_commitment {
+            if constraint_commitment.tree_depth() != domain.lde_domain_size().ilog2() as usize {
+                return Err(invalid("constraint commitment"));
+            }
+        }
+        if let Some(deep_evaluations) = &self.deep_evaluations {
+            if deep_evaluations.len() != domain.lde_domain_size() {
+                return Err(invalid("DEEP composition polynomial evaluations"));
+            }
+        }
+        if let Some(query_positions) = &self.query_positions {
+            if query_positions
+                .iter()
+                .any(|&position| position >= domain.lde_domain_size())
+            {
+                return Err(invalid("query positions"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// OOD EVALUATIONS
+// ================================================================================================
+
+/// Out-of-domain point together with evaluations of trace polynomials and constraint composition
+/// polynomial columns at this point.
+struct OodEvaluations<E: FieldElement> {
+    z: E,
+    trace_states: Vec<Vec<E>>,
+    constraint_evaluations: Vec<E>,
+}
+
+impl<E: FieldElement> Serializable for OodEvaluations<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.z.write_into(target);
+        target.write_u8(self.trace_states.len() as u8);
+        for trace_state in self.trace_states.iter() {
+            target.write_u16(trace_state.len() as u16);
+            trace_state.write_into(target);
+        }
+        target.write_u16(self.constraint_evaluations.len() as u16);
+        self.constraint_evaluations.write_into(target);
+    }
+}
+
+impl<E: FieldElement> Deserializable for OodEvaluations<E> {
+    /// Reads out-of-domain evaluations from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if out-of-domain evaluations could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let z = E::read_from(source)?;
+        let num_trace_states = source.read_u8()? as usize;
+        let mut trace_states = Vec::with_capacity(num_trace_states);
+        for _ in 0..num_trace_states {
+            let num_elements = source.read_u16()? as usize;
+            trace_states.push(E::read_batch_from(source, num_elements)?);
+        }
+        let num_evaluations = source.read_u16()? as usize;
+        let constraint_evaluations = E::read_batch_from(source, num_evaluations)?;
+
+        Ok(Self {
+            z,
+            trace_states,
+            constraint_evaluations,
+        })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds a FRI proof for the specified DEEP composition polynomial evaluations, and returns it
+/// together with the positions at which trace and constraint commitments should be queried.
+fn build_fri_proof<E, H, R>(
+    channel: &mut ProverChannel<E, H, R>,
+    options: &ProofOptions,
+    deep_evaluations: Vec<E>,
+    observer: &dyn ProverObserver,
+    token: Option<&CancellationToken>,
+) -> Result<(LowDegreeProof, Vec<usize>), ProverError>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    // compute FRI layers for the composition polynomial; building the layers is aborted if the
+    // cancellation token is cancelled in the process
+    let timer = StepTimer::start();
+    let lde_domain_size = deep_evaluations.len();
+    let mut fri_prover = FriProver::new(options.to_fri_options());
+    if let Some(token) = token {
+        fri_prover = fri_prover.with_cancellation_token(token.clone());
+    }
+    fri_prover.build_layers(channel, deep_evaluations)?;
+    // each layer is at most half the size of the previous one, and thus, all layers together
+    // (including their Merkle trees) occupy at most twice as much memory as the first layer
+    observer.on_event(&timer.event(
+        ProverStep::FriLayersBuilt {
+            num_layers: fri_prover.num_layers(),
+        },
+        2 * (matrix_bytes::<E>(lde_domain_size, 1) + tree_bytes::<H::FriHasher>(lde_domain_size)),
+    ));
+
+    // determine query positions
+    let timer = StepTimer::start();
+
+    // apply proof-of-work to the query seed
+    channel.grind_query_seed();
+
+    // generate pseudo-random query positions
+    let query_positions = channel.get_query_positions();
+    observer.on_event(&timer.event(
+        ProverStep::QueryPositionsDrawn {
+            num_queries: query_positions.len(),
+        },
+        0,
+    ));
+
+    let fri_proof = fri_prover.build_proof(&query_positions);
+    Ok((LowDegreeProof::Fri(fri_proof), query_positions))
+}
+
+/// Builds a STIR proof for the specified DEEP composition polynomial evaluations, and returns it
+/// together with the positions at which trace and constraint commitments should be queried.
+///
+/// The query positions are the positions queried in the first STIR round; the query seed
+/// grinding is performed by STIR before every round, and thus, no separate proof-of-work is
+/// applied to the query seed.
+fn build_stir_proof<E, H, R>(
+    channel: &mut ProverChannel<E, H, R>,
+    options: &ProofOptions,
+    deep_evaluations: Vec<E>,
+    observer: &dyn ProverObserver,
+    token: Option<&CancellationToken>,
+) -> Result<(LowDegreeProof, Vec<usize>), ProverError>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    // execute STIR rounds; the execution is aborted if the cancellation token is cancelled in the
+    // process
+    let timer = StepTimer::start();
+    let lde_domain_size = deep_evaluations.len();
+    let mut stir_prover = StirProver::new(options.to_stir_options());
+    if let Some(token) = token {
+        stir_prover = stir_prover.with_cancellation_token(token.clone());
+    }
+    stir_prover.build_rounds(channel, deep_evaluations)?;
+    // STIR evaluation domains shrink from round to round, and thus, evaluations committed to in
+    // all rounds together occupy at most twice as much memory as evaluations in the first round
+    observer.on_event(&timer.event(
+        ProverStep::StirRoundsBuilt {
+            num_rounds: stir_prover.num_rounds(),
+        },
+        2 * (matrix_bytes::<E>(lde_domain_size, 1) + tree_bytes::<H::FriHasher>(lde_domain_size)),
+    ));
+
+    let query_positions = stir_prover.query_positions().to_vec();
+    let stir_proof = stir_prover.build_proof();
+    Ok((LowDegreeProof::Stir(stir_proof), query_positions))
+}
+
+/// Returns [ProverError::Cancelled] if the specified cancellation token has been cancelled.
+fn check_cancelled(token: Option<&CancellationToken>) -> Result<(), ProverError> {
+    match token {
+        Some(token) if token.is_cancelled() => Err(ProverError::Cancelled),
+        _ => Ok(()),
+    }
+}
+
+/// Returns [ProverError::InvalidMerkleTreeArity] if a Merkle tree of the specified arity cannot be
+/// built over the specified number of leaves; that is, if `num_leaves` is not a power of `arity`
+/// greater than one.
+fn check_merkle_tree_leaves(arity: usize, num_leaves: usize) -> Result<(), ProverError> {
+    if num_leaves < arity || num_leaves.ilog2() % arity.ilog2() != 0 {
+        return Err(ProverError::InvalidMerkleTreeArity(arity, num_leaves));
+    }
+    Ok(())
+}
+
+/// Returns an estimate of the number of bytes occupied by the data structures built while
+/// generating a proof for the specified AIR in the field `E`.
+///
+/// The estimate is the sum of the sizes of:
+/// * trace polynomials and their evaluations over the LDE domain for all trace segments, as well
+///   as Merkle trees committing to the extended segments;
+/// * the constraint evaluation table; in table mode, the table is assumed to have a column for
+///   transition constraints and for every assertion (this is an upper bound, as assertions with
+///   the same divisor share a column);
+/// * composition polynomial columns and their evaluations over the LDE domain, as well as the
+///   Merkle tree committing to these evaluations;
+/// * evaluations of the DEEP composition polynomial, together with an upper bound on the size of
+///   all FRI layers (or STIR oracles) and their Merkle trees.
+///
+/// Some of these structures are released before the others are built, and thus, the estimate is
+/// an upper bound on memory usage, except for temporary allocations and the execution trace.
+fn estimate_memory_usage<P, E>(air: &P::Air, mode: ConstraintEvaluationMode) -> usize
+where
+    P: Prover + ?Sized,
+    E: FieldElement<BaseField = P::BaseField>,
+{
+    let context = air.context();
+    let layout = air.trace_layout();
+    let trace_length = context.trace_len();
+    let ce_domain_size = context.ce_domain_size();
+    let lde_domain_size = context.lde_domain_size();
+    let num_composition_columns = ce_domain_size / trace_length;
+
+    // execution trace: polynomials, evaluations over the LDE domain, and Merkle trees
+    let trace_bytes = (trace_length + lde_domain_size)
+        * (layout.main_trace_width() * P::BaseField::ELEMENT_BYTES
+            + layout.aux_trace_width() * E::ELEMENT_BYTES)
+        + (layout.num_aux_segments() + 1) * tree_bytes::<TraceHasher<P>>(lde_domain_size);
+
+    // constraint evaluation table
+    let num_ce_columns = match mode {
+        ConstraintEvaluationMode::Table => context.num_assertions() + 1,
+        ConstraintEvaluationMode::Streaming => 1,
+    };
+    let ce_bytes = matrix_bytes::<E>(ce_domain_size, num_ce_columns);
+
+    // composition polynomial: columns, evaluations over the LDE domain, and the Merkle tree
+    let composition_bytes =
+        matrix_bytes::<E>(trace_length + lde_domain_size, num_composition_columns)
+            + tree_bytes::<ConstraintHasher<P>>(lde_domain_size);
+
+    // DEEP composition polynomial evaluations and the low-degree test
+    let ldt_bytes = matrix_bytes::<E>(lde_domain_size, 1)
+        + 2 * (matrix_bytes::<E>(lde_domain_size, 1)
+            + tree_bytes::<<P::HashFn as CommitmentHashers>::FriHasher>(lde_domain_size));
+
+    trace_bytes + ce_bytes + composition_bytes + ldt_bytes
+}
@@ -35,6 +35,7 @@ pub struct MockAir {
     context: AirContext<BaseElement>,
     assertions: Vec<Assertion<BaseElement>>,
     periodic_columns: Vec<Vec<BaseElement>>,
+    domain_offset: Option<BaseElement>,
 }
 
 impl MockAir {
@@ -59,6 +60,12 @@ impl MockAir {
         result
     }
 
+    pub fn with_domain_offset(domain_offset: BaseElement, trace_length: usize) -> Self {
+        let mut result = Self::with_trace_length(trace_length);
+        result.domain_offset = Some(domain_offset);
+        result
+    }
+
     pub fn with_assertions(assertions: Vec<Assertion<BaseElement>>, trace_length: usize) -> Self {
         let mut result = Self::new(
             TraceInfo::new(4, trace_length),
@@ -80,6 +87,7 @@ impl Air for MockAir {
             context,
             assertions: Vec::new(),
             periodic_columns: Vec::new(),
+            domain_offset: None,
         }
     }
 
@@ -102,6 +110,11 @@ impl Air for MockAir {
     fn get_periodic_column_values(&self) -> Vec<Vec<Self::BaseField>> {
         self.periodic_columns.clone()
     }
+
+    fn domain_offset(&self) -> Self::BaseField {
+        self.domain_offset
+            .unwrap_or_else(|| self.options().domain_offset())
+    }
 }
 
 // HELPER FUNCTIONS
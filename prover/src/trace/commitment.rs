@@ -7,7 +7,9 @@ use crate::RowMatrix;
 use air::proof::Queries;
 use crypto::{ElementHasher, MerkleTree};
 use math::FieldElement;
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 use super::TraceLde;
 
@@ -75,19 +77,25 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceCommitmen
     // QUERY TRACE
     // --------------------------------------------------------------------------------------------
     /// Returns trace table rows at the specified positions along with Merkle authentication paths
-    /// from the commitment root to these rows.
-    pub fn query(&self, positions: &[usize]) -> Vec<Queries> {
+    /// from the commitment cap of the specified height to these rows.
+    pub fn query(&self, positions: &[usize], cap_height: usize) -> Vec<Queries> {
         // build queries for the main trace segment
         let mut result = vec![build_segment_queries(
             self.trace_lde.get_main_segment(),
             &self.main_segment_tree,
             positions,
+            cap_height,
         )];
 
         // build queries for auxiliary trace segments
         for (i, segment_tree) in self.aux_segment_trees.iter().enumerate() {
             let segment_lde = self.trace_lde.get_aux_segment(i);
-            result.push(build_segment_queries(segment_lde, segment_tree, positions));
+            result.push(build_segment_queries(
+                segment_lde,
+                segment_tree,
+                positions,
+                cap_height,
+            ));
         }
 
         result
@@ -112,13 +120,72 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceCommitmen
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> Serializable
+    for TraceCommitment<E, H>
+{
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.trace_lde.write_into(target);
+        self.main_segment_tree.write_into(target);
+        for segment_tree in self.aux_segment_trees.iter() {
+            segment_tree.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> Deserializable
+    for TraceCommitment<E, H>
+{
+    /// Reads a trace commitment from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid trace commitment could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let trace_lde = TraceLde::<E>::read_from(source)?;
+        let main_segment_tree = MerkleTree::<H>::read_from(source)?;
+        validate_num_leaves(&main_segment_tree, trace_lde.trace_len())?;
+
+        let mut aux_segment_trees = Vec::new();
+        for _ in 0..trace_lde.num_aux_segments() {
+            let segment_tree = MerkleTree::<H>::read_from(source)?;
+            validate_num_leaves(&segment_tree, trace_lde.trace_len())?;
+            aux_segment_trees.push(segment_tree);
+        }
+
+        Ok(Self {
+            trace_lde,
+            main_segment_tree,
+            aux_segment_trees,
+        })
+    }
+}
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
+/// Returns an error if the number of leaves in the specified tree is not equal to the number of
+/// rows in the trace LDE.
+fn validate_num_leaves<H: ElementHasher>(
+    tree: &MerkleTree<H>,
+    num_rows: usize,
+) -> Result<(), DeserializationError> {
+    if tree.leaves().len() != num_rows {
+        return Err(DeserializationError::InvalidValue(format!(
+            "number of leaves in trace commitment must be {num_rows}, but was {}",
+            tree.leaves().len()
+        )));
+    }
+    Ok(())
+}
+
 fn build_segment_queries<E, H>(
     segment_lde: &RowMatrix<E>,
     segment_tree: &MerkleTree<H>,
     positions: &[usize],
+    cap_height: usize,
 ) -> Queries
 where
     E: FieldElement,
@@ -133,7 +200,7 @@ where
 
     // build Merkle authentication paths to the leaves specified by positions
     let trace_proof = segment_tree
-        .prove_batch(positions)
+        .prove_batch_with_cap(positions, cap_height)
         .expect("failed to generate a Merkle proof for trace queries");
 
     Queries::new(trace_proof, trace_states)
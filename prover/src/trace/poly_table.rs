@@ -8,7 +8,9 @@ use crate::{
     ColMatrix,
 };
 use math::{FieldElement, StarkField};
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 // TRACE POLYNOMIAL TABLE
 // ================================================================================================
@@ -18,6 +20,7 @@ use utils::collections::Vec;
 /// Coefficients of the polynomials for the main trace segment are always in the base field.
 /// However, coefficients of the polynomials for the auxiliary trace segments may be either in the
 /// base field, or in the extension field, depending on whether extension field is being used.
+#[derive(Clone)]
 pub struct TracePolyTable<E: FieldElement> {
     main_segment_polys: ColMatrix<E::BaseField>,
     aux_segment_polys: Vec<ColMatrix<E>>,
@@ -72,12 +75,12 @@ impl<E: FieldElement> TracePolyTable<E> {
     }
 
     /// Returns an iterator over the polynomials of the main trace segment.
-    pub fn main_trace_polys(&self) -> ColumnIter<E::BaseField> {
+    pub fn main_trace_polys(&self) -> ColumnIter<'_, E::BaseField> {
         self.main_segment_polys.columns()
     }
 
     /// Returns an iterator over the polynomials of all auxiliary trace segments.
-    pub fn aux_trace_polys(&self) -> MultiColumnIter<E> {
+    pub fn aux_trace_polys(&self) -> MultiColumnIter<'_, E> {
         MultiColumnIter::new(self.aux_segment_polys.as_slice())
     }
 
@@ -96,3 +99,46 @@ impl<E: FieldElement> TracePolyTable<E> {
         self.main_segment_polys.get_column(idx)
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement> Serializable for TracePolyTable<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.main_segment_polys.write_into(target);
+        target.write_u8(self.aux_segment_polys.len() as u8);
+        for aux_segment_polys in self.aux_segment_polys.iter() {
+            aux_segment_polys.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement> Deserializable for TracePolyTable<E> {
+    /// Reads a table of trace polynomials from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid trace polynomial table could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let main_segment_polys = ColMatrix::read_from(source)?;
+
+        let num_aux_segments = source.read_u8()? as usize;
+        let mut aux_segment_polys = Vec::with_capacity(num_aux_segments);
+        for _ in 0..num_aux_segments {
+            let polys = ColMatrix::<E>::read_from(source)?;
+            if polys.num_rows() != main_segment_polys.num_rows() {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "polynomials in auxiliary segment must be of size {}, but were {}",
+                    main_segment_polys.num_rows(),
+                    polys.num_rows()
+                )));
+            }
+            aux_segment_polys.push(polys);
+        }
+
+        Ok(Self {
+            main_segment_polys,
+            aux_segment_polys,
+        })
+    }
+}
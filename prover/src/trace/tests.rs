@@ -49,7 +49,7 @@ fn extend_trace_table() {
     // build extended trace commitment
     let trace_polys = trace.main_segment().interpolate_columns();
     let trace_lde = RowMatrix::evaluate_polys_over::<8>(&trace_polys, &domain);
-    let trace_tree = trace_lde.commit_to_rows::<Blake3>();
+    let trace_tree = trace_lde.commit_to_rows::<Blake3>(2);
     let trace_comm = TraceCommitment::<BaseElement, Blake3>::new(
         trace_lde,
         trace_tree,
@@ -102,7 +102,7 @@ fn commit_trace_table() {
     // build extended trace commitment
     let trace_polys = trace.main_segment().interpolate_columns();
     let trace_lde = RowMatrix::evaluate_polys_over::<8>(&trace_polys, &domain);
-    let trace_tree = trace_lde.commit_to_rows::<Blake3>();
+    let trace_tree = trace_lde.commit_to_rows::<Blake3>(2);
     let trace_comm = TraceCommitment::<BaseElement, Blake3>::new(
         trace_lde,
         trace_tree,
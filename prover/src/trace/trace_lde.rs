@@ -4,9 +4,11 @@
 // LICENSE file in the root directory of this source tree.
 
 use crate::RowMatrix;
-use air::EvaluationFrame;
+use air::EvaluationFrameBatch;
 use math::FieldElement;
-use utils::collections::Vec;
+use utils::{
+    collections::Vec, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
+};
 
 // TRACE LOW DEGREE EXTENSION
 // ================================================================================================
@@ -56,6 +58,11 @@ impl<E: FieldElement> TraceLde<E> {
         self.main_segment_lde.num_cols()
     }
 
+    /// Returns the number of auxiliary segments in the execution trace.
+    pub fn num_aux_segments(&self) -> usize {
+        self.aux_segment_ldes.len()
+    }
+
     /// Returns number of columns in the auxiliary segments of the execution trace.
     pub fn aux_trace_width(&self) -> usize {
         self.aux_segment_ldes
@@ -73,37 +80,51 @@ impl<E: FieldElement> TraceLde<E> {
         self.blowup
     }
 
-    /// Reads current and next rows from the main trace segment into the specified frame.
-    pub fn read_main_trace_frame_into(
+    /// Reads current and next rows for a batch of LDE steps from the main trace segment into the
+    /// specified frame batch.
+    ///
+    /// The batch starts at `lde_step`, and each subsequent frame is `lde_step_stride` steps
+    /// after the previous one.
+    pub fn read_main_trace_batch_into(
         &self,
         lde_step: usize,
-        frame: &mut EvaluationFrame<E::BaseField>,
+        lde_step_stride: usize,
+        batch: &mut EvaluationFrameBatch<E::BaseField>,
     ) {
-        // at the end of the trace, next state wraps around and we read the first step again
-        let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
-
-        // copy main trace segment values into the frame
-        frame
-            .current_mut()
-            .copy_from_slice(self.main_segment_lde.row(lde_step));
-        frame
-            .next_mut()
-            .copy_from_slice(self.main_segment_lde.row(next_lde_step));
+        for row in 0..batch.num_rows() {
+            let lde_step = lde_step + row * lde_step_stride;
+            // at the end of the trace, next state wraps around and we read the first step again
+            let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
+            batch.set_frame(
+                row,
+                self.main_segment_lde.row(lde_step),
+                self.main_segment_lde.row(next_lde_step),
+            );
+        }
     }
 
-    /// Reads current and next rows from the auxiliary trace segment into the specified frame.
+    /// Reads current and next rows for a batch of LDE steps from the auxiliary trace segment into
+    /// the specified frame batch.
+    ///
+    /// The batch starts at `lde_step`, and each subsequent frame is `lde_step_stride` steps
+    /// after the previous one.
     ///
     /// # Panics
     /// This currently assumes that there is exactly one auxiliary trace segment, and will panic
     /// otherwise.
-    pub fn read_aux_trace_frame_into(&self, lde_step: usize, frame: &mut EvaluationFrame<E>) {
-        // at the end of the trace, next state wraps around and we read the first step again
-        let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
-
-        // copy auxiliary trace segment values into the frame
+    pub fn read_aux_trace_batch_into(
+        &self,
+        lde_step: usize,
+        lde_step_stride: usize,
+        batch: &mut EvaluationFrameBatch<E>,
+    ) {
         let segment = &self.aux_segment_ldes[0];
-        frame.current_mut().copy_from_slice(segment.row(lde_step));
-        frame.next_mut().copy_from_slice(segment.row(next_lde_step));
+        for row in 0..batch.num_rows() {
+            let lde_step = lde_step + row * lde_step_stride;
+            // at the end of the trace, next state wraps around and we read the first step again
+            let next_lde_step = (lde_step + self.blowup()) % self.trace_len();
+            batch.set_frame(row, segment.row(lde_step), segment.row(next_lde_step));
+        }
     }
 
     /// Returns a reference to [Matrix] representing the main trace segment.
@@ -117,3 +138,50 @@ impl<E: FieldElement> TraceLde<E> {
         &self.aux_segment_ldes[aux_segment_idx]
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<E: FieldElement> Serializable for TraceLde<E> {
+    /// Serializes `self` and writes the resulting bytes into the `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u32(self.blowup as u32);
+        self.main_segment_lde.write_into(target);
+        target.write_u8(self.aux_segment_ldes.len() as u8);
+        for aux_segment_lde in self.aux_segment_ldes.iter() {
+            aux_segment_lde.write_into(target);
+        }
+    }
+}
+
+impl<E: FieldElement> Deserializable for TraceLde<E> {
+    /// Reads a trace low-degree extension table from the specified `source` and returns the
+    /// result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid trace LDE could not be read from the `source`.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let blowup = source.read_u32()? as usize;
+        let main_segment_lde = RowMatrix::read_from(source)?;
+
+        let num_aux_segments = source.read_u8()? as usize;
+        let mut aux_segment_ldes = Vec::with_capacity(num_aux_segments);
+        for _ in 0..num_aux_segments {
+            let aux_segment_lde = RowMatrix::<E>::read_from(source)?;
+            if aux_segment_lde.num_rows() != main_segment_lde.num_rows() {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "auxiliary trace segment must have {} rows, but had {}",
+                    main_segment_lde.num_rows(),
+                    aux_segment_lde.num_rows()
+                )));
+            }
+            aux_segment_ldes.push(aux_segment_lde);
+        }
+
+        Ok(Self {
+            main_segment_lde,
+            aux_segment_ldes,
+            blowup,
+        })
+    }
+}
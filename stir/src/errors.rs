@@ -0,0 +1,85 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::fmt;
+
+use crypto::RandomCoinError;
+
+// VERIFIER ERROR
+// ================================================================================================
+
+/// Defines errors which can occur during STIR proof verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifierError {
+    /// Attempt to draw a random value from a public coin failed.
+    RandomCoinError(RandomCoinError),
+    /// Number of oracle commitments does not match the number of STIR rounds.
+    NumCommitmentsMismatch(usize, usize),
+    /// Number of out-of-domain answers does not match the number of STIR rounds.
+    NumOodAnswersMismatch(usize, usize),
+    /// Number of proof-of-work nonces does not match the number of STIR rounds.
+    NumRoundNoncesMismatch(usize, usize),
+    /// Proof-of-work nonce for one of the STIR rounds did not meet the grinding requirement.
+    RoundProofOfWorkVerificationFailed(usize),
+    /// Number of query positions does not match the number of provided evaluations.
+    NumPositionEvaluationMismatch(usize, usize),
+    /// Degree bound of the tested polynomial is not one less than a power of two.
+    UnsupportedDegree(usize),
+    /// Evaluations at queried positions did not match oracle commitment made by the prover.
+    OracleCommitmentMismatch,
+    /// Evaluations provided to the verifier did not match the values of the first oracle.
+    InitialEvaluationsMismatch,
+    /// One of the points in an oracle domain is also a point at which the oracle was sampled.
+    QuotientUndefined(usize),
+    /// Final polynomial has a number of coefficients different from the one expected after the
+    /// last folding.
+    FinalPolyDegreeMismatch(usize, usize),
+    /// Folding was not performed correctly in the last STIR round.
+    InvalidFinalFolding,
+}
+
+impl fmt::Display for VerifierError {
+    #[rustfmt::skip]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RandomCoinError(err) => {
+                write!(f, "failed to draw a random value from the public coin: {err}")
+            }
+            Self::NumCommitmentsMismatch(expected, actual) => {
+                write!(f, "expected {expected} STIR oracle commitments, but {actual} were provided")
+            }
+            Self::NumOodAnswersMismatch(expected, actual) => {
+                write!(f, "expected {expected} STIR out-of-domain answers, but {actual} were provided")
+            }
+            Self::NumRoundNoncesMismatch(expected, actual) => {
+                write!(f, "expected {expected} STIR round proof-of-work nonces, but {actual} were provided")
+            }
+            Self::RoundProofOfWorkVerificationFailed(round) => {
+                write!(f, "proof-of-work verification failed at round {round}")
+            }
+            Self::NumPositionEvaluationMismatch(num_positions, num_evaluations) => write!(f,
+                "the number of query positions must be the same as the number of polynomial evaluations, but {num_positions} and {num_evaluations} were provided"
+            ),
+            Self::UnsupportedDegree(degree) => {
+                write!(f, "degree bound {degree} is not one less than a power of two")
+            }
+            Self::OracleCommitmentMismatch => {
+                write!(f, "STIR queries did not match oracle commitment made by the prover")
+            }
+            Self::InitialEvaluationsMismatch => {
+                write!(f, "polynomial evaluations did not match the values of the first STIR oracle")
+            }
+            Self::QuotientUndefined(round) => {
+                write!(f, "quotient is undefined at a queried point in round {round}")
+            }
+            Self::FinalPolyDegreeMismatch(expected, actual) => {
+                write!(f, "expected STIR final polynomial with {expected} coefficients, but it had {actual}")
+            }
+            Self::InvalidFinalFolding => {
+                write!(f, "folding is inconsistent with the final polynomial at the last STIR round")
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! This crate contains an implementation of the STIR protocol which can be used by the
+//! Winterfell STARK prover and verifier as an alternative to FRI.
+//!
+//! STIR stands for Shift To Improve Rate, and, like FRI, is an interactive oracle proof of
+//! proximity to Reed-Solomon codes: given a commitment to a set of evaluations of some function
+//! over domain *D*, the verifier can be convinced that the function is a polynomial of degree at
+//! most *d*, by making a small number of queries to the commitment.
+//!
+//! In each round of FRI, both the degree of the tested polynomial and the size of the evaluation
+//! domain are reduced by the folding factor *k*, and thus the rate of the code stays the same. In
+//! each round of STIR, the degree is reduced by *k* while the domain is only halved. Since the
+//! rate of the code improves with every round, fewer queries are needed in later rounds to
+//! achieve the same soundness, which results in significantly smaller proofs.
+//!
+//! # Proof generation
+//! STIR proofs are generated by a [StirProver] in two steps:
+//!
+//! 1. First, all rounds of the protocol are executed via
+//!    [build_rounds()](StirProver::build_rounds()) function. During this phase, the
+//!    prover writes oracle commitments and out-of-domain answers into the [ProverChannel], and
+//!    draws challenges and query positions from it. The commitments should be recorded and sent
+//!    to the verifier as they will be needed during the proof verification procedure.
+//! 2. Then, the proof is built via [build_proof()](StirProver::build_proof()) function.
+//!    The output of this function is an instance of the [StirProof] struct. When STIR is
+//!    executed as a part of the STARK protocol, STIR proof is included into a STARK proof.
+//!
+//! # Proof verification
+//! STIR proofs are verified by a [StirVerifier] as follows:
+//! 1. First, a STIR proof needs to be converted into a [VerifierChannel]. This crate provides a
+//!    default implementation of the verifier channel, but when STIR proof verification is
+//!    executed as a part of the larger STARK protocol, STARK verifier handles this conversion.
+//! 2. Then, a [StirVerifier] should be instantiated (via [new()](StirVerifier::new()) function).
+//!    This will replay all rounds of the STIR protocol from the verifier's perspective - i.e.,
+//!    the verifier will read the prover's messages from the channel, and generate all challenges
+//!    and query positions.
+//! 3. Finally, the query phase of the STIR protocol should be executed via
+//!    [verify()](StirVerifier::verify()) function. Note that evaluations of the tested
+//!    polynomial at [query_positions()](StirVerifier::query_positions()) are provided to the
+//!    [verify()](StirVerifier::verify()) function directly. The values of all other oracles, the
+//!    verifier reads from the specified verifier channel.
+//!
+//! # Protocol parameters
+//! The current implementation supports executing STIR protocol with dynamically configurable
+//! parameters including:
+//!
+//! * Base STARK field,
+//! * Extension field,
+//! * Domain blowup factor,
+//! * Hash function (used for Merkle tree commitments),
+//! * Folding factor (used for degree reduction in each STIR round),
+//! * Maximum degree of the final polynomial,
+//! * Number of queries in the first STIR round.
+//!
+//! # References
+//! * [STIR: Reed–Solomon Proximity Testing with Fewer Queries](https://eprint.iacr.org/2024/390)
+//! * [Fast Reed-Solomon Interactive Oracle Proofs of Proximity](https://eccc.weizmann.ac.il/report/2017/134/)
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+
+mod prover;
+pub use prover::{DefaultProverChannel, ProverChannel, StirProver};
+
+mod verifier;
+pub use verifier::{DefaultVerifierChannel, StirVerifier, VerifierChannel};
+
+mod options;
+pub use options::{StirOptions, NUM_OOD_SAMPLES};
+
+mod proof;
+pub use proof::StirProof;
+
+mod errors;
+pub use errors::VerifierError;
@@ -0,0 +1,174 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use math::StarkField;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Number of out-of-domain samples drawn by the verifier in each STIR round.
+pub const NUM_OOD_SAMPLES: usize = 2;
+
+// STIR OPTIONS
+// ================================================================================================
+
+/// STIR protocol config options for proof generation and verification.
+#[derive(Clone, PartialEq, Eq)]
+pub struct StirOptions {
+    folding_factor: usize,
+    remainder_max_degree: usize,
+    blowup_factor: usize,
+    num_queries: usize,
+    merkle_cap_height: usize,
+    grinding_factor: u32,
+}
+
+impl StirOptions {
+    /// Returns a new [StirOptions] struct instantiated with the specified parameters.
+    ///
+    /// The `num_queries` parameter specifies the number of queries made in the first STIR round;
+    /// the number of queries in the subsequent rounds is derived from it (see
+    /// [num_round_queries()](StirOptions::num_round_queries)).
+    ///
+    /// # Panics
+    /// Panics if:
+    /// - `blowup_factor` is smaller than 2 or is not a power of two.
+    /// - `folding_factor` is not 2, 4, 8, or 16.
+    /// - `num_queries` is zero.
+    pub fn new(
+        blowup_factor: usize,
+        folding_factor: usize,
+        remainder_max_degree: usize,
+        num_queries: usize,
+    ) -> Self {
+        assert!(
+            blowup_factor.is_power_of_two(),
+            "blowup factor must be a power of two, but was {blowup_factor}"
+        );
+        assert!(
+            blowup_factor > 1,
+            "blowup factor must be greater than 1, but was {blowup_factor}"
+        );
+        assert!(
+            folding_factor == 2
+                || folding_factor == 4
+                || folding_factor == 8
+                || folding_factor == 16,
+            "folding factor {folding_factor} is not supported"
+        );
+        assert!(
+            num_queries > 0,
+            "number of queries must be greater than zero"
+        );
+        StirOptions {
+            folding_factor,
+            remainder_max_degree,
+            blowup_factor,
+            num_queries,
+            merkle_cap_height: 0,
+            grinding_factor: 0,
+        }
+    }
+
+    /// Updates the height of Merkle tree caps used to commit to STIR oracles.
+    ///
+    /// When the cap height is `h`, the prover commits to all 2^h nodes at depth `h` of each
+    /// oracle tree (rather than to the tree root), and authentication paths for oracle queries
+    /// stop at the cap. By default, cap height is set to 0.
+    pub fn with_merkle_cap_height(mut self, merkle_cap_height: usize) -> Self {
+        self.merkle_cap_height = merkle_cap_height;
+        self
+    }
+
+    /// Updates the number of bits of proof-of-work grinding performed before drawing query
+    /// positions in each STIR round.
+    ///
+    /// When the grinding factor is `g` and greater than 0, the prover searches for a nonce which,
+    /// when absorbed into the public coin, results in at least `g` leading zeros. The nonce is
+    /// absorbed before the query positions for the round are drawn, and is included in the
+    /// proof. By default, the grinding factor is set to 0 (no grinding).
+    pub fn with_grinding_factor(mut self, grinding_factor: u32) -> Self {
+        self.grinding_factor = grinding_factor;
+        self
+    }
+
+    /// Returns the offset by which the evaluation domain of the first STIR oracle is shifted.
+    ///
+    /// The domain of each subsequent oracle consists of the elements at odd positions of the
+    /// previous domain; thus, it is shifted by a different offset.
+    pub fn domain_offset<B: StarkField>(&self) -> B {
+        B::GENERATOR
+    }
+
+    /// Returns the factor by which the degree of a polynomial is reduced in each STIR round.
+    pub fn folding_factor(&self) -> usize {
+        self.folding_factor
+    }
+
+    /// Returns maximum allowed remainder polynomial degree.
+    ///
+    /// The final polynomial sent by the prover has at most `remainder_max_degree + 1`
+    /// coefficients.
+    pub fn remainder_max_degree(&self) -> usize {
+        self.remainder_max_degree
+    }
+
+    /// Returns a blowup factor of the evaluation domain of the first STIR oracle.
+    pub fn blowup_factor(&self) -> usize {
+        self.blowup_factor
+    }
+
+    /// Returns the number of queries made in the first STIR round.
+    pub fn num_queries(&self) -> usize {
+        self.num_queries
+    }
+
+    /// Returns the height of Merkle tree caps used to commit to STIR oracles.
+    pub fn merkle_cap_height(&self) -> usize {
+        self.merkle_cap_height
+    }
+
+    /// Returns the number of bits of proof-of-work grinding performed before drawing query
+    /// positions in each STIR round.
+    pub fn grinding_factor(&self) -> u32 {
+        self.grinding_factor
+    }
+
+    /// Computes and return the number of STIR rounds required for the specified domain size.
+    ///
+    /// In each round, the degree of the tested polynomial is reduced by the folding factor while
+    /// the size of the evaluation domain is halved. Rounds are executed until folding the
+    /// polynomial once more results in a polynomial with at most `remainder_max_degree + 1`
+    /// coefficients; this final folding does not count as a round.
+    pub fn num_rounds(&self, domain_size: usize) -> usize {
+        let mut result = 0;
+        let mut degree_plus_1 = domain_size / self.blowup_factor;
+        while degree_plus_1 / self.folding_factor > self.remainder_max_degree + 1 {
+            degree_plus_1 /= self.folding_factor;
+            result += 1;
+        }
+        result
+    }
+
+    /// Returns the log2 of the inverse of the rate of the oracle queried in the specified round.
+    ///
+    /// Since the degree is reduced by the folding factor `k` while the domain size is only
+    /// halved, the rate of the oracle in round `i` is ρ * (2 / k)^i, where ρ = 1 / blowup factor.
+    pub fn round_rate_bits(&self, round: usize) -> u32 {
+        self.blowup_factor.ilog2() + round as u32 * (self.folding_factor.ilog2() - 1)
+    }
+
+    /// Returns the number of queries made in the specified STIR round.
+    ///
+    /// The number of queries in round `i` is chosen such that each round contributes at least as
+    /// many bits of query security as the first round: i.e., t_i = ceil(t_0 * log2(1 / ρ_0) /
+    /// log2(1 / ρ_i)), where t_0 is the number of queries in the first round and ρ_i is the rate
+    /// of the oracle in round `i`.
+    pub fn num_round_queries(&self, round: usize) -> usize {
+        let query_bits = self.num_queries * self.round_rate_bits(0) as usize;
+        let rate_bits = self.round_rate_bits(round) as usize;
+        (query_bits + rate_bits - 1) / rate_bits
+    }
+}
@@ -0,0 +1,421 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{options::NUM_OOD_SAMPLES, StirOptions};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher};
+use math::FieldElement;
+use utils::{
+    collections::Vec, string::ToString, ByteReader, ByteWriter, Deserializable,
+    DeserializationError, Serializable, SliceReader,
+};
+
+// STIR PROOF
+// ================================================================================================
+
+/// A proof generated by a STIR prover.
+///
+/// A STIR proof contains information proving that a function *f* is a polynomial of some bounded
+/// degree *d*. STIR proofs cannot be instantiated directly - they must be generated by a instance
+/// of a [StirProver](crate::StirProver), and can be verified by a instance of a
+/// [StirVerifier](crate::StirVerifier) via [VerifierChannel](crate::VerifierChannel) interface.
+///
+/// A proof consists of one opening for each oracle committed to by the prover, the answers to
+/// out-of-domain queries made in each STIR round, and a final polynomial. Each opening contains
+/// a set of oracle evaluations at positions queried by the verifier as well as Merkle
+/// authentication paths for these evaluations (the Merkle paths are compressed into a batch
+/// Merkle proof). The final polynomial is given by its list of coefficients. When proof-of-work
+/// grinding is performed before drawing query positions, the proof also contains the
+/// proof-of-work nonce for each STIR round.
+///
+/// All values in a proof are stored as vectors of bytes. Thus, the values must be parsed before
+/// they can be returned to the user. To do this, [parse_openings()](StirProof::parse_openings()),
+/// [parse_ood_answers()](StirProof::parse_ood_answers()), and
+/// [parse_final_poly()](StirProof::parse_final_poly()) methods can be used.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StirProof {
+    openings: Vec<StirProofOpening>,
+    ood_answers: Vec<u8>,
+    final_poly: Vec<u8>,
+    pow_nonces: Vec<u64>,
+}
+
+impl StirProof {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Creates a new STIR proof from the provided oracle openings, out-of-domain answers, final
+    /// polynomial, and round proof-of-work nonces.
+    ///
+    /// # Panics
+    /// Panics if `openings` or `final_poly` is empty.
+    pub(crate) fn new<E: FieldElement>(
+        openings: Vec<StirProofOpening>,
+        ood_answers: Vec<E>,
+        final_poly: Vec<E>,
+        pow_nonces: Vec<u64>,
+    ) -> Self {
+        assert!(
+            !openings.is_empty(),
+            "number of openings must be greater than zero"
+        );
+        assert!(
+            !final_poly.is_empty(),
+            "number of final polynomial coefficients must be greater than zero"
+        );
+        StirProof {
+            openings,
+            ood_answers: ood_answers.to_bytes(),
+            final_poly: final_poly.to_bytes(),
+            pow_nonces,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of STIR rounds in this proof.
+    ///
+    /// The number of oracles committed to by the prover is one greater than the number of rounds.
+    pub fn num_rounds(&self) -> usize {
+        self.openings.len() - 1
+    }
+
+    /// Returns proof-of-work nonces found by the prover before drawing query positions in each
+    /// STIR round (including the final one).
+    ///
+    /// The nonces are empty if no proof-of-work grinding was performed.
+    pub fn pow_nonces(&self) -> &[u64] {
+        &self.pow_nonces
+    }
+
+    /// Returns the size of this proof in bytes.
+    pub fn size(&self) -> usize {
+        // +1 for number of openings, +2 for number of out-of-domain answer bytes, +2 for final
+        // polynomial length, +1 for number of nonces
+        self.openings.iter().fold(
+            self.ood_answers.len() + self.final_poly.len() + self.pow_nonces.len() * 8 + 6,
+            |acc, opening| acc + opening.size(),
+        )
+    }
+
+    // PARSING
+    // --------------------------------------------------------------------------------------------
+
+    /// Decomposes this proof into vectors of queried oracle values for each STIR round and
+    /// corresponding Merkle authentication paths for each query (grouped into batch Merkle
+    /// proofs).
+    ///
+    /// # Panics
+    /// Panics if `domain_size` is not a power of two.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of oracle openings in this proof is not consistent with the specified
+    ///   `domain_size` and `options`.
+    /// * Any of the openings could not be parsed successfully.
+    #[allow(clippy::type_complexity)]
+    pub fn parse_openings<H, E>(
+        self,
+        mut domain_size: usize,
+        options: &StirOptions,
+    ) -> Result<(Vec<Vec<E>>, Vec<BatchMerkleProof<H>>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        assert!(
+            domain_size.is_power_of_two(),
+            "domain size must be a power of two"
+        );
+
+        let num_rounds = options.num_rounds(domain_size);
+        if self.openings.len() != num_rounds + 1 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {} STIR oracle openings, but {} were provided",
+                num_rounds + 1,
+                self.openings.len()
+            )));
+        }
+
+        let mut opening_proofs = Vec::new();
+        let mut opening_queries = Vec::new();
+
+        // parse all openings; the domain of each oracle is half the size of the previous one
+        let folding_factor = options.folding_factor();
+        for (i, opening) in self.openings.into_iter().enumerate() {
+            let (qv, mp) = opening
+                .parse(domain_size / folding_factor, folding_factor)
+                .map_err(|err| {
+                    DeserializationError::InvalidValue(format!(
+                        "failed to parse STIR opening {i}: {err}"
+                    ))
+                })?;
+            opening_proofs.push(mp);
+            opening_queries.push(qv);
+            domain_size /= 2;
+        }
+
+        Ok((opening_queries, opening_proofs))
+    }
+
+    /// Returns a vector of out-of-domain answers for all STIR rounds parsed from this proof.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The number of answers is not a multiple of the number of out-of-domain samples per
+    ///   round.
+    /// * Any of the answers could not be parsed correctly.
+    /// * Not all bytes have been consumed while parsing the answers.
+    pub fn parse_ood_answers<E: FieldElement>(&self) -> Result<Vec<E>, DeserializationError> {
+        let num_elements = self.ood_answers.len() / E::ELEMENT_BYTES;
+        if num_elements % NUM_OOD_SAMPLES != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of out-of-domain answers must be a multiple of {NUM_OOD_SAMPLES}, but {num_elements} was implied"
+            )));
+        }
+        let mut reader = SliceReader::new(&self.ood_answers);
+        let answers = E::read_batch_from(&mut reader, num_elements).map_err(|err| {
+            DeserializationError::InvalidValue(format!(
+                "failed to parse STIR out-of-domain answers: {err}"
+            ))
+        })?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(answers)
+    }
+
+    /// Returns the coefficients of the final polynomial parsed from this proof.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * Any of the coefficients could not be parsed correctly.
+    /// * Not all bytes have been consumed while parsing the coefficients.
+    pub fn parse_final_poly<E: FieldElement>(&self) -> Result<Vec<E>, DeserializationError> {
+        let num_elements = self.final_poly.len() / E::ELEMENT_BYTES;
+        let mut reader = SliceReader::new(&self.final_poly);
+        let final_poly = E::read_batch_from(&mut reader, num_elements).map_err(|err| {
+            DeserializationError::InvalidValue(format!(
+                "failed to parse STIR final polynomial: {err}"
+            ))
+        })?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+        Ok(final_poly)
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl Serializable for StirProof {
+    /// Serializes `self` and writes the resulting bytes into the `target` writer.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write oracle openings
+        target.write_u8(self.openings.len() as u8);
+        for opening in self.openings.iter() {
+            opening.write_into(target);
+        }
+
+        // write out-of-domain answers
+        target.write_u16(self.ood_answers.len() as u16);
+        target.write_bytes(&self.ood_answers);
+
+        // write final polynomial
+        target.write_u16(self.final_poly.len() as u16);
+        target.write_bytes(&self.final_poly);
+
+        // write proof-of-work nonces
+        target.write_u8(self.pow_nonces.len() as u8);
+        for &nonce in self.pow_nonces.iter() {
+            target.write_u64(nonce);
+        }
+    }
+}
+
+impl Deserializable for StirProof {
+    /// Reads a STIR proof from the specified `source` and returns the result.
+    ///
+    /// # Errors
+    /// Returns an error if a valid proof could not be read from the source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read oracle openings
+        let num_openings = source.read_u8()? as usize;
+        if num_openings == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a STIR proof must contain at least one oracle opening".to_string(),
+            ));
+        }
+        let openings = StirProofOpening::read_batch_from(source, num_openings)?;
+
+        // read out-of-domain answers
+        let num_answer_bytes = source.read_u16()? as usize;
+        let ood_answers = source.read_vec(num_answer_bytes)?;
+
+        // read final polynomial
+        let num_final_poly_bytes = source.read_u16()? as usize;
+        if num_final_poly_bytes == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a STIR final polynomial must contain at least one coefficient".to_string(),
+            ));
+        }
+        let final_poly = source.read_vec(num_final_poly_bytes)?;
+
+        // read proof-of-work nonces
+        let num_nonces = source.read_u8()? as usize;
+        let pow_nonces = (0..num_nonces)
+            .map(|_| source.read_u64())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(StirProof {
+            openings,
+            ood_answers,
+            final_poly,
+            pow_nonces,
+        })
+    }
+}
+
+// STIR PROOF OPENING
+// ================================================================================================
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StirProofOpening {
+    values: Vec<u8>,
+    paths: Vec<u8>,
+}
+
+impl StirProofOpening {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Creates a new oracle opening from the specified query values and the corresponding Merkle
+    /// paths aggregated into a single batch Merkle proof.
+    ///
+    /// # Panics
+    /// Panics if `query_values` is an empty slice.
+    pub(crate) fn new<H: Hasher, E: FieldElement, const N: usize>(
+        query_values: Vec<[E; N]>,
+        merkle_proof: BatchMerkleProof<H>,
+    ) -> Self {
+        assert!(!query_values.is_empty(), "query values cannot be empty");
+
+        // concatenate all query values and all internal Merkle proof nodes into vectors of bytes;
+        // we care about internal nodes only because leaf nodes can be reconstructed from hashes
+        // of query values
+        StirProofOpening {
+            values: query_values.to_bytes(),
+            paths: merkle_proof.serialize_nodes(),
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the size of this opening in bytes.
+    pub fn size(&self) -> usize {
+        // +4 for length of values, +4 for length of paths
+        self.values.len() + 4 + self.paths.len() + 4
+    }
+
+    // PARSING
+    // --------------------------------------------------------------------------------------------
+    /// Decomposes this opening into a combination of query values and corresponding Merkle
+    /// authentication paths (grouped together into a single batch Merkle proof).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * This opening does not contain at least one query.
+    /// * Parsing of any of the query values or the corresponding Merkle paths fails.
+    /// * Not all bytes have been consumed while parsing this opening.
+    pub fn parse<H, E>(
+        self,
+        num_leaves: usize,
+        folding_factor: usize,
+    ) -> Result<(Vec<E>, BatchMerkleProof<H>), DeserializationError>
+    where
+        E: FieldElement,
+        H: ElementHasher<BaseField = E::BaseField>,
+    {
+        // make sure the number of value bytes can be parsed into a whole number of queries
+        let num_query_bytes = E::ELEMENT_BYTES * folding_factor;
+        if self.values.len() % num_query_bytes != 0 {
+            return Err(DeserializationError::InvalidValue(format!(
+                "number of value bytes ({}) does not divide into whole number of queries",
+                self.values.len(),
+            )));
+        }
+
+        let num_queries = self.values.len() / num_query_bytes;
+        if num_queries == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a STIR oracle opening must contain at least one query".to_string(),
+            ));
+        }
+        let mut hashed_queries = vec![H::Digest::default(); num_queries];
+        let mut query_values = Vec::with_capacity(num_queries * folding_factor);
+
+        // read bytes corresponding to each query, convert them into field elements,
+        // and also hash them to build leaf nodes of the batch Merkle proof
+        let mut reader = SliceReader::new(&self.values);
+        for query_hash in hashed_queries.iter_mut() {
+            let mut qe = E::read_batch_from(&mut reader, folding_factor)?;
+            *query_hash = H::hash_elements(&qe);
+            query_values.append(&mut qe);
+        }
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        // build batch Merkle proof; STIR oracle trees are always binary
+        let mut reader = SliceReader::new(&self.paths);
+        let tree_depth = num_leaves.ilog2() as u8;
+        let merkle_proof =
+            BatchMerkleProof::deserialize(&mut reader, hashed_queries, tree_depth, 2)?;
+        if reader.has_more_bytes() {
+            return Err(DeserializationError::UnconsumedBytes);
+        }
+
+        Ok((query_values, merkle_proof))
+    }
+}
+
+// SERIALIZATION / DESERIALIZATION
+// ------------------------------------------------------------------------------------------------
+
+impl Serializable for StirProofOpening {
+    /// Serializes this opening and writes the resulting bytes to the specified `target`.
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // write value bytes
+        target.write_u32(self.values.len() as u32);
+        target.write_bytes(&self.values);
+
+        // write path bytes
+        target.write_u32(self.paths.len() as u32);
+        target.write_bytes(&self.paths);
+    }
+}
+
+impl Deserializable for StirProofOpening {
+    /// Reads a single oracle opening form the `source` and returns it.
+    ///
+    /// # Errors
+    /// Returns an error if a valid opening could not be read from the specified source.
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        // read values
+        let num_value_bytes = source.read_u32()?;
+        if num_value_bytes == 0 {
+            return Err(DeserializationError::InvalidValue(
+                "a STIR oracle opening must contain at least one queried evaluation".to_string(),
+            ));
+        }
+        let values = source.read_vec(num_value_bytes as usize)?;
+
+        // read paths
+        let num_paths_bytes = source.read_u32()?;
+        let paths = source.read_vec(num_paths_bytes as usize)?;
+
+        Ok(StirProofOpening { values, paths })
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::marker::PhantomData;
+use crypto::{ElementHasher, Hasher, MerkleTree, RandomCoin};
+use math::FieldElement;
+use utils::collections::Vec;
+
+// PROVER CHANNEL TRAIT
+// ================================================================================================
+
+/// Defines an interface for a channel over which a prover communicates with a verifier.
+///
+/// The prover uses this channel to send commitments to STIR oracles and answers to out-of-domain
+/// queries to the verifier, and to draw random challenges and query positions from the channel.
+///
+/// In the interactive version of the protocol, the verifier chooses the challenges and the query
+/// positions uniformly at random. In the non-interactive version, they are drawn pseudo-randomly
+/// based on the values the prover has written into the channel up to this point.
+pub trait ProverChannel<E: FieldElement> {
+    /// Hash function used by the prover to commit to oracle evaluations.
+    type Hasher: Hasher;
+
+    /// Sends an oracle commitment to the verifier.
+    ///
+    /// An oracle commitment is a cap of a Merkle tree built from evaluations of a polynomial over
+    /// the domain of the oracle. Similarly to FRI layers, the evaluations are grouped so that all
+    /// values needed to fold the polynomial at a single point are put into a single leaf of the
+    /// Merkle tree.
+    fn commit_stir_oracle(
+        &mut self,
+        oracle_commitment: &[<<Self as ProverChannel<E>>::Hasher as Hasher>::Digest],
+    );
+
+    /// Sends field elements to the verifier.
+    ///
+    /// This is used to send answers to out-of-domain queries and the coefficients of the final
+    /// polynomial.
+    fn send_stir_elements(&mut self, elements: &[E]);
+
+    /// Returns a random challenge drawn uniformly at random from the entire field.
+    ///
+    /// This is used to draw folding challenges, out-of-domain sample points, and combination
+    /// challenges.
+    fn draw_stir_challenge(&mut self) -> E;
+
+    /// Performs proof-of-work grinding before the next set of query positions is drawn, and
+    /// returns the nonce found.
+    ///
+    /// The nonce must result in at least `grinding_factor` leading zeros when absorbed into the
+    /// state of the channel (see [RandomCoin::check_leading_zeros()]). The nonce is then
+    /// absorbed into the channel so that the query positions depend on it.
+    fn grind_stir_round(&mut self, grinding_factor: u32) -> u64;
+
+    /// Returns a set of `num_queries` unique positions drawn uniformly at random from a domain
+    /// of the specified size.
+    fn draw_stir_query_positions(&mut self, num_queries: usize, domain_size: usize) -> Vec<usize>;
+}
+
+// DEFAULT PROVER CHANNEL IMPLEMENTATION
+// ================================================================================================
+
+/// Provides a default implementation of the [ProverChannel] trait.
+///
+/// Though this implementation is intended primarily for testing purposes, it can be used in
+/// production use cases as well.
+pub struct DefaultProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    public_coin: R,
+    commitments: Vec<Vec<H::Digest>>,
+    _field_element: PhantomData<E>,
+}
+
+impl<E, H, R> DefaultProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    /// Returns a new prover channel with a public coin seeded with the specified elements.
+    pub fn new(seed: &[E::BaseField]) -> Self {
+        DefaultProverChannel {
+            public_coin: RandomCoin::new(seed),
+            commitments: Vec::new(),
+            _field_element: PhantomData,
+        }
+    }
+
+    /// Returns a list of oracle commitments written by the prover into this channel.
+    pub fn oracle_commitments(&self) -> &[Vec<H::Digest>] {
+        &self.commitments
+    }
+}
+
+impl<E, H, R> ProverChannel<E> for DefaultProverChannel<E, H, R>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    type Hasher = H;
+
+    fn commit_stir_oracle(&mut self, oracle_commitment: &[H::Digest]) {
+        let oracle_root = MerkleTree::<H>::get_cap_root(oracle_commitment, 2)
+            .expect("failed to compute root of a STIR oracle commitment");
+        self.commitments.push(oracle_commitment.to_vec());
+        self.public_coin.reseed_with_digest(&oracle_root);
+    }
+
+    fn send_stir_elements(&mut self, elements: &[E]) {
+        self.public_coin.reseed_with_elements(elements);
+    }
+
+    fn draw_stir_challenge(&mut self) -> E {
+        self.public_coin
+            .draw()
+            .expect("failed to draw STIR challenge")
+    }
+
+    fn grind_stir_round(&mut self, grinding_factor: u32) -> u64 {
+        let nonce = self
+            .public_coin
+            .find_nonce(1..u64::MAX, grinding_factor)
+            .expect("nonce not found");
+        self.public_coin.reseed_with_int(nonce);
+        nonce
+    }
+
+    fn draw_stir_query_positions(&mut self, num_queries: usize, domain_size: usize) -> Vec<usize> {
+        self.public_coin
+            .draw_integers(num_queries, domain_size)
+            .expect("failed to draw query position")
+    }
+}
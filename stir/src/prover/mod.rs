@@ -0,0 +1,454 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{
+    options::NUM_OOD_SAMPLES,
+    proof::{StirProof, StirProofOpening},
+    StirOptions,
+};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, Hasher, MerkleTree};
+use fri::{folding::fold_positions, utils::hash_values};
+use math::{fft, polynom, FieldElement, StarkField};
+use utils::{
+    collections::Vec, flatten_vector_elements, group_slice_elements, transpose_slice,
+    CancellationToken, Cancelled,
+};
+
+mod channel;
+pub use channel::{DefaultProverChannel, ProverChannel};
+
+#[cfg(test)]
+mod tests;
+
+// TYPES AND INTERFACES
+// ================================================================================================
+
+/// Implements the prover component of the STIR protocol.
+///
+/// Given evaluations of a function *f* over domain *D* (`evaluations`), a STIR prover generates
+/// a proof that *f* is a polynomial of some bounded degree *d*, such that *d* < |*D*| /
+/// *blowup_factor*.
+///
+/// The prover is parametrized with the following types:
+///
+/// * `B` specifies the base field of the STARK protocol.
+/// * `E` specifies the field in which the STIR protocol is executed. This can be the same as the
+///   base field `B`, but it can also be an extension of the base field in cases when the base
+///   field is too small to provide desired security level for the STIR protocol.
+/// * `C` specifies the type used to simulate prover-verifier interaction.
+/// * `H` specifies the hash function used to build oracle Merkle trees.
+///
+/// Proof generation is performed in two phases: round phase and proof building phase.
+///
+/// # Round phase
+/// During the round phase, which is executed via [build_rounds()](StirProver::build_rounds())
+/// function, the prover executes the interactive part of the protocol. The prover first commits
+/// to `evaluations` (the first oracle), and then in each round:
+///
+/// 1. Draws a folding challenge and folds the current polynomial *f_i* by the folding factor *k*.
+///    Folding is the same operation as the degree-respecting projection used in FRI.
+/// 2. Evaluates the folded polynomial *g_i* over the domain of the next oracle and commits to
+///    these evaluations. The next domain consists of the elements at odd positions of the current
+///    domain: it is half the size of the current domain (rather than *k* times smaller), and does
+///    not intersect the domain over which *g_i* is queried.
+/// 3. Answers out-of-domain queries about *g_i*, and performs proof-of-work grinding if a grinding
+///    factor is specified in the [StirOptions].
+/// 4. Draws query positions in the domain of the current oracle. The values of *g_i* at the
+///    points corresponding to these positions can be computed by the verifier from the values of
+///    *f_i*.
+/// 5. Defines the next polynomial *f_{i+1}* as the quotient of *g_i* by the out-of-domain and
+///    query points, corrected to have the same degree bound as *g_i*. The verifier can compute
+///    the values of *f_{i+1}* from the values of *g_i* without any additional commitments.
+///
+/// After the last round, the prover folds the last polynomial once more and sends the result (the
+/// final polynomial) to the verifier in its entirety. The final set of query positions is then
+/// drawn to check the last folding.
+///
+/// # Proof building phase
+/// In the proof building phase, which is executed via [build_proof()](StirProver::build_proof())
+/// function, the prover decommits all oracles at the query positions of the corresponding
+/// rounds, and builds a [StirProof] from these evaluations, the out-of-domain answers and the
+/// final polynomial. After the proof is generated, the prover deletes its internal state.
+///
+/// Query positions are drawn by the prover during the round phase; thus, when STIR is executed as
+/// a part of the STARK protocol, the positions at which the first oracle is queried
+/// ([query_positions()](StirProver::query_positions())) are also the positions at which the
+/// trace and the constraint evaluations must be queried.
+///
+/// Calling [build_rounds()](StirProver::build_rounds()) when the internal state is dirty, or
+/// calling [build_proof()](StirProver::build_proof()) on a clean state will result in a panic.
+pub struct StirProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    options: StirOptions,
+    oracles: Vec<StirOracle<E, H>>,
+    query_positions: Vec<Vec<usize>>,
+    ood_answers: Vec<E>,
+    final_poly: Vec<E>,
+    pow_nonces: Vec<u64>,
+    cancellation_token: Option<CancellationToken>,
+    _channel: PhantomData<C>,
+}
+
+struct StirOracle<E: FieldElement, H: Hasher> {
+    tree: MerkleTree<H>,
+    evaluations: Vec<E>,
+}
+
+// PROVER IMPLEMENTATION
+// ================================================================================================
+
+impl<B, E, C, H> StirProver<B, E, C, H>
+where
+    B: StarkField,
+    E: FieldElement<BaseField = B>,
+    C: ProverChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = B>,
+{
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+    /// Returns a new STIR prover instantiated with the provided `options`.
+    pub fn new(options: StirOptions) -> Self {
+        StirProver {
+            options,
+            oracles: Vec::new(),
+            query_positions: Vec::new(),
+            ood_answers: Vec::new(),
+            final_poly: Vec::new(),
+            pow_nonces: Vec::new(),
+            cancellation_token: None,
+            _channel: PhantomData,
+        }
+    }
+
+    /// Returns this prover with the specified cancellation token attached to it.
+    ///
+    /// Once the token has been cancelled, [build_rounds()](StirProver::build_rounds()) returns
+    /// before executing the next STIR round, and leaves the prover in a clean state. Thus, the
+    /// caller must check the token before building the proof.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = Some(token);
+        self
+    }
+
+    // ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the options used by this prover.
+    pub fn options(&self) -> &StirOptions {
+        &self.options
+    }
+
+    /// Returns number of STIR rounds executed during the last execution of the
+    /// [build_rounds()](StirProver::build_rounds()) method.
+    pub fn num_rounds(&self) -> usize {
+        self.oracles.len().saturating_sub(1)
+    }
+
+    /// Returns the positions at which the first oracle (i.e., the `evaluations` passed to
+    /// [build_rounds()](StirProver::build_rounds())) is queried.
+    ///
+    /// # Panics
+    /// Panics if the rounds have not been built yet.
+    pub fn query_positions(&self) -> &[usize] {
+        assert!(
+            !self.final_poly.is_empty(),
+            "STIR rounds have not been built yet"
+        );
+        &self.query_positions[0]
+    }
+
+    /// Clears the internal state of the prover.
+    pub fn reset(&mut self) {
+        self.oracles.clear();
+        self.query_positions.clear();
+        self.ood_answers.clear();
+        self.final_poly.clear();
+        self.pow_nonces.clear();
+    }
+
+    // ROUND PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Executes all rounds of the STIR protocol for the specified `evaluations` of a polynomial
+    /// over the domain defined by the size of `evaluations` and the domain offset specified in
+    /// the options of this prover.
+    ///
+    /// # Errors
+    /// Returns [Cancelled] if the cancellation token attached to this prover has been cancelled;
+    /// in such a case, the execution is aborted before the next round, and the prover state is
+    /// reset.
+    ///
+    /// # Panics
+    /// Panics if:
+    /// * The prover state is dirty (the rounds have already been built).
+    /// * The number of `evaluations` is not a power of two, or the domain of the last oracle is
+    ///   too small to be committed to.
+    pub fn build_rounds(&mut self, channel: &mut C, evaluations: Vec<E>) -> Result<(), Cancelled> {
+        assert!(
+            self.oracles.is_empty(),
+            "a prior proof generation request has not been completed yet"
+        );
+        assert!(
+            evaluations.len().is_power_of_two(),
+            "number of evaluations must be a power of two, but was {}",
+            evaluations.len()
+        );
+
+        let folding_factor = self.options.folding_factor();
+        let mut domain_size = evaluations.len();
+        let mut domain_offset = self.options.domain_offset::<B>();
+        let num_rounds = self.options.num_rounds(domain_size);
+
+        // interpolate the evaluations into a polynomial, and commit to them as the first oracle
+        let mut poly = evaluations.clone();
+        let inv_twiddles = fft::get_inv_twiddles::<B>(domain_size);
+        fft::interpolate_poly_with_offset(&mut poly, &inv_twiddles, domain_offset);
+        poly.truncate(domain_size / self.options.blowup_factor());
+        self.commit_oracle(channel, evaluations);
+
+        for round in 0..num_rounds {
+            if self.is_cancelled() {
+                self.reset();
+                return Err(Cancelled);
+            }
+
+            let domain_generator = B::get_root_of_unity(domain_size.ilog2());
+
+            // fold the polynomial by the folding factor
+            let alpha = channel.draw_stir_challenge();
+            let folded_poly = fold_poly(&poly, folding_factor, alpha);
+
+            // evaluate the folded polynomial over the next domain, which consists of elements at
+            // odd positions of the current domain, and commit to the evaluations
+            let next_domain_offset = domain_offset * domain_generator;
+            let twiddles = fft::get_twiddles::<B>(folded_poly.len());
+            let next_evaluations = fft::evaluate_poly_with_offset(
+                &folded_poly,
+                &twiddles,
+                next_domain_offset,
+                domain_size / 2 / folded_poly.len(),
+            );
+            self.commit_oracle(channel, next_evaluations);
+
+            // answer out-of-domain queries about the folded polynomial
+            let mut points = (0..NUM_OOD_SAMPLES)
+                .map(|_| channel.draw_stir_challenge())
+                .collect::<Vec<E>>();
+            let mut values = polynom::eval_many(&folded_poly, &points);
+            channel.send_stir_elements(&values);
+            self.ood_answers.extend_from_slice(&values);
+
+            // draw the query positions; the values of the folded polynomial at the points
+            // corresponding to these positions are computed by the verifier from the values of
+            // the current oracle
+            let comb_coefficient = channel.draw_stir_challenge();
+            self.grind_round(channel);
+            let num_queries = self.options.num_round_queries(round);
+            let positions = channel.draw_stir_query_positions(num_queries, domain_size);
+            for position in fold_positions(&positions, domain_size, folding_factor) {
+                let x = domain_offset * domain_generator.exp_vartime((position as u64).into());
+                let point = E::from(x.exp_vartime((folding_factor as u64).into()));
+                values.push(polynom::eval(&folded_poly, point));
+                points.push(point);
+            }
+            self.query_positions.push(positions);
+
+            // the polynomial for the next round is the quotient of the folded polynomial by all
+            // sampled points, corrected to the degree bound of the folded polynomial
+            poly = compute_corrected_quotient(&folded_poly, &points, &values, comb_coefficient);
+            domain_offset = next_domain_offset;
+            domain_size /= 2;
+        }
+
+        // fold the last polynomial once more and send the result to the verifier directly
+        let alpha = channel.draw_stir_challenge();
+        let final_poly = fold_poly(&poly, folding_factor, alpha);
+        channel.send_stir_elements(&final_poly);
+        self.grind_round(channel);
+        let num_queries = self.options.num_round_queries(num_rounds);
+        let positions = channel.draw_stir_query_positions(num_queries, domain_size);
+        self.query_positions.push(positions);
+        self.final_poly = final_poly;
+        Ok(())
+    }
+
+    /// Returns true if the cancellation token attached to this prover has been cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .map_or(false, |token| token.is_cancelled())
+    }
+
+    /// Commits to the `evaluations` of a polynomial over the domain of the next oracle.
+    fn commit_oracle(&mut self, channel: &mut C, evaluations: Vec<E>) {
+        let folding_factor = self.options.folding_factor();
+        let oracle = match folding_factor {
+            2 => build_oracle::<E, H, 2>(&evaluations),
+            4 => build_oracle::<E, H, 4>(&evaluations),
+            8 => build_oracle::<E, H, 8>(&evaluations),
+            16 => build_oracle::<E, H, 16>(&evaluations),
+            _ => unimplemented!("folding factor {} is not supported", folding_factor),
+        };
+        channel.commit_stir_oracle(oracle.tree.cap(self.options.merkle_cap_height()));
+        self.oracles.push(oracle);
+    }
+
+    /// Performs proof-of-work grinding before query positions are drawn, if required.
+    fn grind_round(&mut self, channel: &mut C) {
+        let grinding_factor = self.options.grinding_factor();
+        if grinding_factor > 0 {
+            self.pow_nonces
+                .push(channel.grind_stir_round(grinding_factor));
+        }
+    }
+
+    // PROOF BUILDING PHASE
+    // --------------------------------------------------------------------------------------------
+    /// Builds a STIR proof by decommitting each oracle at the query positions drawn in the
+    /// corresponding round.
+    ///
+    /// For each query position, the values needed to fold the polynomial at the corresponding
+    /// point are recorded into the proof together with Merkle authentication paths from the caps
+    /// of oracle commitment trees.
+    ///
+    /// # Panics
+    /// Panics is the prover state is clean (the rounds have not been built yet).
+    pub fn build_proof(&mut self) -> StirProof {
+        assert!(
+            !self.final_poly.is_empty(),
+            "STIR rounds have not been built yet"
+        );
+
+        let folding_factor = self.options.folding_factor();
+        let cap_height = self.options.merkle_cap_height();
+        let mut domain_size = self.oracles[0].evaluations.len();
+
+        let mut openings = Vec::with_capacity(self.oracles.len());
+        for (oracle, positions) in self.oracles.iter().zip(self.query_positions.iter()) {
+            let positions = fold_positions(positions, domain_size, folding_factor);
+
+            // sort of a static dispatch for folding_factor parameter
+            let opening = match folding_factor {
+                2 => query_oracle::<E, H, 2>(oracle, &positions, cap_height),
+                4 => query_oracle::<E, H, 4>(oracle, &positions, cap_height),
+                8 => query_oracle::<E, H, 8>(oracle, &positions, cap_height),
+                16 => query_oracle::<E, H, 16>(oracle, &positions, cap_height),
+                _ => unimplemented!("folding factor {} is not supported", folding_factor),
+            };
+            openings.push(opening);
+            domain_size /= 2;
+        }
+
+        let proof = StirProof::new(
+            openings,
+            self.ood_answers.clone(),
+            self.final_poly.clone(),
+            self.pow_nonces.clone(),
+        );
+
+        // clear the state so that another proof can be generated
+        self.reset();
+
+        proof
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Builds an oracle from the specified evaluations; the evaluations are transposed into a matrix
+/// of N columns so that all values needed to fold the polynomial at a single point are committed
+/// to in a single leaf of the oracle Merkle tree.
+fn build_oracle<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>, const N: usize>(
+    evaluations: &[E],
+) -> StirOracle<E, H> {
+    let transposed_evaluations = transpose_slice::<E, N>(evaluations);
+    let hashed_evaluations = hash_values::<H, E, N>(&transposed_evaluations);
+    let tree =
+        MerkleTree::<H>::new(hashed_evaluations).expect("failed to construct STIR oracle tree");
+    StirOracle {
+        tree,
+        evaluations: flatten_vector_elements(transposed_evaluations),
+    }
+}
+
+/// Builds a single oracle opening by querying the evaluations of the passed in oracle at the
+/// specified positions.
+fn query_oracle<E: FieldElement, H: Hasher, const N: usize>(
+    oracle: &StirOracle<E, H>,
+    positions: &[usize],
+    cap_height: usize,
+) -> StirProofOpening {
+    // build Merkle authentication paths for all query positions
+    let proof = oracle
+        .tree
+        .prove_batch_with_cap(positions, cap_height)
+        .expect("failed to generate a Merkle proof for STIR oracle queries");
+
+    // build a list of evaluations at each position; since oracle evaluations are stored in
+    // transposed form, a position refers to N evaluations which are committed in a single leaf
+    let evaluations: &[[E; N]] = group_slice_elements(&oracle.evaluations);
+    let queried_values = positions
+        .iter()
+        .map(|&position| evaluations[position])
+        .collect::<Vec<_>>();
+
+    StirProofOpening::new(queried_values, proof)
+}
+
+/// Folds the polynomial `p` by the specified `folding_factor` using `alpha` as the folding
+/// challenge.
+///
+/// Writing p(x) = sum_j x^j * p_j(x^k) for k = `folding_factor`, the result is the polynomial
+/// sum_j alpha^j * p_j(x). Its value at y is the value at `alpha` of the polynomial interpolating
+/// p over all x such that x^k = y.
+fn fold_poly<E: FieldElement>(p: &[E], folding_factor: usize, alpha: E) -> Vec<E> {
+    let mut result = vec![E::ZERO; (p.len() / folding_factor).max(1)];
+    let mut alpha_power = E::ONE;
+    for j in 0..folding_factor {
+        for (i, coeff) in result.iter_mut().enumerate() {
+            if let Some(&value) = p.get(i * folding_factor + j) {
+                *coeff += alpha_power * value;
+            }
+        }
+        alpha_power *= alpha;
+    }
+    result
+}
+
+/// Returns the polynomial (p(x) - ans(x)) / v(x) * sum_{l=0}^{m} (r * x)^l, where ans interpolates
+/// the specified `values` at the specified `points`, v is the vanishing polynomial of `points`,
+/// m is the number of points, and r is `comb_coefficient`.
+///
+/// The degree of the result is smaller than the number of coefficients in `p`.
+fn compute_corrected_quotient<E: FieldElement>(
+    p: &[E],
+    points: &[E],
+    values: &[E],
+    comb_coefficient: E,
+) -> Vec<E> {
+    let ans = polynom::interpolate(points, values, false);
+    let mut quotient = polynom::sub(p, &ans);
+    for &point in points {
+        polynom::syn_div_in_place(&mut quotient, 1, point);
+    }
+
+    let correction = (0..=points.len())
+        .scan(E::ONE, |power, _| {
+            let result = *power;
+            *power *= comb_coefficient;
+            Some(result)
+        })
+        .collect::<Vec<_>>();
+
+    let mut result = polynom::mul(&quotient, &correction);
+    result.resize(p.len(), E::ZERO);
+    result
+}
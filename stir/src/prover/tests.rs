@@ -0,0 +1,227 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use super::{DefaultProverChannel, StirProver};
+use crate::{
+    verifier::{DefaultVerifierChannel, StirVerifier},
+    StirOptions, StirProof, VerifierError,
+};
+use crypto::{hashers::Blake3_256, DefaultRandomCoin, Hasher, RandomCoin};
+use math::{fft, fields::f128::BaseElement, FieldElement};
+use utils::{
+    collections::Vec, CancellationToken, Cancelled, Deserializable, Serializable, SliceReader,
+};
+
+type Blake3 = Blake3_256<BaseElement>;
+
+// PROVE/VERIFY TEST
+// ================================================================================================
+
+#[test]
+fn stir_folding_2() {
+    let options = StirOptions::new(8, 2, 7, 16);
+    stir_prove_verify(1 << 10, options)
+}
+
+#[test]
+fn stir_folding_4() {
+    let options = StirOptions::new(8, 4, 7, 16);
+    assert_eq!(4, options.num_rounds((1 << 12) * 8));
+    stir_prove_verify(1 << 12, options)
+}
+
+#[test]
+fn stir_folding_16_with_merkle_cap() {
+    let options = StirOptions::new(4, 16, 15, 16).with_merkle_cap_height(2);
+    stir_prove_verify(1 << 12, options)
+}
+
+#[test]
+fn stir_no_rounds() {
+    // the polynomial is folded directly into the final polynomial
+    let options = StirOptions::new(8, 8, 7, 16);
+    assert_eq!(0, options.num_rounds(64 * 8));
+    stir_prove_verify(64, options)
+}
+
+#[test]
+fn stir_folding_4_with_grinding() {
+    let options = StirOptions::new(8, 4, 7, 16).with_grinding_factor(8);
+    stir_prove_verify(1 << 12, options)
+}
+
+#[test]
+fn stir_num_round_queries() {
+    // with blowup factor 8 and folding factor 4, the rate improves by 1 bit in every round
+    let options = StirOptions::new(8, 4, 7, 30);
+    assert_eq!(30, options.num_round_queries(0));
+    assert_eq!(23, options.num_round_queries(1));
+    assert_eq!(18, options.num_round_queries(2));
+
+    // with folding factor 2, the rate stays the same
+    let options = StirOptions::new(8, 2, 7, 30);
+    assert_eq!(30, options.num_round_queries(3));
+}
+
+#[test]
+fn stir_invalid_evaluations() {
+    let options = StirOptions::new(8, 4, 7, 16);
+    let evaluations = build_evaluations(1 << 12, options.blowup_factor());
+    let (proof, commitments, positions) = build_proof(&options, evaluations.clone());
+
+    let mut invalid_evaluations = query_evaluations(&evaluations, &positions);
+    invalid_evaluations[3] += BaseElement::ONE;
+    let result = verify_proof(
+        proof,
+        commitments,
+        &invalid_evaluations,
+        (1 << 12) - 1,
+        &options,
+    );
+    assert_eq!(Err(VerifierError::InitialEvaluationsMismatch), result);
+}
+
+#[test]
+fn stir_grinding_verification_fail() {
+    let options = StirOptions::new(8, 4, 7, 16).with_grinding_factor(8);
+    let evaluations = build_evaluations(1 << 12, options.blowup_factor());
+    let (proof, commitments, positions) = build_proof(&options, evaluations.clone());
+    assert_eq!(5, proof.pow_nonces().len());
+    let evaluations = query_evaluations(&evaluations, &positions);
+
+    // a nonce is expected for every round when grinding is enabled, and none otherwise
+    let result = verify_proof(
+        proof.clone(),
+        commitments.clone(),
+        &evaluations,
+        (1 << 12) - 1,
+        &StirOptions::new(8, 4, 7, 16),
+    );
+    assert_eq!(Err(VerifierError::NumRoundNoncesMismatch(0, 5)), result);
+
+    // nonces must satisfy the grinding factor expected by the verifier
+    let result = verify_proof(
+        proof,
+        commitments,
+        &evaluations,
+        (1 << 12) - 1,
+        &options.clone().with_grinding_factor(32),
+    );
+    assert_eq!(
+        Err(VerifierError::RoundProofOfWorkVerificationFailed(0)),
+        result
+    );
+}
+
+#[test]
+fn stir_cancellation() {
+    let options = StirOptions::new(8, 4, 7, 16);
+    let evaluations = build_evaluations(1 << 12, options.blowup_factor());
+
+    // once the token is cancelled, the rounds are not executed and the prover is left in a clean
+    // state
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut channel =
+        DefaultProverChannel::<BaseElement, Blake3, DefaultRandomCoin<Blake3>>::new(&[]);
+    let mut prover = StirProver::new(options).with_cancellation_token(token);
+    assert_eq!(
+        Err(Cancelled),
+        prover.build_rounds(&mut channel, evaluations)
+    );
+    assert_eq!(0, prover.num_rounds());
+}
+
+// TEST UTILS
+// ================================================================================================
+
+pub fn build_evaluations(trace_length: usize, lde_blowup: usize) -> Vec<BaseElement> {
+    let mut p = (0..trace_length as u128)
+        .map(BaseElement::new)
+        .collect::<Vec<_>>();
+    let domain_size = trace_length * lde_blowup;
+    p.resize(domain_size, BaseElement::ZERO);
+
+    let twiddles = fft::get_twiddles::<BaseElement>(domain_size);
+
+    fft::evaluate_poly(&mut p, &twiddles);
+    p
+}
+
+fn query_evaluations(evaluations: &[BaseElement], positions: &[usize]) -> Vec<BaseElement> {
+    positions.iter().map(|&p| evaluations[p]).collect()
+}
+
+fn build_proof(
+    options: &StirOptions,
+    evaluations: Vec<BaseElement>,
+) -> (StirProof, Vec<Vec<<Blake3 as Hasher>::Digest>>, Vec<usize>) {
+    let mut channel =
+        DefaultProverChannel::<BaseElement, Blake3, DefaultRandomCoin<Blake3>>::new(&[]);
+    let mut prover = StirProver::new(options.clone());
+    prover.build_rounds(&mut channel, evaluations).unwrap();
+    let positions = prover.query_positions().to_vec();
+    let proof = prover.build_proof();
+    (proof, channel.oracle_commitments().to_vec(), positions)
+}
+
+pub fn verify_proof(
+    proof: StirProof,
+    commitments: Vec<Vec<<Blake3 as Hasher>::Digest>>,
+    evaluations: &[BaseElement],
+    max_degree: usize,
+    options: &StirOptions,
+) -> Result<(), VerifierError> {
+    // test proof serialization / deserialization
+    let mut proof_bytes = Vec::new();
+    proof.write_into(&mut proof_bytes);
+
+    let mut reader = SliceReader::new(&proof_bytes);
+    let proof = StirProof::read_from(&mut reader).unwrap();
+
+    // verify the proof
+    let domain_size = (max_degree + 1) * options.blowup_factor();
+    let mut channel = DefaultVerifierChannel::<BaseElement, Blake3>::new(
+        proof,
+        commitments,
+        domain_size,
+        options,
+    )
+    .unwrap();
+    let mut coin = DefaultRandomCoin::<Blake3>::new(&[]);
+    let verifier = StirVerifier::new(&mut channel, &mut coin, options.clone(), max_degree)?;
+    verifier.verify(&mut channel, evaluations)
+}
+
+fn stir_prove_verify(trace_length: usize, options: StirOptions) {
+    let lde_blowup = options.blowup_factor();
+    let evaluations = build_evaluations(trace_length, lde_blowup);
+
+    // generate the proof and make sure it can be verified
+    let (proof, commitments, positions) = build_proof(&options, evaluations.clone());
+    let queried_evaluations = query_evaluations(&evaluations, &positions);
+    let result = verify_proof(
+        proof,
+        commitments,
+        &queried_evaluations,
+        trace_length - 1,
+        &options,
+    );
+    assert!(result.is_ok(), "{:}", result.err().unwrap());
+
+    // make sure the proof fails if the degree of the polynomial is too high; the evaluations
+    // are of a polynomial of degree 2 * trace_length - 1 over the same domain
+    let evaluations = build_evaluations(trace_length * 2, lde_blowup / 2);
+    let (proof, commitments, positions) = build_proof(&options, evaluations.clone());
+    let queried_evaluations = query_evaluations(&evaluations, &positions);
+    let result = verify_proof(
+        proof,
+        commitments,
+        &queried_evaluations,
+        trace_length - 1,
+        &options,
+    );
+    assert!(result.is_err());
+}
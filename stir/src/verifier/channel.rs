@@ -0,0 +1,168 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use crate::{StirOptions, StirProof, VerifierError};
+use crypto::{BatchMerkleProof, ElementHasher, Hasher, MerkleTree};
+use math::FieldElement;
+use utils::{collections::Vec, DeserializationError};
+
+// VERIFIER CHANNEL TRAIT
+// ================================================================================================
+
+/// Defines an interface for a channel over which a verifier communicates with a prover.
+///
+/// This trait abstracts away implementation specifics of the [StirProof] struct. Thus, instead of
+/// dealing with STIR proofs directly, the verifier can read the data as if it was sent by the
+/// prover via an interactive channel.
+///
+/// Note: that reading removes the data from the channel. Thus, reading duplicated values from
+/// the channel should not be possible.
+pub trait VerifierChannel<E: FieldElement> {
+    /// Hash function used by the prover to commit to oracle evaluations.
+    type Hasher: ElementHasher<BaseField = E::BaseField>;
+
+    // REQUIRED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads and removes from the channel all oracle commitments sent by the prover.
+    ///
+    /// Each oracle commitment is a cap of the oracle Merkle tree; a cap consisting of a single
+    /// node is the tree root.
+    fn read_stir_oracle_commitments(
+        &mut self,
+    ) -> Vec<Vec<<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest>>;
+
+    /// Reads and removes from the channel the answers to out-of-domain queries for all STIR
+    /// rounds.
+    fn read_stir_ood_answers(&mut self) -> Vec<E>;
+
+    /// Reads and removes from the channel proof-of-work nonces for all STIR rounds.
+    ///
+    /// The returned vector is empty if the prover did not perform proof-of-work grinding.
+    fn read_stir_round_nonces(&mut self) -> Vec<u64>;
+
+    /// Reads and removes from the channel the coefficients of the final polynomial.
+    fn read_stir_final_poly(&mut self) -> Vec<E>;
+
+    /// Reads and removes from the channel evaluations of the next oracle at the queried
+    /// positions.
+    ///
+    /// It is expected that oracle queries and oracle proofs for the same oracle are consistent.
+    /// That is, query values hash into the leaf nodes of corresponding Merkle authentication
+    /// paths.
+    fn take_next_stir_oracle_queries(&mut self) -> Vec<E>;
+
+    /// Reads and removes from the channel Merkle authentication paths for queried evaluations of
+    /// the next oracle.
+    fn take_next_stir_oracle_proof(&mut self) -> BatchMerkleProof<Self::Hasher>;
+
+    // PROVIDED METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns query values of the next oracle at the specified positions; `folding_factor`
+    /// values are returned for each position.
+    ///
+    /// This also checks if the values are valid against the provided oracle commitment.
+    ///
+    /// # Errors
+    /// Returns an error if query values did not match oracle commitment.
+    fn read_oracle_queries(
+        &mut self,
+        positions: &[usize],
+        folding_factor: usize,
+        commitment: &[<<Self as VerifierChannel<E>>::Hasher as Hasher>::Digest],
+    ) -> Result<Vec<E>, VerifierError> {
+        let oracle_proof = self.take_next_stir_oracle_proof();
+        MerkleTree::<Self::Hasher>::verify_batch_with_cap(commitment, positions, &oracle_proof)
+            .map_err(|_| VerifierError::OracleCommitmentMismatch)?;
+
+        let oracle_queries = self.take_next_stir_oracle_queries();
+        if oracle_queries.len() != positions.len() * folding_factor {
+            return Err(VerifierError::OracleCommitmentMismatch);
+        }
+        Ok(oracle_queries)
+    }
+}
+
+// DEFAULT VERIFIER CHANNEL IMPLEMENTATION
+// ================================================================================================
+
+/// Provides a default implementation of the [VerifierChannel] trait.
+///
+/// Default verifier channel can be instantiated directly from a [StirProof] struct.
+///
+/// Though this implementation is primarily intended for testing purposes, it can be used in
+/// production use cases as well.
+pub struct DefaultVerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+    oracle_commitments: Vec<Vec<H::Digest>>,
+    oracle_proofs: Vec<BatchMerkleProof<H>>,
+    oracle_queries: Vec<Vec<E>>,
+    ood_answers: Vec<E>,
+    round_nonces: Vec<u64>,
+    final_poly: Vec<E>,
+}
+
+impl<E, H> DefaultVerifierChannel<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    /// Builds a new verifier channel from the specified [StirProof].
+    ///
+    /// # Errors
+    /// Returns an error if the specified `proof` could not be parsed correctly.
+    pub fn new(
+        proof: StirProof,
+        oracle_commitments: Vec<Vec<H::Digest>>,
+        domain_size: usize,
+        options: &StirOptions,
+    ) -> Result<Self, DeserializationError> {
+        let round_nonces = proof.pow_nonces().to_vec();
+        let ood_answers = proof.parse_ood_answers()?;
+        let final_poly = proof.parse_final_poly()?;
+        let (oracle_queries, oracle_proofs) = proof.parse_openings::<H, E>(domain_size, options)?;
+
+        Ok(DefaultVerifierChannel {
+            oracle_commitments,
+            oracle_proofs,
+            oracle_queries,
+            ood_answers,
+            round_nonces,
+            final_poly,
+        })
+    }
+}
+
+impl<E, H> VerifierChannel<E> for DefaultVerifierChannel<E, H>
+where
+    E: FieldElement,
+    H: ElementHasher<BaseField = E::BaseField>,
+{
+    type Hasher = H;
+
+    fn read_stir_oracle_commitments(&mut self) -> Vec<Vec<H::Digest>> {
+        self.oracle_commitments.drain(..).collect()
+    }
+
+    fn read_stir_ood_answers(&mut self) -> Vec<E> {
+        self.ood_answers.drain(..).collect()
+    }
+
+    fn read_stir_round_nonces(&mut self) -> Vec<u64> {
+        self.round_nonces.drain(..).collect()
+    }
+
+    fn read_stir_final_poly(&mut self) -> Vec<E> {
+        self.final_poly.drain(..).collect()
+    }
+
+    fn take_next_stir_oracle_queries(&mut self) -> Vec<E> {
+        self.oracle_queries.remove(0)
+    }
+
+    fn take_next_stir_oracle_proof(&mut self) -> BatchMerkleProof<H> {
+        self.oracle_proofs.remove(0)
+    }
+}
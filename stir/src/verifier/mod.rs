@@ -0,0 +1,428 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+//! Contains an implementation of STIR verifier and associated components.
+
+use crate::{options::NUM_OOD_SAMPLES, StirOptions, VerifierError};
+use core::marker::PhantomData;
+use crypto::{ElementHasher, MerkleTree, RandomCoin};
+use fri::folding::fold_positions;
+use math::{polynom, FieldElement, StarkField};
+use utils::collections::Vec;
+
+mod channel;
+pub use channel::{DefaultVerifierChannel, VerifierChannel};
+
+// STIR VERIFIER
+// ================================================================================================
+/// Implements the verifier component of the STIR protocol.
+///
+/// Given a small number of evaluations of some function *f* over domain *D* and a STIR proof, a
+/// STIR verifier determines whether *f* is a polynomial of some bounded degree *d*, such that
+/// *d* < |*D*| / *blowup_factor*.
+///
+/// The verifier is parametrized by the following types:
+///
+/// * `E` specifies the field in which the STIR protocol is executed.
+/// * `C` specifies the type used to simulate prover-verifier interaction. This type is used
+///   as an abstraction for a [StirProof](crate::StirProof). Meaning, the verifier does not
+///   consume a STIR proof directly, but reads it via [VerifierChannel] interface.
+/// * `H` specifies the Hash function used by the prover to commit to oracle evaluations.
+/// * `R` specifies the public coin used to draw the verifier's challenges.
+///
+/// Proof verification is performed in two phases: round phase and query phase.
+///
+/// # Round phase
+/// During the round phase, which is executed when the verifier is instantiated via
+/// [new()](StirVerifier::new()) function, the verifier reads oracle commitments, out-of-domain
+/// answers, the final polynomial, and proof-of-work nonces from the channel, and draws all
+/// challenges and query positions of the protocol from the public coin in the same order as the
+/// prover did.
+///
+/// # Query phase
+/// During the query phase, which is executed via [verify()](StirVerifier::verify()) function,
+/// the verifier reads oracle evaluations at the query positions of each round (together with
+/// corresponding Merkle paths) from the channel. The verifier then checks that:
+/// * The Merkle paths are valid against the oracle commitments.
+/// * The evaluations of the first oracle match the evaluations provided to the verifier.
+/// * The folded values of the last polynomial are consistent with the final polynomial.
+///
+/// The values of the polynomials tested in every round after the first are not committed to
+/// directly; instead, the verifier computes them from the values of the committed oracles, the
+/// out-of-domain answers, and the folded values of the preceding polynomial.
+pub struct StirVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    max_poly_degree: usize,
+    domain_size: usize,
+    options: StirOptions,
+    oracle_commitments: Vec<Vec<H::Digest>>,
+    rounds: Vec<StirRound<E>>,
+    final_alpha: E,
+    final_poly: Vec<E>,
+    final_positions: Vec<usize>,
+    _channel: PhantomData<C>,
+    _public_coin: PhantomData<R>,
+}
+
+/// Challenges and query positions of a single STIR round.
+struct StirRound<E: FieldElement> {
+    alpha: E,
+    ood_points: Vec<E>,
+    ood_answers: Vec<E>,
+    comb_coefficient: E,
+    positions: Vec<usize>,
+}
+
+impl<E, C, H, R> StirVerifier<E, C, H, R>
+where
+    E: FieldElement,
+    C: VerifierChannel<E, Hasher = H>,
+    H: ElementHasher<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    /// Returns a new instance of STIR verifier created from the specified parameters.
+    ///
+    /// The `max_poly_degree` parameter specifies the highest polynomial degree accepted by the
+    /// returned verifier. In combination with `blowup_factor` from the `options` parameter,
+    /// `max_poly_degree` also defines the domain over which the tested polynomial is evaluated.
+    ///
+    /// Creating a STIR verifier executes the round phase of the STIR protocol from the
+    /// verifier's perspective. The verifier stores all challenges and query positions in its
+    /// internal state, and, thus, an instance of STIR verifier can be used to verify only a
+    /// single proof.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * `max_poly_degree` is not one less than a power of two.
+    /// * The number of oracle commitments, out-of-domain answers, or coefficients of the final
+    ///   polynomial read from the channel is inconsistent with the number of STIR rounds implied
+    ///   by `max_poly_degree` and `options`.
+    /// * The number of nodes in any of the oracle commitments (Merkle tree caps) is not a power
+    ///   of two.
+    /// * The number of proof-of-work nonces read from the channel is inconsistent with the
+    ///   grinding factor specified in the `options` parameter, or any of the nonces does not
+    ///   result in the required number of leading zeros.
+    /// * An error was encountered while drawing a random value from the coin.
+    pub fn new(
+        channel: &mut C,
+        public_coin: &mut R,
+        options: StirOptions,
+        max_poly_degree: usize,
+    ) -> Result<Self, VerifierError> {
+        let degree_plus_1 = max_poly_degree + 1;
+        if !degree_plus_1.is_power_of_two() {
+            return Err(VerifierError::UnsupportedDegree(max_poly_degree));
+        }
+
+        // infer evaluation domain info
+        let domain_size = degree_plus_1 * options.blowup_factor();
+        let num_rounds = options.num_rounds(domain_size);
+        let folding_factor = options.folding_factor();
+
+        // read the data sent by the prover, and make sure it is consistent with the number of
+        // rounds
+        let oracle_commitments = channel.read_stir_oracle_commitments();
+        if oracle_commitments.len() != num_rounds + 1 {
+            return Err(VerifierError::NumCommitmentsMismatch(
+                num_rounds + 1,
+                oracle_commitments.len(),
+            ));
+        }
+
+        let ood_answers = channel.read_stir_ood_answers();
+        if ood_answers.len() != num_rounds * NUM_OOD_SAMPLES {
+            return Err(VerifierError::NumOodAnswersMismatch(
+                num_rounds * NUM_OOD_SAMPLES,
+                ood_answers.len(),
+            ));
+        }
+
+        let round_nonces = channel.read_stir_round_nonces();
+        let grinding_factor = options.grinding_factor();
+        let expected_num_nonces = if grinding_factor > 0 {
+            num_rounds + 1
+        } else {
+            0
+        };
+        if round_nonces.len() != expected_num_nonces {
+            return Err(VerifierError::NumRoundNoncesMismatch(
+                expected_num_nonces,
+                round_nonces.len(),
+            ));
+        }
+
+        let final_poly = channel.read_stir_final_poly();
+        let final_poly_size = (degree_plus_1 / folding_factor.pow(num_rounds as u32 + 1)).max(1);
+        if final_poly.len() != final_poly_size {
+            return Err(VerifierError::FinalPolyDegreeMismatch(
+                final_poly_size,
+                final_poly.len(),
+            ));
+        }
+
+        // replay the interaction with the prover
+        reseed_with_commitment::<H, R>(public_coin, &oracle_commitments[0])?;
+        let mut rounds = Vec::with_capacity(num_rounds);
+        let mut round_domain_size = domain_size;
+        for (round, ood_answers) in ood_answers.chunks(NUM_OOD_SAMPLES).enumerate() {
+            let alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
+            reseed_with_commitment::<H, R>(public_coin, &oracle_commitments[round + 1])?;
+
+            let ood_points = (0..NUM_OOD_SAMPLES)
+                .map(|_| public_coin.draw())
+                .collect::<Result<Vec<E>, _>>()
+                .map_err(VerifierError::RandomCoinError)?;
+            public_coin.reseed_with_elements(ood_answers);
+
+            let comb_coefficient = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
+            verify_pow_nonce(public_coin, &round_nonces, round, grinding_factor)?;
+            let positions = public_coin
+                .draw_integers(options.num_round_queries(round), round_domain_size)
+                .map_err(VerifierError::RandomCoinError)?;
+
+            rounds.push(StirRound {
+                alpha,
+                ood_points,
+                ood_answers: ood_answers.to_vec(),
+                comb_coefficient,
+                positions,
+            });
+            round_domain_size /= 2;
+        }
+
+        let final_alpha = public_coin.draw().map_err(VerifierError::RandomCoinError)?;
+        public_coin.reseed_with_elements(&final_poly);
+        verify_pow_nonce(public_coin, &round_nonces, num_rounds, grinding_factor)?;
+        let final_positions = public_coin
+            .draw_integers(options.num_round_queries(num_rounds), round_domain_size)
+            .map_err(VerifierError::RandomCoinError)?;
+
+        Ok(StirVerifier {
+            max_poly_degree,
+            domain_size,
+            options,
+            oracle_commitments,
+            rounds,
+            final_alpha,
+            final_poly,
+            final_positions,
+            _channel: PhantomData,
+            _public_coin: PhantomData,
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns maximum degree of a polynomial accepted by this verifier.
+    pub fn max_poly_degree(&self) -> usize {
+        self.max_poly_degree
+    }
+
+    /// Returns size of the domain over which a polynomial commitment checked by this verifier
+    /// has been evaluated.
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    /// Returns protocol configuration options for this verifier.
+    pub fn options(&self) -> &StirOptions {
+        &self.options
+    }
+
+    /// Returns the number of STIR rounds executed by the prover.
+    pub fn num_rounds(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Returns the positions in the evaluation domain at which the tested polynomial must be
+    /// queried; the evaluations at these positions must be passed to
+    /// [verify()](StirVerifier::verify()).
+    pub fn query_positions(&self) -> &[usize] {
+        self.rounds
+            .first()
+            .map(|round| &round.positions)
+            .unwrap_or(&self.final_positions)
+    }
+
+    // VERIFICATION PROCEDURE
+    // --------------------------------------------------------------------------------------------
+    /// Executes the query phase of the STIR protocol.
+    ///
+    /// Returns `Ok(())` if values in the `evaluations` slice represent evaluations of a
+    /// polynomial with degree <= `max_poly_degree` at the positions returned by
+    /// [query_positions()](StirVerifier::query_positions()).
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// * The length of `evaluations` is not equal to the number of query positions.
+    /// * Evaluations of any of the oracles did not match the oracle commitments.
+    /// * The evaluations of the first oracle did not match `evaluations`.
+    /// * Any of the points in the domain of an oracle was also sampled by the verifier.
+    /// * The folded values of the last polynomial did not match the final polynomial.
+    pub fn verify(&self, channel: &mut C, evaluations: &[E]) -> Result<(), VerifierError> {
+        let query_positions = self.query_positions();
+        if query_positions.len() != evaluations.len() {
+            return Err(VerifierError::NumPositionEvaluationMismatch(
+                query_positions.len(),
+                evaluations.len(),
+            ));
+        }
+
+        let folding_factor = self.options.folding_factor();
+        let mut domain_size = self.domain_size;
+        let mut domain_offset = self.options.domain_offset::<E::BaseField>();
+
+        // the points sampled in the previous round, the polynomial interpolating the values at
+        // these points, and the degree correction coefficient; these are needed to compute the
+        // values of the current polynomial from the values of the current oracle
+        let mut quotient_data: Option<(Vec<E>, Vec<E>, E)> = None;
+
+        for (round, commitment) in self.oracle_commitments.iter().enumerate() {
+            let (positions, alpha) = match self.rounds.get(round) {
+                Some(stir_round) => (&stir_round.positions, stir_round.alpha),
+                None => (&self.final_positions, self.final_alpha),
+            };
+
+            // read oracle values for the rows corresponding to the queried positions
+            let domain_generator = E::BaseField::get_root_of_unity(domain_size.ilog2());
+            let row_length = domain_size / folding_factor;
+            let folded_positions = fold_positions(positions, domain_size, folding_factor);
+            let mut values =
+                channel.read_oracle_queries(&folded_positions, folding_factor, commitment)?;
+
+            if round == 0 {
+                // the values of the first oracle must match the provided evaluations
+                for (&position, &evaluation) in positions.iter().zip(evaluations) {
+                    let idx = folded_positions
+                        .iter()
+                        .position(|&v| v == position % row_length)
+                        .unwrap();
+                    if values[idx * folding_factor + position / row_length] != evaluation {
+                        return Err(VerifierError::InitialEvaluationsMismatch);
+                    }
+                }
+            }
+
+            // build the x coordinates for each row; the i-th value in a row corresponds to
+            // x * g^(i * row_length), where x is the first point of the row
+            let folding_roots = (0..folding_factor)
+                .map(|i| domain_generator.exp_vartime(((row_length * i) as u64).into()))
+                .collect::<Vec<_>>();
+            let xs = folded_positions
+                .iter()
+                .flat_map(|&position| {
+                    let x = domain_offset * domain_generator.exp_vartime((position as u64).into());
+                    folding_roots.iter().map(move |&root| E::from(x * root))
+                })
+                .collect::<Vec<_>>();
+
+            // compute the values of the current polynomial from the values of the oracle
+            if let Some((points, ans, comb_coefficient)) = &quotient_data {
+                for (value, &x) in values.iter_mut().zip(xs.iter()) {
+                    *value = compute_corrected_quotient(*value, x, points, ans, *comb_coefficient)
+                        .ok_or(VerifierError::QuotientUndefined(round))?;
+                }
+            }
+
+            // fold the current polynomial at the points corresponding to each row
+            let mut points = Vec::with_capacity(folded_positions.len());
+            let mut folded_values = Vec::with_capacity(folded_positions.len());
+            for (row_xs, row_values) in xs.chunks(folding_factor).zip(values.chunks(folding_factor))
+            {
+                let row_poly = polynom::interpolate(row_xs, row_values, false);
+                points.push(row_xs[0].exp((folding_factor as u64).into()));
+                folded_values.push(polynom::eval(&row_poly, alpha));
+            }
+
+            match self.rounds.get(round) {
+                Some(stir_round) => {
+                    // the folded values are the values of the committed polynomial at the
+                    // points corresponding to each row
+                    let mut sampled_points = stir_round.ood_points.clone();
+                    sampled_points.append(&mut points);
+                    let mut sampled_values = stir_round.ood_answers.clone();
+                    sampled_values.append(&mut folded_values);
+                    let ans = polynom::interpolate(&sampled_points, &sampled_values, false);
+                    quotient_data = Some((sampled_points, ans, stir_round.comb_coefficient));
+
+                    domain_offset *= domain_generator;
+                    domain_size /= 2;
+                }
+                None => {
+                    // the folded values must be consistent with the final polynomial
+                    for (&point, &value) in points.iter().zip(folded_values.iter()) {
+                        if polynom::eval(&self.final_poly, point) != value {
+                            return Err(VerifierError::InvalidFinalFolding);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reseeds the public coin with the root of the Merkle tree computed from the oracle commitment.
+fn reseed_with_commitment<H: ElementHasher, R: RandomCoin<BaseField = H::BaseField>>(
+    public_coin: &mut R,
+    commitment: &[H::Digest],
+) -> Result<(), VerifierError> {
+    let oracle_root = MerkleTree::<H>::get_cap_root(commitment, 2)
+        .map_err(|_| VerifierError::OracleCommitmentMismatch)?;
+    public_coin.reseed_with_digest(&oracle_root);
+    Ok(())
+}
+
+/// Makes sure the prover performed proof-of-work grinding before query positions were drawn in
+/// the specified round, and absorbs the nonce into the public coin.
+fn verify_pow_nonce<R: RandomCoin>(
+    public_coin: &mut R,
+    nonces: &[u64],
+    round: usize,
+    grinding_factor: u32,
+) -> Result<(), VerifierError> {
+    if grinding_factor > 0 {
+        let nonce = nonces[round];
+        if public_coin.check_leading_zeros(nonce) < grinding_factor {
+            return Err(VerifierError::RoundProofOfWorkVerificationFailed(round));
+        }
+        public_coin.reseed_with_int(nonce);
+    }
+    Ok(())
+}
+
+/// Returns (g(x) - ans(x)) / v(x) * sum_{l=0}^{m} (r * x)^l, where g(x) = `value`, v is the
+/// vanishing polynomial of `points`, m is the number of points, and r is `comb_coefficient`.
+///
+/// Returns None if `x` is one of the `points`.
+fn compute_corrected_quotient<E: FieldElement>(
+    value: E,
+    x: E,
+    points: &[E],
+    ans: &[E],
+    comb_coefficient: E,
+) -> Option<E> {
+    let denominator = points.iter().fold(E::ONE, |acc, &point| acc * (x - point));
+    if denominator == E::ZERO {
+        return None;
+    }
+
+    let rx = comb_coefficient * x;
+    let (correction, _) = (0..=points.len()).fold((E::ZERO, E::ONE), |(sum, power), _| {
+        (sum + power, power * rx)
+    });
+
+    Some((value - polynom::eval(ans, x)) / denominator * correction)
+}
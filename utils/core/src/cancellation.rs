@@ -0,0 +1,64 @@
+// Copyright (c) Facebook, Inc. and its affiliates.
+//
+// This source code is licensed under the MIT license found in the
+// LICENSE file in the root directory of this source tree.
+
+use core::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(not(feature = "std"))]
+use alloc::sync::Arc;
+
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+// CANCELLATION TOKEN
+// ================================================================================================
+
+/// A flag which can be used to request cooperative cancellation of a long-running operation.
+///
+/// A token can be cloned cheaply, and all clones share the same flag. Thus, one clone can be
+/// passed to the operation, while another clone is retained by the caller (potentially in a
+/// different thread) to cancel the operation via [cancel()](CancellationToken::cancel). The
+/// operation is expected to check the token periodically via
+/// [is_cancelled()](CancellationToken::is_cancelled), and to stop as soon as possible once the
+/// token has been cancelled.
+///
+/// Once cancelled, a token cannot be reset.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Returns a new token which has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests cancellation of all operations which were given this token or any of its clones.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if cancellation has been requested via this token or any of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+// CANCELLED
+// ================================================================================================
+
+/// An error returned by an operation which was stopped before completion because its
+/// [CancellationToken] has been cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the operation was cancelled")
+    }
+}
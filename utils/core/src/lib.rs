@@ -25,6 +25,9 @@ pub use serde::{ByteReader, ByteWriter, Deserializable, Serializable, SliceReade
 mod errors;
 pub use errors::DeserializationError;
 
+mod cancellation;
+pub use cancellation::{CancellationToken, Cancelled};
+
 #[cfg(test)]
 mod tests;
 
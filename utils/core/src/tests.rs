@@ -3,7 +3,9 @@
 // This source code is licensed under the MIT license found in the
 // LICENSE file in the root directory of this source tree.
 
-use super::{collections::Vec, ByteReader, ByteWriter, Serializable, SliceReader};
+use super::{
+    collections::Vec, ByteReader, ByteWriter, CancellationToken, Serializable, SliceReader,
+};
 
 // VECTOR UTILS TESTS
 // ================================================================================================
@@ -28,6 +30,23 @@ fn group_vector_elements() {
     }
 }
 
+// CANCELLATION TOKEN TESTS
+// ================================================================================================
+
+#[test]
+fn cancellation_token() {
+    let token = CancellationToken::new();
+    let clone = token.clone();
+    assert!(!token.is_cancelled());
+    assert!(!clone.is_cancelled());
+
+    // cancelling any clone cancels all of them
+    clone.cancel();
+    assert!(token.is_cancelled());
+    assert!(clone.is_cancelled());
+    assert!(!CancellationToken::new().is_cancelled());
+}
+
 // SLICE READER TESTS
 // ================================================================================================
 
@@ -5,14 +5,22 @@
 
 use crate::VerifierError;
 use air::{
-    proof::{Queries, StarkProof, Table},
-    Air, EvaluationFrame,
+    proof::{LowDegreeProof, Queries, StarkProof, Table},
+    Air, EvaluationFrame, LowDegreeTest,
 };
-use crypto::{BatchMerkleProof, ElementHasher, MerkleTree};
+use crypto::{BatchMerkleProof, CommitmentHashers, ElementHasher, Hasher, MerkleTree};
 use fri::VerifierChannel as FriVerifierChannel;
 use math::{FieldElement, StarkField};
+use stir::VerifierChannel as StirVerifierChannel;
 use utils::{collections::Vec, string::ToString};
 
+// TYPE ALIASES
+// ================================================================================================
+
+type TraceDigest<H> = <<H as CommitmentHashers>::TraceHasher as Hasher>::Digest;
+type ConstraintDigest<H> = <<H as CommitmentHashers>::ConstraintHasher as Hasher>::Digest;
+type FriDigest<H> = <<H as CommitmentHashers>::FriHasher as Hasher>::Digest;
+
 // VERIFIER CHANNEL
 // ================================================================================================
 
@@ -21,19 +29,23 @@ use utils::{collections::Vec, string::ToString};
 /// A channel is instantiated for a specific proof, which is parsed into structs over the
 /// appropriate field (specified by type parameter `E`). This also validates that the proof is
 /// well-formed in the context of the computation for the specified [Air].
-pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> {
+pub struct VerifierChannel<E: FieldElement, H: CommitmentHashers<BaseField = E::BaseField>> {
     // trace queries
-    trace_roots: Vec<H::Digest>,
-    trace_queries: Option<TraceQueries<E, H>>,
+    trace_caps: Vec<Vec<TraceDigest<H>>>,
+    trace_roots: Vec<TraceDigest<H>>,
+    trace_queries: Option<TraceQueries<E, H::TraceHasher>>,
     // constraint queries
-    constraint_root: H::Digest,
-    constraint_queries: Option<ConstraintQueries<E, H>>,
-    // FRI proof
-    fri_roots: Option<Vec<H::Digest>>,
-    fri_layer_proofs: Vec<BatchMerkleProof<H>>,
-    fri_layer_queries: Vec<Vec<E>>,
-    fri_remainder: Option<Vec<E>>,
+    constraint_cap: Vec<ConstraintDigest<H>>,
+    constraint_root: ConstraintDigest<H>,
+    constraint_queries: Option<ConstraintQueries<E, H::ConstraintHasher>>,
+    // low-degree proof; for STIR, layers are STIR oracles and the remainder is the final polynomial
+    ldt_caps: Option<Vec<Vec<FriDigest<H>>>>,
+    ldt_layer_proofs: Vec<BatchMerkleProof<H::FriHasher>>,
+    ldt_layer_queries: Vec<Vec<E>>,
+    ldt_nonces: Option<Vec<u64>>,
+    ldt_remainder: Option<Vec<E>>,
     fri_num_partitions: usize,
+    stir_ood_answers: Option<Vec<E>>,
     // out-of-domain frame
     ood_trace_frame: Option<TraceOodFrame<E>>,
     ood_constraint_evaluations: Option<Vec<E>>,
@@ -41,7 +53,7 @@ pub struct VerifierChannel<E: FieldElement, H: ElementHasher<BaseField = E::Base
     pow_nonce: u64,
 }
 
-impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChannel<E, H> {
+impl<E: FieldElement, H: CommitmentHashers<BaseField = E::BaseField>> VerifierChannel<E, H> {
     // CONSTRUCTOR
     // --------------------------------------------------------------------------------------------
     /// Creates and returns a new [VerifierChannel] initialized from the specified `proof`.
@@ -55,7 +67,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             trace_queries,
             constraint_queries,
             ood_frame,
-            fri_proof,
+            low_degree_proof,
             pow_nonce,
         } = proof;
 
@@ -64,32 +76,121 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
             return Err(VerifierError::InconsistentBaseField);
         }
 
+        // make sure the proof was generated for the same AIR
+        if air.domain_separator() != context.domain_separator() {
+            return Err(VerifierError::InconsistentDomainSeparator);
+        }
+
         let num_trace_segments = air.trace_layout().num_segments();
         let main_trace_width = air.trace_layout().main_trace_width();
         let aux_trace_width = air.trace_layout().aux_trace_width();
         let lde_domain_size = air.lde_domain_size();
         let fri_options = air.options().to_fri_options();
+        let stir_options = air.options().to_stir_options();
+
+        // make sure the low-degree proof was generated by the protocol specified in the options
+        let low_degree_test = air.options().low_degree_test();
+        if low_degree_proof.low_degree_test() != low_degree_test {
+            return Err(VerifierError::ProofDeserializationError(format!(
+                "expected low-degree proof generated by {low_degree_test:?}, but the proof was generated by {:?}",
+                low_degree_proof.low_degree_test()
+            )));
+        }
 
         // --- parse commitments ------------------------------------------------------------------
-        let (trace_roots, constraint_root, fri_roots) = commitments
-            .parse::<H>(
-                num_trace_segments,
-                fri_options.num_fri_layers(lde_domain_size),
-            )
+        let num_ldt_layers = match low_degree_test {
+            LowDegreeTest::Fri => fri_options.num_fri_layers(lde_domain_size),
+            LowDegreeTest::Stir => stir_options.num_rounds(lde_domain_size),
+        };
+        let (trace_caps, constraint_cap, ldt_caps) = commitments
+            .parse::<H>(num_trace_segments, num_ldt_layers)
             .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
 
+        // make sure all Merkle tree caps have the size implied by the Merkle cap height and tree
+        // arity; the last FRI commitment is a commitment to the remainder, and thus, must be a
+        // single node
+        let cap_height = air.options().merkle_cap_height();
+        let merkle_arity = air.options().merkle_arity();
+        validate_num_leaves(lde_domain_size, merkle_arity, "trace")?;
+        let lde_cap_size = get_cap_size(lde_domain_size, cap_height, merkle_arity);
+        for trace_cap in trace_caps.iter() {
+            validate_cap_size::<H::TraceHasher>(trace_cap, lde_cap_size, "trace")?;
+        }
+        validate_cap_size::<H::ConstraintHasher>(
+            &constraint_cap,
+            lde_cap_size,
+            "constraint evaluation",
+        )?;
+        match low_degree_test {
+            LowDegreeTest::Fri => {
+                let mut fri_layer_domain_size = lde_domain_size;
+                for (i, fri_cap) in ldt_caps.iter().enumerate() {
+                    fri_layer_domain_size /= fri_options.layer_folding_factor(i);
+                    let fri_cap_size = if i == num_ldt_layers {
+                        1
+                    } else {
+                        validate_num_leaves(fri_layer_domain_size, merkle_arity, "FRI layer")?;
+                        get_cap_size(fri_layer_domain_size, cap_height, merkle_arity)
+                    };
+                    validate_cap_size::<H::FriHasher>(fri_cap, fri_cap_size, "FRI layer")?;
+                }
+            }
+            LowDegreeTest::Stir => {
+                // the domain of every STIR oracle is half the size of the previous one, and each
+                // leaf of an oracle tree contains `folding_factor` evaluations; STIR oracle trees
+                // are always binary
+                let mut oracle_domain_size = lde_domain_size;
+                for stir_cap in ldt_caps.iter() {
+                    let num_leaves = oracle_domain_size / stir_options.folding_factor();
+                    let stir_cap_size = get_cap_size(num_leaves, cap_height, 2);
+                    validate_cap_size::<H::FriHasher>(stir_cap, stir_cap_size, "STIR oracle")?;
+                    oracle_domain_size /= 2;
+                }
+            }
+        }
+
+        // compute roots of trace and constraint evaluation trees from their caps; the roots are
+        // used to reseed the public coin
+        let trace_roots = trace_caps
+            .iter()
+            .map(|cap| get_cap_root::<H::TraceHasher>(cap, merkle_arity))
+            .collect();
+        let constraint_root = get_cap_root::<H::ConstraintHasher>(&constraint_cap, merkle_arity);
+
         // --- parse trace and constraint queries -------------------------------------------------
         let trace_queries = TraceQueries::new(trace_queries, air)?;
         let constraint_queries = ConstraintQueries::new(constraint_queries, air)?;
 
-        // --- parse FRI proofs -------------------------------------------------------------------
-        let fri_num_partitions = fri_proof.num_partitions();
-        let fri_remainder = fri_proof
-            .parse_remainder()
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
-        let (fri_layer_queries, fri_layer_proofs) = fri_proof
-            .parse_layers::<H, E>(lde_domain_size, fri_options.folding_factor())
-            .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+        // --- parse low-degree proof -------------------------------------------------------------
+        let (ldt_layer_queries, ldt_layer_proofs, ldt_nonces, ldt_remainder);
+        let (fri_num_partitions, stir_ood_answers);
+        match low_degree_proof {
+            LowDegreeProof::Fri(fri_proof) => {
+                fri_num_partitions = fri_proof.num_partitions();
+                stir_ood_answers = None;
+                ldt_nonces = fri_proof.pow_nonces().to_vec();
+                ldt_remainder = fri_proof
+                    .parse_remainder()
+                    .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+                (ldt_layer_queries, ldt_layer_proofs) = fri_proof
+                    .parse_layers::<H::FriHasher, E>(lde_domain_size, &fri_options)
+                    .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+            }
+            LowDegreeProof::Stir(stir_proof) => {
+                fri_num_partitions = 0;
+                ldt_nonces = stir_proof.pow_nonces().to_vec();
+                stir_ood_answers =
+                    Some(stir_proof.parse_ood_answers().map_err(|err| {
+                        VerifierError::ProofDeserializationError(err.to_string())
+                    })?);
+                ldt_remainder = stir_proof
+                    .parse_final_poly()
+                    .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+                (ldt_layer_queries, ldt_layer_proofs) = stir_proof
+                    .parse_openings::<H::FriHasher, E>(lde_domain_size, &stir_options)
+                    .map_err(|err| VerifierError::ProofDeserializationError(err.to_string()))?;
+            }
+        }
 
         // --- parse out-of-domain evaluation frame -----------------------------------------------
         let (ood_main_trace_frame, ood_aux_trace_frame, ood_constraint_evaluations) = ood_frame
@@ -99,17 +200,21 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
 
         Ok(VerifierChannel {
             // trace queries
+            trace_caps,
             trace_roots,
             trace_queries: Some(trace_queries),
             // constraint queries
+            constraint_cap,
             constraint_root,
             constraint_queries: Some(constraint_queries),
-            // FRI proof
-            fri_roots: Some(fri_roots),
-            fri_layer_proofs,
-            fri_layer_queries,
-            fri_remainder: Some(fri_remainder),
+            // low-degree proof
+            ldt_caps: Some(ldt_caps),
+            ldt_layer_proofs,
+            ldt_layer_queries,
+            ldt_nonces: Some(ldt_nonces),
+            ldt_remainder: Some(ldt_remainder),
             fri_num_partitions,
+            stir_ood_answers,
             // out-of-domain evaluation
             ood_trace_frame: Some(ood_trace_frame),
             ood_constraint_evaluations: Some(ood_constraint_evaluations),
@@ -124,13 +229,17 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     /// Returns execution trace commitments sent by the prover.
     ///
     /// For computations requiring multiple trace segment, the returned slice will contain a
-    /// commitment for each trace segment.
-    pub fn read_trace_commitments(&self) -> &[H::Digest] {
+    /// commitment for each trace segment. Each commitment is the root of the trace segment Merkle
+    /// tree computed from the tree cap sent by the prover.
+    pub fn read_trace_commitments(&self) -> &[TraceDigest<H>] {
         &self.trace_roots
     }
 
     /// Returns constraint evaluation commitment sent by the prover.
-    pub fn read_constraint_commitment(&self) -> H::Digest {
+    ///
+    /// The commitment is the root of the constraint evaluation Merkle tree computed from the tree
+    /// cap sent by the prover.
+    pub fn read_constraint_commitment(&self) -> ConstraintDigest<H> {
         self.constraint_root
     }
 
@@ -172,8 +281,8 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
         let queries = self.trace_queries.take().expect("already read");
 
         // make sure the states included in the proof correspond to the trace commitment
-        for (root, proof) in self.trace_roots.iter().zip(queries.query_proofs.iter()) {
-            MerkleTree::verify_batch(root, positions, proof)
+        for (cap, proof) in self.trace_caps.iter().zip(queries.query_proofs.iter()) {
+            MerkleTree::verify_batch_with_cap(cap, positions, proof)
                 .map_err(|_| VerifierError::TraceQueryDoesNotMatchCommitment)?;
         }
 
@@ -189,7 +298,7 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
     ) -> Result<Table<E>, VerifierError> {
         let queries = self.constraint_queries.take().expect("already read");
 
-        MerkleTree::verify_batch(&self.constraint_root, positions, &queries.query_proofs)
+        MerkleTree::verify_batch_with_cap(&self.constraint_cap, positions, &queries.query_proofs)
             .map_err(|_| VerifierError::ConstraintQueryDoesNotMatchCommitment)?;
 
         Ok(queries.evaluations)
@@ -202,28 +311,67 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> VerifierChanne
 impl<E, H> FriVerifierChannel<E> for VerifierChannel<E, H>
 where
     E: FieldElement,
-    H: ElementHasher<BaseField = E::BaseField>,
+    H: CommitmentHashers<BaseField = E::BaseField>,
 {
-    type Hasher = H;
+    type Hasher = H::FriHasher;
 
     fn read_fri_num_partitions(&self) -> usize {
         self.fri_num_partitions
     }
 
-    fn read_fri_layer_commitments(&mut self) -> Vec<H::Digest> {
-        self.fri_roots.take().expect("already read")
+    fn read_fri_layer_commitments(&mut self) -> Vec<Vec<FriDigest<H>>> {
+        self.ldt_caps.take().expect("already read")
+    }
+
+    fn read_fri_layer_nonces(&mut self) -> Vec<u64> {
+        self.ldt_nonces.take().expect("already read")
     }
 
-    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H> {
-        self.fri_layer_proofs.remove(0)
+    fn take_next_fri_layer_proof(&mut self) -> BatchMerkleProof<H::FriHasher> {
+        self.ldt_layer_proofs.remove(0)
     }
 
     fn take_next_fri_layer_queries(&mut self) -> Vec<E> {
-        self.fri_layer_queries.remove(0)
+        self.ldt_layer_queries.remove(0)
     }
 
     fn take_fri_remainder(&mut self) -> Vec<E> {
-        self.fri_remainder.take().expect("already read")
+        self.ldt_remainder.take().expect("already read")
+    }
+}
+
+// STIR VERIFIER CHANNEL IMPLEMENTATION
+// ================================================================================================
+
+impl<E, H> StirVerifierChannel<E> for VerifierChannel<E, H>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+{
+    type Hasher = H::FriHasher;
+
+    fn read_stir_oracle_commitments(&mut self) -> Vec<Vec<FriDigest<H>>> {
+        self.ldt_caps.take().expect("already read")
+    }
+
+    fn read_stir_ood_answers(&mut self) -> Vec<E> {
+        self.stir_ood_answers.take().expect("already read")
+    }
+
+    fn read_stir_round_nonces(&mut self) -> Vec<u64> {
+        self.ldt_nonces.take().expect("already read")
+    }
+
+    fn read_stir_final_poly(&mut self) -> Vec<E> {
+        self.ldt_remainder.take().expect("already read")
+    }
+
+    fn take_next_stir_oracle_queries(&mut self) -> Vec<E> {
+        self.ldt_layer_queries.remove(0)
+    }
+
+    fn take_next_stir_oracle_proof(&mut self) -> BatchMerkleProof<H::FriHasher> {
+        self.ldt_layer_proofs.remove(0)
     }
 }
 
@@ -257,13 +405,19 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
         );
 
         let num_queries = air.options().num_queries();
+        let merkle_arity = air.options().merkle_arity();
 
         // parse main trace segment queries; parsing also validates that hashes of each table row
         // form the leaves of Merkle authentication paths in the proofs
         let main_segment_width = air.trace_layout().main_trace_width();
         let main_segment_queries = queries.remove(0);
         let (main_segment_query_proofs, main_segment_states) = main_segment_queries
-            .parse::<H, E::BaseField>(air.lde_domain_size(), num_queries, main_segment_width)
+            .parse::<H, E::BaseField>(
+                air.lde_domain_size(),
+                num_queries,
+                main_segment_width,
+                merkle_arity,
+            )
             .map_err(|err| {
                 VerifierError::ProofDeserializationError(format!(
                     "main trace segment query deserialization failed: {err}"
@@ -281,7 +435,12 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> TraceQueries<E
             for (i, segment_queries) in queries.into_iter().enumerate() {
                 let segment_width = air.trace_layout().get_aux_segment_width(i);
                 let (segment_query_proof, segment_trace_states) = segment_queries
-                    .parse::<H, E>(air.lde_domain_size(), num_queries, segment_width)
+                    .parse::<H, E>(
+                        air.lde_domain_size(),
+                        num_queries,
+                        segment_width,
+                        merkle_arity,
+                    )
                     .map_err(|err| {
                         VerifierError::ProofDeserializationError(format!(
                             "auxiliary trace segment query deserialization failed: {err}"
@@ -325,8 +484,14 @@ impl<E: FieldElement, H: ElementHasher<BaseField = E::BaseField>> ConstraintQuer
         air: &A,
     ) -> Result<Self, VerifierError> {
         let num_queries = air.options().num_queries();
+        let merkle_arity = air.options().merkle_arity();
         let (query_proofs, evaluations) = queries
-            .parse::<H, E>(air.lde_domain_size(), num_queries, air.ce_blowup_factor())
+            .parse::<H, E>(
+                air.lde_domain_size(),
+                num_queries,
+                air.ce_blowup_factor(),
+                merkle_arity,
+            )
             .map_err(|err| {
                 VerifierError::ProofDeserializationError(format!(
                     "constraint evaluation query deserialization failed: {err}"
@@ -356,3 +521,54 @@ impl<E: FieldElement> TraceOodFrame<E> {
         }
     }
 }
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Returns the number of nodes in a cap of the specified height for a Merkle tree with the
+/// specified number of leaves and arity.
+///
+/// This mirrors [MerkleTree::cap()] which limits cap height to `depth - 1`.
+fn get_cap_size(num_leaves: usize, cap_height: usize, arity: usize) -> usize {
+    let depth = (num_leaves.ilog2() / arity.ilog2()) as usize;
+    arity.pow(cap_height.min(depth - 1) as u32)
+}
+
+/// Returns an error if a Merkle tree of the specified arity cannot be built over the specified
+/// number of leaves; that is, if `num_leaves` is not a power of `arity` greater than one.
+fn validate_num_leaves(
+    num_leaves: usize,
+    arity: usize,
+    commitment_name: &str,
+) -> Result<(), VerifierError> {
+    if num_leaves < arity || num_leaves.ilog2() % arity.ilog2() != 0 {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "{commitment_name} commitment cannot be a Merkle tree of arity {arity} over {num_leaves} leaves"
+        )));
+    }
+    Ok(())
+}
+
+/// Returns an error if the number of nodes in the specified Merkle tree `cap` is not equal to the
+/// `expected` number.
+fn validate_cap_size<H: ElementHasher>(
+    cap: &[H::Digest],
+    expected: usize,
+    commitment_name: &str,
+) -> Result<(), VerifierError> {
+    if cap.len() != expected {
+        return Err(VerifierError::ProofDeserializationError(format!(
+            "{commitment_name} commitment must consist of {expected} Merkle tree nodes, but was {}",
+            cap.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Computes the root of a Merkle tree from the specified tree cap.
+///
+/// The number of nodes in the cap is expected to have been validated already.
+fn get_cap_root<H: ElementHasher>(cap: &[H::Digest], arity: usize) -> H::Digest {
+    MerkleTree::<H>::get_cap_root(cap, arity)
+        .expect("Merkle tree cap size must be a power of the tree arity")
+}
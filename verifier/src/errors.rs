@@ -16,6 +16,9 @@ pub enum VerifierError {
     /// This error occurs when base field read by a verifier from a proof does not match the
     /// base field of AIR with which the verifier was instantiated.
     InconsistentBaseField,
+    /// This error occurs when the domain separator read by a verifier from a proof does not match
+    /// the domain separator of AIR with which the verifier was instantiated.
+    InconsistentDomainSeparator,
     /// This error occurs when the base field in which the proof was generated does not support
     /// field extension of degree specified by the proof.
     UnsupportedFieldExtension(usize),
@@ -41,6 +44,9 @@ pub enum VerifierError {
     /// constraint evaluation queries do not represent a polynomial of the degree expected by the
     /// verifier.
     FriVerificationFailed(fri::VerifierError),
+    /// This error occurs when the DEEP composition polynomial evaluations derived from trace and
+    /// constraint evaluation queries are rejected by the STIR verifier.
+    StirVerificationFailed(stir::VerifierError),
 }
 
 impl fmt::Display for VerifierError {
@@ -50,6 +56,9 @@ impl fmt::Display for VerifierError {
             Self::InconsistentBaseField =>  {
                 write!(f, "base field of the proof does not match base field of the specified AIR")
             }
+            Self::InconsistentDomainSeparator =>  {
+                write!(f, "domain separator of the proof does not match domain separator of the specified AIR")
+            }
             Self::UnsupportedFieldExtension(degree) => {
                 write!(f, "field extension of degree {degree} is not supported for the proof base field")
             }
@@ -74,6 +83,9 @@ impl fmt::Display for VerifierError {
             Self::FriVerificationFailed(err) => {
                 write!(f, "verification of low-degree proof failed: {err}")
             }
+            Self::StirVerificationFailed(err) => {
+                write!(f, "verification of STIR low-degree proof failed: {err}")
+            }
         }
     }
 }
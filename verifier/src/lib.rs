@@ -33,9 +33,10 @@
 extern crate alloc;
 
 pub use air::{
-    proof::StarkProof, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ConstraintCompositionCoefficients, ConstraintDivisor,
-    DeepCompositionCoefficients, EvaluationFrame, FieldExtension, ProofOptions, TraceInfo,
+    proof::StarkProof, AggregateAir, AggregateInputs, Air, AirContext, Assertion,
+    AuxTraceRandElements, BoundaryConstraint, BoundaryConstraintGroup,
+    ConstraintCompositionCoefficients, ConstraintDivisor, DeepCompositionCoefficients,
+    EvaluationFrame, EvaluationFrameBatch, FieldExtension, LowDegreeTest, ProofOptions, TraceInfo,
     TransitionConstraintDegree, TransitionConstraintGroup,
 };
 
@@ -50,9 +51,10 @@ pub use utils::{
 };
 
 pub use crypto;
-use crypto::{ElementHasher, RandomCoin};
+use crypto::{CommitmentHashers, RandomCoin};
 
 use fri::FriVerifier;
+use stir::StirVerifier;
 
 mod channel;
 use channel::VerifierChannel;
@@ -74,6 +76,11 @@ pub use errors::VerifierError;
 /// `proof` attests to the correct execution of the computation against public inputs specified
 /// by `pub_inputs`. If the verification is successful, `Ok(())` is returned.
 ///
+/// `HashFn` specifies the hash functions used for trace, constraint, and FRI layer commitments.
+/// This can be a single [ElementHasher](crypto::ElementHasher) or
+/// [DistinctHashers](crypto::DistinctHashers). The hash function of `RandCoin` does not need to
+/// be the same as any of the commitment hash functions.
+///
 /// # Errors
 /// Returns an error if combination of the provided proof and public inputs does not attest to
 /// a correct execution of the computation. This could happen for many various reasons, including:
@@ -86,12 +93,13 @@ pub fn verify<AIR, HashFn, RandCoin>(
 ) -> Result<(), VerifierError> 
 where 
     AIR: Air, 
-    HashFn: ElementHasher<BaseField = AIR::BaseField>,
-    RandCoin: RandomCoin<BaseField = AIR::BaseField, Hasher = HashFn>,
+    HashFn: CommitmentHashers<BaseField = AIR::BaseField>,
+    RandCoin: RandomCoin<BaseField = AIR::BaseField>,
 {
-    // build a seed for the public coin; the initial seed is a hash of the proof context and the
-    // public inputs, but as the protocol progresses, the coin will be reseeded with the info
-    // received from the prover
+    // build a seed for the public coin; the initial seed is a hash of the proof context (which
+    // includes the domain separator of the AIR) and the public inputs, but as the protocol
+    // progresses, the coin will be reseeded with the info received from the prover; the domain
+    // separator in the proof context is checked against the AIR when the channel is created
     let mut public_coin_seed = proof.context.to_elements();
     public_coin_seed.append(&mut pub_inputs.to_elements());
     
@@ -137,8 +145,8 @@ fn perform_verification<A, E, H, R>(
 where
     A: Air,
     E: FieldElement<BaseField = A::BaseField>,
-    H: ElementHasher<BaseField = A::BaseField>,
-    R: RandomCoin<BaseField = A::BaseField, Hasher = H>,
+    H: CommitmentHashers<BaseField = A::BaseField>,
+    R: RandomCoin<BaseField = A::BaseField>,
 {
     // 1 ----- trace commitment -------------------------------------------------------------------
     // Read the commitments to evaluations of the trace polynomials over the LDE domain sent by the
@@ -152,7 +160,7 @@ where
     let trace_commitments = channel.read_trace_commitments();
 
     // reseed the coin with the commitment to the main trace segment
-    public_coin.reseed(trace_commitments[0]);
+    public_coin.reseed_with_digest(&trace_commitments[0]);
 
     // process auxiliary trace segments (if any), to build a set of random elements for each segment
     let mut aux_trace_rand_elements = AuxTraceRandElements::<E>::new();
@@ -161,7 +169,7 @@ where
             .get_aux_trace_segment_random_elements(i, &mut public_coin)
             .map_err(|_| VerifierError::RandomCoinError)?;
         aux_trace_rand_elements.add_segment_elements(rand_elements);
-        public_coin.reseed(*commitment);
+        public_coin.reseed_with_digest(commitment);
     }
 
     // build random coefficients for the composition polynomial
@@ -176,7 +184,7 @@ where
     // to the prover, and the prover evaluates trace and constraint composition polynomials at z,
     // and sends the results back to the verifier.
     let constraint_commitment = channel.read_constraint_commitment();
-    public_coin.reseed(constraint_commitment);
+    public_coin.reseed_with_digest(&constraint_commitment);
     let z = public_coin
         .draw::<E>()
         .map_err(|_| VerifierError::RandomCoinError)?;
@@ -205,14 +213,14 @@ where
 
         let mut current = ood_main_trace_frame.current().to_vec();
         current.extend_from_slice(aux_trace_frame.current());
-        public_coin.reseed(H::hash_elements(&current));
+        public_coin.reseed_with_elements(&current);
 
         let mut next = ood_main_trace_frame.next().to_vec();
         next.extend_from_slice(aux_trace_frame.next());
-        public_coin.reseed(H::hash_elements(&next));
+        public_coin.reseed_with_elements(&next);
     } else {
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.current()));
-        public_coin.reseed(H::hash_elements(ood_main_trace_frame.next()));
+        public_coin.reseed_with_elements(ood_main_trace_frame.current());
+        public_coin.reseed_with_elements(ood_main_trace_frame.next());
     }
 
     // read evaluations of composition polynomial columns sent by the prover, and reduce them into
@@ -226,18 +234,18 @@ where
         .fold(E::ZERO, |result, (i, &value)| {
             result + z.exp_vartime((i as u32).into()) * value
         });
-    public_coin.reseed(H::hash_elements(&ood_constraint_evaluations));
+    public_coin.reseed_with_elements(&ood_constraint_evaluations);
 
     // finally, make sure the values are the same
     if ood_constraint_evaluation_1 != ood_constraint_evaluation_2 {
         return Err(VerifierError::InconsistentOodConstraintEvaluations);
     }
 
-    // 4 ----- FRI commitments --------------------------------------------------------------------
+    // 4 ----- low-degree proof commitments -------------------------------------------------------
     // draw coefficients for computing DEEP composition polynomial from the public coin; in the
     // interactive version of the protocol, the verifier sends these coefficients to the prover
     // and the prover uses them to compute the DEEP composition polynomial. the prover, then
-    // applies FRI protocol to the evaluations of the DEEP composition polynomial.
+    // applies FRI (or STIR) protocol to the evaluations of the DEEP composition polynomial.
     let deep_coefficients = air
         .get_deep_composition_coefficients::<E, R>(&mut public_coin)
         .map_err(|_| VerifierError::RandomCoinError)?;
@@ -247,32 +255,63 @@ where
     // The verifier uses these commitments to update the public coin and draw random points alpha
     // from them; in the interactive version of the protocol, the verifier sends these alphas to
     // the prover, and the prover uses them to compute and commit to the subsequent FRI layers.
-    let fri_verifier = FriVerifier::new(
-        &mut channel,
-        &mut public_coin,
-        air.options().to_fri_options(),
-        air.trace_poly_degree(),
-    )
-    .map_err(VerifierError::FriVerificationFailed)?;
+    //
+    // when STIR is used, the STIR verifier reads oracle commitments and out-of-domain answers
+    // for all STIR rounds from the channel, and draws all STIR challenges and query positions.
+    let low_degree_verifier = match air.options().low_degree_test() {
+        LowDegreeTest::Fri => LowDegreeVerifier::Fri(
+            FriVerifier::new(
+                &mut channel,
+                &mut public_coin,
+                air.options().to_fri_options(),
+                air.trace_poly_degree(),
+            )
+            .map_err(VerifierError::FriVerificationFailed)?,
+        ),
+        LowDegreeTest::Stir => LowDegreeVerifier::Stir(
+            StirVerifier::new(
+                &mut channel,
+                &mut public_coin,
+                air.options().to_stir_options(),
+                air.trace_poly_degree(),
+            )
+            .map_err(VerifierError::StirVerificationFailed)?,
+        ),
+    };
     // TODO: make sure air.lde_domain_size() == fri_verifier.domain_size()
 
     // 5 ----- trace and constraint queries -------------------------------------------------------
-    // read proof-of-work nonce sent by the prover and update the public coin with it
-    let pow_nonce = channel.read_pow_nonce();
-    public_coin.reseed_with_int(pow_nonce);
+    let query_positions = match &low_degree_verifier {
+        LowDegreeVerifier::Fri(_) => {
+            // read proof-of-work nonce sent by the prover and update the public coin with it
+            let pow_nonce = channel.read_pow_nonce();
+            public_coin.reseed_with_int(pow_nonce);
+
+            // make sure the proof-of-work specified by the grinding factor is satisfied
+            if public_coin.leading_zeros() < air.options().grinding_factor() {
+                return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+            }
 
-    // make sure the proof-of-work specified by the grinding factor is satisfied
-    if public_coin.leading_zeros() < air.options().grinding_factor() {
-        return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
-    }
+            // draw pseudo-random query positions for the LDE domain from the public coin; in the
+            // interactive version of the protocol, the verifier sends these query positions to
+            // the prover, and the prover responds with decommitments against these positions for
+            // trace and constraint composition polynomial evaluations.
+            public_coin
+                .draw_integers(air.options().num_queries(), air.lde_domain_size())
+                .map_err(|_| VerifierError::RandomCoinError)?
+        }
+        LowDegreeVerifier::Stir(stir_verifier) => {
+            // with STIR, proof-of-work is performed before query positions of every STIR round
+            // are drawn, and thus, the query seed nonce is not used
+            if channel.read_pow_nonce() != 0 {
+                return Err(VerifierError::QuerySeedProofOfWorkVerificationFailed);
+            }
 
-    // draw pseudo-random query positions for the LDE domain from the public coin; in the
-    // interactive version of the protocol, the verifier sends these query positions to the prover,
-    // and the prover responds with decommitments against these positions for trace and constraint
-    // composition polynomial evaluations.
-    let query_positions = public_coin
-        .draw_integers(air.options().num_queries(), air.lde_domain_size())
-        .map_err(|_| VerifierError::RandomCoinError)?;
+            // trace and constraint commitments are queried at the positions of the first STIR
+            // round
+            stir_verifier.query_positions().to_vec()
+        }
+    };
 
     // read evaluations of trace and constraint composition polynomials at the queried positions;
     // this also checks that the read values are valid against trace and constraint commitments
@@ -296,7 +335,26 @@ where
     // 7 ----- Verify low-degree proof -------------------------------------------------------------
     // make sure that evaluations of the DEEP composition polynomial we computed in the previous
     // step are in fact evaluations of a polynomial of degree equal to trace polynomial degree
-    fri_verifier
-        .verify(&mut channel, &deep_evaluations, &query_positions)
-        .map_err(VerifierError::FriVerificationFailed)
+    match low_degree_verifier {
+        LowDegreeVerifier::Fri(fri_verifier) => fri_verifier
+            .verify(&mut channel, &deep_evaluations, &query_positions)
+            .map_err(VerifierError::FriVerificationFailed),
+        LowDegreeVerifier::Stir(stir_verifier) => stir_verifier
+            .verify(&mut channel, &deep_evaluations)
+            .map_err(VerifierError::StirVerificationFailed),
+    }
+}
+
+// LOW-DEGREE VERIFIER
+// ================================================================================================
+
+/// Verifier of the low-degree proof for the DEEP composition polynomial.
+enum LowDegreeVerifier<E, H, R>
+where
+    E: FieldElement,
+    H: CommitmentHashers<BaseField = E::BaseField>,
+    R: RandomCoin<BaseField = E::BaseField>,
+{
+    Fri(FriVerifier<E, VerifierChannel<E, H>, H::FriHasher, R>),
+    Stir(StirVerifier<E, VerifierChannel<E, H>, H::FriHasher, R>),
 }
@@ -528,12 +528,19 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "concurrent")]
+pub use prover::rayon;
+#[cfg(feature = "std")]
+pub use prover::LogObserver;
 pub use prover::{
-    crypto, iterators, math, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
-    BoundaryConstraintGroup, ByteReader, ByteWriter, ColMatrix, ConstraintCompositionCoefficients,
-    ConstraintDivisor, DeepCompositionCoefficients, Deserializable, DeserializationError,
-    EvaluationFrame, FieldExtension, ProofOptions, Prover, ProverError, Serializable, SliceReader,
-    StarkProof, Trace, TraceInfo, TraceLayout, TraceTable, TraceTableFragment,
-    TransitionConstraintDegree, TransitionConstraintGroup,
+    crypto, grinding, iterators, math, AggregateAir, AggregateInputs, AggregateProver,
+    AggregateTrace, Air, AirContext, Assertion, AuxTraceRandElements, BoundaryConstraint,
+    BoundaryConstraintGroup, ByteReader, ByteWriter, CancellationToken, ColMatrix,
+    ConstraintCompositionCoefficients, ConstraintDivisor, ConstraintEvaluationMode,
+    DeepCompositionCoefficients, Deserializable, DeserializationError, DivergentConstraint,
+    EvaluationFrame, EvaluationFrameBatch, FieldExtension, LowDegreeTest, NoopObserver,
+    ProofOptions, Prover, ProverContext, ProverError, ProverEvent, ProverObserver, ProverStage,
+    ProverStep, Serializable, SliceReader, StagedProver, StarkProof, Trace, TraceInfo, TraceLayout,
+    TraceTable, TraceTableFragment, TransitionConstraintDegree, TransitionConstraintGroup,
 };
 pub use verifier::{verify, VerifierError};